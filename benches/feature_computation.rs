@@ -0,0 +1,74 @@
+//! Benchmarks for feature computation and snapshot building.
+//!
+//! Run with `cargo bench --bench feature_computation`. The agent targets
+//! under 20ms of processing per window.
+
+mod fixtures;
+
+use chrono::Duration;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use fixtures::{keyboard_events, mixed_window, mouse_events, window, EVENT_COUNTS, WINDOW_SECS};
+use synheart_sensor_agent::{compute_features, FeatureConfig, HsiBuilder};
+
+/// Keyboard features, measured on windows with only keyboard events.
+fn bench_keyboard_features(c: &mut Criterion) {
+    let config = FeatureConfig::default();
+    let mut group = c.benchmark_group("keyboard_features");
+    for count in EVENT_COUNTS {
+        let window = window(keyboard_events(count));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &window, |b, window| {
+            b.iter(|| compute_features(black_box(window), &config))
+        });
+    }
+    group.finish();
+}
+
+/// Mouse features, measured on windows with only mouse events.
+fn bench_mouse_features(c: &mut Criterion) {
+    let config = FeatureConfig::default();
+    let mut group = c.benchmark_group("mouse_features");
+    for count in EVENT_COUNTS {
+        let window = window(mouse_events(count));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &window, |b, window| {
+            b.iter(|| compute_features(black_box(window), &config))
+        });
+    }
+    group.finish();
+}
+
+/// All features, on windows with both keyboard and mouse events.
+fn bench_compute_features(c: &mut Criterion) {
+    let config = FeatureConfig::default();
+    let mut group = c.benchmark_group("compute_features");
+    for count in EVENT_COUNTS {
+        let window = mixed_window(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &window, |b, window| {
+            b.iter(|| compute_features(black_box(window), &config))
+        });
+    }
+    group.finish();
+}
+
+/// Snapshot building from precomputed features.
+fn bench_hsi_build(c: &mut Criterion) {
+    let builder = HsiBuilder::new();
+    let resolution = Duration::seconds(WINDOW_SECS);
+    let mut group = c.benchmark_group("hsi_build");
+    for count in EVENT_COUNTS {
+        let window = mixed_window(count);
+        let features = compute_features(&window, &FeatureConfig::default());
+        group.bench_with_input(BenchmarkId::from_parameter(count), &window, |b, window| {
+            b.iter(|| builder.build(black_box(window), black_box(&features), resolution, None))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_keyboard_features,
+    bench_mouse_features,
+    bench_compute_features,
+    bench_hsi_build
+);
+criterion_main!(benches);
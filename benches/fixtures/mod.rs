@@ -0,0 +1,68 @@
+//! Pre-built event windows for the benchmarks.
+//!
+//! Windows are built once, outside the timed loops, so allocation does not
+//! add noise to the measurements.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use synheart_sensor_agent::collector::{KeyboardEvent, MouseEvent, SensorEvent};
+use synheart_sensor_agent::core::EventWindow;
+
+/// Event counts benchmarked for each feature group.
+pub const EVENT_COUNTS: [usize; 3] = [10, 100, 1000];
+
+/// Length of every fixture window.
+pub const WINDOW_SECS: i64 = 10;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 14, 32, 0).unwrap()
+}
+
+/// Offset of the `i`th of `count` events spread evenly over the window.
+fn offset(i: usize, count: usize) -> Duration {
+    Duration::milliseconds((i as i64 * WINDOW_SECS * 1000) / count as i64)
+}
+
+/// Alternating key down and key up events with irregular spacing.
+pub fn keyboard_events(count: usize) -> Vec<SensorEvent> {
+    (0..count)
+        .map(|i| {
+            let mut event = KeyboardEvent::new(i % 2 == 0);
+            // Jitter the spacing so intervals, pauses and bursts all vary
+            event.timestamp = start() + offset(i, count) + Duration::milliseconds((i % 7) as i64);
+            SensorEvent::Keyboard(event)
+        })
+        .collect()
+}
+
+/// Mostly movements, with a click, release and scroll every few events.
+pub fn mouse_events(count: usize) -> Vec<SensorEvent> {
+    (0..count)
+        .map(|i| {
+            let mut event = match i % 10 {
+                3 => MouseEvent::click(true),
+                4 => MouseEvent::click_release(true).with_hold_duration_ms(90.0),
+                8 => MouseEvent::scroll(0.0, if i % 20 == 8 { 3.0 } else { -3.0 }),
+                _ => MouseEvent::movement((i % 13) as f64, (i % 5) as f64),
+            };
+            event.timestamp = start() + offset(i, count);
+            SensorEvent::Mouse(event)
+        })
+        .collect()
+}
+
+/// A window holding `events`.
+pub fn window(events: Vec<SensorEvent>) -> EventWindow {
+    let mut window = EventWindow::new(start(), Duration::seconds(WINDOW_SECS));
+    for event in events {
+        window.add_event(event);
+    }
+    window
+}
+
+/// A window with `count` keyboard events and `count` mouse events, interleaved.
+pub fn mixed_window(count: usize) -> EventWindow {
+    let mut events = keyboard_events(count);
+    events.extend(mouse_events(count));
+    events.sort_by_key(SensorEvent::timestamp);
+    window(events)
+}
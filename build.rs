@@ -0,0 +1,18 @@
+//! Build script: compiles the Protocol Buffers schema when the `proto` feature is enabled.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "proto")]
+    {
+        const PROTO: &str = "src/proto/synheart_sensor_agent.proto";
+        println!("cargo:rerun-if-changed={PROTO}");
+
+        // Use the vendored protoc so no system install is required
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc available");
+        std::env::set_var("PROTOC", protoc);
+
+        prost_build::compile_protos(&[PROTO], &["src/proto"])
+            .expect("Failed to compile Protocol Buffers schema");
+    }
+}
@@ -18,7 +18,7 @@ use std::time::Duration;
 
 use synheart_sensor_agent::{
     collector::{check_permission, Collector, CollectorConfig, SensorEvent},
-    core::{compute_features, HsiBuilder, WindowManager},
+    core::{compute_features, FeatureConfig, HsiBuilder, WindowManager},
     transparency::TransparencyLog,
     PRIVACY_DECLARATION,
 };
@@ -32,6 +32,13 @@ fn main() {
     println!("{PRIVACY_DECLARATION}");
     println!();
 
+    // Collection requires recorded consent
+    if !synheart_sensor_agent::consent::has_consent() {
+        println!("Consent has not been recorded.");
+        println!("Run `synheart-sensor start` once and consent to collection first.");
+        return;
+    }
+
     // Check for permission
     print!("Checking Input Monitoring permission... ");
     if check_permission() {
@@ -52,10 +59,11 @@ fn main() {
     let config = CollectorConfig {
         capture_keyboard: true,
         capture_mouse: true,
+        ..CollectorConfig::default()
     };
 
     let mut collector = Collector::new(config);
-    let mut window_manager = WindowManager::new(10, 300); // 10s windows, 5min session gap
+    let mut window_manager = WindowManager::new(10, 300, None); // 10s windows, 5min session gap
     let hsi_builder = HsiBuilder::new();
     let transparency_log = TransparencyLog::new();
 
@@ -93,7 +101,7 @@ fn main() {
                 event_count += 1;
 
                 // Log event type
-                match &event {
+                match &*event {
                     SensorEvent::Keyboard(e) => {
                         transparency_log.record_keyboard_event();
                         if event_count <= 10 || event_count % 50 == 0 {
@@ -114,6 +122,7 @@ fn main() {
                             );
                         }
                     }
+                    SensorEvent::Touch(_) => {}
                 }
 
                 // Process in window manager
@@ -129,11 +138,11 @@ fn main() {
         }
 
         // Process completed windows
-        for window in window_manager.take_completed_windows() {
+        for (resolution, window) in window_manager.take_completed_windows() {
             transparency_log.record_window_completed();
 
-            let features = compute_features(&window);
-            let snapshot = hsi_builder.build(&window, &features);
+            let features = compute_features(&window, &FeatureConfig::default());
+            let snapshot = hsi_builder.build(&window, &features, resolution, None);
 
             println!();
             println!("=== Window Completed ===");
@@ -200,7 +209,7 @@ fn main() {
 
     // Flush remaining window
     window_manager.flush();
-    for window in window_manager.take_completed_windows() {
+    for (_, window) in window_manager.take_completed_windows() {
         println!("Final window: {} events", window.event_count());
         transparency_log.record_window_completed();
     }
@@ -0,0 +1,167 @@
+//! Fuzz `WindowManager::process_event` with arbitrary event streams.
+//!
+//! Events arrive in any order, with duplicate timestamps and timestamps
+//! centuries away from each other. The manager must not panic, and every
+//! completed window must be non-empty and end no earlier than it starts.
+//!
+//! Run with `cargo +nightly fuzz run fuzz_window_manager` from the repo root.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use libfuzzer_sys::fuzz_target;
+use synheart_sensor_agent::collector::types::KeyboardEventType;
+use synheart_sensor_agent::collector::{KeyboardEvent, MouseEvent, SensorEvent};
+use synheart_sensor_agent::core::{AdaptiveWindowConfig, EventWindow, WindowManager};
+
+/// Largest distance of an event from the base time (about 1000 years).
+const MAX_OFFSET_MS: i64 = 1000 * 365 * 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    window_durations_secs: Vec<u16>,
+    session_gap_threshold_secs: u16,
+    min_events: Option<u8>,
+    clock_aligned: bool,
+    adaptive: Option<(u16, u16, u8)>,
+    events: Vec<FuzzEvent>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum FuzzEvent {
+    Keyboard {
+        offset_ms: i64,
+        is_key_down: bool,
+        is_navigation: bool,
+        modifier_active: bool,
+    },
+    Move {
+        offset_ms: i64,
+        delta_x: f64,
+        delta_y: f64,
+    },
+    Click {
+        offset_ms: i64,
+        is_left: bool,
+        is_release: bool,
+    },
+    Scroll {
+        offset_ms: i64,
+        delta_x: f64,
+        delta_y: f64,
+    },
+    /// Flush partway through the stream, as on pause
+    Flush,
+}
+
+fn timestamp(base: DateTime<Utc>, offset_ms: i64) -> DateTime<Utc> {
+    base + Duration::milliseconds(offset_ms % MAX_OFFSET_MS)
+}
+
+fn sensor_event(base: DateTime<Utc>, event: &FuzzEvent) -> Option<SensorEvent> {
+    let event = match *event {
+        FuzzEvent::Keyboard {
+            offset_ms,
+            is_key_down,
+            is_navigation,
+            modifier_active,
+        } => {
+            let event_type = if is_navigation {
+                KeyboardEventType::NavigationKey
+            } else {
+                KeyboardEventType::TypingTap
+            };
+            let mut event = KeyboardEvent::with_type(is_key_down, event_type)
+                .with_modifier_active(modifier_active);
+            event.timestamp = timestamp(base, offset_ms);
+            SensorEvent::Keyboard(event)
+        }
+        FuzzEvent::Move {
+            offset_ms,
+            delta_x,
+            delta_y,
+        } => {
+            let mut event = MouseEvent::movement(delta_x, delta_y);
+            event.timestamp = timestamp(base, offset_ms);
+            SensorEvent::Mouse(event)
+        }
+        FuzzEvent::Click {
+            offset_ms,
+            is_left,
+            is_release,
+        } => {
+            let mut event = if is_release {
+                MouseEvent::click_release(is_left)
+            } else {
+                MouseEvent::click(is_left)
+            };
+            event.timestamp = timestamp(base, offset_ms);
+            SensorEvent::Mouse(event)
+        }
+        FuzzEvent::Scroll {
+            offset_ms,
+            delta_x,
+            delta_y,
+        } => {
+            let mut event = MouseEvent::scroll(delta_x, delta_y);
+            event.timestamp = timestamp(base, offset_ms);
+            SensorEvent::Mouse(event)
+        }
+        FuzzEvent::Flush => return None,
+    };
+    Some(event)
+}
+
+fn check_completed(manager: &mut WindowManager) {
+    for (_, window) in manager.take_completed_windows() {
+        check_window(&window);
+    }
+}
+
+fn check_window(window: &EventWindow) {
+    assert!(
+        window.end >= window.start,
+        "window ends at {} before it starts at {}",
+        window.end,
+        window.start
+    );
+    assert!(!window.is_empty(), "completed window has no events");
+}
+
+fuzz_target!(|input: Input| {
+    let base = Utc.with_ymd_and_hms(2024, 1, 15, 14, 32, 0).unwrap();
+    let durations: Vec<u64> = input
+        .window_durations_secs
+        .iter()
+        .take(4)
+        .map(|&secs| u64::from(secs))
+        .collect();
+    let gap = u64::from(input.session_gap_threshold_secs);
+    let min_events = input.min_events.map(usize::from);
+
+    let mut manager = match input.adaptive {
+        Some((min_secs, max_secs, target_events)) => WindowManager::adaptive(
+            AdaptiveWindowConfig {
+                min_secs: u64::from(min_secs),
+                max_secs: u64::from(max_secs),
+                target_events: usize::from(target_events),
+            },
+            gap,
+            min_events,
+        ),
+        None => WindowManager::with_resolutions(durations, gap, min_events),
+    };
+    manager.set_clock_aligned(input.clock_aligned);
+
+    for event in &input.events {
+        match sensor_event(base, event) {
+            Some(event) => manager.process_event(event),
+            None => manager.flush(),
+        }
+        check_completed(&mut manager);
+    }
+
+    manager.flush();
+    check_completed(&mut manager);
+});
@@ -0,0 +1,118 @@
+//! Bounded event channel between the capture thread and the agent.
+//!
+//! When the agent falls behind, the channel fills up and events have to be
+//! dropped. The overflow policy decides which ones.
+//!
+//! Events travel boxed, so a send or an eviction moves a pointer rather than
+//! the whole event.
+
+use crate::collector::types::SensorEvent;
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+
+/// Default number of events buffered between the collector and the agent.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Which events to drop when the event channel is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the incoming event, keeping the buffered backlog
+    #[default]
+    DropNewest,
+    /// Discard the oldest buffered events to make room for the incoming one
+    DropOldest,
+}
+
+/// Send an event without blocking, applying the overflow policy if the channel is full.
+///
+/// `receiver` must be a handle to the same channel; it is used to evict the
+/// oldest events under [`OverflowPolicy::DropOldest`], which keeps the
+/// remaining events in FIFO order. Returns the number of events dropped.
+pub fn send_with_policy(
+    sender: &Sender<Box<SensorEvent>>,
+    receiver: &Receiver<Box<SensorEvent>>,
+    event: Box<SensorEvent>,
+    policy: OverflowPolicy,
+) -> u64 {
+    let mut event = event;
+    let mut dropped = 0;
+
+    loop {
+        match sender.try_send(event) {
+            Ok(()) => return dropped,
+            Err(TrySendError::Disconnected(_)) => return dropped + 1,
+            Err(TrySendError::Full(rejected)) => match policy {
+                OverflowPolicy::DropNewest => return dropped + 1,
+                OverflowPolicy::DropOldest => {
+                    // The consumer may have made room in the meantime
+                    if receiver.try_recv().is_ok() {
+                        dropped += 1;
+                    }
+                    event = rejected;
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::types::KeyboardEvent;
+    use crossbeam_channel::bounded;
+
+    fn key_event(is_key_down: bool) -> Box<SensorEvent> {
+        Box::new(SensorEvent::Keyboard(KeyboardEvent::new(is_key_down)))
+    }
+
+    fn is_key_down(event: &SensorEvent) -> bool {
+        match event {
+            SensorEvent::Keyboard(event) => event.is_key_down,
+            SensorEvent::Mouse(_) | SensorEvent::Touch(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_drop_newest_keeps_backlog() {
+        let (sender, receiver) = bounded(2);
+        let policy = OverflowPolicy::DropNewest;
+
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(true), policy),
+            0
+        );
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(true), policy),
+            0
+        );
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(false), policy),
+            1
+        );
+
+        let events: Vec<bool> = receiver.try_iter().map(|e| is_key_down(&e)).collect();
+        assert_eq!(events, vec![true, true]);
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_latest_in_order() {
+        let (sender, receiver) = bounded(2);
+        let policy = OverflowPolicy::DropOldest;
+
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(true), policy),
+            0
+        );
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(false), policy),
+            0
+        );
+        assert_eq!(
+            send_with_policy(&sender, &receiver, key_event(true), policy),
+            1
+        );
+
+        // The first event was evicted; FIFO order is preserved
+        let events: Vec<bool> = receiver.try_iter().map(|e| is_key_down(&e)).collect();
+        assert_eq!(events, vec![false, true]);
+    }
+}
@@ -0,0 +1,196 @@
+//! macOS trackpad gesture capture.
+//!
+//! Gesture events (`kCGEventGesture`) are not covered by the `CGEventType`
+//! enum of the `core-graphics` crate, so they get their own listen-only event
+//! tap created through the raw Core Graphics API. Each gesture is bridged to
+//! an `NSEvent` to learn whether it is a pinch, a swipe or a plain touch
+//! update.
+//!
+//! Privacy: Only the gesture category and its magnitude are read. Touch
+//! positions and the window or view under the gesture are never accessed.
+
+use core_foundation::base::TCFType;
+use core_foundation::mach_port::{CFMachPort, CFMachPortRef};
+use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
+type CGEventRef = *mut c_void;
+type CGEventTapProxy = *mut c_void;
+type CGEventTapCallBack =
+    unsafe extern "C" fn(CGEventTapProxy, u32, CGEventRef, *mut c_void) -> CGEventRef;
+type Id = *mut c_void;
+type Sel = *mut c_void;
+
+/// `kCGSessionEventTap`
+const SESSION_EVENT_TAP: u32 = 1;
+/// `kCGHeadInsertEventTap`
+const HEAD_INSERT_EVENT_TAP: u32 = 0;
+/// `kCGEventTapOptionListenOnly`
+const TAP_OPTION_LISTEN_ONLY: u32 = 1;
+/// `kCGEventGesture`, the window server's type for all trackpad gestures
+const CG_EVENT_GESTURE: u32 = 29;
+
+/// `NSEventTypeGesture`: touches changed, without a recognized gesture
+const NS_EVENT_TYPE_GESTURE: u64 = 29;
+/// `NSEventTypeMagnify`: pinch to zoom
+const NS_EVENT_TYPE_MAGNIFY: u64 = 30;
+/// `NSEventTypeSwipe`: page or space swipe
+const NS_EVENT_TYPE_SWIPE: u64 = 31;
+/// `NSTouchPhaseTouching`: began, moved or stationary touches
+const NS_TOUCH_PHASE_TOUCHING: u64 = 1 | 2 | 4;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn CGEventTapCreate(
+        tap: u32,
+        place: u32,
+        options: u32,
+        events_of_interest: u64,
+        callback: CGEventTapCallBack,
+        user_info: *mut c_void,
+    ) -> CFMachPortRef;
+    fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+}
+
+// Loads NSEvent into the Objective-C runtime
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {}
+
+#[link(name = "objc")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> Id;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+    fn objc_autoreleasePoolPush() -> *mut c_void;
+    fn objc_autoreleasePoolPop(pool: *mut c_void);
+}
+
+/// A trackpad gesture, reduced to its category and magnitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Gesture {
+    /// Pinch to zoom, with the signed magnification step
+    Pinch { magnification: f64 },
+    /// Page or space swipe, with the swipe deltas (-1, 0 or 1 per axis)
+    Swipe { delta_x: f64, delta_y: f64 },
+    /// Two or more fingers resting or moving on the trackpad
+    MultiFingerContact,
+}
+
+/// Create a gesture event tap and add it to `run_loop`.
+///
+/// `handler` is called on the run loop's thread for every recognized
+/// gesture. The tap stays active as long as the returned port is alive.
+/// Returns `None` if the tap could not be created.
+pub(super) fn add_gesture_tap(run_loop: &CFRunLoop, handler: fn(Gesture)) -> Option<CFMachPort> {
+    let port = unsafe {
+        CGEventTapCreate(
+            SESSION_EVENT_TAP,
+            HEAD_INSERT_EVENT_TAP,
+            TAP_OPTION_LISTEN_ONLY,
+            1 << CG_EVENT_GESTURE,
+            gesture_callback,
+            handler as *mut c_void,
+        )
+    };
+    if port.is_null() {
+        return None;
+    }
+    let port = unsafe { CFMachPort::wrap_under_create_rule(port) };
+    let source = port.create_runloop_source(0).ok()?;
+    unsafe {
+        run_loop.add_source(&source, kCFRunLoopCommonModes);
+        CGEventTapEnable(port.as_concrete_TypeRef(), true);
+    }
+    Some(port)
+}
+
+unsafe extern "C" fn gesture_callback(
+    _proxy: CGEventTapProxy,
+    event_type: u32,
+    event: CGEventRef,
+    user_info: *mut c_void,
+) -> CGEventRef {
+    if event_type == CG_EVENT_GESTURE && !event.is_null() {
+        let handler: fn(Gesture) = std::mem::transmute(user_info);
+        if let Some(gesture) = read_gesture(event) {
+            handler(gesture);
+        }
+    }
+
+    // Listen-only: the event is passed on unchanged
+    event
+}
+
+/// Classify a gesture event through its `NSEvent` bridge.
+unsafe fn read_gesture(event: CGEventRef) -> Option<Gesture> {
+    // The bridged NSEvent is autoreleased and this thread has no pool
+    let pool = objc_autoreleasePoolPush();
+
+    let ns_event = send_ptr(class(b"NSEvent\0"), sel(b"eventWithCGEvent:\0"), event);
+    let gesture = if ns_event.is_null() {
+        None
+    } else {
+        match send_u64(ns_event, sel(b"type\0")) {
+            NS_EVENT_TYPE_MAGNIFY => Some(Gesture::Pinch {
+                magnification: send_f64(ns_event, sel(b"magnification\0")),
+            }),
+            NS_EVENT_TYPE_SWIPE => Some(Gesture::Swipe {
+                delta_x: send_f64(ns_event, sel(b"deltaX\0")),
+                delta_y: send_f64(ns_event, sel(b"deltaY\0")),
+            }),
+            NS_EVENT_TYPE_GESTURE => {
+                // Only the number of touches is read, never their positions
+                let touches = send_touches(ns_event, NS_TOUCH_PHASE_TOUCHING);
+                let count = if touches.is_null() {
+                    0
+                } else {
+                    send_u64(touches, sel(b"count\0"))
+                };
+                (count >= 2).then_some(Gesture::MultiFingerContact)
+            }
+            _ => None,
+        }
+    };
+
+    objc_autoreleasePoolPop(pool);
+    gesture
+}
+
+unsafe fn class(name: &[u8]) -> Id {
+    objc_getClass(name.as_ptr().cast())
+}
+
+unsafe fn sel(name: &[u8]) -> Sel {
+    sel_registerName(name.as_ptr().cast())
+}
+
+unsafe fn send_ptr(receiver: Id, selector: Sel, arg: *mut c_void) -> Id {
+    let send: unsafe extern "C" fn(Id, Sel, *mut c_void) -> Id =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    send(receiver, selector, arg)
+}
+
+unsafe fn send_u64(receiver: Id, selector: Sel) -> u64 {
+    let send: unsafe extern "C" fn(Id, Sel) -> u64 =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    send(receiver, selector)
+}
+
+unsafe fn send_f64(receiver: Id, selector: Sel) -> f64 {
+    let send: unsafe extern "C" fn(Id, Sel) -> f64 =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    send(receiver, selector)
+}
+
+/// `[event touchesMatchingPhase:phase inView:nil]`
+unsafe fn send_touches(event: Id, phase: u64) -> Id {
+    let send: unsafe extern "C" fn(Id, Sel, u64, Id) -> Id =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    send(
+        event,
+        sel(b"touchesMatchingPhase:inView:\0"),
+        phase,
+        std::ptr::null_mut(),
+    )
+}
@@ -3,15 +3,21 @@
 //! This module captures keyboard and mouse events at the system level using
 //! macOS's Core Graphics event tap API. It requires Input Monitoring permission.
 
-use crate::collector::types::{KeyboardEvent, KeyboardEventType, MouseEvent, SensorEvent};
+use crate::collector::channel::{send_with_policy, OverflowPolicy, DEFAULT_CHANNEL_CAPACITY};
+use crate::collector::gesture::{add_gesture_tap, Gesture};
+use crate::collector::supervisor::{supervise, DEFAULT_MAX_RESTARTS, RESTART_DELAY};
+use crate::collector::types::{
+    KeyboardEvent, KeyboardEventType, MouseEvent, ScrollMagnitudeBuckets, SensorEvent,
+};
+use chrono::{DateTime, Duration, Utc};
 use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
 use core_graphics::event::{
-    CGEvent, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
-    CallbackResult,
+    CGEvent, CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+    CGEventType, CallbackResult,
 };
 use crossbeam_channel::{bounded, Receiver, Sender};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 
 /// Configuration for which event sources to capture.
@@ -19,6 +25,16 @@ use std::thread::{self, JoinHandle};
 pub struct CollectorConfig {
     pub capture_keyboard: bool,
     pub capture_mouse: bool,
+    /// Capture touchscreen and stylus input (not supported on macOS yet)
+    pub capture_touch: bool,
+    /// Number of events buffered before the overflow policy applies
+    pub channel_capacity: usize,
+    /// Which events to drop when the buffer is full
+    pub overflow_policy: OverflowPolicy,
+    /// How many times a panicked capture thread is restarted
+    pub max_restarts: u32,
+    /// Boundaries of the scroll magnitude buckets
+    pub scroll_magnitude_buckets: ScrollMagnitudeBuckets,
 }
 
 impl Default for CollectorConfig {
@@ -26,6 +42,11 @@ impl Default for CollectorConfig {
         Self {
             capture_keyboard: true,
             capture_mouse: true,
+            capture_touch: false,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            scroll_magnitude_buckets: ScrollMagnitudeBuckets::default(),
         }
     }
 }
@@ -33,9 +54,13 @@ impl Default for CollectorConfig {
 /// The macOS event collector using CGEvent tap.
 pub struct MacOSCollector {
     config: CollectorConfig,
-    sender: Sender<SensorEvent>,
-    receiver: Receiver<SensorEvent>,
+    sender: Sender<Box<SensorEvent>>,
+    receiver: Receiver<Box<SensorEvent>>,
     running: Arc<AtomicBool>,
+    /// Events dropped because the channel was full, not yet taken
+    dropped: Arc<AtomicU64>,
+    /// Times the capture thread was restarted after a panic, not yet taken
+    restarts: Arc<Mutex<Vec<DateTime<Utc>>>>,
     thread_handle: Option<JoinHandle<()>>,
 }
 
@@ -43,19 +68,24 @@ impl MacOSCollector {
     /// Create a new macOS collector with the given configuration.
     pub fn new(config: CollectorConfig) -> Self {
         // Use a bounded channel to prevent unbounded memory growth
-        let (sender, receiver) = bounded(10_000);
+        let (sender, receiver) = bounded(config.channel_capacity.max(1));
 
         Self {
             config,
             sender,
             receiver,
             running: Arc::new(AtomicBool::new(false)),
+            dropped: Arc::new(AtomicU64::new(0)),
+            restarts: Arc::new(Mutex::new(Vec::new())),
             thread_handle: None,
         }
     }
 
     /// Start capturing events in a background thread.
     ///
+    /// The capture thread runs under a supervisor that restarts it if it
+    /// panics, up to `max_restarts` times.
+    ///
     /// Returns an error if:
     /// - The collector is already running
     /// - Input Monitoring permission is not granted
@@ -66,15 +96,37 @@ impl MacOSCollector {
 
         self.running.store(true, Ordering::SeqCst);
 
-        let sender = self.sender.clone();
+        let sink = EventSink {
+            sender: self.sender.clone(),
+            receiver: self.receiver.clone(),
+            policy: self.config.overflow_policy,
+            dropped: self.dropped.clone(),
+            scroll_buckets: self.config.scroll_magnitude_buckets,
+        };
         let running = self.running.clone();
         let config = self.config.clone();
+        let restarts = self.restarts.clone();
 
         let handle = thread::spawn(move || {
-            if let Err(e) = run_event_loop(sender, running.clone(), config) {
-                eprintln!("Event loop error: {e:?}");
-            }
-            running.store(false, Ordering::SeqCst);
+            let max_restarts = config.max_restarts;
+            let worker_running = running.clone();
+            supervise(
+                move || {
+                    if let Err(e) =
+                        run_event_loop(sink.clone(), worker_running.clone(), config.clone())
+                    {
+                        eprintln!("Event loop error: {e:?}");
+                    }
+                },
+                running,
+                max_restarts,
+                RESTART_DELAY,
+                |at| {
+                    if let Ok(mut restarts) = restarts.lock() {
+                        restarts.push(at);
+                    }
+                },
+            );
         });
 
         self.thread_handle = Some(handle);
@@ -96,13 +148,26 @@ impl MacOSCollector {
     }
 
     /// Get the receiver for sensor events.
-    pub fn receiver(&self) -> &Receiver<SensorEvent> {
+    pub fn receiver(&self) -> &Receiver<Box<SensorEvent>> {
         &self.receiver
     }
 
     /// Try to receive an event without blocking.
     pub fn try_recv(&self) -> Option<SensorEvent> {
-        self.receiver.try_recv().ok()
+        self.receiver.try_recv().ok().map(|event| *event)
+    }
+
+    /// Get the number of events dropped since the last call.
+    pub fn take_dropped_events(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+
+    /// Get the times the capture thread was restarted since the last call.
+    pub fn take_restarts(&self) -> Vec<DateTime<Utc>> {
+        self.restarts
+            .lock()
+            .map(|mut restarts| std::mem::take(&mut *restarts))
+            .unwrap_or_default()
     }
 }
 
@@ -119,6 +184,8 @@ pub enum CollectorError {
     PermissionDenied,
     TapCreationFailed,
     RunLoopSourceFailed,
+    /// An I/O operation of the collector failed
+    Io(std::io::Error),
 }
 
 impl std::fmt::Display for CollectorError {
@@ -132,11 +199,25 @@ impl std::fmt::Display for CollectorError {
             CollectorError::RunLoopSourceFailed => {
                 write!(f, "Failed to create run loop source")
             }
+            CollectorError::Io(e) => write!(f, "{e}"),
         }
     }
 }
 
-impl std::error::Error for CollectorError {}
+impl std::error::Error for CollectorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CollectorError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CollectorError {
+    fn from(e: std::io::Error) -> Self {
+        CollectorError::Io(e)
+    }
+}
 
 /// Build a list of event types to capture based on configuration.
 fn build_event_types(config: &CollectorConfig) -> Vec<CGEventType> {
@@ -154,6 +235,7 @@ fn build_event_types(config: &CollectorConfig) -> Vec<CGEventType> {
         types.push(CGEventType::LeftMouseUp);
         types.push(CGEventType::RightMouseDown);
         types.push(CGEventType::RightMouseUp);
+        types.push(CGEventType::OtherMouseDown);
         types.push(CGEventType::LeftMouseDragged);
         types.push(CGEventType::RightMouseDragged);
         types.push(CGEventType::ScrollWheel);
@@ -162,23 +244,53 @@ fn build_event_types(config: &CollectorConfig) -> Vec<CGEventType> {
     types
 }
 
+/// Where the event tap callback delivers events.
+#[derive(Clone)]
+struct EventSink {
+    sender: Sender<Box<SensorEvent>>,
+    /// Handle to the same channel, used to evict old events when full
+    receiver: Receiver<Box<SensorEvent>>,
+    policy: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    scroll_buckets: ScrollMagnitudeBuckets,
+}
+
+impl EventSink {
+    /// Send an event without blocking the tap, applying the overflow policy.
+    fn deliver(&self, sensor_event: Box<SensorEvent>) {
+        let dropped = send_with_policy(&self.sender, &self.receiver, sensor_event, self.policy);
+        if dropped > 0 {
+            self.dropped.fetch_add(dropped, Ordering::Relaxed);
+        }
+    }
+}
+
+// The tap callback can't capture variables, so the sink lives in a
+// thread-local set up by the event loop thread
+thread_local! {
+    static EVENT_SINK: std::cell::RefCell<Option<EventSink>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Run `f` with the event loop thread's sink, if it is set up.
+fn with_sink(f: impl FnOnce(&EventSink)) {
+    EVENT_SINK.with(|sink_cell| {
+        if let Some(ref sink) = *sink_cell.borrow() {
+            f(sink);
+        }
+    });
+}
+
 /// Run the Core Graphics event loop.
 fn run_event_loop(
-    sender: Sender<SensorEvent>,
+    sink: EventSink,
     running: Arc<AtomicBool>,
     config: CollectorConfig,
 ) -> Result<(), CollectorError> {
     // Build the list of event types to capture
     let event_types = build_event_types(&config);
 
-    // Store sender in a thread-local for the callback
-    // Note: We need to use a different approach since the callback can't capture variables
-    thread_local! {
-        static EVENT_SENDER: std::cell::RefCell<Option<Sender<SensorEvent>>> = const { std::cell::RefCell::new(None) };
-    }
-
-    EVENT_SENDER.with(|s| {
-        *s.borrow_mut() = Some(sender);
+    EVENT_SINK.with(|s| {
+        *s.borrow_mut() = Some(sink);
     });
 
     // Callback function for CGEvent tap
@@ -187,17 +299,10 @@ fn run_event_loop(
         event_type: CGEventType,
         event: &CGEvent,
     ) -> CallbackResult {
-        thread_local! {
-            static EVENT_SENDER: std::cell::RefCell<Option<Sender<SensorEvent>>> = const { std::cell::RefCell::new(None) };
-        }
-
-        // Try to get the sender and process the event
-        EVENT_SENDER.with(|sender_cell| {
-            if let Some(ref sender) = *sender_cell.borrow() {
-                if let Some(sensor_event) = process_cg_event(event_type, event) {
-                    // Don't block if the channel is full - just drop the event
-                    let _ = sender.try_send(sensor_event);
-                }
+        // Try to get the sink and process the event
+        with_sink(|sink| {
+            if let Some(sensor_event) = process_cg_event(event_type, event, &sink.scroll_buckets) {
+                sink.deliver(sensor_event);
             }
         });
 
@@ -227,6 +332,18 @@ fn run_event_loop(
         run_loop.add_source(&source, kCFRunLoopCommonModes);
     }
 
+    // Trackpad gestures need a tap of their own; without it, scrolling is
+    // still captured, only as wheel scrolls
+    let _gesture_tap = if config.capture_mouse {
+        let gesture_tap = add_gesture_tap(&run_loop, handle_gesture);
+        if gesture_tap.is_none() {
+            eprintln!("Failed to create gesture event tap, trackpad gestures are not captured");
+        }
+        gesture_tap
+    } else {
+        None
+    };
+
     // Enable the tap
     tap.enable();
 
@@ -275,6 +392,18 @@ fn is_navigation_key(keycode: i64) -> bool {
     )
 }
 
+/// Check whether Shift, Control, Option or Command is held during an event.
+///
+/// Only the modifier state is read; which modifier is held is discarded.
+fn has_active_modifier(event: &CGEvent) -> bool {
+    event.get_flags().intersects(
+        CGEventFlags::CGEventFlagShift
+            | CGEventFlags::CGEventFlagControl
+            | CGEventFlags::CGEventFlagAlternate
+            | CGEventFlags::CGEventFlagCommand,
+    )
+}
+
 /// Classify a keyboard event as navigation or typing based on key code.
 ///
 /// Privacy: The key code is used only for classification and is immediately discarded.
@@ -290,34 +419,124 @@ fn classify_keyboard_event(event: &CGEvent) -> KeyboardEventType {
     }
 }
 
+thread_local! {
+    /// Press times of the left and right buttons, used to measure hold duration.
+    static BUTTON_PRESSED_AT: std::cell::Cell<[Option<DateTime<Utc>>; 2]> =
+        const { std::cell::Cell::new([None, None]) };
+}
+
+/// Record a button press and return the click event.
+fn button_press(is_left: bool) -> MouseEvent {
+    let click = MouseEvent::click(is_left);
+    BUTTON_PRESSED_AT.with(|pressed| {
+        let mut times = pressed.get();
+        times[usize::from(!is_left)] = Some(click.timestamp);
+        pressed.set(times);
+    });
+    click
+}
+
+/// Build a release event carrying the hold duration of the matching press.
+fn button_release(is_left: bool) -> MouseEvent {
+    let release = MouseEvent::click_release(is_left);
+    let pressed_at = BUTTON_PRESSED_AT.with(|pressed| {
+        let mut times = pressed.get();
+        let pressed_at = times[usize::from(!is_left)].take();
+        pressed.set(times);
+        pressed_at
+    });
+
+    match pressed_at {
+        Some(pressed_at) => {
+            let hold_ms = (release.timestamp - pressed_at).num_milliseconds() as f64;
+            release.with_hold_duration_ms(hold_ms)
+        }
+        None => release,
+    }
+}
+
+/// How long after the last multi-finger trackpad contact continuous
+/// scrolling is still attributed to the trackpad (covers momentum scrolling).
+const TRACKPAD_SCROLL_WINDOW_MS: i64 = 1500;
+
+thread_local! {
+    /// Time of the last multi-finger trackpad contact seen by the gesture tap.
+    static TRACKPAD_TOUCHED_AT: std::cell::Cell<Option<DateTime<Utc>>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Check whether a scroll event came from a two-finger trackpad swipe.
+///
+/// Trackpads and Magic Mice both scroll continuously, but only the trackpad
+/// reports touches through gesture events, so a continuous scroll counts as a
+/// trackpad swipe only shortly after a multi-finger trackpad contact.
+fn is_trackpad_scroll(
+    is_continuous: bool,
+    touched_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    is_continuous
+        && touched_at.is_some_and(|touched_at| {
+            now - touched_at <= Duration::milliseconds(TRACKPAD_SCROLL_WINDOW_MS)
+        })
+}
+
+/// Turn a trackpad gesture into a sensor event and send it.
+///
+/// Multi-finger contacts only mark the trackpad as in use for the scroll
+/// classification; they are not events of their own.
+fn handle_gesture(gesture: Gesture) {
+    with_sink(|sink| {
+        let mouse_event = match gesture {
+            Gesture::MultiFingerContact => {
+                TRACKPAD_TOUCHED_AT.with(|touched_at| touched_at.set(Some(Utc::now())));
+                return;
+            }
+            Gesture::Pinch { magnification } => MouseEvent::trackpad_pinch(magnification),
+            Gesture::Swipe { delta_x, delta_y } => {
+                MouseEvent::trackpad_swipe_with_buckets(delta_x, delta_y, &sink.scroll_buckets)
+            }
+        };
+        sink.deliver(Box::new(SensorEvent::Mouse(mouse_event)));
+    });
+}
+
 /// Process a CGEvent and convert it to a SensorEvent.
 ///
+/// The event is boxed here, once, so it moves into the channel as a pointer.
+///
 /// Privacy: This function ONLY extracts timing and magnitude information,
 /// never key codes, characters, or absolute coordinates. Key codes are used
 /// internally only to classify events as navigation vs typing, then discarded.
-fn process_cg_event(event_type: CGEventType, event: &CGEvent) -> Option<SensorEvent> {
+fn process_cg_event(
+    event_type: CGEventType,
+    event: &CGEvent,
+    scroll_buckets: &ScrollMagnitudeBuckets,
+) -> Option<Box<SensorEvent>> {
     use core_graphics::event::CGEventType::*;
 
-    match event_type {
+    let sensor_event = match event_type {
         // Keyboard events - capture timing and classification only, NO key codes stored
         KeyDown => {
             let event_class = classify_keyboard_event(event);
-            Some(SensorEvent::Keyboard(KeyboardEvent::with_type(
-                true,
-                event_class,
-            )))
+            Some(SensorEvent::Keyboard(
+                KeyboardEvent::with_type(true, event_class)
+                    .with_modifier_active(has_active_modifier(event)),
+            ))
         }
         KeyUp => {
             let event_class = classify_keyboard_event(event);
-            Some(SensorEvent::Keyboard(KeyboardEvent::with_type(
-                false,
-                event_class,
-            )))
+            Some(SensorEvent::Keyboard(
+                KeyboardEvent::with_type(false, event_class)
+                    .with_modifier_active(has_active_modifier(event)),
+            ))
         }
         FlagsChanged => {
             // Modifier key change - treat as typing key event (not navigation)
             // We can't easily determine down/up for modifiers, so we just record it
-            Some(SensorEvent::Keyboard(KeyboardEvent::new(true)))
+            Some(SensorEvent::Keyboard(KeyboardEvent::modifier_key(
+                has_active_modifier(event),
+            )))
         }
 
         // Mouse movement - capture delta magnitude only, NO absolute position
@@ -328,16 +547,28 @@ fn process_cg_event(event_type: CGEventType, event: &CGEvent) -> Option<SensorEv
             let delta_y =
                 event.get_double_value_field(core_graphics::event::EventField::MOUSE_EVENT_DELTA_Y);
 
-            Some(SensorEvent::Mouse(MouseEvent::movement(delta_x, delta_y)))
+            let is_drag = matches!(event_type, LeftMouseDragged | RightMouseDragged);
+
+            Some(SensorEvent::Mouse(
+                MouseEvent::movement(delta_x, delta_y).with_drag(is_drag),
+            ))
         }
 
-        // Click events - left button
-        LeftMouseDown => Some(SensorEvent::Mouse(MouseEvent::click(true))),
-        LeftMouseUp => None, // We only count the down event as a "click"
+        // Click events - left button (the release carries hold duration only)
+        LeftMouseDown => Some(SensorEvent::Mouse(button_press(true))),
+        LeftMouseUp => Some(SensorEvent::Mouse(button_release(true))),
 
         // Click events - right button
-        RightMouseDown => Some(SensorEvent::Mouse(MouseEvent::click(false))),
-        RightMouseUp => None, // We only count the down event as a "click"
+        RightMouseDown => Some(SensorEvent::Mouse(button_press(false))),
+        RightMouseUp => Some(SensorEvent::Mouse(button_release(false))),
+
+        // Click events - middle button (other buttons are ignored)
+        OtherMouseDown => {
+            let button = event.get_integer_value_field(
+                core_graphics::event::EventField::MOUSE_EVENT_BUTTON_NUMBER,
+            );
+            (button == 2).then(|| SensorEvent::Mouse(MouseEvent::middle_click()))
+        }
 
         // Scroll events
         ScrollWheel => {
@@ -348,20 +579,37 @@ fn process_cg_event(event_type: CGEventType, event: &CGEvent) -> Option<SensorEv
                 core_graphics::event::EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_1,
             );
 
-            Some(SensorEvent::Mouse(MouseEvent::scroll(delta_x, delta_y)))
+            // Trackpads (and Magic Mice) scroll continuously; wheels in steps
+            let is_continuous = event.get_integer_value_field(
+                core_graphics::event::EventField::SCROLL_WHEEL_EVENT_IS_CONTINUOUS,
+            ) != 0;
+            let touched_at = TRACKPAD_TOUCHED_AT.with(|touched_at| touched_at.get());
+            let from_trackpad = is_trackpad_scroll(is_continuous, touched_at, Utc::now());
+
+            Some(SensorEvent::Mouse(if from_trackpad {
+                MouseEvent::trackpad_swipe_with_buckets(delta_x, delta_y, scroll_buckets)
+            } else {
+                MouseEvent::scroll_with_buckets(delta_x, delta_y, scroll_buckets)
+            }))
         }
 
         // Ignore other event types
         _ => None,
-    }
+    };
+    sensor_event.map(Box::new)
 }
 
-/// Check if the application has Input Monitoring permission.
+/// Check if collection is permitted: consent has been recorded and the
+/// application has Input Monitoring permission.
 ///
-/// Note: This doesn't actually check the permission - macOS doesn't provide
-/// a direct API for that. Instead, attempting to create the tap will fail
-/// if permission is not granted.
+/// Note: This doesn't actually check the Input Monitoring permission - macOS
+/// doesn't provide a direct API for that. Instead, attempting to create the
+/// tap will fail if permission is not granted.
 pub fn check_permission() -> bool {
+    if !crate::consent::has_consent() {
+        return false;
+    }
+
     // On macOS 10.15+, we can try to create a passive tap to check permission
     // If it fails, permission is likely not granted
     let result = CGEventTap::new(
@@ -386,6 +634,20 @@ mod tests {
         assert!(config.capture_mouse);
     }
 
+    #[test]
+    fn test_trackpad_scroll_classification() {
+        let now = Utc::now();
+        let touched = Some(now - Duration::milliseconds(200));
+
+        assert!(is_trackpad_scroll(true, touched, now));
+        // Wheel steps are never trackpad swipes
+        assert!(!is_trackpad_scroll(false, touched, now));
+        // Continuous scrolling without trackpad touches (e.g. a Magic Mouse)
+        assert!(!is_trackpad_scroll(true, None, now));
+        let stale = Some(now - Duration::milliseconds(TRACKPAD_SCROLL_WINDOW_MS + 1));
+        assert!(!is_trackpad_scroll(true, stale, now));
+    }
+
     #[test]
     fn test_collector_creation() {
         let collector = MacOSCollector::new(CollectorConfig::default());
@@ -0,0 +1,342 @@
+//! Collector that replays pre-recorded events.
+//!
+//! `MockCollector` has the same interface as the platform collector but needs
+//! no system permissions: it drains a fixed list of events through the event
+//! channel in timestamp order. Events keep their recorded timestamps, so
+//! windows and features computed from them are deterministic. Sessions
+//! exported with raw events can be replayed as fixtures.
+
+use crate::collector::types::SensorEvent;
+use crate::collector::{CollectorConfig, CollectorError};
+use crate::core::hsi::HsiSnapshot;
+use crate::export::{self, ExportError};
+use chrono::{DateTime, Utc};
+use crossbeam_channel::{bounded, Receiver, SendTimeoutError, Sender};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long a blocked send waits before checking whether to stop.
+const SEND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A collector that replays a fixed list of events.
+pub struct MockCollector {
+    config: CollectorConfig,
+    /// Events to replay, in timestamp order
+    events: Arc<Vec<SensorEvent>>,
+    /// Replay speed relative to the recorded timing
+    playback_speed: f64,
+    sender: Sender<Box<SensorEvent>>,
+    receiver: Receiver<Box<SensorEvent>>,
+    running: Arc<AtomicBool>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl MockCollector {
+    /// Create a collector that replays `events` as fast as possible.
+    ///
+    /// Events from sources disabled in `config` are left out.
+    pub fn new(config: CollectorConfig, events: Vec<SensorEvent>) -> Self {
+        let mut events: Vec<SensorEvent> = events
+            .into_iter()
+            .filter(|event| match event {
+                SensorEvent::Keyboard(_) => config.capture_keyboard,
+                SensorEvent::Mouse(_) => config.capture_mouse,
+                SensorEvent::Touch(_) => config.capture_touch,
+            })
+            .collect();
+        events.sort_by_key(SensorEvent::timestamp);
+        let (sender, receiver) = bounded(config.channel_capacity.max(1));
+
+        Self {
+            config,
+            events: Arc::new(events),
+            playback_speed: f64::INFINITY,
+            sender,
+            receiver,
+            running: Arc::new(AtomicBool::new(false)),
+            thread_handle: None,
+        }
+    }
+
+    /// Load a fixture and replay it with the default config.
+    ///
+    /// The fixture is either a session export recorded with
+    /// `include_raw_events_in_export`, or a JSON array or JSON Lines file of
+    /// `SensorEvent`s. Either may be gzip-compressed.
+    pub fn new_from_fixture(path: &Path) -> Result<Self, ExportError> {
+        let content =
+            export::read_to_string(path).map_err(|e| ExportError::IoError(e.to_string()))?;
+        let parsed = if export::is_jsonl(path) {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<SensorEvent>, _>>()
+        } else {
+            serde_json::from_str(&content)
+        };
+
+        let events = match parsed {
+            Ok(events) => events,
+            Err(e) => {
+                let events = session_raw_events(&export::read_snapshots(path)?);
+                if events.is_empty() {
+                    return Err(ExportError::ParseError(e.to_string()));
+                }
+                events
+            }
+        };
+        Ok(Self::new(CollectorConfig::default(), events))
+    }
+
+    /// Set the replay speed: 1.0 replays with the recorded gaps between
+    /// events, 2.0 at twice the speed, and `f64::INFINITY` without pauses.
+    pub fn with_playback_speed(mut self, playback_speed: f64) -> Self {
+        self.playback_speed = playback_speed;
+        self
+    }
+
+    /// Start replaying events in a background thread.
+    ///
+    /// Unlike the platform collectors, the mock never drops events: it waits
+    /// for room in the channel instead.
+    pub fn start(&mut self) -> Result<(), CollectorError> {
+        if self.running.load(Ordering::SeqCst) {
+            return Err(CollectorError::AlreadyRunning);
+        }
+        self.running.store(true, Ordering::SeqCst);
+
+        let events = self.events.clone();
+        let sender = self.sender.clone();
+        let running = self.running.clone();
+        let playback_speed = self.playback_speed;
+
+        let handle = thread::spawn(move || {
+            let mut previous: Option<DateTime<Utc>> = None;
+            for event in events.iter() {
+                if let Some(gap) = playback_gap(previous, event.timestamp(), playback_speed) {
+                    thread::sleep(gap);
+                }
+                previous = Some(event.timestamp());
+
+                let mut event = Box::new(event.clone());
+                loop {
+                    if !running.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    match sender.send_timeout(event, SEND_POLL_INTERVAL) {
+                        Ok(()) => break,
+                        Err(SendTimeoutError::Timeout(rejected)) => event = rejected,
+                        Err(SendTimeoutError::Disconnected(_)) => return,
+                    }
+                }
+            }
+        });
+
+        self.thread_handle = Some(handle);
+        Ok(())
+    }
+
+    /// Stop replaying events.
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// Check if the collector is currently running.
+    ///
+    /// Stays true after the last event is replayed, until [`MockCollector::stop`].
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Get the receiver for sensor events.
+    pub fn receiver(&self) -> &Receiver<Box<SensorEvent>> {
+        &self.receiver
+    }
+
+    /// Try to receive an event without blocking.
+    pub fn try_recv(&self) -> Option<SensorEvent> {
+        self.receiver.try_recv().ok().map(|event| *event)
+    }
+
+    /// Get the number of events dropped since the last call.
+    ///
+    /// The mock collector never drops events, so this is always zero.
+    pub fn take_dropped_events(&self) -> u64 {
+        0
+    }
+
+    /// Get the times the capture thread was restarted since the last call.
+    ///
+    /// The replay thread is not supervised, so this is always empty.
+    pub fn take_restarts(&self) -> Vec<DateTime<Utc>> {
+        Vec::new()
+    }
+
+    /// Get the collector configuration.
+    pub fn config(&self) -> &CollectorConfig {
+        &self.config
+    }
+}
+
+impl Drop for MockCollector {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Collect the raw events embedded in a session's window snapshots.
+fn session_raw_events(snapshots: &[HsiSnapshot]) -> Vec<SensorEvent> {
+    snapshots
+        .iter()
+        .filter_map(HsiSnapshot::raw_events)
+        .flat_map(|window| {
+            let keyboard = window
+                .keyboard_events
+                .into_iter()
+                .map(SensorEvent::Keyboard);
+            let mouse = window.mouse_events.into_iter().map(SensorEvent::Mouse);
+            let touch = window.touch_events.into_iter().map(SensorEvent::Touch);
+            keyboard.chain(mouse).chain(touch)
+        })
+        .collect()
+}
+
+/// How long to wait before replaying an event recorded at `at`.
+fn playback_gap(
+    previous: Option<DateTime<Utc>>,
+    at: DateTime<Utc>,
+    playback_speed: f64,
+) -> Option<Duration> {
+    if !playback_speed.is_finite() || playback_speed <= 0.0 {
+        return None;
+    }
+    let gap = (at - previous?).to_std().ok()?;
+    Some(gap.div_f64(playback_speed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::types::{KeyboardEvent, MouseEvent, TouchEvent};
+
+    fn key_event(at: DateTime<Utc>) -> SensorEvent {
+        let mut event = KeyboardEvent::new(true);
+        event.timestamp = at;
+        SensorEvent::Keyboard(event)
+    }
+
+    #[test]
+    fn test_replays_events_in_timestamp_order() {
+        let start = Utc::now();
+        let mut mouse = MouseEvent::movement(3.0, 4.0);
+        mouse.timestamp = start + chrono::Duration::milliseconds(5);
+        let events = vec![
+            key_event(start + chrono::Duration::milliseconds(10)),
+            SensorEvent::Mouse(mouse),
+            key_event(start),
+        ];
+
+        let mut collector = MockCollector::new(CollectorConfig::default(), events);
+        collector.start().unwrap();
+        let received: Vec<DateTime<Utc>> = (0..3)
+            .map(|_| collector.receiver().recv().unwrap().timestamp())
+            .collect();
+        collector.stop();
+
+        assert_eq!(
+            received,
+            vec![
+                start,
+                start + chrono::Duration::milliseconds(5),
+                start + chrono::Duration::milliseconds(10)
+            ]
+        );
+        assert!(collector.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_disabled_sources_are_skipped() {
+        let config = CollectorConfig {
+            capture_keyboard: false,
+            ..CollectorConfig::default()
+        };
+        let events = vec![
+            key_event(Utc::now()),
+            SensorEvent::Mouse(MouseEvent::movement(1.0, 0.0)),
+            SensorEvent::Touch(TouchEvent::new(2, 1.5)),
+        ];
+
+        let mut collector = MockCollector::new(config, events);
+        collector.start().unwrap();
+        let event = collector.receiver().recv().unwrap();
+        assert!(matches!(*event, SensorEvent::Mouse(_)));
+        collector.stop();
+        assert!(collector.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_playback_gap() {
+        let start = Utc::now();
+        let later = start + chrono::Duration::seconds(2);
+
+        assert_eq!(playback_gap(None, start, 1.0), None);
+        assert_eq!(
+            playback_gap(Some(start), later, 2.0),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(playback_gap(Some(start), later, f64::INFINITY), None);
+    }
+
+    #[test]
+    fn test_new_from_fixture() {
+        let path = std::env::temp_dir().join(format!(
+            "synheart-mock-fixture-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        let events = vec![key_event(Utc::now())];
+        std::fs::write(&path, serde_json::to_string(&events).unwrap()).unwrap();
+
+        let collector = MockCollector::new_from_fixture(&path).unwrap();
+        assert_eq!(collector.events.len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_new_from_session_export() {
+        use crate::core::features::{compute_features, FeatureConfig};
+        use crate::core::hsi::HsiBuilder;
+        use crate::core::windowing::EventWindow;
+
+        let start = Utc::now();
+        let window: EventWindow = (0..3)
+            .map(|i| key_event(start + chrono::Duration::milliseconds(i * 100)))
+            .collect();
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().with_raw_events(true).build(
+            &window,
+            &features,
+            chrono::Duration::seconds(10),
+            None,
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "synheart-mock-session-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        export::write_snapshot_file(&path, &[snapshot]).unwrap();
+
+        let collector = MockCollector::new_from_fixture(&path).unwrap();
+        assert_eq!(collector.events.len(), 3);
+        assert_eq!(collector.events[0].timestamp(), start);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,8 +3,13 @@
 //! This module provides platform-specific implementations for capturing
 //! keyboard and mouse events in a privacy-preserving manner.
 
+pub mod channel;
+pub mod mock;
+pub mod supervisor;
 pub mod types;
 
+#[cfg(target_os = "macos")]
+mod gesture;
 #[cfg(target_os = "macos")]
 pub mod macos;
 
@@ -12,8 +17,12 @@ pub mod macos;
 pub mod noop;
 
 // Re-export commonly used types
+pub use channel::{OverflowPolicy, DEFAULT_CHANNEL_CAPACITY};
+pub use mock::MockCollector;
+pub use supervisor::DEFAULT_MAX_RESTARTS;
 pub use types::{
-    KeyboardEvent, MouseEvent, MouseEventType, ScrollDirection, ScrollMagnitude, SensorEvent,
+    KeyboardEvent, MouseEvent, MouseEventType, Platform, ScrollDirection, ScrollMagnitude,
+    ScrollMagnitudeBuckets, SensorEvent, TouchEvent,
 };
 
 #[cfg(target_os = "macos")]
@@ -3,7 +3,10 @@
 //! This exists so the crate (and binary) can compile on non-Apple targets
 //! without pulling in CoreGraphics/CoreFoundation dependencies.
 
-use crate::collector::types::SensorEvent;
+use crate::collector::channel::{OverflowPolicy, DEFAULT_CHANNEL_CAPACITY};
+use crate::collector::supervisor::DEFAULT_MAX_RESTARTS;
+use crate::collector::types::{ScrollMagnitudeBuckets, SensorEvent};
+use chrono::{DateTime, Utc};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -15,6 +18,16 @@ use std::sync::Arc;
 pub struct CollectorConfig {
     pub capture_keyboard: bool,
     pub capture_mouse: bool,
+    /// Capture touchscreen and stylus input (no collector supports it yet)
+    pub capture_touch: bool,
+    /// Number of events buffered before the overflow policy applies
+    pub channel_capacity: usize,
+    /// Which events to drop when the buffer is full
+    pub overflow_policy: OverflowPolicy,
+    /// How many times a panicked capture thread is restarted
+    pub max_restarts: u32,
+    /// Boundaries of the scroll magnitude buckets
+    pub scroll_magnitude_buckets: ScrollMagnitudeBuckets,
 }
 
 impl Default for CollectorConfig {
@@ -22,6 +35,11 @@ impl Default for CollectorConfig {
         Self {
             capture_keyboard: true,
             capture_mouse: true,
+            capture_touch: false,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            scroll_magnitude_buckets: ScrollMagnitudeBuckets::default(),
         }
     }
 }
@@ -30,30 +48,46 @@ impl Default for CollectorConfig {
 #[derive(Debug)]
 pub enum CollectorError {
     AlreadyRunning,
+    /// An I/O operation of the collector failed
+    Io(std::io::Error),
 }
 
 impl std::fmt::Display for CollectorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CollectorError::AlreadyRunning => write!(f, "Collector is already running"),
+            CollectorError::Io(e) => write!(f, "{e}"),
         }
     }
 }
 
-impl std::error::Error for CollectorError {}
+impl std::error::Error for CollectorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CollectorError::Io(e) => Some(e),
+            CollectorError::AlreadyRunning => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CollectorError {
+    fn from(e: std::io::Error) -> Self {
+        CollectorError::Io(e)
+    }
+}
 
 /// A noop collector that never emits events.
 pub struct NoopCollector {
     _config: CollectorConfig,
-    _sender: Sender<SensorEvent>,
-    receiver: Receiver<SensorEvent>,
+    _sender: Sender<Box<SensorEvent>>,
+    receiver: Receiver<Box<SensorEvent>>,
     running: Arc<AtomicBool>,
 }
 
 impl NoopCollector {
     /// Create a new noop collector.
     pub fn new(config: CollectorConfig) -> Self {
-        let (sender, receiver) = bounded(10_000);
+        let (sender, receiver) = bounded(config.channel_capacity.max(1));
         Self {
             _config: config,
             _sender: sender,
@@ -84,17 +118,57 @@ impl NoopCollector {
     }
 
     /// Get the receiver for sensor events.
-    pub fn receiver(&self) -> &Receiver<SensorEvent> {
+    pub fn receiver(&self) -> &Receiver<Box<SensorEvent>> {
         &self.receiver
     }
 
     /// Try to receive an event without blocking.
     pub fn try_recv(&self) -> Option<SensorEvent> {
-        self.receiver.try_recv().ok()
+        self.receiver.try_recv().ok().map(|event| *event)
+    }
+
+    /// Get the number of events dropped since the last call.
+    ///
+    /// The noop collector never emits events, so this is always zero.
+    pub fn take_dropped_events(&self) -> u64 {
+        0
+    }
+
+    /// Get the times the capture thread was restarted since the last call.
+    ///
+    /// The noop collector has no capture thread, so this is always empty.
+    pub fn take_restarts(&self) -> Vec<DateTime<Utc>> {
+        Vec::new()
     }
 }
 
-/// On non-macOS platforms there is no Input Monitoring permission gate.
+/// Check if collection is permitted.
+///
+/// On non-macOS platforms there is no Input Monitoring permission gate, so
+/// this only checks that consent has been recorded.
 pub fn check_permission() -> bool {
-    true
+    crate::consent::has_consent()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_io_error_conversion() {
+        fn read_device(path: &std::path::Path) -> Result<Vec<u8>, CollectorError> {
+            Ok(std::fs::read(path)?)
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("synheart-no-device-{}", uuid::Uuid::new_v4()));
+        let err = read_device(&path).unwrap_err();
+        let CollectorError::Io(ref io) = err else {
+            panic!("expected an I/O error, got {err:?}");
+        };
+        assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(err.to_string(), io.to_string());
+        assert!(err.source().is_some());
+    }
 }
@@ -0,0 +1,147 @@
+//! Watchdog for the collector's capture thread.
+//!
+//! The capture thread can panic if the platform revokes the event source
+//! mid-session (e.g. the macOS event tap on screen lock). The supervisor
+//! joins the capture thread and restarts it after a short delay, up to a
+//! configured number of times.
+
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default number of times a panicked capture thread is restarted.
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Delay before restarting a panicked capture thread.
+pub const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Run `worker` on a capture thread, restarting it if it panics.
+///
+/// A worker that returns normally, or panics after `running` was cleared by
+/// `stop()`, is not restarted. `on_restart` is called with the time of each
+/// restart. Clears `running` once the worker is no longer being restarted.
+pub fn supervise<F>(
+    worker: F,
+    running: Arc<AtomicBool>,
+    max_restarts: u32,
+    restart_delay: Duration,
+    mut on_restart: impl FnMut(DateTime<Utc>),
+) where
+    F: Fn() + Send + Sync + 'static,
+{
+    let worker = Arc::new(worker);
+    let mut restarts = 0;
+
+    loop {
+        let run = worker.clone();
+        let handle = match std::thread::Builder::new()
+            .name("collector".to_string())
+            .spawn(move || run())
+        {
+            Ok(handle) => handle,
+            Err(e) => {
+                eprintln!("Failed to start collector thread: {e}");
+                break;
+            }
+        };
+
+        if handle.join().is_ok() || !running.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if restarts >= max_restarts {
+            eprintln!("Collector thread panicked; giving up after {restarts} restart(s)");
+            break;
+        }
+        restarts += 1;
+        eprintln!("Collector thread panicked; restarting ({restarts}/{max_restarts})");
+
+        // Wait in short steps so stop() is not held up by the delay
+        let deadline = std::time::Instant::now() + restart_delay;
+        while std::time::Instant::now() < deadline && running.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(50).min(restart_delay));
+        }
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
+        on_restart(Utc::now());
+    }
+
+    running.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    #[test]
+    fn test_restarts_after_panic() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let running = Arc::new(AtomicBool::new(true));
+        let mut restarted_at = Vec::new();
+
+        let counter = attempts.clone();
+        supervise(
+            move || {
+                // Panic on the first two runs, then exit normally
+                if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                    panic!("event tap revoked");
+                }
+            },
+            running.clone(),
+            3,
+            Duration::from_millis(10),
+            |at| restarted_at.push(at),
+        );
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(restarted_at.len(), 2);
+        assert!(!running.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_gives_up_after_max_restarts() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let running = Arc::new(AtomicBool::new(true));
+        let mut restarts = 0;
+
+        let counter = attempts.clone();
+        supervise(
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                panic!("event tap revoked");
+            },
+            running.clone(),
+            2,
+            Duration::from_millis(10),
+            |_| restarts += 1,
+        );
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(restarts, 2);
+        assert!(!running.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_no_restart_when_stopped() {
+        let running = Arc::new(AtomicBool::new(true));
+        let mut restarts = 0;
+
+        let flag = running.clone();
+        supervise(
+            move || {
+                // Panic during shutdown is not restarted
+                flag.store(false, Ordering::SeqCst);
+                panic!("tap torn down");
+            },
+            running.clone(),
+            3,
+            Duration::from_millis(10),
+            |_| restarts += 1,
+        );
+
+        assert_eq!(restarts, 0);
+    }
+}
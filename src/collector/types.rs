@@ -5,6 +5,43 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Operating system that produced an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    MacOS,
+    Windows,
+    Linux,
+    /// Events recorded before the platform was tracked
+    #[default]
+    Unknown,
+}
+
+impl Platform {
+    /// The platform this binary was built for.
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Platform::MacOS
+        } else if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "linux") {
+            Platform::Linux
+        } else {
+            Platform::Unknown
+        }
+    }
+
+    /// Name used in snapshot metadata (e.g. `"macos"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Platform::MacOS => "macos",
+            Platform::Windows => "windows",
+            Platform::Linux => "linux",
+            Platform::Unknown => "unknown",
+        }
+    }
+}
+
 /// Classification of keyboard events for behavioral analysis.
 ///
 /// Privacy guarantee: This classification does NOT capture which specific key was pressed,
@@ -34,6 +71,15 @@ pub struct KeyboardEvent {
     /// Classification of the key event (typing vs navigation)
     #[serde(default)]
     pub event_type: KeyboardEventType,
+    /// Whether a modifier (Shift, Control, Option, Command) was held during the event
+    #[serde(default)]
+    pub modifier_active: bool,
+    /// Whether the event is a modifier key itself being pressed or released
+    #[serde(default)]
+    pub is_modifier_key: bool,
+    /// Platform whose collector captured the event
+    #[serde(default)]
+    pub source_platform: Platform,
 }
 
 impl KeyboardEvent {
@@ -43,6 +89,9 @@ impl KeyboardEvent {
             timestamp: Utc::now(),
             is_key_down,
             event_type: KeyboardEventType::TypingTap,
+            modifier_active: false,
+            is_modifier_key: false,
+            source_platform: Platform::current(),
         }
     }
 
@@ -52,6 +101,9 @@ impl KeyboardEvent {
             timestamp: Utc::now(),
             is_key_down,
             event_type,
+            modifier_active: false,
+            is_modifier_key: false,
+            source_platform: Platform::current(),
         }
     }
 
@@ -61,9 +113,31 @@ impl KeyboardEvent {
             timestamp: Utc::now(),
             is_key_down,
             event_type: KeyboardEventType::NavigationKey,
+            modifier_active: false,
+            is_modifier_key: false,
+            source_platform: Platform::current(),
+        }
+    }
+
+    /// Create a modifier key event (Shift, Control, Option or Command).
+    ///
+    /// Modifier changes are typing events but are not themselves taps made
+    /// with a modifier held. `modifier_active` is whether any modifier is
+    /// held after the change; which modifier changed is not recorded.
+    pub fn modifier_key(modifier_active: bool) -> Self {
+        Self {
+            modifier_active,
+            is_modifier_key: true,
+            ..Self::new(true)
         }
     }
 
+    /// Set whether a modifier was held during the event.
+    pub fn with_modifier_active(mut self, modifier_active: bool) -> Self {
+        self.modifier_active = modifier_active;
+        self
+    }
+
     /// Check if this is a typing tap (not a navigation key).
     pub fn is_typing_tap(&self) -> bool {
         self.event_type == KeyboardEventType::TypingTap
@@ -84,8 +158,18 @@ pub enum MouseEventType {
     LeftClick,
     /// Right button click
     RightClick,
-    /// Scroll event
+    /// Middle button click (link opening, paste on Linux, closing tabs)
+    MiddleClick,
+    /// Left button release (paired with a preceding LeftClick)
+    LeftRelease,
+    /// Right button release (paired with a preceding RightClick)
+    RightRelease,
+    /// Scroll event from a mouse wheel
     Scroll,
+    /// Two-finger trackpad swipe (continuous scrolling)
+    TrackpadSwipe,
+    /// Trackpad pinch (zoom) gesture
+    TrackpadPinch,
 }
 
 /// Scroll direction (privacy-preserving - no exact amounts).
@@ -97,6 +181,18 @@ pub enum ScrollDirection {
     Right,
 }
 
+impl ScrollDirection {
+    /// Get the direction pointing the opposite way along the same axis.
+    pub fn opposite(self) -> Self {
+        match self {
+            ScrollDirection::Up => ScrollDirection::Down,
+            ScrollDirection::Down => ScrollDirection::Up,
+            ScrollDirection::Left => ScrollDirection::Right,
+            ScrollDirection::Right => ScrollDirection::Left,
+        }
+    }
+}
+
 /// A mouse event capturing only timing and magnitude information.
 ///
 /// Privacy guarantee: No absolute coordinates are captured. Only movement
@@ -108,12 +204,18 @@ pub struct MouseEvent {
     /// Type of mouse event
     pub event_type: MouseEventType,
     /// Movement magnitude (distance moved, not direction or absolute position)
-    /// Only set for Move events
+    /// for Move events, or button hold duration in milliseconds for release events
     pub delta_magnitude: Option<f64>,
     /// Scroll direction (only set for Scroll events)
     pub scroll_direction: Option<ScrollDirection>,
     /// Scroll magnitude bucket (small/medium/large)
     pub scroll_magnitude: Option<ScrollMagnitude>,
+    /// Whether a Move event happened while a button was held (a drag)
+    #[serde(default)]
+    pub is_drag: bool,
+    /// Platform whose collector captured the event
+    #[serde(default)]
+    pub source_platform: Platform,
 }
 
 /// Bucketed scroll magnitude to avoid precise tracking.
@@ -124,6 +226,41 @@ pub enum ScrollMagnitude {
     Large,  // > 10 lines
 }
 
+/// Boundaries between scroll magnitude buckets, in lines (or points).
+///
+/// Applications scroll in different step sizes (terminal, browser, document
+/// editor), so the boundaries can be tuned per deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScrollMagnitudeBuckets {
+    /// Scrolls below this are Small
+    pub small_threshold: f64,
+    /// Scrolls above this are Large; anything in between is Medium
+    pub large_threshold: f64,
+}
+
+impl Default for ScrollMagnitudeBuckets {
+    fn default() -> Self {
+        Self {
+            small_threshold: 3.0,
+            large_threshold: 10.0,
+        }
+    }
+}
+
+impl ScrollMagnitudeBuckets {
+    /// Bucket a scroll distance (whole lines; fractions are dropped).
+    pub fn bucket(&self, total: f64) -> ScrollMagnitude {
+        let total = total.trunc();
+        if total < self.small_threshold {
+            ScrollMagnitude::Small
+        } else if total <= self.large_threshold {
+            ScrollMagnitude::Medium
+        } else {
+            ScrollMagnitude::Large
+        }
+    }
+}
+
 impl MouseEvent {
     /// Create a new mouse move event with delta magnitude.
     pub fn movement(delta_x: f64, delta_y: f64) -> Self {
@@ -134,6 +271,8 @@ impl MouseEvent {
             delta_magnitude: Some(magnitude),
             scroll_direction: None,
             scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::current(),
         }
     }
 
@@ -149,11 +288,84 @@ impl MouseEvent {
             delta_magnitude: None,
             scroll_direction: None,
             scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::current(),
+        }
+    }
+
+    /// Create a new middle button click event.
+    pub fn middle_click() -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event_type: MouseEventType::MiddleClick,
+            delta_magnitude: None,
+            scroll_direction: None,
+            scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::current(),
+        }
+    }
+
+    /// Create a new button release event.
+    ///
+    /// The hold duration can be attached with [`MouseEvent::with_hold_duration_ms`]
+    /// when the collector saw the matching press.
+    pub fn click_release(is_left: bool) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event_type: if is_left {
+                MouseEventType::LeftRelease
+            } else {
+                MouseEventType::RightRelease
+            },
+            delta_magnitude: None,
+            scroll_direction: None,
+            scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::current(),
         }
     }
 
-    /// Create a new scroll event.
+    /// Mark a Move event as a drag (movement with a button held).
+    pub fn with_drag(mut self, is_drag: bool) -> Self {
+        self.is_drag = is_drag;
+        self
+    }
+
+    /// Attach a button hold duration (in milliseconds) to a release event.
+    pub fn with_hold_duration_ms(mut self, hold_ms: f64) -> Self {
+        self.delta_magnitude = Some(hold_ms);
+        self
+    }
+
+    /// Check if this is a button press (left or right click).
+    pub fn is_click(&self) -> bool {
+        matches!(
+            self.event_type,
+            MouseEventType::LeftClick | MouseEventType::RightClick
+        )
+    }
+
+    /// Check if this is a button release.
+    pub fn is_release(&self) -> bool {
+        matches!(
+            self.event_type,
+            MouseEventType::LeftRelease | MouseEventType::RightRelease
+        )
+    }
+
+    /// Create a new scroll event, bucketing the magnitude with the default
+    /// thresholds.
     pub fn scroll(delta_x: f64, delta_y: f64) -> Self {
+        Self::scroll_with_buckets(delta_x, delta_y, &ScrollMagnitudeBuckets::default())
+    }
+
+    /// Create a new scroll event, bucketing the magnitude with `buckets`.
+    pub fn scroll_with_buckets(
+        delta_x: f64,
+        delta_y: f64,
+        buckets: &ScrollMagnitudeBuckets,
+    ) -> Self {
         // Determine direction from deltas
         let direction = if delta_y.abs() > delta_x.abs() {
             if delta_y > 0.0 {
@@ -168,14 +380,7 @@ impl MouseEvent {
         };
 
         // Bucket the magnitude
-        let total = (delta_x.abs() + delta_y.abs()) as i32;
-        let magnitude = if total < 3 {
-            ScrollMagnitude::Small
-        } else if total <= 10 {
-            ScrollMagnitude::Medium
-        } else {
-            ScrollMagnitude::Large
-        };
+        let magnitude = buckets.bucket(delta_x.abs() + delta_y.abs());
 
         Self {
             timestamp: Utc::now(),
@@ -183,6 +388,81 @@ impl MouseEvent {
             delta_magnitude: None,
             scroll_direction: Some(direction),
             scroll_magnitude: Some(magnitude),
+            is_drag: false,
+            source_platform: Platform::current(),
+        }
+    }
+
+    /// Create a new trackpad swipe event.
+    ///
+    /// Direction and magnitude are bucketed the same way as wheel scrolls.
+    pub fn trackpad_swipe(delta_x: f64, delta_y: f64) -> Self {
+        Self::trackpad_swipe_with_buckets(delta_x, delta_y, &ScrollMagnitudeBuckets::default())
+    }
+
+    /// Create a new trackpad swipe event, bucketing the magnitude with `buckets`.
+    pub fn trackpad_swipe_with_buckets(
+        delta_x: f64,
+        delta_y: f64,
+        buckets: &ScrollMagnitudeBuckets,
+    ) -> Self {
+        Self {
+            event_type: MouseEventType::TrackpadSwipe,
+            ..Self::scroll_with_buckets(delta_x, delta_y, buckets)
+        }
+    }
+
+    /// Create a new trackpad pinch event.
+    ///
+    /// Only the size of the zoom step is kept, not whether it zoomed in or out.
+    pub fn trackpad_pinch(magnification: f64) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event_type: MouseEventType::TrackpadPinch,
+            delta_magnitude: Some(magnification.abs()),
+            scroll_direction: None,
+            scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::current(),
+        }
+    }
+
+    /// Check if this is a scroll, from a wheel or a trackpad swipe.
+    pub fn is_scroll(&self) -> bool {
+        matches!(
+            self.event_type,
+            MouseEventType::Scroll | MouseEventType::TrackpadSwipe
+        )
+    }
+}
+
+/// A touchscreen or stylus event capturing only contact count and magnitude.
+///
+/// No collector captures touch input yet; the type exists so touch sources
+/// can be added without changing the event format.
+///
+/// Privacy guarantee: No touch positions are captured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TouchEvent {
+    /// Timestamp when the event occurred
+    pub timestamp: DateTime<Utc>,
+    /// Number of simultaneous contacts (fingers or styluses)
+    pub contact_count: u8,
+    /// Movement or pressure magnitude of the contacts
+    pub magnitude: f64,
+    /// Platform whose collector captured the event
+    #[serde(default)]
+    pub source_platform: Platform,
+}
+
+impl TouchEvent {
+    /// Create a new touch event.
+    pub fn new(contact_count: u8, magnitude: f64) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            contact_count,
+            magnitude,
+            source_platform: Platform::current(),
         }
     }
 }
@@ -192,6 +472,7 @@ impl MouseEvent {
 pub enum SensorEvent {
     Keyboard(KeyboardEvent),
     Mouse(MouseEvent),
+    Touch(TouchEvent),
 }
 
 impl SensorEvent {
@@ -199,10 +480,27 @@ impl SensorEvent {
         match self {
             SensorEvent::Keyboard(e) => e.timestamp,
             SensorEvent::Mouse(e) => e.timestamp,
+            SensorEvent::Touch(e) => e.timestamp,
+        }
+    }
+
+    /// Platform whose collector captured the event.
+    pub fn source_platform(&self) -> Platform {
+        match self {
+            SensorEvent::Keyboard(e) => e.source_platform,
+            SensorEvent::Mouse(e) => e.source_platform,
+            SensorEvent::Touch(e) => e.source_platform,
         }
     }
 }
 
+impl From<Box<SensorEvent>> for SensorEvent {
+    /// Unbox an event received from the collector channel.
+    fn from(event: Box<SensorEvent>) -> Self {
+        *event
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,6 +516,22 @@ mod tests {
         let event = MouseEvent::movement(3.0, 4.0);
         assert_eq!(event.event_type, MouseEventType::Move);
         assert!((event.delta_magnitude.unwrap() - 5.0).abs() < 0.001);
+        assert!(!event.is_drag);
+        assert!(MouseEvent::movement(1.0, 0.0).with_drag(true).is_drag);
+    }
+
+    #[test]
+    fn test_click_release_event() {
+        let release = MouseEvent::click_release(true).with_hold_duration_ms(120.0);
+        assert_eq!(release.event_type, MouseEventType::LeftRelease);
+        assert_eq!(release.delta_magnitude, Some(120.0));
+        assert!(release.is_release());
+        assert!(!release.is_click());
+        assert!(MouseEvent::click(false).is_click());
+        assert_eq!(
+            MouseEvent::middle_click().event_type,
+            MouseEventType::MiddleClick
+        );
     }
 
     #[test]
@@ -232,6 +546,61 @@ mod tests {
         assert_eq!(large.scroll_magnitude, Some(ScrollMagnitude::Large));
     }
 
+    #[test]
+    fn test_scroll_with_custom_buckets() {
+        let buckets = ScrollMagnitudeBuckets {
+            small_threshold: 1.0,
+            large_threshold: 4.0,
+        };
+        let medium = MouseEvent::scroll_with_buckets(0.0, 2.0, &buckets);
+        assert_eq!(medium.scroll_magnitude, Some(ScrollMagnitude::Medium));
+
+        let large = MouseEvent::scroll_with_buckets(0.0, 5.0, &buckets);
+        assert_eq!(large.scroll_magnitude, Some(ScrollMagnitude::Large));
+
+        // Fractions of a line are dropped, as with the default buckets
+        let default = ScrollMagnitudeBuckets::default();
+        assert_eq!(default.bucket(10.5), ScrollMagnitude::Medium);
+    }
+
+    #[test]
+    fn test_trackpad_events() {
+        let swipe = MouseEvent::trackpad_swipe(0.0, -15.0);
+        assert_eq!(swipe.event_type, MouseEventType::TrackpadSwipe);
+        assert_eq!(swipe.scroll_direction, Some(ScrollDirection::Up));
+        assert_eq!(swipe.scroll_magnitude, Some(ScrollMagnitude::Large));
+        assert!(swipe.is_scroll());
+
+        let pinch = MouseEvent::trackpad_pinch(-0.25);
+        assert_eq!(pinch.delta_magnitude, Some(0.25));
+        assert!(!pinch.is_scroll());
+    }
+
+    #[test]
+    fn test_event_platform() {
+        let event = SensorEvent::Keyboard(KeyboardEvent::new(true));
+        assert_eq!(event.source_platform(), Platform::current());
+        assert_eq!(
+            serde_json::to_string(&Platform::MacOS).unwrap(),
+            format!("\"{}\"", Platform::MacOS.as_str())
+        );
+
+        // Events recorded before the field existed have an unknown platform
+        let json = r#"{"timestamp":"2024-01-01T00:00:00Z","is_key_down":true}"#;
+        let event: KeyboardEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.source_platform, Platform::Unknown);
+    }
+
+    #[test]
+    fn test_scroll_direction_opposite() {
+        assert_eq!(ScrollDirection::Up.opposite(), ScrollDirection::Down);
+        assert_eq!(ScrollDirection::Left.opposite(), ScrollDirection::Right);
+        assert_eq!(
+            ScrollDirection::Down.opposite().opposite(),
+            ScrollDirection::Down
+        );
+    }
+
     #[test]
     fn test_keyboard_event_type_default() {
         let event = KeyboardEvent::new(true);
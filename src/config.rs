@@ -1,7 +1,11 @@
 //! Configuration for the Synheart Sensor Agent.
 
+use crate::core::features::FeatureConfig;
+use crate::core::privacy::DifferentialPrivacyConfig;
+use crate::core::windowing::AdaptiveWindowConfig;
+use crate::store::StorageBackend;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Main configuration for the sensor agent.
@@ -25,6 +29,339 @@ pub struct Config {
 
     /// Gap threshold for session boundaries (in seconds)
     pub session_gap_threshold_secs: u64,
+
+    /// Thresholds used when computing window features
+    #[serde(default)]
+    pub feature_config: FeatureConfig,
+
+    /// Windows with fewer events than this are discarded (None keeps all non-empty windows)
+    #[serde(default)]
+    pub min_events_per_window: Option<usize>,
+
+    /// Adaptive window bounds (None uses fixed `window_duration` windows)
+    #[serde(default)]
+    pub adaptive_window: Option<AdaptiveWindowConfig>,
+
+    /// Whether windows start on wall-clock multiples of `window_duration`
+    #[serde(default)]
+    pub clock_aligned_windows: bool,
+
+    /// HMAC-SHA256 key file for signing snapshots (None leaves snapshots unsigned)
+    #[serde(default)]
+    pub signing_key_path: Option<PathBuf>,
+
+    /// Laplace noise added to axis scores (None exports exact scores)
+    #[serde(default)]
+    pub differential_privacy: Option<DifferentialPrivacyConfig>,
+
+    /// Whether exported snapshot files are gzip-compressed (`.json.gz`)
+    #[serde(default)]
+    pub compress_exports: bool,
+
+    /// Whether snapshots are appended to a `.jsonl` session file as each window completes
+    #[serde(default)]
+    pub streaming_export: bool,
+
+    /// Whether snapshots carry their window's raw timing events in
+    /// `meta.raw_events`, so `synheart-sensor replay` can recompute features
+    #[serde(default)]
+    pub include_raw_events_in_export: bool,
+
+    /// File name (without extension) for session and export files; supports
+    /// `{timestamp}`, `{date}`, `{time}`, `{instance_id}`, `{session_id}`
+    /// and `{snapshot_count}`
+    #[serde(default = "default_export_filename_pattern")]
+    pub export_filename_pattern: String,
+
+    /// Where session snapshots are persisted (`json` files or a `sqlite` database)
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+
+    /// Maximum number of unsent snapshots kept in the gateway queue (oldest dropped first)
+    #[serde(default = "default_max_queued_snapshots")]
+    pub max_queued_snapshots: usize,
+
+    /// Session files older than this many days are deleted (None keeps them forever)
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+
+    /// Whether each run keeps its transparency log and flux baselines in
+    /// `data_path/sessions/<session_id>/` instead of directly in `data_path`
+    #[serde(default)]
+    pub session_scoped_data: bool,
+
+    /// synheart-flux baseline tracking settings
+    #[serde(default)]
+    pub flux: FluxConfig,
+
+    /// Gateway host and TLS settings
+    #[serde(default)]
+    pub gateway: GatewayConnectionConfig,
+}
+
+/// Move settings saved by older versions to their current place.
+fn migrate_legacy_fields(value: &mut serde_json::Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+
+    // `pause_threshold_ms` moved into `feature_config`
+    if let Some(pause_threshold_ms) = object.remove("pause_threshold_ms") {
+        if let Some(feature_config) = object
+            .entry("feature_config")
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+        {
+            feature_config
+                .entry("pause_threshold_ms")
+                .or_insert(pause_threshold_ms);
+        }
+    }
+}
+
+/// Prefix of environment variables that override config fields.
+pub const ENV_PREFIX: &str = "SYNHEART_";
+
+/// Prefixed variables read by the CLI rather than `Config`.
+const CLI_ENV_VARS: &[&str] = &[
+    "SYNHEART_GATEWAY_PORT",
+    "SYNHEART_GATEWAY_TOKEN",
+    "SYNHEART_GATEWAY_CONFIG_DIR",
+];
+
+/// How an environment variable's value is turned into a config value.
+#[derive(Clone, Copy)]
+enum EnvValue {
+    /// A JSON value such as `10`, `true` or `{"min_secs": 5, ...}`
+    Json,
+    /// Taken verbatim as a string
+    Text,
+    /// A comma-separated source list as accepted by `--sources`
+    Sources,
+}
+
+/// Environment variables that override config fields, with the field's path.
+///
+/// An empty value sets optional fields to `null`.
+const ENV_OVERRIDES: &[(&str, &[&str], EnvValue)] = &[
+    (
+        "SYNHEART_WINDOW_DURATION_SECS",
+        &["window_duration"],
+        EnvValue::Json,
+    ),
+    ("SYNHEART_SOURCES", &["sources"], EnvValue::Sources),
+    ("SYNHEART_EXPORT_PATH", &["export_path"], EnvValue::Text),
+    ("SYNHEART_DATA_PATH", &["data_path"], EnvValue::Text),
+    ("SYNHEART_PAUSED", &["paused"], EnvValue::Json),
+    (
+        "SYNHEART_SESSION_GAP_THRESHOLD_SECS",
+        &["session_gap_threshold_secs"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_PAUSE_THRESHOLD_MS",
+        &["feature_config", "pause_threshold_ms"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_BURST_INTERVAL_MS",
+        &["feature_config", "burst_interval_ms"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_MICRO_ADJUSTMENT_THRESHOLD",
+        &["feature_config", "micro_adjustment_threshold"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_ACCELERATION_SPIKE_THRESHOLD",
+        &["feature_config", "acceleration_spike_threshold"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_PARALLEL_FEATURES",
+        &["feature_config", "parallel"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_MIN_EVENTS_PER_WINDOW",
+        &["min_events_per_window"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_ADAPTIVE_WINDOW",
+        &["adaptive_window"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_CLOCK_ALIGNED_WINDOWS",
+        &["clock_aligned_windows"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_SIGNING_KEY_PATH",
+        &["signing_key_path"],
+        EnvValue::Text,
+    ),
+    (
+        "SYNHEART_DIFFERENTIAL_PRIVACY",
+        &["differential_privacy"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_COMPRESS_EXPORTS",
+        &["compress_exports"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_STREAMING_EXPORT",
+        &["streaming_export"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_INCLUDE_RAW_EVENTS_IN_EXPORT",
+        &["include_raw_events_in_export"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_EXPORT_FILENAME_PATTERN",
+        &["export_filename_pattern"],
+        EnvValue::Text,
+    ),
+    (
+        "SYNHEART_STORAGE_BACKEND",
+        &["storage_backend"],
+        EnvValue::Text,
+    ),
+    (
+        "SYNHEART_MAX_QUEUED_SNAPSHOTS",
+        &["max_queued_snapshots"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_RETENTION_DAYS",
+        &["retention_days"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_SESSION_SCOPED_DATA",
+        &["session_scoped_data"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_FLUX_BASELINE_WINDOW",
+        &["flux", "baseline_window"],
+        EnvValue::Json,
+    ),
+    (
+        "SYNHEART_GATEWAY_HOST",
+        &["gateway", "host"],
+        EnvValue::Text,
+    ),
+    ("SYNHEART_GATEWAY_TLS", &["gateway", "tls"], EnvValue::Json),
+    (
+        "SYNHEART_GATEWAY_CA_CERT",
+        &["gateway", "ca_cert_path"],
+        EnvValue::Text,
+    ),
+];
+
+impl EnvValue {
+    fn parse(self, name: &str, raw: &str) -> Result<serde_json::Value, ConfigError> {
+        if raw.trim().is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+        match self {
+            EnvValue::Json => serde_json::from_str(raw)
+                .map_err(|e| ConfigError::InvalidEnv(format!("{name}: {e}"))),
+            EnvValue::Text => Ok(serde_json::Value::String(raw.to_string())),
+            EnvValue::Sources => serde_json::to_value(SourceConfig::from_csv(raw))
+                .map_err(|e| ConfigError::SerializeError(Box::new(e))),
+        }
+    }
+}
+
+/// Get the process environment, skipping variables that are not valid UTF-8.
+fn process_env() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// Print a warning for each unrecognized `SYNHEART_` variable.
+fn warn_unrecognized(names: &[String]) {
+    for name in names {
+        eprintln!("Warning: Ignoring unrecognized environment variable {name}");
+    }
+}
+
+/// Get the directory holding the config file and consent token.
+fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("synheart-sensor-agent")
+}
+
+/// Collect the dot-notation paths of all non-object values.
+fn collect_setting_keys(value: &serde_json::Value, prefix: &str, keys: &mut Vec<String>) {
+    match value.as_object() {
+        Some(object) => {
+            for (name, child) in object {
+                let key = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{prefix}.{name}")
+                };
+                collect_setting_keys(child, &key, keys);
+            }
+        }
+        None => keys.push(prefix.to_string()),
+    }
+}
+
+/// Interpret a command-line value for a setting whose current value is `existing`.
+///
+/// Returns the candidate JSON values to try, in order.
+fn setting_candidates(
+    existing: &serde_json::Value,
+    raw: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    use serde_json::Value;
+
+    let trimmed = raw.trim();
+    if trimmed.is_empty() || trimmed == "null" {
+        return Ok(vec![Value::Null]);
+    }
+    match existing {
+        Value::Bool(_) => match trimmed.to_lowercase().as_str() {
+            "true" | "1" => Ok(vec![Value::Bool(true)]),
+            "false" | "0" => Ok(vec![Value::Bool(false)]),
+            _ => Err(format!("expected true, false, 1 or 0, got {raw:?}")),
+        },
+        Value::Number(_) => match serde_json::from_str(trimmed) {
+            Ok(Value::Number(number)) => Ok(vec![Value::Number(number)]),
+            _ => Err(format!("expected a number, got {raw:?}")),
+        },
+        Value::String(_) => Ok(vec![Value::String(raw.to_string())]),
+        // Unset optional settings may be numbers, paths or objects
+        _ => Ok(serde_json::from_str(trimmed)
+            .into_iter()
+            .chain([Value::String(raw.to_string())])
+            .collect()),
+    }
+}
+
+/// Default maximum number of unsent snapshots kept in the gateway queue.
+pub const DEFAULT_MAX_QUEUED_SNAPSHOTS: usize = 10_000;
+
+fn default_max_queued_snapshots() -> usize {
+    DEFAULT_MAX_QUEUED_SNAPSHOTS
+}
+
+/// Default file name pattern for session and export files.
+pub const DEFAULT_EXPORT_FILENAME_PATTERN: &str = "session_{timestamp}";
+
+fn default_export_filename_pattern() -> String {
+    DEFAULT_EXPORT_FILENAME_PATTERN.to_string()
 }
 
 impl Default for Config {
@@ -40,66 +377,340 @@ impl Default for Config {
             data_path: data_dir,
             paused: false,
             session_gap_threshold_secs: 300, // 5 minutes
+            feature_config: FeatureConfig::default(),
+            min_events_per_window: None,
+            adaptive_window: None,
+            clock_aligned_windows: false,
+            signing_key_path: None,
+            differential_privacy: None,
+            compress_exports: false,
+            streaming_export: false,
+            include_raw_events_in_export: false,
+            export_filename_pattern: default_export_filename_pattern(),
+            storage_backend: StorageBackend::Json,
+            max_queued_snapshots: DEFAULT_MAX_QUEUED_SNAPSHOTS,
+            retention_days: None,
+            session_scoped_data: false,
+            flux: FluxConfig::default(),
+            gateway: GatewayConnectionConfig::default(),
         }
     }
 }
 
 impl Config {
-    /// Load configuration from the default location.
+    /// Load configuration from the default location, then apply
+    /// `SYNHEART_*` environment variable overrides.
     pub fn load() -> Result<Self, ConfigError> {
+        let mut config = Self::load_file()?;
+        warn_unrecognized(&config.apply_env_overrides(process_env())?);
+        Ok(config)
+    }
+
+    /// Build a configuration from defaults and `SYNHEART_*` environment
+    /// variables only, ignoring any config file.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        warn_unrecognized(&config.apply_env_overrides(process_env())?);
+        Ok(config)
+    }
+
+    /// Override fields from `SYNHEART_*` variables (e.g. `SYNHEART_WINDOW_DURATION_SECS=30`).
+    ///
+    /// Variables without the prefix are skipped. Returns the names of
+    /// prefixed variables that match no field.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Result<Vec<String>, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut unrecognized = Vec::new();
+        for (name, raw) in vars {
+            if !name.starts_with(ENV_PREFIX) || CLI_ENV_VARS.contains(&name.as_str()) {
+                continue;
+            }
+            let Some(&(_, path, kind)) = ENV_OVERRIDES.iter().find(|(var, _, _)| *var == name)
+            else {
+                unrecognized.push(name);
+                continue;
+            };
+
+            let mut value = serde_json::to_value(&*self)
+                .map_err(|e| ConfigError::SerializeError(Box::new(e)))?;
+            let mut target = &mut value;
+            for key in path {
+                target = &mut target[*key];
+            }
+            *target = kind.parse(&name, &raw)?;
+            *self = serde_json::from_value(value)
+                .map_err(|e| ConfigError::InvalidEnv(format!("{name}: {e}")))?;
+        }
+        unrecognized.sort();
+        Ok(unrecognized)
+    }
+
+    /// Get the dot-notation keys accepted by [`Config::set_value`], sorted.
+    pub fn setting_keys() -> Vec<String> {
+        let mut keys = Vec::new();
+        if let Ok(value) = serde_json::to_value(Self::default()) {
+            collect_setting_keys(&value, "", &mut keys);
+        }
+        keys
+    }
+
+    /// Set a single setting from its dot-notation key (e.g. `sources.keyboard`).
+    ///
+    /// The value is read according to the setting's type: booleans accept
+    /// `true`/`false`/`1`/`0`, and `null` or an empty value clears optional
+    /// settings.
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let valid_keys = Self::setting_keys();
+        if !valid_keys.iter().any(|valid| valid == key) {
+            return Err(ConfigError::UnknownKey {
+                key: key.to_string(),
+                valid_keys,
+            });
+        }
+        let invalid = |message: String| ConfigError::InvalidValue {
+            key: key.to_string(),
+            message,
+        };
+
+        let current =
+            serde_json::to_value(&*self).map_err(|e| ConfigError::SerializeError(Box::new(e)))?;
+        let pointer = format!("/{}", key.replace('.', "/"));
+        let existing = current.pointer(&pointer).cloned().unwrap_or_default();
+
+        let mut message = String::new();
+        for candidate in setting_candidates(&existing, value).map_err(invalid)? {
+            let mut patched = current.clone();
+            if let Some(target) = patched.pointer_mut(&pointer) {
+                *target = candidate;
+            }
+            match serde_json::from_value(patched) {
+                Ok(config) => {
+                    *self = config;
+                    return Ok(());
+                }
+                Err(e) => message = e.to_string(),
+            }
+        }
+        Err(invalid(message))
+    }
+
+    /// Load configuration from the default location without environment overrides.
+    ///
+    /// Use this when the config will be saved back, so overrides are not persisted.
+    pub fn load_file() -> Result<Self, ConfigError> {
         let config_path = Self::config_path();
 
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)
-                .map_err(|e| ConfigError::IoError(e.to_string()))?;
-            let config: Config = serde_json::from_str(&content)
-                .map_err(|e| ConfigError::ParseError(e.to_string()))?;
-            Ok(config)
+            let content = std::fs::read_to_string(&config_path)?;
+            Self::parse(&content, ConfigFormat::from_path(&config_path))
         } else {
             Ok(Self::default())
         }
     }
 
+    /// Parse a configuration file's contents.
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let parse_error =
+            |e: Box<dyn std::error::Error + Send + Sync>| ConfigError::ParseError(format, e);
+        let mut value: serde_json::Value = match format {
+            ConfigFormat::Json => {
+                serde_json::from_str(content).map_err(|e| parse_error(Box::new(e)))?
+            }
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| parse_error(Box::new(e)))?,
+        };
+        migrate_legacy_fields(&mut value);
+        serde_json::from_value(value).map_err(|e| parse_error(Box::new(e)))
+    }
+
+    /// Encode this configuration as a configuration file's contents.
+    ///
+    /// TOML has no null, so unset optional settings are omitted there.
+    pub fn encode(&self, format: ConfigFormat) -> Result<String, ConfigError> {
+        match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| ConfigError::SerializeError(Box::new(e))),
+            ConfigFormat::Toml => {
+                toml::to_string_pretty(self).map_err(|e| ConfigError::SerializeError(Box::new(e)))
+            }
+        }
+    }
+
     /// Save configuration to the default location.
     pub fn save(&self) -> Result<(), ConfigError> {
         let config_path = Self::config_path();
 
         // Ensure parent directory exists
         if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| ConfigError::IoError(e.to_string()))?;
+            std::fs::create_dir_all(parent)?;
         }
 
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| ConfigError::SerializeError(e.to_string()))?;
+        let content = self.encode(ConfigFormat::from_path(&config_path))?;
 
-        std::fs::write(&config_path, content).map_err(|e| ConfigError::IoError(e.to_string()))?;
+        std::fs::write(&config_path, content)?;
 
         Ok(())
     }
 
     /// Get the path to the configuration file.
+    ///
+    /// This is `config.toml` if it exists, otherwise `config.json`.
     pub fn config_path() -> PathBuf {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("synheart-sensor-agent")
-            .join("config.json")
+        let dir = config_dir();
+        let toml_path = dir.join("config.toml");
+        if toml_path.exists() {
+            toml_path
+        } else {
+            dir.join("config.json")
+        }
+    }
+
+    /// Get the path to the stored consent token.
+    pub fn consent_token_path() -> PathBuf {
+        config_dir().join("consent.token")
+    }
+
+    /// Get the path to the SQLite snapshot database.
+    pub fn snapshot_db_path(&self) -> PathBuf {
+        self.data_path.join("snapshots.db")
+    }
+
+    /// Get the path to the gateway's persistent queue of unsent snapshots.
+    pub fn pending_queue_path(&self) -> PathBuf {
+        self.data_path.join("pending_snapshots.jsonl")
+    }
+
+    /// Get the path to the stored device UUID reported to the gateway.
+    pub fn device_id_path(&self) -> PathBuf {
+        self.data_path.join("device_id")
+    }
+
+    /// Get the path to the control socket of a running agent.
+    pub fn ipc_socket_path(&self) -> PathBuf {
+        self.data_path.join("agent.sock")
+    }
+
+    /// Get the directory holding per-session data directories.
+    pub fn sessions_path(&self) -> PathBuf {
+        self.data_path.join("sessions")
+    }
+
+    /// Get the directory for a session's transparency log and flux baselines.
+    ///
+    /// This is `data_path` itself unless `session_scoped_data` is enabled.
+    pub fn session_data_path(&self, session_id: &str) -> PathBuf {
+        if self.session_scoped_data {
+            self.sessions_path().join(session_id)
+        } else {
+            self.data_path.clone()
+        }
+    }
+
+    /// Get the path of a session's transparency log.
+    pub fn transparency_log_path(&self, session_id: &str) -> PathBuf {
+        self.session_data_path(session_id).join("transparency.json")
+    }
+
+    /// List the transparency logs that exist, for cumulative statistics.
+    ///
+    /// With `session_scoped_data` each session keeps its own log, so this
+    /// lists every session's log after the shared one left by runs made
+    /// before the setting was enabled.
+    pub fn transparency_log_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.data_path.join("transparency.json")];
+        if self.session_scoped_data {
+            paths.extend(
+                self.session_directories()
+                    .into_iter()
+                    .map(|dir| dir.join("transparency.json")),
+            );
+        }
+        paths.retain(|path| path.is_file());
+        paths
     }
 
     /// Ensure all required directories exist.
     pub fn ensure_directories(&self) -> Result<(), ConfigError> {
-        std::fs::create_dir_all(&self.export_path)
-            .map_err(|e| ConfigError::IoError(e.to_string()))?;
-        std::fs::create_dir_all(&self.data_path)
-            .map_err(|e| ConfigError::IoError(e.to_string()))?;
+        std::fs::create_dir_all(&self.export_path)?;
+        std::fs::create_dir_all(&self.data_path)?;
+        if self.session_scoped_data {
+            std::fs::create_dir_all(self.sessions_path())?;
+        }
         Ok(())
     }
+
+    /// Ensure all required directories exist, including the session's data directory.
+    ///
+    /// Returns the session's data directory.
+    pub fn ensure_session_directories(&self, session_id: &str) -> Result<PathBuf, ConfigError> {
+        self.ensure_directories()?;
+        let path = self.session_data_path(session_id);
+        std::fs::create_dir_all(&path)?;
+        Ok(path)
+    }
+
+    /// List the per-session data directories, oldest session first.
+    pub fn session_directories(&self) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(self.sessions_path()) else {
+            return Vec::new();
+        };
+        let mut directories: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        // Session IDs embed their start time, so names sort chronologically
+        directories.sort();
+        directories
+    }
+}
+
+/// Default number of sessions in the flux rolling baseline.
+pub const DEFAULT_FLUX_BASELINE_WINDOW: usize = 20;
+
+/// Settings for synheart-flux baseline tracking.
+///
+/// Missing fields take their defaults when deserialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FluxConfig {
+    /// Number of sessions in the rolling baseline
+    pub baseline_window: usize,
+}
+
+impl Default for FluxConfig {
+    fn default() -> Self {
+        Self {
+            baseline_window: DEFAULT_FLUX_BASELINE_WINDOW,
+        }
+    }
+}
+
+/// Gateway connection settings.
+///
+/// The port and token come from the command line or the gateway runtime
+/// directory; these settings override the host and TLS found there. Missing
+/// fields take their defaults when deserialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayConnectionConfig {
+    /// Gateway host (None keeps the runtime directory's, or 127.0.0.1)
+    pub host: Option<String>,
+    /// Whether to connect over HTTPS
+    pub tls: bool,
+    /// PEM CA bundle to trust, e.g. for a self-signed gateway (implies `tls`)
+    pub ca_cert_path: Option<PathBuf>,
 }
 
 /// Configuration for which input sources to capture.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SourceConfig {
     pub keyboard: bool,
     pub mouse: bool,
+    /// Touchscreen and stylus input (no collector captures it yet)
+    #[serde(default)]
+    pub touch: bool,
 }
 
 impl Default for SourceConfig {
@@ -107,46 +718,117 @@ impl Default for SourceConfig {
         Self {
             keyboard: true,
             mouse: true,
+            touch: false,
         }
     }
 }
 
 impl SourceConfig {
     /// Parse source configuration from a comma-separated string.
+    ///
+    /// `all` enables keyboard and mouse; touch must be listed explicitly.
     pub fn from_csv(s: &str) -> Self {
         let sources: Vec<String> = s.split(',').map(|s| s.trim().to_lowercase()).collect();
 
         Self {
             keyboard: sources.iter().any(|s| s == "keyboard" || s == "all"),
             mouse: sources.iter().any(|s| s == "mouse" || s == "all"),
+            touch: sources.iter().any(|s| s == "touch"),
         }
     }
 
-    /// Check if at least one source is enabled.
+    /// Check if at least one captured source is enabled.
+    ///
+    /// Touch does not count, as no collector captures it yet.
     pub fn any_enabled(&self) -> bool {
         self.keyboard || self.mouse
     }
 }
 
+/// File format of a configuration file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a file extension (`.toml`, anything else is JSON).
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigFormat::Json => write!(f, "JSON"),
+            ConfigFormat::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
 /// Configuration errors.
 #[derive(Debug)]
 pub enum ConfigError {
-    IoError(String),
-    ParseError(String),
-    SerializeError(String),
+    IoError(std::io::Error),
+    /// The config file is not valid in its format
+    ParseError(ConfigFormat, Box<dyn std::error::Error + Send + Sync>),
+    SerializeError(Box<dyn std::error::Error + Send + Sync>),
+    /// A `SYNHEART_` environment variable has an invalid value
+    InvalidEnv(String),
+    /// `config set` was given a key that names no setting
+    UnknownKey {
+        key: String,
+        valid_keys: Vec<String>,
+    },
+    /// `config set` was given a value of the wrong type
+    InvalidValue {
+        key: String,
+        message: String,
+    },
 }
 
 impl std::fmt::Display for ConfigError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ConfigError::IoError(e) => write!(f, "IO error: {e}"),
-            ConfigError::ParseError(e) => write!(f, "Parse error: {e}"),
+            ConfigError::ParseError(format, e) => write!(f, "{format} parse error: {e}"),
             ConfigError::SerializeError(e) => write!(f, "Serialize error: {e}"),
+            ConfigError::InvalidEnv(e) => write!(f, "Invalid environment override: {e}"),
+            ConfigError::UnknownKey { key, valid_keys } => write!(
+                f,
+                "Unknown setting {key:?}; valid keys are: {}",
+                valid_keys.join(", ")
+            ),
+            ConfigError::InvalidValue { key, message } => {
+                write!(f, "Invalid value for {key}: {message}")
+            }
         }
     }
 }
 
-impl std::error::Error for ConfigError {}
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::IoError(e) => Some(e),
+            ConfigError::ParseError(_, e) | ConfigError::SerializeError(e) => Some(e.as_ref()),
+            ConfigError::InvalidEnv(_)
+            | ConfigError::UnknownKey { .. }
+            | ConfigError::InvalidValue { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::IoError(e)
+    }
+}
 
 /// Serde support for Duration.
 mod duration_serde {
@@ -172,6 +854,7 @@ mod duration_serde {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::features::DEFAULT_PAUSE_THRESHOLD_MS;
 
     #[test]
     fn test_source_config_parsing() {
@@ -186,6 +869,12 @@ mod tests {
         let config = SourceConfig::from_csv("all");
         assert!(config.keyboard);
         assert!(config.mouse);
+        assert!(!config.touch);
+
+        let config = SourceConfig::from_csv("mouse, touch");
+        assert!(config.touch);
+        assert!(config.any_enabled());
+        assert!(!SourceConfig::from_csv("touch").any_enabled());
     }
 
     #[test]
@@ -195,5 +884,266 @@ mod tests {
         assert!(config.sources.keyboard);
         assert!(config.sources.mouse);
         assert!(!config.paused);
+        assert_eq!(config.feature_config, FeatureConfig::default());
+        assert_eq!(config.adaptive_window, None);
+        assert!(!config.clock_aligned_windows);
+        assert_eq!(config.signing_key_path, None);
+        assert_eq!(config.differential_privacy, None);
+        assert!(!config.compress_exports);
+        assert!(!config.streaming_export);
+        assert_eq!(config.export_filename_pattern, "session_{timestamp}");
+        assert_eq!(config.storage_backend, StorageBackend::Json);
+        assert_eq!(config.retention_days, None);
+        assert!(!config.session_scoped_data);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = Config {
+            retention_days: Some(14),
+            ..Config::default()
+        };
+        let toml = config.encode(ConfigFormat::Toml).unwrap();
+        assert!(toml.contains("retention_days = 14"));
+
+        let parsed = Config::parse(&toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hand_written_toml() {
+        let toml = r#"
+# Longer windows for reading-heavy sessions
+window_duration = 30
+pause_threshold_ms = 800
+session_gap_threshold_secs = 600
+export_path = "/tmp/exports"
+data_path = "/tmp/data"
+paused = false
+
+[sources]
+keyboard = true
+mouse = false
+"#;
+        let config = Config::parse(toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(config.window_duration, Duration::from_secs(30));
+        assert!(!config.sources.mouse);
+        // Legacy top-level field is migrated as for JSON
+        assert_eq!(config.feature_config.pause_threshold_ms, 800);
+
+        let err = Config::parse("window_duration = ", ConfigFormat::Toml).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::ParseError(ConfigFormat::Toml, _)
+        ));
+        let source = std::error::Error::source(&err).unwrap();
+        assert!(source.downcast_ref::<toml::de::Error>().is_some());
+        assert!(err.to_string().starts_with("TOML parse error"));
+    }
+
+    #[test]
+    fn test_config_format_from_path() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.json")),
+            ConfigFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut config = Config::default();
+        config.set_value("sources.keyboard", "0").unwrap();
+        config.set_value("window_duration", "30").unwrap();
+        config.set_value("compress_exports", "true").unwrap();
+        config.set_value("retention_days", "14").unwrap();
+        config.set_value("storage_backend", "sqlite").unwrap();
+        config
+            .set_value("feature_config.pause_threshold_ms", "750")
+            .unwrap();
+        config.set_value("flux.baseline_window", "30").unwrap();
+
+        assert!(!config.sources.keyboard);
+        assert_eq!(config.window_duration, Duration::from_secs(30));
+        assert!(config.compress_exports);
+        assert_eq!(config.retention_days, Some(14));
+        assert_eq!(config.storage_backend, StorageBackend::Sqlite);
+        assert_eq!(config.feature_config.pause_threshold_ms, 750);
+        assert_eq!(config.flux.baseline_window, 30);
+
+        config.set_value("retention_days", "null").unwrap();
+        assert_eq!(config.retention_days, None);
+
+        // Unset paths are taken as strings even when they look like numbers
+        config.set_value("signing_key_path", "2024").unwrap();
+        assert_eq!(config.signing_key_path, Some(PathBuf::from("2024")));
+    }
+
+    #[test]
+    fn test_set_value_errors() {
+        let mut config = Config::default();
+
+        let err = config.set_value("sources.trackpad", "true").unwrap_err();
+        assert!(matches!(err, ConfigError::UnknownKey { .. }));
+        assert!(err.to_string().contains("sources.keyboard"));
+
+        assert!(matches!(
+            config.set_value("paused", "yes"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            config.set_value("storage_backend", "postgres"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert_eq!(config.storage_backend, StorageBackend::Json);
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut config = Config {
+            retention_days: Some(30),
+            ..Config::default()
+        };
+        let unrecognized = config
+            .apply_env_overrides(env(&[
+                ("SYNHEART_WINDOW_DURATION_SECS", "30"),
+                ("SYNHEART_PAUSE_THRESHOLD_MS", "750"),
+                ("SYNHEART_COMPRESS_EXPORTS", "true"),
+                ("SYNHEART_SOURCES", "keyboard"),
+                ("SYNHEART_DATA_PATH", "/var/lib/synheart"),
+                ("SYNHEART_STORAGE_BACKEND", "sqlite"),
+                ("SYNHEART_RETENTION_DAYS", ""),
+                (
+                    "SYNHEART_ADAPTIVE_WINDOW",
+                    r#"{"min_secs":5,"max_secs":30,"target_events":200}"#,
+                ),
+                ("SYNHEART_GATEWAY_TOKEN", "read-by-the-cli"),
+                ("SYNHEART_GATEWAY_HOST", "gateway.lan"),
+                ("SYNHEART_GATEWAY_CA_CERT", "/etc/synheart/ca.pem"),
+                ("SYNHEART_WINDOW_SECS", "5"),
+                ("HOME", "/root"),
+            ]))
+            .unwrap();
+
+        assert_eq!(unrecognized, vec!["SYNHEART_WINDOW_SECS".to_string()]);
+        assert_eq!(config.window_duration, Duration::from_secs(30));
+        assert_eq!(config.feature_config.pause_threshold_ms, 750);
+        assert!(config.compress_exports);
+        assert!(config.sources.keyboard && !config.sources.mouse);
+        assert_eq!(config.data_path, PathBuf::from("/var/lib/synheart"));
+        assert_eq!(config.storage_backend, StorageBackend::Sqlite);
+        assert_eq!(config.retention_days, None);
+        assert_eq!(config.gateway.host.as_deref(), Some("gateway.lan"));
+        assert_eq!(
+            config.gateway.ca_cert_path,
+            Some(PathBuf::from("/etc/synheart/ca.pem"))
+        );
+        assert_eq!(
+            config.adaptive_window,
+            Some(AdaptiveWindowConfig {
+                min_secs: 5,
+                max_secs: 30,
+                target_events: 200,
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_env_override_names_variable() {
+        let mut config = Config::default();
+        let err = config
+            .apply_env_overrides(env(&[("SYNHEART_MAX_QUEUED_SNAPSHOTS", "lots")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("SYNHEART_MAX_QUEUED_SNAPSHOTS"));
+        assert_eq!(config.max_queued_snapshots, DEFAULT_MAX_QUEUED_SNAPSHOTS);
+    }
+
+    #[test]
+    fn test_session_scoped_data_directories() {
+        let root = std::env::temp_dir().join(format!("session_scoped_{}", uuid::Uuid::new_v4()));
+        let mut config = Config {
+            export_path: root.join("exports"),
+            data_path: root.join("data"),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.ensure_session_directories("SESS-1").unwrap(),
+            root.join("data")
+        );
+        assert!(config.session_directories().is_empty());
+
+        config.session_scoped_data = true;
+        let first = config.ensure_session_directories("SESS-1").unwrap();
+        let second = config.ensure_session_directories("SESS-2").unwrap();
+        assert_eq!(first, root.join("data").join("sessions").join("SESS-1"));
+        assert!(first.is_dir());
+        assert_eq!(
+            config.session_directories(),
+            vec![first.clone(), second.clone()]
+        );
+
+        // Each session's transparency log is found alongside the shared one
+        assert_eq!(
+            config.transparency_log_path("SESS-2"),
+            second.join("transparency.json")
+        );
+        assert!(config.transparency_log_paths().is_empty());
+        std::fs::write(root.join("data").join("transparency.json"), "{}").unwrap();
+        std::fs::write(second.join("transparency.json"), "{}").unwrap();
+        assert_eq!(
+            config.transparency_log_paths(),
+            vec![
+                root.join("data").join("transparency.json"),
+                second.join("transparency.json")
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_feature_config_defaults_when_missing() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value.as_object_mut().unwrap().remove("feature_config");
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.feature_config, FeatureConfig::default());
+
+        // Partially specified thresholds keep the defaults for the rest
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["feature_config"] = serde_json::json!({ "burst_interval_ms": 80 });
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.feature_config.burst_interval_ms, 80);
+        assert_eq!(
+            config.feature_config.pause_threshold_ms,
+            DEFAULT_PAUSE_THRESHOLD_MS
+        );
+    }
+
+    #[test]
+    fn test_legacy_pause_threshold_migrated() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("feature_config");
+        object.insert("pause_threshold_ms".to_string(), serde_json::json!(250));
+
+        migrate_legacy_fields(&mut value);
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.feature_config.pause_threshold_ms, 250);
+        assert_eq!(
+            config.feature_config.micro_adjustment_threshold,
+            FeatureConfig::default().micro_adjustment_threshold
+        );
     }
 }
@@ -0,0 +1,56 @@
+//! Record of the user's consent to collection.
+//!
+//! Consent is given once, before the first collection, and stored as a random
+//! UUID token in `consent.token` next to the config file. The token is embedded
+//! in every snapshot's `privacy.consent_token` so downstream systems can check
+//! that consent was obtained. It identifies the consent record, not the user.
+
+use crate::config::Config;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Read the stored consent token, if consent has been given.
+pub fn load_consent_token(path: &Path) -> Option<String> {
+    let token = std::fs::read_to_string(path).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Check whether consent has been recorded at the default location.
+pub fn has_consent() -> bool {
+    load_consent_token(&Config::consent_token_path()).is_some()
+}
+
+/// Record consent and return the consent token.
+///
+/// An existing token is kept, so consenting again does not change it.
+pub fn grant_consent(path: &Path) -> std::io::Result<String> {
+    if let Some(token) = load_consent_token(path) {
+        return Ok(token);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let token = Uuid::new_v4().to_string();
+    std::fs::write(path, format!("{token}\n"))?;
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grant_consent_once() {
+        let dir = std::env::temp_dir().join(format!("synheart-consent-{}", Uuid::new_v4()));
+        let path = dir.join("consent.token");
+        assert_eq!(load_consent_token(&path), None);
+
+        let token = grant_consent(&path).unwrap();
+        assert!(Uuid::parse_str(&token).is_ok());
+        assert_eq!(load_consent_token(&path), Some(token.clone()));
+        assert_eq!(grant_consent(&path).unwrap(), token);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,622 @@
+//! Axis providers for HSI snapshots.
+//!
+//! An [`AxisProvider`] turns a window and its features into axis readings.
+//! `HsiBuilder` runs the built-in providers plus any registered with
+//! [`HsiBuilder::register_axis_provider`](crate::core::hsi::HsiBuilder::register_axis_provider),
+//! so downstream crates can add axes without modifying this crate.
+
+use crate::core::features::{WindowFeatures, IKI_ENTROPY_BINS};
+use crate::core::hsi::{HsiAxisReading, HsiDirection};
+use crate::core::windowing::EventWindow;
+
+/// Mean keyboard pause (in milliseconds) at or above which re-engagement scores 0.
+const REENGAGEMENT_PAUSE_CAP_MS: f64 = 5000.0;
+
+/// Number of pauses in a window needed for full re-engagement confidence.
+const REENGAGEMENT_FULL_CONFIDENCE_PAUSES: u32 = 5;
+
+/// Expected 99th percentile of mouse velocity, used to normalize the velocity
+/// percentile axes when a window has no observed movement to take it from.
+const MOUSE_VELOCITY_P99: f64 = 200.0;
+
+/// Built-in axes computed from keyboard events alone.
+const KEYBOARD_AXES: &[&str] = &[
+    "typing_rate",
+    "typing_burstiness",
+    "session_continuity",
+    "typing_cadence_stability",
+    "typing_gap_ratio",
+    "typing_interaction_intensity",
+    "keyboard_scroll_rate",
+    "iki_p25",
+    "iki_p50",
+    "iki_p75",
+    "iki_p95",
+    "iki_entropy",
+    "iki_autocorrelation",
+    "reengagement_rate",
+];
+
+/// Built-in axes computed from mouse events alone.
+const MOUSE_AXES: &[&str] = &[
+    "idle_ratio",
+    "mouse_velocity_p25",
+    "mouse_velocity_p50",
+    "mouse_velocity_p75",
+    "mouse_velocity_p95",
+    "scroll_reversal_rate",
+    "click_hold_mean",
+    "click_hold_cv",
+    "middle_click_rate",
+    "scroll_click_ratio",
+];
+
+/// Input device an axis is computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    Keyboard,
+    Mouse,
+}
+
+/// Get the input device a built-in axis is computed from.
+///
+/// Axes that combine both devices, and axes of registered providers, have
+/// no single source and return `None`.
+pub fn axis_input_source(axis: &str) -> Option<InputSource> {
+    if KEYBOARD_AXES.contains(&axis) {
+        Some(InputSource::Keyboard)
+    } else if MOUSE_AXES.contains(&axis) {
+        Some(InputSource::Mouse)
+    } else {
+        None
+    }
+}
+
+/// HSI axes domain a provider's readings belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisDomain {
+    Affect,
+    Engagement,
+    Behavior,
+}
+
+/// Computes axis readings for a window.
+///
+/// `HsiBuilder` sets each reading's `window_id` to the snapshot's window, and
+/// fills in `evidence_source_ids` with the keyboard/mouse source when a
+/// provider leaves it `None`.
+pub trait AxisProvider: Send + Sync {
+    /// Compute the readings for a window from its features.
+    fn compute(&self, window: &EventWindow, features: &WindowFeatures) -> Vec<HsiAxisReading>;
+
+    /// Domain the readings are reported under.
+    fn domain(&self) -> AxisDomain {
+        AxisDomain::Behavior
+    }
+}
+
+/// Source quality (0-1) of a window, based on its event count.
+pub fn window_quality(window: &EventWindow) -> f64 {
+    let event_count = window.event_count();
+    if event_count == 0 {
+        0.0
+    } else if event_count < 10 {
+        0.5
+    } else if event_count < 50 {
+        0.75
+    } else {
+        0.95
+    }
+}
+
+/// Velocity that maps to a score of 1.0 on the velocity percentile axes.
+///
+/// This is the window's observed 99th percentile, or [`MOUSE_VELOCITY_P99`]
+/// before any movement has been observed.
+fn mouse_velocity_scale(observed_p99: f64) -> f64 {
+    if observed_p99.is_finite() && observed_p99 > 0.0 {
+        observed_p99
+    } else {
+        MOUSE_VELOCITY_P99
+    }
+}
+
+/// The built-in keyboard and mouse behavior axes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultBehaviorAxisProvider;
+
+impl AxisProvider for DefaultBehaviorAxisProvider {
+    fn compute(&self, window: &EventWindow, features: &WindowFeatures) -> Vec<HsiAxisReading> {
+        // Calculate confidence based on data availability
+        let confidence = window_quality(window) * 0.9; // Slightly lower than quality
+
+        // Build behavioral axis readings
+        let mut behavior_readings = vec![
+            // Typing rate (normalized to 0-1 by clamping to max 10 keys/sec)
+            HsiAxisReading {
+                axis: "typing_rate".to_string(),
+                score: Some((features.keyboard.typing_rate / 10.0).min(1.0)),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: Some("keys_per_sec_normalized".to_string()),
+                evidence_source_ids: None,
+                notes: None,
+            },
+            // Burst index (already 0-1)
+            HsiAxisReading {
+                axis: "typing_burstiness".to_string(),
+                score: Some(features.keyboard.burst_index),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::Bidirectional),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some("Clustering of keystrokes".to_string()),
+            },
+            // Session continuity (already 0-1)
+            HsiAxisReading {
+                axis: "session_continuity".to_string(),
+                score: Some(features.keyboard.session_continuity),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: None,
+            },
+            // Idle ratio (already 0-1)
+            HsiAxisReading {
+                axis: "idle_ratio".to_string(),
+                score: Some(features.mouse.idle_ratio),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsLess),
+                unit: Some("ratio".to_string()),
+                evidence_source_ids: None,
+                notes: None,
+            },
+            // Focus continuity proxy (already 0-1)
+            HsiAxisReading {
+                axis: "focus_continuity".to_string(),
+                score: Some(features.behavioral.focus_continuity_proxy),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some("Derived from typing and mouse patterns".to_string()),
+            },
+            // Interaction rhythm (already 0-1)
+            HsiAxisReading {
+                axis: "interaction_rhythm".to_string(),
+                score: Some(features.behavioral.interaction_rhythm),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: None,
+            },
+            // Motor stability (already 0-1)
+            HsiAxisReading {
+                axis: "motor_stability".to_string(),
+                score: Some(features.behavioral.motor_stability),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: None,
+            },
+            // Friction (already 0-1)
+            HsiAxisReading {
+                axis: "friction".to_string(),
+                score: Some(features.behavioral.friction),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some("Micro-adjustments and hesitation".to_string()),
+            },
+            // Typing cadence stability (already 0-1)
+            HsiAxisReading {
+                axis: "typing_cadence_stability".to_string(),
+                score: Some(features.keyboard.typing_cadence_stability),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some("Rhythmic consistency of typing".to_string()),
+            },
+            // Typing gap ratio (already 0-1)
+            HsiAxisReading {
+                axis: "typing_gap_ratio".to_string(),
+                score: Some(features.keyboard.typing_gap_ratio),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsLess),
+                unit: Some("ratio".to_string()),
+                evidence_source_ids: None,
+                notes: Some("Proportion of inter-tap intervals classified as gaps".to_string()),
+            },
+            // Typing interaction intensity (already 0-1)
+            HsiAxisReading {
+                axis: "typing_interaction_intensity".to_string(),
+                score: Some(features.keyboard.typing_interaction_intensity),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some("Composite of speed, cadence stability, and gap behavior".to_string()),
+            },
+            // Keyboard scroll rate (normalized to 0-1, capped at 5 keys/sec)
+            HsiAxisReading {
+                axis: "keyboard_scroll_rate".to_string(),
+                score: Some((features.keyboard.keyboard_scroll_rate / 5.0).min(1.0)),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: Some("nav_keys_per_sec_normalized".to_string()),
+                evidence_source_ids: None,
+                notes: Some(
+                    "Navigation keys (arrows, page up/down) - separate from mouse scroll"
+                        .to_string(),
+                ),
+            },
+            // Burstiness (already 0-1)
+            HsiAxisReading {
+                axis: "burstiness".to_string(),
+                score: Some(features.behavioral.burstiness),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::Bidirectional),
+                unit: None,
+                evidence_source_ids: None,
+                notes: Some(
+                    "Whether interactions occur in clusters (high) or evenly (low)".to_string(),
+                ),
+            },
+        ];
+
+        // Inter-key interval percentiles (normalized to 0-1, capped at 2000ms)
+        for (axis, value_ms, label) in [
+            ("iki_p25", features.keyboard.iki_p25_ms, "25th"),
+            ("iki_p50", features.keyboard.iki_p50_ms, "50th"),
+            ("iki_p75", features.keyboard.iki_p75_ms, "75th"),
+            ("iki_p95", features.keyboard.iki_p95_ms, "95th"),
+        ] {
+            behavior_readings.push(HsiAxisReading {
+                axis: axis.to_string(),
+                score: Some((value_ms / 2000.0).clamp(0.0, 1.0)),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsLess),
+                unit: Some("iki_ms_normalized".to_string()),
+                evidence_source_ids: None,
+                notes: Some(format!(
+                    "{label} percentile of inter-key intervals (capped at 2000ms)"
+                )),
+            });
+        }
+
+        // IKI entropy (normalized to 0-1 by the maximum entropy of the histogram)
+        behavior_readings.push(HsiAxisReading {
+            axis: "iki_entropy".to_string(),
+            score: Some(
+                (features.keyboard.iki_entropy / (IKI_ENTROPY_BINS as f64).log2()).clamp(0.0, 1.0),
+            ),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::HigherIsLess),
+            unit: Some("entropy_normalized".to_string()),
+            evidence_source_ids: None,
+            notes: Some("Disorder of typing rhythm (Shannon entropy of IKI histogram)".to_string()),
+        });
+
+        // IKI lag-1 autocorrelation (shifted from [-1, 1] by +0.5 and clipped to 0-1)
+        behavior_readings.push(HsiAxisReading {
+            axis: "iki_autocorrelation".to_string(),
+            score: Some((features.keyboard.iki_autocorrelation_lag1 + 0.5).clamp(0.0, 1.0)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::Bidirectional),
+            unit: None,
+            evidence_source_ids: None,
+            notes: Some("Smooth flow (high) vs staccato alternation (low)".to_string()),
+        });
+
+        // Mouse velocity percentiles (normalized to 0-1 against the observed p99)
+        let velocity_scale = mouse_velocity_scale(features.mouse.velocity_p99);
+        for (axis, velocity, label) in [
+            ("mouse_velocity_p25", features.mouse.velocity_p25, "25th"),
+            ("mouse_velocity_p50", features.mouse.velocity_p50, "50th"),
+            ("mouse_velocity_p75", features.mouse.velocity_p75, "75th"),
+            ("mouse_velocity_p95", features.mouse.velocity_p95, "95th"),
+        ] {
+            behavior_readings.push(HsiAxisReading {
+                axis: axis.to_string(),
+                score: Some(velocity.clamp(0.0, velocity_scale) / velocity_scale),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: Some("velocity_normalized".to_string()),
+                evidence_source_ids: None,
+                notes: Some(format!("{label} percentile of cursor speed")),
+            });
+        }
+
+        // Scroll reversal rate (normalized to 0-1, capped at 2 reversals/sec)
+        behavior_readings.push(HsiAxisReading {
+            axis: "scroll_reversal_rate".to_string(),
+            score: Some((features.mouse.scroll_reversal_rate / 2.0).min(1.0)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::HigherIsMore),
+            unit: Some("reversals_per_sec_normalized".to_string()),
+            evidence_source_ids: None,
+            notes: Some("Rapid scroll direction changes (reading confusion or search)".to_string()),
+        });
+
+        // Click hold duration (normalized to 0-1, capped at 1000ms)
+        behavior_readings.push(HsiAxisReading {
+            axis: "click_hold_mean".to_string(),
+            score: Some((features.mouse.click_hold_mean_ms / 1000.0).min(1.0)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::HigherIsMore),
+            unit: Some("hold_ms_normalized".to_string()),
+            evidence_source_ids: None,
+            notes: None,
+        });
+        // Click hold coefficient of variation (capped at 1.0)
+        let click_hold_cv = if features.mouse.click_hold_mean_ms > 0.0 {
+            features.mouse.click_hold_variability / features.mouse.click_hold_mean_ms
+        } else {
+            0.0
+        };
+        behavior_readings.push(HsiAxisReading {
+            axis: "click_hold_cv".to_string(),
+            score: Some(click_hold_cv.clamp(0.0, 1.0)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::HigherIsLess),
+            unit: Some("coefficient_of_variation".to_string()),
+            evidence_source_ids: None,
+            notes: Some("Consistency of click hold duration".to_string()),
+        });
+
+        // Middle click rate (normalized to 0-1, capped at 1 click/sec)
+        behavior_readings.push(HsiAxisReading {
+            axis: "middle_click_rate".to_string(),
+            score: Some(features.mouse.middle_click_rate.min(1.0)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::HigherIsMore),
+            unit: Some("clicks_per_sec_normalized".to_string()),
+            evidence_source_ids: None,
+            notes: Some("Middle button clicks (opening links, pasting, closing tabs)".to_string()),
+        });
+
+        // Scroll-to-click ratio, squashed to 0-1 by a sigmoid of its log
+        // (ratio / (1 + ratio)): 0.5 when scrolls and clicks are even
+        let ratio = features.mouse.scroll_to_click_ratio.max(0.0);
+        behavior_readings.push(HsiAxisReading {
+            axis: "scroll_click_ratio".to_string(),
+            score: Some(ratio / (1.0 + ratio)),
+            confidence,
+            window_id: String::new(),
+            direction: Some(HsiDirection::Bidirectional),
+            unit: Some("ratio_sigmoid".to_string()),
+            evidence_source_ids: None,
+            notes: Some(
+                "Scroll-heavy reading (high) vs click-heavy form filling (low)".to_string(),
+            ),
+        });
+
+        behavior_readings
+    }
+}
+
+/// The built-in engagement axes, derived from the same keyboard and mouse features.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultEngagementAxisProvider;
+
+impl AxisProvider for DefaultEngagementAxisProvider {
+    fn compute(&self, window: &EventWindow, features: &WindowFeatures) -> Vec<HsiAxisReading> {
+        let confidence = window_quality(window) * 0.9;
+
+        // Share of the window with sustained activity on either input
+        let persistence = features
+            .keyboard
+            .session_continuity
+            .max(1.0 - features.mouse.idle_ratio)
+            .clamp(0.0, 1.0);
+
+        // Shorter pauses mean activity resumes sooner; undefined without pauses
+        let pause_count = features.keyboard.pause_count;
+        let reengagement = (pause_count > 0).then(|| {
+            1.0 - (features.keyboard.mean_pause_ms / REENGAGEMENT_PAUSE_CAP_MS).clamp(0.0, 1.0)
+        });
+        let reengagement_confidence = confidence
+            * f64::from(pause_count.min(REENGAGEMENT_FULL_CONFIDENCE_PAUSES))
+            / f64::from(REENGAGEMENT_FULL_CONFIDENCE_PAUSES);
+
+        // Keyboard share of input activity; undefined without any input
+        let keyboard_rate = features.keyboard.typing_rate + features.keyboard.keyboard_scroll_rate;
+        let total_rate = keyboard_rate + features.mouse.mouse_activity_rate;
+        let modality_ratio =
+            (total_rate > 0.0).then(|| (keyboard_rate / total_rate).clamp(0.0, 1.0));
+
+        vec![
+            HsiAxisReading {
+                axis: "interaction_persistence".to_string(),
+                score: Some(persistence),
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: Some("ratio".to_string()),
+                evidence_source_ids: None,
+                notes: Some(
+                    "Share of the window with sustained keyboard or mouse activity".to_string(),
+                ),
+            },
+            HsiAxisReading {
+                axis: "reengagement_rate".to_string(),
+                score: reengagement,
+                confidence: reengagement_confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: Some("pause_ms_inverted".to_string()),
+                evidence_source_ids: None,
+                notes: Some("How quickly typing resumes after a pause".to_string()),
+            },
+            HsiAxisReading {
+                axis: "input_modality_ratio".to_string(),
+                score: modality_ratio,
+                confidence,
+                window_id: String::new(),
+                direction: Some(HsiDirection::Bidirectional),
+                unit: Some("ratio".to_string()),
+                evidence_source_ids: None,
+                notes: Some("Keyboard (1) vs mouse (0) dominance of input".to_string()),
+            },
+        ]
+    }
+
+    fn domain(&self) -> AxisDomain {
+        AxisDomain::Engagement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use chrono::{Duration, Utc};
+
+    struct EventCountProvider;
+
+    impl AxisProvider for EventCountProvider {
+        fn compute(&self, window: &EventWindow, _features: &WindowFeatures) -> Vec<HsiAxisReading> {
+            vec![HsiAxisReading {
+                axis: "event_density".to_string(),
+                score: Some((window.event_count() as f64 / 100.0).min(1.0)),
+                confidence: 1.0,
+                window_id: String::new(),
+                direction: Some(HsiDirection::HigherIsMore),
+                unit: None,
+                evidence_source_ids: None,
+                notes: None,
+            }]
+        }
+
+        fn domain(&self) -> AxisDomain {
+            AxisDomain::Engagement
+        }
+    }
+
+    #[test]
+    fn test_registered_provider_readings_are_merged() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let default_snapshot =
+            HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let snapshot = HsiBuilder::new()
+            .register_axis_provider(Box::new(EventCountProvider))
+            .build(&window, &features, Duration::seconds(10), None);
+        assert_eq!(
+            snapshot.readings().count(),
+            default_snapshot.readings().count() + 1
+        );
+
+        // Custom readings land in their domain, stamped with the snapshot window and source
+        let engagement = snapshot.axes.as_ref().unwrap().engagement.as_ref().unwrap();
+        let reading = engagement.readings.last().unwrap();
+        assert_eq!(reading.axis, "event_density");
+        assert_eq!(reading.window_id, snapshot.window_ids[0]);
+        assert_eq!(reading.evidence_source_ids, snapshot.source_ids);
+
+        // The extra axis changes the schema hash
+        assert_ne!(
+            snapshot.axis_schema_hash(),
+            default_snapshot.axis_schema_hash()
+        );
+    }
+
+    #[test]
+    fn test_engagement_axes() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = WindowFeatures::default();
+        features.keyboard.session_continuity = 0.4;
+        features.keyboard.pause_count = 2;
+        features.keyboard.mean_pause_ms = 1000.0;
+        features.keyboard.typing_rate = 3.0;
+        features.mouse.idle_ratio = 0.2;
+        features.mouse.mouse_activity_rate = 1.0;
+
+        let readings = DefaultEngagementAxisProvider.compute(&window, &features);
+        let score = |axis: &str| {
+            readings
+                .iter()
+                .find(|r| r.axis == axis)
+                .and_then(|r| r.score)
+                .unwrap()
+        };
+
+        assert!((score("interaction_persistence") - 0.8).abs() < 1e-9);
+        assert!((score("reengagement_rate") - 0.8).abs() < 1e-9);
+        assert!((score("input_modality_ratio") - 0.75).abs() < 1e-9);
+        assert_eq!(
+            DefaultEngagementAxisProvider.domain(),
+            AxisDomain::Engagement
+        );
+    }
+
+    #[test]
+    fn test_engagement_axes_undefined_without_activity() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let readings = DefaultEngagementAxisProvider.compute(&window, &WindowFeatures::default());
+
+        let reengagement = readings
+            .iter()
+            .find(|r| r.axis == "reengagement_rate")
+            .unwrap();
+        assert_eq!(reengagement.score, None);
+        assert_eq!(reengagement.confidence, 0.0);
+        let modality = readings
+            .iter()
+            .find(|r| r.axis == "input_modality_ratio")
+            .unwrap();
+        assert_eq!(modality.score, None);
+    }
+
+    #[test]
+    fn test_velocity_axes_normalized_by_observed_p99() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = WindowFeatures::default();
+        features.mouse.velocity_p50 = 20.0;
+        features.mouse.velocity_p95 = 50.0;
+        features.mouse.velocity_p99 = 40.0;
+
+        let readings = DefaultBehaviorAxisProvider.compute(&window, &features);
+        let score = |axis: &str| {
+            readings
+                .iter()
+                .find(|r| r.axis == axis)
+                .and_then(|r| r.score)
+                .unwrap()
+        };
+        assert!((score("mouse_velocity_p50") - 0.5).abs() < 1e-9);
+        assert_eq!(score("mouse_velocity_p95"), 1.0);
+
+        // No observed movement: fall back to the expected range
+        assert_eq!(mouse_velocity_scale(0.0), MOUSE_VELOCITY_P99);
+        assert_eq!(mouse_velocity_scale(f64::NAN), MOUSE_VELOCITY_P99);
+    }
+}
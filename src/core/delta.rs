@@ -0,0 +1,119 @@
+//! Window-to-window feature deltas.
+//!
+//! A single window's features say little about where a session is heading.
+//! `FeatureDeltaTracker` keeps the previous window's features and reports the
+//! signed change of key fields for each new window.
+
+use crate::core::features::WindowFeatures;
+use crate::core::windowing::EventWindow;
+use serde::{Deserialize, Serialize};
+
+/// Signed change of key features since the previous window.
+///
+/// Each field is `None` for the first window of a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeatureDelta {
+    /// Change in typing keys per second
+    pub typing_rate_delta: Option<f64>,
+    /// Change in the number of typing pauses
+    pub pause_count_delta: Option<i64>,
+    /// Change in the ratio of active typing time to window time
+    pub session_continuity_delta: Option<f64>,
+    /// Change in the mouse idle ratio
+    pub idle_ratio_delta: Option<f64>,
+}
+
+/// Tracks the previous window's features to compute deltas.
+///
+/// Windows must be fed in order and from a single window resolution.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureDeltaTracker {
+    /// Features of the previous window in the current session
+    previous: Option<WindowFeatures>,
+}
+
+impl FeatureDeltaTracker {
+    /// Create a new tracker with no previous window.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the delta for the next completed window and remember its features.
+    ///
+    /// A window that starts a new session is never compared with the
+    /// previous session's last window.
+    pub fn observe(&mut self, window: &EventWindow, features: &WindowFeatures) -> FeatureDelta {
+        if window.is_session_start {
+            self.previous = None;
+        }
+
+        let delta = match self.previous {
+            Some(ref previous) => FeatureDelta {
+                typing_rate_delta: Some(
+                    features.keyboard.typing_rate - previous.keyboard.typing_rate,
+                ),
+                pause_count_delta: Some(
+                    i64::from(features.keyboard.pause_count)
+                        - i64::from(previous.keyboard.pause_count),
+                ),
+                session_continuity_delta: Some(
+                    features.keyboard.session_continuity - previous.keyboard.session_continuity,
+                ),
+                idle_ratio_delta: Some(features.mouse.idle_ratio - previous.mouse.idle_ratio),
+            },
+            None => FeatureDelta::default(),
+        };
+
+        self.previous = Some(features.clone());
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn features(typing_rate: f64, pause_count: u32, idle_ratio: f64) -> WindowFeatures {
+        let mut features = WindowFeatures::default();
+        features.keyboard.typing_rate = typing_rate;
+        features.keyboard.pause_count = pause_count;
+        features.mouse.idle_ratio = idle_ratio;
+        features
+    }
+
+    #[test]
+    fn test_deltas_between_windows() {
+        let mut tracker = FeatureDeltaTracker::new();
+        let start = Utc::now();
+        let mut first = EventWindow::new(start, Duration::seconds(10));
+        first.is_session_start = true;
+        let second = EventWindow::new(start + Duration::seconds(10), Duration::seconds(10));
+
+        assert_eq!(
+            tracker.observe(&first, &features(2.0, 3, 0.5)),
+            FeatureDelta::default()
+        );
+
+        let delta = tracker.observe(&second, &features(3.5, 1, 0.25));
+        assert_eq!(delta.typing_rate_delta, Some(1.5));
+        assert_eq!(delta.pause_count_delta, Some(-2));
+        assert_eq!(delta.session_continuity_delta, Some(0.0));
+        assert_eq!(delta.idle_ratio_delta, Some(-0.25));
+    }
+
+    #[test]
+    fn test_new_session_has_no_delta() {
+        let mut tracker = FeatureDeltaTracker::new();
+        let start = Utc::now();
+        tracker.observe(
+            &EventWindow::new(start, Duration::seconds(10)),
+            &features(2.0, 3, 0.5),
+        );
+
+        let mut next_session = EventWindow::new(start + Duration::hours(1), Duration::seconds(10));
+        next_session.is_session_start = true;
+        let delta = tracker.observe(&next_session, &features(4.0, 0, 0.1));
+        assert_eq!(delta, FeatureDelta::default());
+    }
+}
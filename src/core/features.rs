@@ -4,7 +4,11 @@
 //! All features are computed from timing and magnitude data only - never content.
 
 use crate::collector::types::{KeyboardEvent, KeyboardEventType, MouseEvent, MouseEventType};
+use crate::core::rhythm::{
+    classify_mouse_behavior, classify_rhythm, MouseBehaviorClass, TypingRhythmClass,
+};
 use crate::core::windowing::EventWindow;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 /// Keyboard-derived behavioral features.
@@ -13,7 +17,7 @@ use serde::{Deserialize, Serialize};
 /// typing keys ONLY. Navigation keys (arrows, page up/down, home/end) are tracked
 /// separately via keyboard_scroll_rate to avoid inflating typing metrics during
 /// navigation-heavy text editing sessions.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct KeyboardFeatures {
     /// Typing keys per second (excludes navigation keys)
     pub typing_rate: f64,
@@ -23,6 +27,18 @@ pub struct KeyboardFeatures {
     pub mean_pause_ms: f64,
     /// Standard deviation of inter-key intervals
     pub latency_variability: f64,
+    /// 25th percentile of inter-key intervals in milliseconds
+    pub iki_p25_ms: f64,
+    /// Median inter-key interval in milliseconds
+    pub iki_p50_ms: f64,
+    /// 75th percentile of inter-key intervals in milliseconds
+    pub iki_p75_ms: f64,
+    /// 95th percentile of inter-key intervals in milliseconds
+    pub iki_p95_ms: f64,
+    /// Shannon entropy (bits) of the inter-key interval histogram (50ms bins, 0-2000ms)
+    pub iki_entropy: f64,
+    /// Lag-1 autocorrelation of inter-key intervals (-1 to 1, positive = smooth flow)
+    pub iki_autocorrelation_lag1: f64,
     /// Average key hold duration in milliseconds
     pub hold_time_mean: f64,
     /// Burstiness index (0-1, higher = more bursty)
@@ -42,10 +58,16 @@ pub struct KeyboardFeatures {
     pub keyboard_scroll_rate: f64,
     /// Total navigation key events in the window
     pub navigation_key_count: u32,
+    /// Fraction of typing taps made while a modifier was held (bimanual typing proxy)
+    pub modifier_active_tap_ratio: f64,
+    /// Inter-key intervals over 500ms, bucketed as 500-750ms, 750-1000ms,
+    /// 1000-2000ms, 2000-5000ms and over 5000ms
+    #[serde(default)]
+    pub pause_histogram: [u32; PAUSE_HISTOGRAM_BUCKETS],
 }
 
 /// Mouse-derived behavioral features.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MouseFeatures {
     /// Movement events per second
     pub mouse_activity_rate: f64,
@@ -53,18 +75,99 @@ pub struct MouseFeatures {
     pub mean_velocity: f64,
     /// Standard deviation of velocity
     pub velocity_variability: f64,
+    /// 25th percentile of cursor speed (relative units)
+    pub velocity_p25: f64,
+    /// Median cursor speed (relative units)
+    pub velocity_p50: f64,
+    /// 75th percentile of cursor speed (relative units)
+    pub velocity_p75: f64,
+    /// 95th percentile of cursor speed (relative units)
+    pub velocity_p95: f64,
+    /// 99th percentile of cursor speed (relative units), the top of the
+    /// observed range
+    #[serde(default)]
+    pub velocity_p99: f64,
     /// Count of sudden acceleration changes
     pub acceleration_spikes: u32,
     /// Clicks per window
     pub click_rate: f64,
-    /// Scroll events per window
+    /// Average button hold duration in milliseconds (press to release)
+    pub click_hold_mean_ms: f64,
+    /// Standard deviation of button hold duration in milliseconds
+    pub click_hold_variability: f64,
+    /// Mouse wheel scroll events per second (trackpad swipes are counted in
+    /// `trackpad_scroll_rate`)
     pub scroll_rate: f64,
+    /// Number of scroll direction reversals (opposite-direction scrolls within 500ms)
+    pub scroll_direction_reversals: u32,
+    /// Scroll direction reversals per second
+    pub scroll_reversal_rate: f64,
     /// Ratio of idle time to active time
     pub idle_ratio: f64,
     /// Ratio of small movements to total movements
     pub micro_adjustment_ratio: f64,
     /// Total idle time in milliseconds (periods with no mouse activity > 1 second)
     pub idle_time_ms: u64,
+    /// Fraction of movement events made while dragging (button held)
+    #[serde(default)]
+    pub drag_movement_ratio: f64,
+    /// Number of drag episodes (runs of consecutive drag movements)
+    #[serde(default)]
+    pub drag_episode_count: u32,
+    /// Middle button clicks per second
+    #[serde(default)]
+    pub middle_click_rate: f64,
+    /// Two-finger trackpad swipe scroll events per second
+    #[serde(default)]
+    pub trackpad_scroll_rate: f64,
+    /// Trackpad pinch gestures per second
+    #[serde(default)]
+    pub trackpad_pinch_rate: f64,
+    /// Scroll events per click (scrolls / max(clicks, 1))
+    #[serde(default)]
+    pub scroll_to_click_ratio: f64,
+    /// Whether scrolling or clicking dominates the window
+    #[serde(default)]
+    pub dominant_modality: InputModality,
+}
+
+/// Which mouse interaction dominates a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InputModality {
+    /// Mostly scrolling (e.g. reading a document)
+    Scrolling,
+    /// Mostly clicking (e.g. filling in a form)
+    Clicking,
+    /// Neither dominates, or there was no scrolling or clicking
+    #[default]
+    Balanced,
+}
+
+impl InputModality {
+    /// Classify a window from its scroll and click counts.
+    pub fn from_counts(scrolls: usize, clicks: usize) -> Self {
+        if scrolls + clicks == 0 {
+            return InputModality::Balanced;
+        }
+        let ratio = scrolls as f64 / clicks.max(1) as f64;
+        if ratio >= SCROLLING_MIN_SCROLL_TO_CLICK_RATIO {
+            InputModality::Scrolling
+        } else if ratio <= CLICKING_MAX_SCROLL_TO_CLICK_RATIO {
+            InputModality::Clicking
+        } else {
+            InputModality::Balanced
+        }
+    }
+
+    /// Get the wire name of this modality (e.g. `SCROLLING`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputModality::Scrolling => "SCROLLING",
+            InputModality::Clicking => "CLICKING",
+            InputModality::Balanced => "BALANCED",
+        }
+    }
 }
 
 /// Derived behavioral signals combining keyboard and mouse data.
@@ -73,7 +176,7 @@ pub struct MouseFeatures {
 /// - These signals are computed locally in the sensor agent
 /// - Additional enriched signals (distraction_score, focus_hint) are computed in Flux
 /// - Task switch metrics are NOT captured (requires app context, violates privacy policy)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BehavioralSignals {
     /// Overall interaction rhythm (regularity of input)
     pub interaction_rhythm: f64,
@@ -91,48 +194,195 @@ pub struct BehavioralSignals {
     /// - High session continuity (> 0.7)
     /// - Consistent activity throughout the window
     pub deep_focus_block: bool,
+    /// Confidence (0-1) in the keyboard contribution to these signals;
+    /// 0.0 when the window has no keyboard events or too few events overall
+    #[serde(default)]
+    pub keyboard_confidence: f64,
+    /// Confidence (0-1) in the mouse contribution to these signals;
+    /// 0.0 when the window has no mouse events or too few events overall
+    #[serde(default)]
+    pub mouse_confidence: f64,
+}
+
+/// Which input sources a window has enough data from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DataQuality {
+    /// Both keyboard and mouse events
+    Full,
+    /// Keyboard events but no mouse events
+    KeyboardOnly,
+    /// Mouse events but no keyboard events
+    MouseOnly,
+    /// Fewer than `SPARSE_EVENT_THRESHOLD` events in total
+    Sparse,
+}
+
+impl DataQuality {
+    /// Classify a window from its keyboard and mouse event counts.
+    pub fn from_event_counts(keyboard_events: usize, mouse_events: usize) -> Self {
+        if keyboard_events + mouse_events < SPARSE_EVENT_THRESHOLD {
+            DataQuality::Sparse
+        } else if mouse_events == 0 {
+            DataQuality::KeyboardOnly
+        } else if keyboard_events == 0 {
+            DataQuality::MouseOnly
+        } else {
+            DataQuality::Full
+        }
+    }
+
+    /// Get the wire name of this quality (e.g. `KEYBOARD_ONLY`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataQuality::Full => "FULL",
+            DataQuality::KeyboardOnly => "KEYBOARD_ONLY",
+            DataQuality::MouseOnly => "MOUSE_ONLY",
+            DataQuality::Sparse => "SPARSE",
+        }
+    }
 }
 
 /// All computed features for a window.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct WindowFeatures {
     pub keyboard: KeyboardFeatures,
     pub mouse: MouseFeatures,
     pub behavioral: BehavioralSignals,
+    /// Typing rhythm pattern derived from the keyboard features
+    #[serde(default)]
+    pub rhythm_class: TypingRhythmClass,
+    /// Mouse behavior mode derived from the mouse features
+    #[serde(default)]
+    pub mouse_class: MouseBehaviorClass,
+    /// Which input sources the window has data from (None if unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_quality: Option<DataQuality>,
+}
+
+/// Windows with fewer events than this (keyboard and mouse combined) are sparse.
+pub const SPARSE_EVENT_THRESHOLD: usize = 10;
+
+/// Default threshold for considering a gap as a "pause" (in milliseconds).
+pub const DEFAULT_PAUSE_THRESHOLD_MS: u64 = 500;
+
+/// Default inter-key interval below which typing counts as a burst (in milliseconds).
+pub const DEFAULT_BURST_INTERVAL_MS: u64 = 100;
+
+/// Default threshold for micro-adjustments (in movement magnitude units).
+pub const DEFAULT_MICRO_ADJUSTMENT_THRESHOLD: f64 = 5.0;
+
+/// Default threshold for acceleration spikes (change in velocity).
+pub const DEFAULT_ACCELERATION_SPIKE_THRESHOLD: f64 = 50.0;
+
+/// Tunable parameters for feature computation.
+///
+/// Missing fields take their defaults when deserialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureConfig {
+    /// Inter-key gap (in milliseconds) above which an interval counts as a pause
+    pub pause_threshold_ms: u64,
+    /// Inter-key interval (in milliseconds) below which typing counts as a burst
+    pub burst_interval_ms: u64,
+    /// Movement magnitude below which a mouse movement is a micro-adjustment
+    pub micro_adjustment_threshold: f64,
+    /// Change in velocity between consecutive movements that counts as a spike
+    pub acceleration_spike_threshold: f64,
+    /// Compute features for windows completed together (one per resolution)
+    /// concurrently, with [`compute_features_parallel`]
+    pub parallel: bool,
+}
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        Self {
+            pause_threshold_ms: DEFAULT_PAUSE_THRESHOLD_MS,
+            burst_interval_ms: DEFAULT_BURST_INTERVAL_MS,
+            micro_adjustment_threshold: DEFAULT_MICRO_ADJUSTMENT_THRESHOLD,
+            acceleration_spike_threshold: DEFAULT_ACCELERATION_SPIKE_THRESHOLD,
+            parallel: false,
+        }
+    }
 }
 
-/// Threshold for considering a gap as a "pause" (in milliseconds).
-const PAUSE_THRESHOLD_MS: i64 = 500;
+/// Width of each inter-key interval histogram bin (in milliseconds).
+const IKI_ENTROPY_BIN_MS: f64 = 50.0;
 
-/// Threshold for micro-adjustments (in movement magnitude units).
-const MICRO_ADJUSTMENT_THRESHOLD: f64 = 5.0;
+/// Number of inter-key interval histogram bins (covers 0-2000ms).
+pub const IKI_ENTROPY_BINS: usize = 40;
 
-/// Threshold for acceleration spikes (change in velocity).
-const ACCELERATION_SPIKE_THRESHOLD: f64 = 50.0;
+/// Number of buckets in `KeyboardFeatures::pause_histogram`.
+pub const PAUSE_HISTOGRAM_BUCKETS: usize = 5;
+
+/// Lower bounds of the pause histogram buckets above 500ms (in milliseconds).
+const PAUSE_HISTOGRAM_BOUNDS_MS: [i64; PAUSE_HISTOGRAM_BUCKETS - 1] = [750, 1000, 2000, 5000];
+
+/// Maximum gap between opposite-direction scrolls to count as a reversal (in milliseconds).
+const SCROLL_REVERSAL_WINDOW_MS: i64 = 500;
+
+/// Scroll-to-click ratio at or above which a window is `Scrolling`.
+const SCROLLING_MIN_SCROLL_TO_CLICK_RATIO: f64 = 2.0;
+
+/// Scroll-to-click ratio at or below which a window is `Clicking`.
+const CLICKING_MAX_SCROLL_TO_CLICK_RATIO: f64 = 0.5;
 
 /// Compute all features from an event window.
-pub fn compute_features(window: &EventWindow) -> WindowFeatures {
-    let keyboard = compute_keyboard_features(&window.keyboard_events, window.duration_secs());
-    let mouse = compute_mouse_features(&window.mouse_events, window.duration_secs());
-    let behavioral = compute_behavioral_signals(&keyboard, &mouse);
+pub fn compute_features(window: &EventWindow, config: &FeatureConfig) -> WindowFeatures {
+    let keyboard =
+        compute_keyboard_features(&window.keyboard_events, window.duration_secs(), config);
+    let mouse = compute_mouse_features(&window.mouse_events, window.duration_secs(), config);
+    let data_quality =
+        DataQuality::from_event_counts(window.keyboard_events.len(), window.mouse_events.len());
+    let behavioral = compute_behavioral_signals(
+        &keyboard,
+        &mouse,
+        window.keyboard_events.len(),
+        window.mouse_events.len(),
+    );
+    let rhythm_class = classify_rhythm(&keyboard);
+    let mouse_class = classify_mouse_behavior(&mouse);
 
     WindowFeatures {
         keyboard,
         mouse,
         behavioral,
+        rhythm_class,
+        mouse_class,
+        data_quality: Some(data_quality),
     }
 }
 
+/// Compute features for several independent windows concurrently.
+///
+/// Results are in the same order as `windows`. Worth it when several window
+/// resolutions complete at once; for a single window use [`compute_features`].
+pub fn compute_features_parallel(
+    windows: &[&EventWindow],
+    config: &FeatureConfig,
+) -> Vec<WindowFeatures> {
+    windows
+        .par_iter()
+        .map(|window| compute_features(window, config))
+        .collect()
+}
+
 /// Compute keyboard features from a list of keyboard events.
 ///
 /// Typing metrics are computed from typing key events ONLY (excludes navigation keys).
 /// Navigation keys (arrows, page up/down, home/end) are tracked separately via
 /// keyboard_scroll_rate to distinguish keyboard scrolling from mouse scrolling.
-fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) -> KeyboardFeatures {
+fn compute_keyboard_features(
+    events: &[KeyboardEvent],
+    window_duration: f64,
+    config: &FeatureConfig,
+) -> KeyboardFeatures {
     if events.is_empty() || window_duration <= 0.0 {
         return KeyboardFeatures::default();
     }
 
+    let pause_threshold_ms = config.pause_threshold_ms as i64;
+
     // Separate typing events from navigation events
     let typing_events: Vec<&KeyboardEvent> = events
         .iter()
@@ -161,6 +411,19 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
         .collect();
     let typing_tap_count = typing_key_presses.len() as u32;
 
+    // Modifier-active taps: chords like Shift+letter need a second hand or finger.
+    // Presses and releases of the modifiers themselves are not taps made with one.
+    let key_taps: Vec<&KeyboardEvent> = typing_key_presses
+        .iter()
+        .filter(|e| !e.is_modifier_key)
+        .copied()
+        .collect();
+    let modifier_active_tap_ratio = if key_taps.is_empty() {
+        0.0
+    } else {
+        key_taps.iter().filter(|e| e.modifier_active).count() as f64 / key_taps.len() as f64
+    };
+
     // Typing rate (typing keys only)
     let typing_rate = typing_tap_count as f64 / window_duration;
 
@@ -173,7 +436,7 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
     // Pause count and mean pause duration
     let pauses: Vec<i64> = intervals
         .iter()
-        .filter(|&&i| i > PAUSE_THRESHOLD_MS)
+        .filter(|&&i| i > pause_threshold_ms)
         .copied()
         .collect();
     let pause_count = pauses.len() as u32;
@@ -183,8 +446,26 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
         pauses.iter().sum::<i64>() as f64 / pauses.len() as f64
     };
 
+    // Pause length distribution (short cognitive pauses vs long breaks)
+    let pause_histogram = compute_pause_histogram(&intervals);
+
     // Latency variability (std dev of intervals)
-    let latency_variability = std_dev(&intervals.iter().map(|&i| i as f64).collect::<Vec<_>>());
+    let interval_values: Vec<f64> = intervals.iter().map(|&i| i as f64).collect();
+    let latency_variability = std_dev(&interval_values);
+
+    // IKI autocorrelation: do fast taps follow fast taps? (must use original order)
+    let iki_autocorrelation_lag1 = lag1_autocorrelation(&interval_values);
+
+    // IKI distribution shape (percentiles over the same intervals)
+    let mut sorted_intervals = interval_values;
+    sorted_intervals.sort_by(|a, b| a.total_cmp(b));
+    let iki_p25_ms = percentile(&sorted_intervals, 25.0);
+    let iki_p50_ms = percentile(&sorted_intervals, 50.0);
+    let iki_p75_ms = percentile(&sorted_intervals, 75.0);
+    let iki_p95_ms = percentile(&sorted_intervals, 95.0);
+
+    // IKI entropy: rhythm disorder of the interval distribution
+    let iki_entropy = histogram_entropy(&sorted_intervals);
 
     // Hold time computation (requires matching key down/up pairs)
     // Only compute from typing events to avoid navigation key hold times
@@ -196,8 +477,9 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
     };
 
     // Burst index: ratio of short intervals to all intervals
-    // Short interval = less than 100ms (fast typing burst)
-    let short_interval_count = intervals.iter().filter(|&&i| i < 100).count();
+    // Short interval = less than `burst_interval_ms` (fast typing burst)
+    let burst_interval_ms = config.burst_interval_ms as i64;
+    let short_interval_count = intervals.iter().filter(|&&i| i < burst_interval_ms).count();
     let burst_index = if intervals.is_empty() {
         0.0
     } else {
@@ -208,7 +490,7 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
     // Active time is sum of intervals (excluding long pauses)
     let active_intervals: Vec<i64> = intervals
         .iter()
-        .filter(|&&i| i <= PAUSE_THRESHOLD_MS * 2) // Allow some breathing room
+        .filter(|&&i| i <= pause_threshold_ms * 2) // Allow some breathing room
         .copied()
         .collect();
     let active_time_ms: i64 = active_intervals.iter().sum();
@@ -237,6 +519,12 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
         pause_count,
         mean_pause_ms,
         latency_variability,
+        iki_p25_ms,
+        iki_p50_ms,
+        iki_p75_ms,
+        iki_p95_ms,
+        iki_entropy,
+        iki_autocorrelation_lag1,
         hold_time_mean,
         burst_index,
         session_continuity: session_continuity.min(1.0), // Cap at 1.0
@@ -246,6 +534,8 @@ fn compute_keyboard_features(events: &[KeyboardEvent], window_duration: f64) ->
         typing_interaction_intensity,
         keyboard_scroll_rate,
         navigation_key_count,
+        modifier_active_tap_ratio,
+        pause_histogram,
     }
 }
 
@@ -270,8 +560,28 @@ fn compute_hold_times(events: &[&KeyboardEvent]) -> Vec<f64> {
     hold_times
 }
 
+/// Bucket the inter-key intervals longer than 500ms by length.
+///
+/// The buckets are fixed, independent of `pause_threshold_ms`, so histograms
+/// stay comparable across configurations.
+fn compute_pause_histogram(intervals: &[i64]) -> [u32; PAUSE_HISTOGRAM_BUCKETS] {
+    let mut histogram = [0; PAUSE_HISTOGRAM_BUCKETS];
+    for &interval in intervals.iter().filter(|&&i| i > 500) {
+        let bucket = PAUSE_HISTOGRAM_BOUNDS_MS
+            .iter()
+            .take_while(|&&bound| interval >= bound)
+            .count();
+        histogram[bucket] += 1;
+    }
+    histogram
+}
+
 /// Compute mouse features from a list of mouse events.
-fn compute_mouse_features(events: &[MouseEvent], window_duration: f64) -> MouseFeatures {
+fn compute_mouse_features(
+    events: &[MouseEvent],
+    window_duration: f64,
+    config: &FeatureConfig,
+) -> MouseFeatures {
     if events.is_empty() || window_duration <= 0.0 {
         return MouseFeatures::default();
     }
@@ -282,17 +592,9 @@ fn compute_mouse_features(events: &[MouseEvent], window_duration: f64) -> MouseF
         .filter(|e| e.event_type == MouseEventType::Move)
         .collect();
 
-    let click_events: Vec<&MouseEvent> = events
-        .iter()
-        .filter(|e| {
-            e.event_type == MouseEventType::LeftClick || e.event_type == MouseEventType::RightClick
-        })
-        .collect();
+    let click_events: Vec<&MouseEvent> = events.iter().filter(|e| e.is_click()).collect();
 
-    let scroll_events: Vec<&MouseEvent> = events
-        .iter()
-        .filter(|e| e.event_type == MouseEventType::Scroll)
-        .collect();
+    let scroll_events: Vec<&MouseEvent> = events.iter().filter(|e| e.is_scroll()).collect();
 
     // Mouse activity rate (movements per second)
     let mouse_activity_rate = move_events.len() as f64 / window_duration;
@@ -311,15 +613,49 @@ fn compute_mouse_features(events: &[MouseEvent], window_duration: f64) -> MouseF
 
     let velocity_variability = std_dev(&velocities);
 
+    // Velocity distribution shape (skew between precision work and fast sweeps)
+    let mut sorted_velocities = velocities.clone();
+    sorted_velocities.sort_by(|a, b| a.total_cmp(b));
+    let velocity_p25 = percentile(&sorted_velocities, 25.0);
+    let velocity_p50 = percentile(&sorted_velocities, 50.0);
+    let velocity_p75 = percentile(&sorted_velocities, 75.0);
+    let velocity_p95 = percentile(&sorted_velocities, 95.0);
+    let velocity_p99 = percentile(&sorted_velocities, 99.0);
+
     // Acceleration spikes (large changes in velocity)
     let acceleration_spikes = velocities
         .windows(2)
-        .filter(|pair| (pair[1] - pair[0]).abs() > ACCELERATION_SPIKE_THRESHOLD)
+        .filter(|pair| (pair[1] - pair[0]).abs() > config.acceleration_spike_threshold)
         .count() as u32;
 
     // Click and scroll rates
     let click_rate = click_events.len() as f64 / window_duration;
-    let scroll_rate = scroll_events.len() as f64 / window_duration;
+    let rate_of = |event_type: MouseEventType| {
+        events.iter().filter(|e| e.event_type == event_type).count() as f64 / window_duration
+    };
+    let middle_click_rate = rate_of(MouseEventType::MiddleClick);
+
+    // Wheel scrolling and trackpad swipes have separate rates
+    let scroll_rate = rate_of(MouseEventType::Scroll);
+    let trackpad_scroll_rate = rate_of(MouseEventType::TrackpadSwipe);
+    let trackpad_pinch_rate = rate_of(MouseEventType::TrackpadPinch);
+
+    // Reading (scroll-heavy) vs form filling (click-heavy)
+    let scroll_to_click_ratio = scroll_events.len() as f64 / click_events.len().max(1) as f64;
+    let dominant_modality = InputModality::from_counts(scroll_events.len(), click_events.len());
+
+    // Click hold duration (pairs presses with their releases)
+    let click_holds = compute_click_hold_times(events);
+    let click_hold_mean_ms = if click_holds.is_empty() {
+        0.0
+    } else {
+        click_holds.iter().sum::<f64>() / click_holds.len() as f64
+    };
+    let click_hold_variability = std_dev(&click_holds);
+
+    // Scroll direction reversals (reading confusion / search behavior)
+    let scroll_direction_reversals = count_scroll_reversals(&scroll_events);
+    let scroll_reversal_rate = scroll_direction_reversals as f64 / window_duration;
 
     // Idle metrics: estimate based on gaps in movement events
     let (idle_ratio, idle_time_ms, _has_long_gap) =
@@ -328,7 +664,7 @@ fn compute_mouse_features(events: &[MouseEvent], window_duration: f64) -> MouseF
     // Micro-adjustment ratio: small movements vs all movements
     let micro_count = velocities
         .iter()
-        .filter(|&&v| v < MICRO_ADJUSTMENT_THRESHOLD)
+        .filter(|&&v| v < config.micro_adjustment_threshold)
         .count();
     let micro_adjustment_ratio = if velocities.is_empty() {
         0.0
@@ -336,17 +672,108 @@ fn compute_mouse_features(events: &[MouseEvent], window_duration: f64) -> MouseF
         micro_count as f64 / velocities.len() as f64
     };
 
+    // Dragging: sustained movement with a button held, distinct from
+    // click-move-click sequences
+    let (drag_move_count, drag_episode_count) = count_drags(&move_events);
+    let drag_movement_ratio = if move_events.is_empty() {
+        0.0
+    } else {
+        drag_move_count as f64 / move_events.len() as f64
+    };
+
     MouseFeatures {
         mouse_activity_rate,
         mean_velocity,
         velocity_variability,
+        velocity_p25,
+        velocity_p50,
+        velocity_p75,
+        velocity_p95,
+        velocity_p99,
         acceleration_spikes,
         click_rate,
+        click_hold_mean_ms,
+        click_hold_variability,
         scroll_rate,
+        scroll_direction_reversals,
+        scroll_reversal_rate,
         idle_ratio,
         micro_adjustment_ratio,
         idle_time_ms,
+        drag_movement_ratio,
+        drag_episode_count,
+        middle_click_rate,
+        trackpad_scroll_rate,
+        trackpad_pinch_rate,
+        scroll_to_click_ratio,
+        dominant_modality,
+    }
+}
+
+/// Count drag movements and drag episodes among movement events.
+///
+/// An episode is a run of consecutive drag movements; any free movement in
+/// between ends it. Returns (drag movement count, episode count).
+fn count_drags(move_events: &[&MouseEvent]) -> (usize, u32) {
+    let mut drag_moves = 0;
+    let mut episodes = 0;
+    let mut in_drag = false;
+    for event in move_events {
+        if event.is_drag {
+            drag_moves += 1;
+            if !in_drag {
+                episodes += 1;
+            }
+        }
+        in_drag = event.is_drag;
     }
+    (drag_moves, episodes)
+}
+
+/// Pair button presses with releases to estimate click hold durations.
+///
+/// Releases whose press fell in an earlier window fall back to the hold
+/// duration recorded by the collector. Holds longer than 2 seconds are drags,
+/// not clicks, and are excluded.
+fn compute_click_hold_times(events: &[MouseEvent]) -> Vec<f64> {
+    let mut hold_times = Vec::new();
+    let mut pressed_at = [None, None];
+
+    for event in events {
+        match event.event_type {
+            MouseEventType::LeftClick => pressed_at[0] = Some(event.timestamp),
+            MouseEventType::RightClick => pressed_at[1] = Some(event.timestamp),
+            MouseEventType::LeftRelease | MouseEventType::RightRelease => {
+                let button = usize::from(event.event_type == MouseEventType::RightRelease);
+                let hold_ms = match pressed_at[button].take() {
+                    Some(down) => Some((event.timestamp - down).num_milliseconds() as f64),
+                    None => event.delta_magnitude,
+                };
+                if let Some(hold_ms) = hold_ms.filter(|ms| (0.0..=2000.0).contains(ms)) {
+                    hold_times.push(hold_ms);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    hold_times
+}
+
+/// Count consecutive scroll events that reverse direction within the reversal window.
+fn count_scroll_reversals(scroll_events: &[&MouseEvent]) -> u32 {
+    scroll_events
+        .windows(2)
+        .filter(|pair| {
+            let gap = (pair[1].timestamp - pair[0].timestamp).num_milliseconds();
+            match (pair[0].scroll_direction, pair[1].scroll_direction) {
+                (Some(prev), Some(next)) => {
+                    next == prev.opposite() && gap <= SCROLL_REVERSAL_WINDOW_MS
+                }
+                _ => false,
+            }
+        })
+        .count() as u32
 }
 
 /// Estimate idle metrics from movement event gaps.
@@ -384,31 +811,52 @@ fn estimate_idle_metrics(move_events: &[&MouseEvent], window_duration: f64) -> (
 }
 
 /// Compute derived behavioral signals from keyboard and mouse features.
+///
+/// Signals that combine both sources only use the part of a source that
+/// has events in the window, so an absent source does not pull them towards
+/// its empty-feature defaults. Sparse windows are weighted the same way and
+/// only get a lower confidence.
 fn compute_behavioral_signals(
     keyboard: &KeyboardFeatures,
     mouse: &MouseFeatures,
+    keyboard_events: usize,
+    mouse_events: usize,
 ) -> BehavioralSignals {
+    let (keyboard_weight, mouse_weight) = match (keyboard_events > 0, mouse_events > 0) {
+        (true, false) => (1.0, 0.0),
+        (false, true) => (0.0, 1.0),
+        // Without any events, both sets of defaults count equally
+        (true, true) | (false, false) => (1.0, 1.0),
+    };
+    let blend = |keyboard_part: f64, mouse_part: f64| {
+        (keyboard_part * keyboard_weight + mouse_part * mouse_weight)
+            / (keyboard_weight + mouse_weight)
+    };
+
     // Interaction rhythm: combines typing regularity and mouse consistency
     // Lower variability = more rhythmic
     let typing_rhythm = 1.0 / (1.0 + keyboard.latency_variability / 100.0);
     let mouse_rhythm = 1.0 / (1.0 + mouse.velocity_variability / 50.0);
-    let interaction_rhythm = (typing_rhythm + mouse_rhythm) / 2.0;
+    let interaction_rhythm = blend(typing_rhythm, mouse_rhythm);
 
     // Friction: indicates hesitation, uncertainty
     // High pause rate, low burst index, many micro-adjustments
-    let friction = (keyboard.pause_count as f64 * 0.1)
-        + (1.0 - keyboard.burst_index) * 0.3
-        + mouse.micro_adjustment_ratio * 0.3;
+    let friction = ((keyboard.pause_count as f64 * 0.1) + (1.0 - keyboard.burst_index) * 0.3)
+        * keyboard_weight
+        + mouse.micro_adjustment_ratio * 0.3 * mouse_weight;
 
     // Motor stability: consistency of physical movements
-    // Low variability in both keyboard and mouse
+    // Low variability in both keyboard and mouse (each source costs at most half)
     let motor_stability = 1.0
-        - (keyboard.latency_variability / 200.0).min(0.5)
-        - (mouse.velocity_variability / 100.0).min(0.5);
+        - 2.0
+            * blend(
+                (keyboard.latency_variability / 200.0).min(0.5),
+                (mouse.velocity_variability / 100.0).min(0.5),
+            );
 
     // Focus continuity proxy: sustained activity patterns
     // High session continuity, low idle ratio
-    let focus_continuity_proxy = keyboard.session_continuity * 0.5 + (1.0 - mouse.idle_ratio) * 0.5;
+    let focus_continuity_proxy = blend(keyboard.session_continuity, 1.0 - mouse.idle_ratio);
 
     // Burstiness: general measure of whether interactions occur in clusters or evenly
     // Combines keyboard burst_index with mouse activity patterns
@@ -421,7 +869,10 @@ fn compute_behavioral_signals(
     } else {
         0.0
     };
-    let burstiness = (keyboard_burstiness * 0.6 + mouse_burstiness * 0.4).clamp(0.0, 1.0);
+    let burstiness = ((keyboard_burstiness * 0.6 * keyboard_weight
+        + mouse_burstiness * 0.4 * mouse_weight)
+        / (0.6 * keyboard_weight + 0.4 * mouse_weight))
+        .clamp(0.0, 1.0);
 
     // Deep focus block detection:
     // - High session continuity (> 0.7) - sustained typing activity
@@ -432,6 +883,15 @@ fn compute_behavioral_signals(
     let minimal_idle = mouse.idle_ratio < 0.3;
     let deep_focus_block = has_activity && sustained_typing && minimal_idle;
 
+    // Sparse windows have too few events to trust either source
+    let data_quality = DataQuality::from_event_counts(keyboard_events, mouse_events);
+    let (keyboard_confidence, mouse_confidence) = match data_quality {
+        DataQuality::Full => (1.0, 1.0),
+        DataQuality::KeyboardOnly => (1.0, 0.0),
+        DataQuality::MouseOnly => (0.0, 1.0),
+        DataQuality::Sparse => (0.0, 0.0),
+    };
+
     BehavioralSignals {
         interaction_rhythm: interaction_rhythm.clamp(0.0, 1.0),
         friction: friction.clamp(0.0, 1.0),
@@ -439,6 +899,8 @@ fn compute_behavioral_signals(
         focus_continuity_proxy: focus_continuity_proxy.clamp(0.0, 1.0),
         burstiness,
         deep_focus_block,
+        keyboard_confidence,
+        mouse_confidence,
     }
 }
 
@@ -453,9 +915,78 @@ fn std_dev(values: &[f64]) -> f64 {
     variance.sqrt()
 }
 
+/// Compute the Shannon entropy (in bits) of inter-key intervals binned into
+/// 50ms buckets. Intervals beyond the last bin are counted in the last bin.
+fn histogram_entropy(intervals_ms: &[f64]) -> f64 {
+    if intervals_ms.is_empty() {
+        return 0.0;
+    }
+
+    let mut bins = [0u32; IKI_ENTROPY_BINS];
+    for &interval in intervals_ms {
+        let bin = ((interval.max(0.0) / IKI_ENTROPY_BIN_MS) as usize).min(IKI_ENTROPY_BINS - 1);
+        bins[bin] += 1;
+    }
+
+    let total = intervals_ms.len() as f64;
+    -bins
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            p * p.log2()
+        })
+        .sum::<f64>()
+}
+
+/// Compute the Pearson correlation between consecutive values (lag-1 autocorrelation).
+///
+/// Returns 0.0 when there are fewer than two pairs or either side has no variance.
+fn lag1_autocorrelation(values: &[f64]) -> f64 {
+    if values.len() < 3 {
+        return 0.0;
+    }
+
+    let x = &values[..values.len() - 1];
+    let y = &values[1..];
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (&a, &b) in x.iter().zip(y) {
+        covariance += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x).powi(2);
+        var_y += (b - mean_y).powi(2);
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    (covariance / (var_x.sqrt() * var_y.sqrt())).clamp(-1.0, 1.0)
+}
+
+/// Compute a percentile (0-100) of an ascending-sorted slice using linear interpolation.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        len => {
+            let rank = (pct / 100.0).clamp(0.0, 1.0) * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            let fraction = rank - lower as f64;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collector::types::{Platform, SensorEvent};
     use chrono::{Duration, Utc};
 
     fn make_keyboard_event(is_down: bool, offset_ms: i64) -> KeyboardEvent {
@@ -463,6 +994,9 @@ mod tests {
             timestamp: Utc::now() + Duration::milliseconds(offset_ms),
             is_key_down: is_down,
             event_type: KeyboardEventType::TypingTap,
+            modifier_active: false,
+            is_modifier_key: false,
+            source_platform: Platform::Unknown,
         }
     }
 
@@ -471,12 +1005,15 @@ mod tests {
             timestamp: Utc::now() + Duration::milliseconds(offset_ms),
             is_key_down: is_down,
             event_type: KeyboardEventType::NavigationKey,
+            modifier_active: false,
+            is_modifier_key: false,
+            source_platform: Platform::Unknown,
         }
     }
 
     #[test]
     fn test_keyboard_features_empty() {
-        let features = compute_keyboard_features(&[], 10.0);
+        let features = compute_keyboard_features(&[], 10.0, &FeatureConfig::default());
         assert_eq!(features.typing_rate, 0.0);
     }
 
@@ -491,7 +1028,7 @@ mod tests {
             make_keyboard_event(false, 250),
         ];
 
-        let features = compute_keyboard_features(&events, 1.0);
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
         assert_eq!(features.typing_rate, 3.0); // 3 key presses in 1 second
     }
 
@@ -502,11 +1039,94 @@ mod tests {
         assert!((sd - 2.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50.0), 0.0);
+        assert_eq!(percentile(&[42.0], 95.0), 42.0);
+
+        let values = vec![10.0, 20.0, 30.0, 40.0, 50.0];
+        assert_eq!(percentile(&values, 0.0), 10.0);
+        assert_eq!(percentile(&values, 25.0), 20.0);
+        assert_eq!(percentile(&values, 50.0), 30.0);
+        assert_eq!(percentile(&values, 100.0), 50.0);
+        assert!((percentile(&values, 95.0) - 48.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_iki_percentiles() {
+        // Key-down intervals: 100, 200, 300, 400ms
+        let events = vec![
+            make_keyboard_event(true, 0),
+            make_keyboard_event(true, 100),
+            make_keyboard_event(true, 300),
+            make_keyboard_event(true, 600),
+            make_keyboard_event(true, 1000),
+        ];
+
+        let features = compute_keyboard_features(&events, 2.0, &FeatureConfig::default());
+        assert_eq!(features.iki_p25_ms, 175.0);
+        assert_eq!(features.iki_p50_ms, 250.0);
+        assert_eq!(features.iki_p75_ms, 325.0);
+        assert!((features.iki_p95_ms - 385.0).abs() < 1e-9);
+        assert!(features.iki_p25_ms <= features.iki_p50_ms);
+        assert!(features.iki_p75_ms <= features.iki_p95_ms);
+    }
+
+    #[test]
+    fn test_iki_entropy_uniform_vs_random() {
+        // Perfectly uniform 120ms intervals all land in one bin
+        let uniform: Vec<KeyboardEvent> = (0..20)
+            .map(|i| make_keyboard_event(true, i * 120))
+            .collect();
+        let features = compute_keyboard_features(&uniform, 5.0, &FeatureConfig::default());
+        assert!(features.iki_entropy < 0.1);
+
+        // Pseudo-random intervals spread across the 0-2000ms range
+        let mut offset = 0;
+        let mut seed: i64 = 7;
+        let random: Vec<KeyboardEvent> = (0..60)
+            .map(|_| {
+                seed = (seed * 1_103_515_245 + 12_345) % 2_147_483_648;
+                offset += 10 + seed % 1_900;
+                make_keyboard_event(true, offset)
+            })
+            .collect();
+        let features = compute_keyboard_features(&random, 60.0, &FeatureConfig::default());
+        let max_entropy = (IKI_ENTROPY_BINS as f64).log2();
+        assert!(features.iki_entropy > 0.7 * max_entropy);
+        assert!(features.iki_entropy <= max_entropy);
+    }
+
+    #[test]
+    fn test_iki_autocorrelation() {
+        // Monotonically increasing intervals: 50, 100, 150, ... ms
+        let mut offset = 0;
+        let increasing: Vec<KeyboardEvent> = (0..10)
+            .map(|i| {
+                offset += i * 50;
+                make_keyboard_event(true, offset)
+            })
+            .collect();
+        let features = compute_keyboard_features(&increasing, 5.0, &FeatureConfig::default());
+        assert!(features.iki_autocorrelation_lag1 > 0.5);
+
+        // Alternating short/long intervals: 50, 300, 50, 300, ... ms
+        let mut offset = 0;
+        let alternating: Vec<KeyboardEvent> = (0..10)
+            .map(|i| {
+                offset += if i % 2 == 0 { 50 } else { 300 };
+                make_keyboard_event(true, offset)
+            })
+            .collect();
+        let features = compute_keyboard_features(&alternating, 5.0, &FeatureConfig::default());
+        assert!(features.iki_autocorrelation_lag1 < -0.5);
+    }
+
     #[test]
     fn test_behavioral_signals_bounds() {
         let keyboard = KeyboardFeatures::default();
         let mouse = MouseFeatures::default();
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
 
         // All signals should be between 0 and 1
         assert!(signals.interaction_rhythm >= 0.0 && signals.interaction_rhythm <= 1.0);
@@ -526,14 +1146,14 @@ mod tests {
             make_keyboard_event(false, 250),
         ];
 
-        let features = compute_keyboard_features(&events, 1.0);
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
         assert_eq!(features.typing_tap_count, 3); // 3 key presses
     }
 
     #[test]
     fn test_typing_cadence_stability_bounds() {
         // Empty events should give default (which uses 0 variability)
-        let features_empty = compute_keyboard_features(&[], 10.0);
+        let features_empty = compute_keyboard_features(&[], 10.0, &FeatureConfig::default());
         assert!(
             features_empty.typing_cadence_stability >= 0.0
                 && features_empty.typing_cadence_stability <= 1.0
@@ -548,7 +1168,7 @@ mod tests {
             make_keyboard_event(true, 200),
             make_keyboard_event(false, 250),
         ];
-        let features = compute_keyboard_features(&events, 1.0);
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
         assert!(
             features.typing_cadence_stability >= 0.0 && features.typing_cadence_stability <= 1.0
         );
@@ -558,7 +1178,7 @@ mod tests {
 
     #[test]
     fn test_typing_gap_ratio_bounds() {
-        let features_empty = compute_keyboard_features(&[], 10.0);
+        let features_empty = compute_keyboard_features(&[], 10.0, &FeatureConfig::default());
         assert_eq!(features_empty.typing_gap_ratio, 0.0);
 
         // Fast typing with no pauses
@@ -568,7 +1188,7 @@ mod tests {
             make_keyboard_event(true, 100),
             make_keyboard_event(false, 150),
         ];
-        let features = compute_keyboard_features(&events, 1.0);
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
         assert!(features.typing_gap_ratio >= 0.0 && features.typing_gap_ratio <= 1.0);
         assert_eq!(features.typing_gap_ratio, 0.0); // No gaps in fast typing
 
@@ -579,13 +1199,54 @@ mod tests {
             make_keyboard_event(true, 600), // 600ms gap = pause
             make_keyboard_event(false, 650),
         ];
-        let features_gaps = compute_keyboard_features(&events_with_gaps, 1.0);
+        let features_gaps =
+            compute_keyboard_features(&events_with_gaps, 1.0, &FeatureConfig::default());
         assert!(features_gaps.typing_gap_ratio > 0.0); // Should have gaps
     }
 
+    #[test]
+    fn test_pause_threshold_is_configurable() {
+        let events = vec![
+            make_keyboard_event(true, 0),
+            make_keyboard_event(false, 50),
+            make_keyboard_event(true, 300), // 300ms gap
+            make_keyboard_event(false, 350),
+        ];
+
+        // Not a pause under the default 500ms threshold
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.pause_count, 0);
+
+        // A pause under a tighter 200ms threshold
+        let features = compute_keyboard_features(
+            &events,
+            1.0,
+            &FeatureConfig {
+                pause_threshold_ms: 200,
+                ..FeatureConfig::default()
+            },
+        );
+        assert_eq!(features.pause_count, 1);
+        assert_eq!(features.mean_pause_ms, 300.0);
+    }
+
+    #[test]
+    fn test_pause_histogram() {
+        let mut events = Vec::new();
+        let mut at = 0;
+        for gap in [100, 600, 800, 1500, 3000, 6000, 300] {
+            at += gap;
+            events.push(make_keyboard_event(true, at));
+        }
+
+        let features = compute_keyboard_features(&events, 20.0, &FeatureConfig::default());
+        assert_eq!(features.pause_histogram, [1, 1, 1, 1, 1]);
+        assert_eq!(features.pause_count, 5);
+    }
+
     #[test]
     fn test_typing_interaction_intensity_bounds() {
-        let features_empty = compute_keyboard_features(&[], 10.0);
+        let features_empty = compute_keyboard_features(&[], 10.0, &FeatureConfig::default());
         assert!(
             features_empty.typing_interaction_intensity >= 0.0
                 && features_empty.typing_interaction_intensity <= 1.0
@@ -604,7 +1265,7 @@ mod tests {
             make_keyboard_event(true, 240),
             make_keyboard_event(false, 270),
         ];
-        let features = compute_keyboard_features(&fast_events, 1.0);
+        let features = compute_keyboard_features(&fast_events, 1.0, &FeatureConfig::default());
         assert!(
             features.typing_interaction_intensity >= 0.0
                 && features.typing_interaction_intensity <= 1.0
@@ -627,7 +1288,7 @@ mod tests {
             make_navigation_event(false, 350), // navigation
         ];
 
-        let features = compute_keyboard_features(&events, 1.0);
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
 
         // Should only count typing key presses (2 typing events)
         assert_eq!(features.typing_tap_count, 2);
@@ -650,7 +1311,7 @@ mod tests {
             make_navigation_event(false, 250),
         ];
 
-        let features = compute_keyboard_features(&nav_only_events, 1.0);
+        let features = compute_keyboard_features(&nav_only_events, 1.0, &FeatureConfig::default());
 
         // Typing metrics should be zero
         assert_eq!(features.typing_tap_count, 0);
@@ -661,9 +1322,53 @@ mod tests {
         assert_eq!(features.keyboard_scroll_rate, 3.0);
     }
 
+    #[test]
+    fn test_modifier_active_tap_ratio() {
+        let events = vec![
+            make_keyboard_event(true, 0).with_modifier_active(true),
+            make_keyboard_event(false, 50).with_modifier_active(true),
+            make_keyboard_event(true, 200),
+            make_keyboard_event(true, 400),
+            make_keyboard_event(true, 600),
+            // Navigation keys with modifiers (e.g. Shift+Arrow) are not typing taps
+            make_navigation_event(true, 800).with_modifier_active(true),
+        ];
+
+        let features = compute_keyboard_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.typing_tap_count, 4);
+        assert!((features.modifier_active_tap_ratio - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_modifier_key_events_not_counted_as_taps() {
+        let at = |event: KeyboardEvent, offset_ms: i64| KeyboardEvent {
+            timestamp: Utc::now() + Duration::milliseconds(offset_ms),
+            ..event
+        };
+
+        // Shift+A as the macOS collector reports it: FlagsChanged on press
+        // (Shift held), the letter with Shift held, FlagsChanged on release
+        let shift_a = vec![
+            at(KeyboardEvent::modifier_key(true), 0),
+            at(KeyboardEvent::new(true).with_modifier_active(true), 100),
+            at(KeyboardEvent::new(false).with_modifier_active(true), 150),
+            at(KeyboardEvent::modifier_key(false), 250),
+        ];
+        let features = compute_keyboard_features(&shift_a, 1.0, &FeatureConfig::default());
+        assert_eq!(features.modifier_active_tap_ratio, 1.0);
+
+        // A lone Shift tap has no taps made with a modifier held
+        let lone_shift = vec![
+            at(KeyboardEvent::modifier_key(true), 0),
+            at(KeyboardEvent::modifier_key(false), 100),
+        ];
+        let features = compute_keyboard_features(&lone_shift, 1.0, &FeatureConfig::default());
+        assert_eq!(features.modifier_active_tap_ratio, 0.0);
+    }
+
     #[test]
     fn test_keyboard_scroll_rate_bounds() {
-        let features_empty = compute_keyboard_features(&[], 10.0);
+        let features_empty = compute_keyboard_features(&[], 10.0, &FeatureConfig::default());
         assert_eq!(features_empty.keyboard_scroll_rate, 0.0);
         assert_eq!(features_empty.navigation_key_count, 0);
 
@@ -676,7 +1381,7 @@ mod tests {
             make_navigation_event(true, 120),
             make_navigation_event(false, 150),
         ];
-        let features = compute_keyboard_features(&nav_events, 1.0);
+        let features = compute_keyboard_features(&nav_events, 1.0, &FeatureConfig::default());
         assert_eq!(features.navigation_key_count, 3);
         assert!(features.keyboard_scroll_rate > 0.0);
     }
@@ -685,7 +1390,7 @@ mod tests {
     fn test_burstiness_bounds() {
         let keyboard = KeyboardFeatures::default();
         let mouse = MouseFeatures::default();
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
 
         // Burstiness should be between 0 and 1
         assert!(signals.burstiness >= 0.0 && signals.burstiness <= 1.0);
@@ -700,7 +1405,7 @@ mod tests {
         };
 
         let mouse = MouseFeatures::default();
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
 
         // Should have elevated burstiness
         assert!(signals.burstiness > 0.4);
@@ -712,7 +1417,7 @@ mod tests {
         // Default (empty) features should NOT be deep focus
         let keyboard = KeyboardFeatures::default();
         let mouse = MouseFeatures::default();
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
         assert!(!signals.deep_focus_block);
 
         // High continuity, low idle, some activity = deep focus
@@ -728,7 +1433,7 @@ mod tests {
             ..Default::default()
         };
 
-        let signals_active = compute_behavioral_signals(&keyboard_active, &mouse_active);
+        let signals_active = compute_behavioral_signals(&keyboard_active, &mouse_active, 10, 10);
         assert!(signals_active.deep_focus_block);
     }
 
@@ -746,7 +1451,7 @@ mod tests {
             ..Default::default()
         };
 
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
         assert!(!signals.deep_focus_block);
     }
 
@@ -763,6 +1468,8 @@ mod tests {
                 delta_magnitude: Some(10.0),
                 scroll_direction: None,
                 scroll_magnitude: None,
+                is_drag: false,
+                source_platform: Platform::Unknown,
             },
             MouseEvent {
                 timestamp: base_time + chrono::Duration::milliseconds(500),
@@ -770,6 +1477,8 @@ mod tests {
                 delta_magnitude: Some(10.0),
                 scroll_direction: None,
                 scroll_magnitude: None,
+                is_drag: false,
+                source_platform: Platform::Unknown,
             },
             MouseEvent {
                 timestamp: base_time + chrono::Duration::milliseconds(2000), // 1500ms gap
@@ -777,16 +1486,176 @@ mod tests {
                 delta_magnitude: Some(10.0),
                 scroll_direction: None,
                 scroll_magnitude: None,
+                is_drag: false,
+                source_platform: Platform::Unknown,
             },
         ];
 
-        let features = compute_mouse_features(&events, 2.0);
+        let features = compute_mouse_features(&events, 2.0, &FeatureConfig::default());
 
         // Should have some idle time (gap of 1500ms, 500ms over threshold)
         assert!(features.idle_time_ms > 0);
         assert!(features.idle_ratio > 0.0);
     }
 
+    #[test]
+    fn test_mouse_velocity_percentiles() {
+        let base_time = chrono::Utc::now();
+        let events: Vec<MouseEvent> = [1.0, 2.0, 3.0, 4.0, 100.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &magnitude)| MouseEvent {
+                timestamp: base_time + chrono::Duration::milliseconds(i as i64 * 100),
+                event_type: MouseEventType::Move,
+                delta_magnitude: Some(magnitude),
+                scroll_direction: None,
+                scroll_magnitude: None,
+                is_drag: false,
+                source_platform: Platform::Unknown,
+            })
+            .collect();
+
+        let features = compute_mouse_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.velocity_p25, 2.0);
+        assert_eq!(features.velocity_p50, 3.0);
+        assert_eq!(features.velocity_p75, 4.0);
+        // A single fast sweep dominates the tail but not the median
+        assert!(features.velocity_p95 > 50.0);
+        assert!(features.velocity_p99 >= features.velocity_p95);
+        assert!(features.velocity_p99 <= 100.0);
+        assert!(features.mean_velocity > features.velocity_p50);
+    }
+
+    #[test]
+    fn test_drag_features() {
+        let base_time = chrono::Utc::now();
+        let at = |offset_ms: i64, event: MouseEvent| MouseEvent {
+            timestamp: base_time + chrono::Duration::milliseconds(offset_ms),
+            ..event
+        };
+        let drag = || MouseEvent::movement(5.0, 0.0).with_drag(true);
+
+        let events = vec![
+            at(0, MouseEvent::movement(5.0, 0.0)),
+            at(100, MouseEvent::click(true)),
+            at(150, drag()),
+            at(200, drag()),
+            at(250, MouseEvent::click_release(true)),
+            at(300, MouseEvent::movement(5.0, 0.0)),
+            // Click-move-click is not a drag
+            at(400, MouseEvent::click(true)),
+            at(450, MouseEvent::click_release(true)),
+            at(500, MouseEvent::movement(5.0, 0.0)),
+            at(600, drag()),
+        ];
+
+        let features = compute_mouse_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.drag_episode_count, 2);
+        assert!((features.drag_movement_ratio - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trackpad_rates() {
+        let events = vec![
+            MouseEvent::scroll(0.0, 5.0),
+            MouseEvent::trackpad_swipe(0.0, 5.0),
+            MouseEvent::trackpad_swipe(0.0, 5.0),
+            MouseEvent::trackpad_pinch(0.5),
+        ];
+
+        let features = compute_mouse_features(&events, 2.0, &FeatureConfig::default());
+        assert_eq!(features.scroll_rate, 0.5);
+        assert_eq!(features.trackpad_scroll_rate, 1.0);
+        assert_eq!(features.trackpad_pinch_rate, 0.5);
+    }
+
+    #[test]
+    fn test_scroll_to_click_ratio() {
+        let scrolls = vec![MouseEvent::scroll(0.0, 5.0); 6];
+        let features = compute_mouse_features(&scrolls, 1.0, &FeatureConfig::default());
+        // No clicks: divided by 1 instead of 0
+        assert_eq!(features.scroll_to_click_ratio, 6.0);
+        assert_eq!(features.dominant_modality, InputModality::Scrolling);
+
+        let mut events = vec![MouseEvent::click(true); 4];
+        events.push(MouseEvent::scroll(0.0, 5.0));
+        let features = compute_mouse_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.scroll_to_click_ratio, 0.25);
+        assert_eq!(features.dominant_modality, InputModality::Clicking);
+
+        assert_eq!(InputModality::from_counts(3, 2), InputModality::Balanced);
+        assert_eq!(InputModality::from_counts(0, 0), InputModality::Balanced);
+    }
+
+    #[test]
+    fn test_middle_click_rate() {
+        let events = vec![
+            MouseEvent::click(true),
+            MouseEvent::middle_click(),
+            MouseEvent::middle_click(),
+        ];
+
+        let features = compute_mouse_features(&events, 2.0, &FeatureConfig::default());
+        assert_eq!(features.middle_click_rate, 1.0);
+        // Middle clicks are tracked separately from left/right clicks
+        assert_eq!(features.click_rate, 0.5);
+    }
+
+    #[test]
+    fn test_scroll_direction_reversals() {
+        use crate::collector::types::ScrollDirection;
+
+        let base_time = chrono::Utc::now();
+        let scroll = |offset_ms: i64, direction: ScrollDirection| MouseEvent {
+            timestamp: base_time + chrono::Duration::milliseconds(offset_ms),
+            event_type: MouseEventType::Scroll,
+            delta_magnitude: None,
+            scroll_direction: Some(direction),
+            scroll_magnitude: None,
+            is_drag: false,
+            source_platform: Platform::Unknown,
+        };
+
+        let events = vec![
+            scroll(0, ScrollDirection::Down),
+            scroll(100, ScrollDirection::Down),
+            scroll(300, ScrollDirection::Up),     // reversal
+            scroll(500, ScrollDirection::Down),   // reversal
+            scroll(1500, ScrollDirection::Up),    // too slow, not a reversal
+            scroll(1600, ScrollDirection::Right), // different axis
+        ];
+
+        let features = compute_mouse_features(&events, 2.0, &FeatureConfig::default());
+        assert_eq!(features.scroll_direction_reversals, 2);
+        assert_eq!(features.scroll_reversal_rate, 1.0);
+    }
+
+    #[test]
+    fn test_click_hold_times() {
+        let base_time = chrono::Utc::now();
+        let at = |offset_ms: i64, event: MouseEvent| MouseEvent {
+            timestamp: base_time + chrono::Duration::milliseconds(offset_ms),
+            ..event
+        };
+
+        let events = vec![
+            // Release whose press was in the previous window
+            at(
+                0,
+                MouseEvent::click_release(true).with_hold_duration_ms(150.0),
+            ),
+            at(100, MouseEvent::click(true)),
+            at(200, MouseEvent::click_release(true)),
+            at(300, MouseEvent::click(false)),
+            at(350, MouseEvent::click_release(false)),
+        ];
+
+        let features = compute_mouse_features(&events, 1.0, &FeatureConfig::default());
+        assert_eq!(features.click_rate, 2.0); // Releases are not clicks
+        assert_eq!(features.click_hold_mean_ms, 100.0);
+        assert!(features.click_hold_variability > 0.0);
+    }
+
     #[test]
     fn test_behavioral_signals_new_fields_bounds() {
         // Test that all new behavioral signals are properly bounded
@@ -803,7 +1672,7 @@ mod tests {
             ..Default::default()
         };
 
-        let signals = compute_behavioral_signals(&keyboard, &mouse);
+        let signals = compute_behavioral_signals(&keyboard, &mouse, 10, 10);
 
         // All signals should be bounded 0-1
         assert!(signals.interaction_rhythm >= 0.0 && signals.interaction_rhythm <= 1.0);
@@ -813,4 +1682,112 @@ mod tests {
         assert!(signals.burstiness >= 0.0 && signals.burstiness <= 1.0);
         // deep_focus_block is a boolean, no bounds check needed
     }
+
+    #[test]
+    fn test_data_quality_from_event_counts() {
+        assert_eq!(DataQuality::from_event_counts(0, 0), DataQuality::Sparse);
+        assert_eq!(DataQuality::from_event_counts(5, 4), DataQuality::Sparse);
+        assert_eq!(
+            DataQuality::from_event_counts(20, 0),
+            DataQuality::KeyboardOnly
+        );
+        assert_eq!(
+            DataQuality::from_event_counts(0, 20),
+            DataQuality::MouseOnly
+        );
+        assert_eq!(DataQuality::from_event_counts(10, 10), DataQuality::Full);
+    }
+
+    #[test]
+    fn test_single_source_windows() {
+        let start = Utc::now();
+        let mut keyboard_window = EventWindow::new(start, Duration::seconds(10));
+        for i in 0..20 {
+            keyboard_window.add_event(SensorEvent::Keyboard(make_keyboard_event(
+                i % 2 == 0,
+                i * 150,
+            )));
+        }
+        let features = compute_features(&keyboard_window, &FeatureConfig::default());
+        assert_eq!(features.data_quality, Some(DataQuality::KeyboardOnly));
+        let signals = &features.behavioral;
+        assert_eq!(signals.keyboard_confidence, 1.0);
+        assert_eq!(signals.mouse_confidence, 0.0);
+        // The missing mouse must not count as zero idle time
+        assert!(
+            (signals.focus_continuity_proxy - features.keyboard.session_continuity).abs() < 1e-9
+        );
+
+        let mut mouse_window = EventWindow::new(start, Duration::seconds(10));
+        for i in 0..20 {
+            let mut event = MouseEvent::movement(30.0, 40.0);
+            event.timestamp = start + Duration::milliseconds(i * 100);
+            mouse_window.add_event(SensorEvent::Mouse(event));
+        }
+        let features = compute_features(&mouse_window, &FeatureConfig::default());
+        assert_eq!(features.data_quality, Some(DataQuality::MouseOnly));
+        let signals = &features.behavioral;
+        assert_eq!(signals.keyboard_confidence, 0.0);
+        assert_eq!(signals.mouse_confidence, 1.0);
+        // The missing keyboard must not count as zero burst index
+        assert!(signals.friction < 0.3);
+        for value in [
+            signals.interaction_rhythm,
+            signals.friction,
+            signals.motor_stability,
+            signals.focus_continuity_proxy,
+            signals.burstiness,
+        ] {
+            assert!(value.is_finite() && (0.0..=1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_sparse_single_source_window() {
+        let start = Utc::now();
+        let mut window = EventWindow::new(start, Duration::seconds(10));
+        for i in 0..4 {
+            window.add_event(SensorEvent::Keyboard(make_keyboard_event(
+                i % 2 == 0,
+                i * 150,
+            )));
+        }
+        let features = compute_features(&window, &FeatureConfig::default());
+        assert_eq!(features.data_quality, Some(DataQuality::Sparse));
+        let signals = &features.behavioral;
+        assert_eq!(signals.keyboard_confidence, 0.0);
+        assert_eq!(signals.mouse_confidence, 0.0);
+        // Still keyboard-only, so the missing mouse is left out of the blend
+        assert!(
+            (signals.focus_continuity_proxy - features.keyboard.session_continuity).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_compute_features_parallel_matches_sequential() {
+        let start = Utc::now();
+        let windows: Vec<EventWindow> = [10, 60, 300]
+            .into_iter()
+            .map(|secs| {
+                let mut window = EventWindow::new(start, Duration::seconds(secs));
+                for i in 0..secs {
+                    let event = make_keyboard_event(true, i * 150);
+                    window.add_event(SensorEvent::Keyboard(event));
+                }
+                window
+            })
+            .collect();
+        let window_refs: Vec<&EventWindow> = windows.iter().collect();
+        let config = FeatureConfig {
+            parallel: true,
+            ..FeatureConfig::default()
+        };
+
+        let parallel = compute_features_parallel(&window_refs, &config);
+        assert_eq!(parallel.len(), windows.len());
+        for (window, features) in windows.iter().zip(&parallel) {
+            let sequential = compute_features(window, &config);
+            crate::assert_features_approx_eq!(features, &sequential, 0.0);
+        }
+    }
 }
@@ -0,0 +1,184 @@
+//! Cross-window deep focus block detection.
+//!
+//! A deep focus block is a run of consecutive windows with sustained typing
+//! (`session_continuity > 0.8`) and almost no idle time (`idle_ratio < 0.1`)
+//! lasting at least 120 seconds.
+
+use crate::core::hsi::HsiSnapshot;
+use chrono::DateTime;
+
+/// Minimum session continuity for a window to extend a deep focus block.
+const MIN_SESSION_CONTINUITY: f64 = 0.8;
+
+/// Maximum idle ratio for a window to extend a deep focus block.
+const MAX_IDLE_RATIO: f64 = 0.1;
+
+/// Minimum streak length for a run of windows to count as a deep focus block.
+pub const DEEP_FOCUS_MIN_SECS: f64 = 120.0;
+
+/// Tracks deep focus streaks across consecutive snapshots.
+///
+/// Snapshots must be fed in order and from a single window resolution.
+#[derive(Debug, Clone, Default)]
+pub struct DeepFocusTracker {
+    /// Length of the current qualifying streak in seconds
+    current_deep_focus_block_secs: f64,
+    /// Number of streaks that reached the minimum length and then ended
+    deep_focus_blocks_completed: u32,
+}
+
+impl DeepFocusTracker {
+    /// Create a new tracker with no streak in progress.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the streak with the next completed snapshot.
+    pub fn observe(&mut self, snapshot: &HsiSnapshot) {
+        let is_session_start = snapshot
+            .meta
+            .as_ref()
+            .and_then(|m| m.get("is_session_start"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // A new session never continues the previous streak
+        if is_session_start {
+            self.end_block();
+        }
+
+        let continuity = snapshot.axis_score("session_continuity").unwrap_or(0.0);
+        let idle_ratio = snapshot.axis_score("idle_ratio").unwrap_or(1.0);
+
+        if continuity > MIN_SESSION_CONTINUITY && idle_ratio < MAX_IDLE_RATIO {
+            self.current_deep_focus_block_secs += snapshot_duration_secs(snapshot);
+        } else {
+            self.end_block();
+        }
+    }
+
+    /// Annotate snapshot metadata with the current block state.
+    pub fn annotate(&self, snapshot: &mut HsiSnapshot) {
+        let meta = snapshot.meta.get_or_insert_with(Default::default);
+        meta.insert(
+            "deep_focus_block_secs".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(self.current_deep_focus_block_secs)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "in_deep_focus_block".to_string(),
+            serde_json::Value::Bool(self.in_deep_focus_block()),
+        );
+        meta.insert(
+            "deep_focus_blocks_completed".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(self.deep_focus_blocks_completed)),
+        );
+    }
+
+    /// Length of the current qualifying streak in seconds.
+    pub fn current_deep_focus_block_secs(&self) -> f64 {
+        self.current_deep_focus_block_secs
+    }
+
+    /// Number of deep focus blocks that have completed.
+    pub fn deep_focus_blocks_completed(&self) -> u32 {
+        self.deep_focus_blocks_completed
+    }
+
+    /// Whether the current streak is long enough to be a deep focus block.
+    pub fn in_deep_focus_block(&self) -> bool {
+        self.current_deep_focus_block_secs >= DEEP_FOCUS_MIN_SECS
+    }
+
+    /// End the current streak, counting it if it qualified.
+    fn end_block(&mut self) {
+        if self.in_deep_focus_block() {
+            self.deep_focus_blocks_completed += 1;
+        }
+        self.current_deep_focus_block_secs = 0.0;
+    }
+}
+
+/// Get the duration of a snapshot's first window in seconds.
+fn snapshot_duration_secs(snapshot: &HsiSnapshot) -> f64 {
+    snapshot
+        .window_ids
+        .first()
+        .and_then(|id| snapshot.windows.get(id))
+        .and_then(|w| {
+            let start = DateTime::parse_from_rfc3339(&w.start).ok()?;
+            let end = DateTime::parse_from_rfc3339(&w.end).ok()?;
+            Some((end - start).num_milliseconds() as f64 / 1000.0)
+        })
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::WindowFeatures;
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use chrono::{Duration, Utc};
+
+    fn snapshot(continuity: f64, idle_ratio: f64, is_session_start: bool) -> HsiSnapshot {
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(60));
+        window.is_session_start = is_session_start;
+        let mut features = WindowFeatures::default();
+        features.keyboard.session_continuity = continuity;
+        features.mouse.idle_ratio = idle_ratio;
+        HsiBuilder::new().build(&window, &features, Duration::seconds(60), None)
+    }
+
+    #[test]
+    fn test_streak_becomes_block() {
+        let mut tracker = DeepFocusTracker::new();
+
+        tracker.observe(&snapshot(0.9, 0.05, true));
+        assert_eq!(tracker.current_deep_focus_block_secs(), 60.0);
+        assert!(!tracker.in_deep_focus_block());
+
+        tracker.observe(&snapshot(0.9, 0.05, false));
+        assert!(tracker.in_deep_focus_block());
+
+        // A distracted window ends the block
+        tracker.observe(&snapshot(0.3, 0.5, false));
+        assert_eq!(tracker.current_deep_focus_block_secs(), 0.0);
+        assert_eq!(tracker.deep_focus_blocks_completed(), 1);
+    }
+
+    #[test]
+    fn test_short_streak_is_not_counted() {
+        let mut tracker = DeepFocusTracker::new();
+
+        tracker.observe(&snapshot(0.9, 0.05, false));
+        tracker.observe(&snapshot(0.9, 0.5, false));
+        assert_eq!(tracker.deep_focus_blocks_completed(), 0);
+    }
+
+    #[test]
+    fn test_session_start_breaks_streak() {
+        let mut tracker = DeepFocusTracker::new();
+
+        tracker.observe(&snapshot(0.9, 0.05, false));
+        tracker.observe(&snapshot(0.9, 0.05, false));
+        tracker.observe(&snapshot(0.9, 0.05, true));
+        assert_eq!(tracker.deep_focus_blocks_completed(), 1);
+        assert_eq!(tracker.current_deep_focus_block_secs(), 60.0);
+    }
+
+    #[test]
+    fn test_annotate_meta() {
+        let mut tracker = DeepFocusTracker::new();
+        let mut snap = snapshot(0.9, 0.05, false);
+        tracker.observe(&snap);
+        tracker.annotate(&mut snap);
+
+        let meta = snap.meta.unwrap();
+        assert_eq!(meta["deep_focus_block_secs"], serde_json::json!(60.0));
+        assert_eq!(meta["in_deep_focus_block"], serde_json::json!(false));
+        assert_eq!(meta["deep_focus_blocks_completed"], serde_json::json!(0));
+    }
+}
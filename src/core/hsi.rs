@@ -3,11 +3,23 @@
 //! This module creates JSON snapshots according to the HSI 1.0 specification.
 //! Each snapshot represents a single time window of behavioral data.
 
-use crate::core::features::WindowFeatures;
+use crate::collector::types::Platform;
+use crate::core::axes::{
+    axis_input_source, window_quality, AxisDomain, AxisProvider, DefaultBehaviorAxisProvider,
+    DefaultEngagementAxisProvider, InputSource,
+};
+use crate::core::delta::FeatureDelta;
+use crate::core::features::{DataQuality, FeatureConfig, WindowFeatures};
+use crate::core::focus::DeepFocusTracker;
+use crate::core::privacy::DifferentialPrivacyConfig;
+use crate::core::validator::{check_reading, missing, validate_snapshot, ValidationError};
 use crate::core::windowing::EventWindow;
-use chrono::Utc;
+use crate::signing::{SnapshotSigner, SIGNATURE_META_KEY};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// The current HSI format version.
@@ -16,6 +28,58 @@ pub const HSI_VERSION: &str = "1.0";
 /// The name of this producer.
 pub const PRODUCER_NAME: &str = "synheart-sensor-agent";
 
+/// Key of the raw event window in snapshot `meta`.
+pub const RAW_EVENTS_META_KEY: &str = "raw_events";
+
+/// Window `meta` keys holding exact feature values or events, which are
+/// left out when differential privacy is enabled.
+pub const EXACT_VALUE_META_KEYS: &[&str] = &[
+    "keyboard_events",
+    "mouse_events",
+    "raw_typing_rate",
+    "pause_count",
+    "raw_mouse_activity_rate",
+    "raw_mean_velocity",
+    "raw_click_rate",
+    "typing_tap_count",
+    "navigation_key_count",
+    "pause_histogram",
+    "keyboard_scroll_rate",
+    "idle_time_ms",
+    "burstiness",
+    "feature_delta",
+    RAW_EVENTS_META_KEY,
+];
+
+/// Largest difference between the window start and end times of two
+/// snapshots that can be merged.
+pub const MERGE_WINDOW_TOLERANCE_SECS: i64 = 5;
+
+/// Key of the axis schema hash in snapshot `meta`.
+pub const AXIS_SCHEMA_HASH_META_KEY: &str = "axis_schema_hash";
+
+/// Hash the set of axis names a snapshot emits.
+///
+/// The names are deduplicated, sorted and joined with newlines; the hash is
+/// the first 8 hex characters of their SHA-256, so it only changes when an
+/// axis is added, removed or renamed.
+pub fn axis_schema_hash<'a>(axes: impl IntoIterator<Item = &'a str>) -> String {
+    let names: BTreeSet<&str> = axes.into_iter().collect();
+    let joined = names.into_iter().collect::<Vec<_>>().join("\n");
+    Sha256::digest(joined.as_bytes())
+        .iter()
+        .take(4)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The system's IANA time zone (e.g. `"America/New_York"`), if it can be determined.
+pub fn local_timezone() -> Option<String> {
+    iana_time_zone::get_timezone()
+        .ok()
+        .filter(|name| name.parse::<chrono_tz::Tz>().is_ok())
+}
+
 // ============================================================================
 // HSI 1.0 Compliant Types
 // ============================================================================
@@ -29,6 +93,17 @@ pub enum HsiDirection {
     Bidirectional,
 }
 
+impl HsiDirection {
+    /// Get the wire name of this direction (e.g. `higher_is_more`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HsiDirection::HigherIsMore => "higher_is_more",
+            HsiDirection::HigherIsLess => "higher_is_less",
+            HsiDirection::Bidirectional => "bidirectional",
+        }
+    }
+}
+
 /// HSI 1.0 source type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -62,7 +137,70 @@ pub struct HsiWindow {
     pub end: String,
     /// Optional label for the window
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub label: Option<String>,
+    pub label: Option<HsiWindowLabel>,
+    /// Duration of the windowing resolution that produced the window, in
+    /// seconds (unset for session summaries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution_secs: Option<i64>,
+}
+
+/// Label of an HSI window.
+///
+/// Serialized as a snake_case string (`"session_start"`); any other string
+/// is kept as a custom label, such as the resolution label `"10s"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum HsiWindowLabel {
+    SessionStart,
+    SessionEnd,
+    SessionSummary,
+    DeepFocusStart,
+    DeepFocusEnd,
+    Custom(String),
+}
+
+impl HsiWindowLabel {
+    /// Get the serialized form of the label.
+    pub fn as_str(&self) -> &str {
+        match self {
+            HsiWindowLabel::SessionStart => "session_start",
+            HsiWindowLabel::SessionEnd => "session_end",
+            HsiWindowLabel::SessionSummary => "session_summary",
+            HsiWindowLabel::DeepFocusStart => "deep_focus_start",
+            HsiWindowLabel::DeepFocusEnd => "deep_focus_end",
+            HsiWindowLabel::Custom(label) => label,
+        }
+    }
+}
+
+impl From<String> for HsiWindowLabel {
+    fn from(label: String) -> Self {
+        match label.as_str() {
+            "session_start" => HsiWindowLabel::SessionStart,
+            "session_end" => HsiWindowLabel::SessionEnd,
+            "session_summary" => HsiWindowLabel::SessionSummary,
+            "deep_focus_start" => HsiWindowLabel::DeepFocusStart,
+            "deep_focus_end" => HsiWindowLabel::DeepFocusEnd,
+            // Earlier versions labelled session starts with their resolution
+            _ if label.starts_with("session_start:") => HsiWindowLabel::SessionStart,
+            _ => HsiWindowLabel::Custom(label),
+        }
+    }
+}
+
+impl From<HsiWindowLabel> for String {
+    fn from(label: HsiWindowLabel) -> Self {
+        match label {
+            HsiWindowLabel::Custom(label) => label,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for HsiWindowLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// HSI 1.0 axis reading
@@ -91,12 +229,19 @@ pub struct HsiAxisReading {
 }
 
 /// HSI 1.0 axes domain (contains readings array)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HsiAxesDomain {
     /// Axis readings
     pub readings: Vec<HsiAxisReading>,
 }
 
+impl HsiAxesDomain {
+    /// Iterate over the readings in this domain.
+    pub fn iter(&self) -> std::slice::Iter<'_, HsiAxisReading> {
+        self.readings.iter()
+    }
+}
+
 /// HSI 1.0 axes container
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HsiAxes {
@@ -124,6 +269,9 @@ pub struct HsiSource {
     /// Optional notes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Platform whose collector captured the source's events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<Platform>,
 }
 
 /// HSI 1.0 privacy declaration
@@ -138,6 +286,12 @@ pub struct HsiPrivacy {
     /// Notes about privacy
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Token recording that the user consented to collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consent_token: Option<String>,
+    /// Epsilon of the Laplace noise added to axis scores (None if scores are exact)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub differential_privacy_epsilon: Option<f64>,
 }
 
 impl Default for HsiPrivacy {
@@ -149,6 +303,8 @@ impl Default for HsiPrivacy {
             notes: Some(
                 "No key content or coordinates captured - timing and magnitude only".to_string(),
             ),
+            consent_token: None,
+            differential_privacy_epsilon: None,
         }
     }
 }
@@ -184,396 +340,1293 @@ pub struct HsiSnapshot {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
-/// Builder for creating HSI 1.0 compliant snapshots.
-pub struct HsiBuilder {
-    instance_id: Uuid,
-    session_id: Option<String>,
-}
+impl HsiSnapshot {
+    /// Axis schema hash of snapshots built with the default axis providers.
+    ///
+    /// Consumers can compare it with [`HsiSnapshot::axis_schema_hash`] to
+    /// detect snapshots whose axes differ from the ones they were written for.
+    pub const CURRENT_AXIS_SCHEMA_HASH: &'static str = "248fa581";
 
-impl HsiBuilder {
-    /// Create a new HSI builder with a unique instance ID.
-    pub fn new() -> Self {
-        Self {
-            instance_id: Uuid::new_v4(),
-            session_id: None,
-        }
+    /// Get the hash of the axis names recorded in `meta.axis_schema_hash`.
+    pub fn axis_schema_hash(&self) -> Option<&str> {
+        self.meta.as_ref()?.get(AXIS_SCHEMA_HASH_META_KEY)?.as_str()
     }
 
-    /// Set the session ID for generated snapshots.
-    pub fn with_session_id(mut self, session_id: String) -> Self {
-        self.session_id = Some(session_id);
-        self
+    /// Record the hash of this snapshot's axis names in `meta.axis_schema_hash`.
+    fn record_axis_schema_hash(&mut self) {
+        let hash = axis_schema_hash(self.readings().map(|reading| reading.axis.as_str()));
+        self.meta.get_or_insert_with(HashMap::new).insert(
+            AXIS_SCHEMA_HASH_META_KEY.to_string(),
+            serde_json::Value::String(hash),
+        );
     }
 
-    /// Get the instance ID.
-    pub fn instance_id(&self) -> Uuid {
-        self.instance_id
+    /// Iterate over all axis readings across all domains.
+    pub fn readings(&self) -> impl Iterator<Item = &HsiAxisReading> {
+        self.axes
+            .iter()
+            .flat_map(|axes| [&axes.affect, &axes.engagement, &axes.behavior])
+            .flatten()
+            .flat_map(|domain| domain.iter())
     }
 
-    /// Build an HSI 1.0 compliant snapshot from a window and its computed features.
-    pub fn build(&self, window: &EventWindow, features: &WindowFeatures) -> HsiSnapshot {
-        let computed_at = Utc::now();
-
-        // Generate window ID
-        let window_id = format!("w_{}", computed_at.timestamp_millis());
+    /// Find the first axis reading with the given name across all domains.
+    pub fn find_reading(&self, axis: &str) -> Option<&HsiAxisReading> {
+        self.readings().find(|reading| reading.axis == axis)
+    }
 
-        // Build windows map
-        let mut windows = HashMap::new();
-        windows.insert(
-            window_id.clone(),
-            HsiWindow {
-                start: window.start.to_rfc3339(),
-                end: window.end.to_rfc3339(),
-                label: if window.is_session_start {
-                    Some("session_start".to_string())
-                } else {
-                    None
-                },
-            },
-        );
+    /// Whether this snapshot is a session summary rather than a single window.
+    pub fn is_session_summary(&self) -> bool {
+        self.windows
+            .values()
+            .any(|window| window.label == Some(HsiWindowLabel::SessionSummary))
+    }
 
-        // Build source
-        let source_id = format!("s_keyboard_mouse_{}", self.instance_id);
-        let mut sources = HashMap::new();
+    /// Get the score of the named axis, if present and available.
+    pub fn axis_score(&self, axis: &str) -> Option<f64> {
+        self.find_reading(axis).and_then(|reading| reading.score)
+    }
 
-        // Calculate quality based on event count
-        let event_count = window.event_count();
-        let quality = if event_count == 0 {
-            0.0
-        } else if event_count < 10 {
-            0.5
-        } else if event_count < 50 {
-            0.75
-        } else {
-            0.95
-        };
+    /// Get the window with its raw timing events, if the snapshot was built
+    /// with [`HsiBuilder::with_raw_events`].
+    pub fn raw_events(&self) -> Option<EventWindow> {
+        let value = self.meta.as_ref()?.get(RAW_EVENTS_META_KEY)?;
+        serde_json::from_value(value.clone()).ok()
+    }
 
-        sources.insert(
-            source_id.clone(),
-            HsiSource {
-                source_type: HsiSourceType::Sensor,
-                quality,
-                degraded: event_count < 10,
-                notes: if event_count < 10 {
-                    Some("Low event count in window".to_string())
-                } else {
-                    None
-                },
-            },
-        );
+    /// Combine this snapshot with one from another source covering the same time.
+    ///
+    /// The snapshots' windows must start and end within
+    /// [`MERGE_WINDOW_TOLERANCE_SECS`] of each other. Windows and sources are
+    /// unioned; readings of an axis present in both keep the one with higher
+    /// confidence (this snapshot's on a tie); `meta` keys of `other` replace
+    /// those of this snapshot. The merged snapshot is unsigned.
+    pub fn merge(&self, other: &HsiSnapshot) -> Result<HsiSnapshot, MergeError> {
+        if self.hsi_version != other.hsi_version {
+            return Err(MergeError::IncompatibleVersion {
+                left: self.hsi_version.clone(),
+                right: other.hsi_version.clone(),
+            });
+        }
+        let (start, end) = self.window_span()?;
+        let (other_start, other_end) = other.window_span()?;
+        let tolerance_ms = MERGE_WINDOW_TOLERANCE_SECS * 1000;
+        let apart = |a: DateTime<Utc>, b: DateTime<Utc>| (a - b).num_milliseconds().abs();
+        if apart(start, other_start) > tolerance_ms || apart(end, other_end) > tolerance_ms {
+            return Err(MergeError::NonOverlappingWindows);
+        }
 
-        // Calculate confidence based on data availability
-        let confidence = quality * 0.9; // Slightly lower than quality
+        let mut merged = self.clone();
+        for window_id in &other.window_ids {
+            if !merged.window_ids.contains(window_id) {
+                merged.window_ids.push(window_id.clone());
+            }
+        }
+        for (window_id, window) in &other.windows {
+            merged
+                .windows
+                .entry(window_id.clone())
+                .or_insert_with(|| window.clone());
+        }
 
-        // Build behavioral axis readings
-        let behavior_readings = vec![
-            // Typing rate (normalized to 0-1 by clamping to max 10 keys/sec)
-            HsiAxisReading {
-                axis: "typing_rate".to_string(),
-                score: Some((features.keyboard.typing_rate / 10.0).min(1.0)),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: Some("keys_per_sec_normalized".to_string()),
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: None,
-            },
-            // Burst index (already 0-1)
-            HsiAxisReading {
-                axis: "typing_burstiness".to_string(),
-                score: Some(features.keyboard.burst_index),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::Bidirectional),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Clustering of keystrokes".to_string()),
-            },
-            // Session continuity (already 0-1)
-            HsiAxisReading {
-                axis: "session_continuity".to_string(),
-                score: Some(features.keyboard.session_continuity),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: None,
-            },
-            // Idle ratio (already 0-1)
-            HsiAxisReading {
-                axis: "idle_ratio".to_string(),
-                score: Some(features.mouse.idle_ratio),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsLess),
-                unit: Some("ratio".to_string()),
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: None,
-            },
-            // Focus continuity proxy (already 0-1)
-            HsiAxisReading {
-                axis: "focus_continuity".to_string(),
-                score: Some(features.behavioral.focus_continuity_proxy),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Derived from typing and mouse patterns".to_string()),
-            },
-            // Interaction rhythm (already 0-1)
-            HsiAxisReading {
-                axis: "interaction_rhythm".to_string(),
-                score: Some(features.behavioral.interaction_rhythm),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: None,
-            },
-            // Motor stability (already 0-1)
-            HsiAxisReading {
-                axis: "motor_stability".to_string(),
-                score: Some(features.behavioral.motor_stability),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: None,
-            },
-            // Friction (already 0-1)
-            HsiAxisReading {
-                axis: "friction".to_string(),
-                score: Some(features.behavioral.friction),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Micro-adjustments and hesitation".to_string()),
-            },
-            // Typing cadence stability (already 0-1)
-            HsiAxisReading {
-                axis: "typing_cadence_stability".to_string(),
-                score: Some(features.keyboard.typing_cadence_stability),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Rhythmic consistency of typing".to_string()),
-            },
-            // Typing gap ratio (already 0-1)
-            HsiAxisReading {
-                axis: "typing_gap_ratio".to_string(),
-                score: Some(features.keyboard.typing_gap_ratio),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsLess),
-                unit: Some("ratio".to_string()),
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Proportion of inter-tap intervals classified as gaps".to_string()),
-            },
-            // Typing interaction intensity (already 0-1)
-            HsiAxisReading {
-                axis: "typing_interaction_intensity".to_string(),
-                score: Some(features.keyboard.typing_interaction_intensity),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some("Composite of speed, cadence stability, and gap behavior".to_string()),
-            },
-            // Keyboard scroll rate (normalized to 0-1, capped at 5 keys/sec)
-            HsiAxisReading {
-                axis: "keyboard_scroll_rate".to_string(),
-                score: Some((features.keyboard.keyboard_scroll_rate / 5.0).min(1.0)),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::HigherIsMore),
-                unit: Some("nav_keys_per_sec_normalized".to_string()),
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some(
-                    "Navigation keys (arrows, page up/down) - separate from mouse scroll"
-                        .to_string(),
-                ),
-            },
-            // Burstiness (already 0-1)
-            HsiAxisReading {
-                axis: "burstiness".to_string(),
-                score: Some(features.behavioral.burstiness),
-                confidence,
-                window_id: window_id.clone(),
-                direction: Some(HsiDirection::Bidirectional),
-                unit: None,
-                evidence_source_ids: Some(vec![source_id.clone()]),
-                notes: Some(
-                    "Whether interactions occur in clusters (high) or evenly (low)".to_string(),
-                ),
-            },
-        ];
-
-        // Build axes
-        let axes = HsiAxes {
-            affect: None,
-            engagement: None,
-            behavior: Some(HsiAxesDomain {
-                readings: behavior_readings,
-            }),
-        };
+        if let Some(ref other_ids) = other.source_ids {
+            let source_ids = merged.source_ids.get_or_insert_with(Vec::new);
+            for source_id in other_ids {
+                if !source_ids.contains(source_id) {
+                    source_ids.push(source_id.clone());
+                }
+            }
+        }
+        if let Some(ref other_sources) = other.sources {
+            let sources = merged.sources.get_or_insert_with(HashMap::new);
+            for (source_id, source) in other_sources {
+                sources
+                    .entry(source_id.clone())
+                    .or_insert_with(|| source.clone());
+            }
+        }
 
-        // Build metadata
-        let mut meta = HashMap::new();
-        meta.insert(
-            "keyboard_events".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(window.keyboard_events.len())),
-        );
-        meta.insert(
-            "mouse_events".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(window.mouse_events.len())),
-        );
-        meta.insert(
-            "duration_secs".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(window.duration_secs())
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
-        meta.insert(
-            "is_session_start".to_string(),
-            serde_json::Value::Bool(window.is_session_start),
-        );
-        if let Some(ref session_id) = self.session_id {
-            meta.insert(
-                "session_id".to_string(),
-                serde_json::Value::String(session_id.clone()),
-            );
+        if let Some(ref other_axes) = other.axes {
+            let axes = merged.axes.get_or_insert_with(HsiAxes::default);
+            merge_domain(&mut axes.affect, other_axes.affect.as_ref());
+            merge_domain(&mut axes.engagement, other_axes.engagement.as_ref());
+            merge_domain(&mut axes.behavior, other_axes.behavior.as_ref());
         }
-        // Include raw feature values in meta for transparency
-        meta.insert(
-            "raw_typing_rate".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(features.keyboard.typing_rate)
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
-        meta.insert(
-            "raw_mean_velocity".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(features.mouse.mean_velocity)
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
-        meta.insert(
-            "raw_click_rate".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(features.mouse.click_rate)
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
-        meta.insert(
-            "typing_tap_count".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(features.keyboard.typing_tap_count)),
-        );
-        meta.insert(
-            "navigation_key_count".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(
-                features.keyboard.navigation_key_count,
-            )),
-        );
-        meta.insert(
-            "keyboard_scroll_rate".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(features.keyboard.keyboard_scroll_rate)
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
-        meta.insert(
-            "idle_time_ms".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(features.mouse.idle_time_ms)),
-        );
-        meta.insert(
-            "deep_focus_block".to_string(),
-            serde_json::Value::Bool(features.behavioral.deep_focus_block),
-        );
-        meta.insert(
-            "burstiness".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(features.behavioral.burstiness)
-                    .unwrap_or(serde_json::Number::from(0)),
-            ),
-        );
 
-        HsiSnapshot {
-            hsi_version: HSI_VERSION.to_string(),
-            observed_at_utc: window.end.to_rfc3339(),
-            computed_at_utc: computed_at.to_rfc3339(),
-            producer: HsiProducer {
-                name: PRODUCER_NAME.to_string(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-                instance_id: Some(self.instance_id.to_string()),
-            },
-            window_ids: vec![window_id],
-            windows,
-            source_ids: Some(vec![source_id]),
-            sources: Some(sources),
-            axes: Some(axes),
-            privacy: HsiPrivacy::default(),
-            meta: Some(meta),
+        merged.privacy.contains_pii |= other.privacy.contains_pii;
+        merged.privacy.raw_biosignals_allowed &= other.privacy.raw_biosignals_allowed;
+        merged.privacy.derived_metrics_allowed &= other.privacy.derived_metrics_allowed;
+
+        if let Some(ref other_meta) = other.meta {
+            merged
+                .meta
+                .get_or_insert_with(HashMap::new)
+                .extend(other_meta.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        if let Some(ref mut meta) = merged.meta {
+            meta.remove(SIGNATURE_META_KEY);
         }
+        if merged.axis_schema_hash().is_some() {
+            merged.record_axis_schema_hash();
+        }
+        Ok(merged)
     }
 
-    /// Build and serialize an HSI snapshot to JSON.
-    pub fn build_json(&self, window: &EventWindow, features: &WindowFeatures) -> String {
-        let snapshot = self.build(window, features);
-        serde_json::to_string_pretty(&snapshot).unwrap_or_else(|_| "{}".to_string())
+    /// Earliest window start and latest window end.
+    fn window_span(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), MergeError> {
+        let parse = |time: &str| {
+            DateTime::parse_from_rfc3339(time)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| MergeError::InvalidWindowTime(time.to_string()))
+        };
+        let mut span: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+        for window in self.windows.values() {
+            let (start, end) = (parse(&window.start)?, parse(&window.end)?);
+            span = Some(span.map_or((start, end), |(s, e)| (s.min(start), e.max(end))));
+        }
+        span.ok_or(MergeError::NonOverlappingWindows)
     }
 }
 
-impl Default for HsiBuilder {
-    fn default() -> Self {
-        Self::new()
+/// Why two snapshots could not be merged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+    /// The snapshots use different HSI versions
+    IncompatibleVersion { left: String, right: String },
+    /// The snapshots do not cover the same time, or one has no windows
+    NonOverlappingWindows,
+    /// A window start or end is not an RFC3339 timestamp
+    InvalidWindowTime(String),
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::IncompatibleVersion { left, right } => {
+                write!(f, "Cannot merge HSI version {left} with {right}")
+            }
+            MergeError::NonOverlappingWindows => write!(
+                f,
+                "Snapshot windows differ by more than {MERGE_WINDOW_TOLERANCE_SECS}s"
+            ),
+            MergeError::InvalidWindowTime(time) => write!(f, "Invalid window time: {time}"),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::core::features::compute_features;
-    use chrono::Duration;
+impl std::error::Error for MergeError {}
 
-    #[test]
-    fn test_hsi_builder_instance_id() {
-        let builder1 = HsiBuilder::new();
-        let builder2 = HsiBuilder::new();
-        assert_ne!(builder1.instance_id(), builder2.instance_id());
+/// Add the readings of `other` to `domain`, keeping the higher-confidence
+/// reading of axes present in both.
+fn merge_domain(domain: &mut Option<HsiAxesDomain>, other: Option<&HsiAxesDomain>) {
+    let Some(other) = other else {
+        return;
+    };
+    let readings = &mut domain.get_or_insert_with(HsiAxesDomain::default).readings;
+    for reading in &other.readings {
+        match readings.iter_mut().find(|r| r.axis == reading.axis) {
+            Some(existing) if reading.confidence > existing.confidence => {
+                *existing = reading.clone()
+            }
+            Some(_) => {}
+            None => readings.push(reading.clone()),
+        }
     }
+}
 
-    #[test]
-    fn test_hsi_snapshot_creation() {
-        let builder = HsiBuilder::new();
-        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
-        let features = compute_features(&window);
-
-        let snapshot = builder.build(&window, &features);
+/// Step-by-step builder for a single window's HSI 1.0 snapshot.
+///
+/// Each step checks its own inputs as it is added (quality and scores in
+/// 0-1, lower_snake_case axis names, no PII) and `build` reports the first
+/// problem found, after checking the assembled snapshot as a whole.
+pub struct HsiSnapshotBuilder<'a> {
+    window: &'a EventWindow,
+    computed_at: DateTime<Utc>,
+    resolution_secs: i64,
+    instance_id: Option<Uuid>,
+    source: Option<(String, HsiSource)>,
+    axes: HsiAxes,
+    meta: HashMap<String, serde_json::Value>,
+    privacy: HsiPrivacy,
+    data_quality: Option<DataQuality>,
+    keyboard_enabled: bool,
+    mouse_enabled: bool,
+    /// Problems found while adding steps, in the order they were found
+    errors: Vec<ValidationError>,
+}
 
-        assert_eq!(snapshot.hsi_version, HSI_VERSION);
-        assert_eq!(snapshot.producer.name, PRODUCER_NAME);
-        assert!(!snapshot.privacy.contains_pii);
-        assert!(snapshot.privacy.derived_metrics_allowed);
+impl<'a> HsiSnapshotBuilder<'a> {
+    /// Start a snapshot of `window`, labelled with its duration.
+    pub fn new(window: &'a EventWindow) -> Self {
+        Self {
+            window,
+            computed_at: Utc::now(),
+            resolution_secs: (window.end - window.start).num_seconds(),
+            instance_id: None,
+            source: None,
+            axes: HsiAxes {
+                affect: None,
+                engagement: None,
+                behavior: None,
+            },
+            meta: HashMap::new(),
+            privacy: HsiPrivacy::default(),
+            data_quality: None,
+            keyboard_enabled: true,
+            mouse_enabled: true,
+            errors: Vec::new(),
+        }
     }
 
-    #[test]
-    fn test_hsi_1_0_compliance() {
-        let builder = HsiBuilder::new();
-        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
-        let features = compute_features(&window);
+    /// Tag the window with the resolution that produced it.
+    ///
+    /// Windows that do not start a session are also labelled with it
+    /// (e.g. `"60s"`).
+    pub fn with_resolution(mut self, resolution: Duration) -> Self {
+        self.resolution_secs = resolution.num_seconds();
+        self
+    }
 
-        let snapshot = builder.build(&window, &features);
+    /// ID of the window, unique per resolution and window start.
+    ///
+    /// Windows of several resolutions complete in the same pass, so the
+    /// build time alone would give them all the same ID.
+    fn window_id(&self) -> String {
+        format!(
+            "w_{}s_{}",
+            self.resolution_secs,
+            self.window.start.timestamp_millis()
+        )
+    }
+
+    /// Record the producer instance ID.
+    pub fn with_instance_id(mut self, instance_id: Uuid) -> Self {
+        self.instance_id = Some(instance_id);
+        self
+    }
+
+    /// Declare the sensor source, cited as evidence by every reading.
+    ///
+    /// The source carries the platform that captured the window's events.
+    pub fn with_source(mut self, source_id: impl Into<String>, quality: f64) -> Self {
+        let source_id = source_id.into();
+        if source_id.is_empty() {
+            self.errors.push(missing("source_ids"));
+        }
+        if !(0.0..=1.0).contains(&quality) {
+            self.errors.push(ValidationError::QualityOutOfRange {
+                source_id: source_id.clone(),
+                quality,
+            });
+        }
+        let source = HsiSource {
+            source_type: HsiSourceType::Sensor,
+            quality,
+            degraded: false,
+            notes: None,
+            platform: Some(self.window.source_platform()),
+        };
+        self.source = Some((source_id, source));
+        self
+    }
+
+    /// Mark the source as degraded unless the window has full data quality.
+    pub fn with_data_quality(mut self, data_quality: DataQuality) -> Self {
+        self.data_quality = Some(data_quality);
+        self
+    }
+
+    /// Declare which input sources were captured.
+    ///
+    /// A disabled source marks the snapshot source as degraded, and its axes
+    /// are reported without a score, so they are not mistaken for a window
+    /// without activity.
+    pub fn with_enabled_sources(mut self, keyboard: bool, mouse: bool) -> Self {
+        self.keyboard_enabled = keyboard;
+        self.mouse_enabled = mouse;
+        self
+    }
+
+    /// Describe the source's data quality and capture settings.
+    fn source_notes(&self) -> Option<String> {
+        let mut notes = Vec::new();
+        if !(self.keyboard_enabled && self.mouse_enabled) {
+            let active: Vec<&str> = [
+                (self.keyboard_enabled, "keyboard"),
+                (self.mouse_enabled, "mouse"),
+            ]
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect();
+            notes.push(if active.is_empty() {
+                "Active sources: none".to_string()
+            } else {
+                format!("Active sources: {}", active.join(", "))
+            });
+        }
+        // A missing source that was never captured is already noted above
+        match self.data_quality {
+            Some(DataQuality::KeyboardOnly) if self.mouse_enabled => {
+                notes.push("No mouse events in window".to_string())
+            }
+            Some(DataQuality::MouseOnly) if self.keyboard_enabled => {
+                notes.push("No keyboard events in window".to_string())
+            }
+            Some(DataQuality::Sparse) => notes.push("Low event count in window".to_string()),
+            _ => {}
+        }
+        (!notes.is_empty()).then(|| notes.join("; "))
+    }
+
+    /// Add the default behavior axes computed from `features`.
+    pub fn with_behavior_axes(self, features: &WindowFeatures) -> Self {
+        self.with_provider_axes(&DefaultBehaviorAxisProvider, features)
+    }
+
+    /// Add the default engagement axes computed from `features`.
+    pub fn with_engagement_axes(self, features: &WindowFeatures) -> Self {
+        self.with_provider_axes(&DefaultEngagementAxisProvider, features)
+    }
+
+    /// Add the readings of any axis provider, in the provider's domain.
+    pub fn with_provider_axes(
+        mut self,
+        provider: &dyn AxisProvider,
+        features: &WindowFeatures,
+    ) -> Self {
+        let mut readings = provider.compute(self.window, features);
+        if readings.is_empty() {
+            return self;
+        }
+        let window_id = self.window_id();
+        for reading in &mut readings {
+            reading.window_id = window_id.clone();
+            check_reading(&mut self.errors, reading);
+        }
+        let domain = match provider.domain() {
+            AxisDomain::Affect => &mut self.axes.affect,
+            AxisDomain::Engagement => &mut self.axes.engagement,
+            AxisDomain::Behavior => &mut self.axes.behavior,
+        };
+        domain
+            .get_or_insert_with(HsiAxesDomain::default)
+            .readings
+            .extend(readings);
+        self
+    }
+
+    /// Add an entry to `meta`, replacing any earlier value for `key`.
+    pub fn with_meta(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        let key = key.into();
+        if key.is_empty() {
+            self.errors.push(missing("meta key"));
+        }
+        self.meta.insert(key, value);
+        self
+    }
+
+    /// Set the privacy declaration. HSI payloads must not contain PII.
+    pub fn with_privacy(mut self, privacy: HsiPrivacy) -> Self {
+        if privacy.contains_pii {
+            self.errors.push(ValidationError::PiiViolation);
+        }
+        self.privacy = privacy;
+        self
+    }
+
+    /// Assemble the snapshot, returning the first problem found in any step
+    /// or in the snapshot as a whole.
+    pub fn build(self) -> Result<HsiSnapshot, ValidationError> {
+        if let Some(error) = self.errors.first() {
+            return Err(error.clone());
+        }
+        let snapshot = self.into_snapshot();
+        match validate_snapshot(&snapshot).into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(snapshot),
+        }
+    }
+
+    /// Assemble the snapshot without reporting problems.
+    pub(crate) fn into_snapshot(mut self) -> HsiSnapshot {
+        let source_notes = self.source_notes();
+        let source_degraded = self
+            .data_quality
+            .is_some_and(|quality| quality != DataQuality::Full)
+            || !(self.keyboard_enabled && self.mouse_enabled);
+        let label = if self.window.is_session_start {
+            HsiWindowLabel::SessionStart
+        } else {
+            HsiWindowLabel::Custom(format!("{}s", self.resolution_secs))
+        };
+        let window_id = self.window_id();
+        let mut windows = HashMap::new();
+        windows.insert(
+            window_id.clone(),
+            HsiWindow {
+                start: self.window.start.to_rfc3339(),
+                end: self.window.end.to_rfc3339(),
+                label: Some(label),
+                resolution_secs: Some(self.resolution_secs),
+            },
+        );
+
+        // Axes of sources that were not captured have no score
+        let (keyboard_enabled, mouse_enabled) = (self.keyboard_enabled, self.mouse_enabled);
+        let is_disabled = |source: InputSource| match source {
+            InputSource::Keyboard => !keyboard_enabled,
+            InputSource::Mouse => !mouse_enabled,
+        };
+        for domain in [
+            &mut self.axes.affect,
+            &mut self.axes.engagement,
+            &mut self.axes.behavior,
+        ] {
+            for reading in domain.iter_mut().flat_map(|d| d.readings.iter_mut()) {
+                // The resolution may have been set after the axes were added
+                reading.window_id = window_id.clone();
+                if axis_input_source(&reading.axis).is_some_and(is_disabled) {
+                    reading.score = None;
+                    reading.notes = Some("source not enabled".to_string());
+                }
+            }
+        }
+
+        let (source_ids, sources) = match self.source {
+            Some((source_id, mut source)) => {
+                source.degraded = source_degraded;
+                source.notes = source_notes;
+                for domain in [
+                    &mut self.axes.affect,
+                    &mut self.axes.engagement,
+                    &mut self.axes.behavior,
+                ] {
+                    let readings = domain.iter_mut().flat_map(|d| d.readings.iter_mut());
+                    for reading in readings {
+                        if reading.evidence_source_ids.is_none() {
+                            reading.evidence_source_ids = Some(vec![source_id.clone()]);
+                        }
+                    }
+                }
+                let mut sources = HashMap::new();
+                sources.insert(source_id.clone(), source);
+                (Some(vec![source_id]), Some(sources))
+            }
+            None => (None, None),
+        };
+
+        HsiSnapshot {
+            hsi_version: HSI_VERSION.to_string(),
+            observed_at_utc: self.window.end.to_rfc3339(),
+            computed_at_utc: self.computed_at.to_rfc3339(),
+            producer: HsiProducer {
+                name: PRODUCER_NAME.to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                instance_id: self.instance_id.map(|id| id.to_string()),
+            },
+            window_ids: vec![window_id],
+            windows,
+            source_ids,
+            sources,
+            axes: Some(self.axes),
+            privacy: self.privacy,
+            meta: Some(self.meta),
+        }
+    }
+}
+
+/// Builder for creating HSI 1.0 compliant snapshots.
+///
+/// Clones share the instance ID, so snapshots built from either cite the
+/// same source.
+#[derive(Clone)]
+pub struct HsiBuilder {
+    instance_id: Uuid,
+    session_id: Option<String>,
+    feature_config: FeatureConfig,
+    axis_providers: Vec<Arc<dyn AxisProvider>>,
+    signer: Option<SnapshotSigner>,
+    consent_token: Option<String>,
+    differential_privacy: Option<DifferentialPrivacyConfig>,
+    include_raw_events: bool,
+    local_timezone: Option<String>,
+    keyboard_enabled: bool,
+    mouse_enabled: bool,
+}
+
+impl HsiBuilder {
+    /// Create a new HSI builder with a unique instance ID.
+    pub fn new() -> Self {
+        Self {
+            instance_id: Uuid::new_v4(),
+            session_id: None,
+            feature_config: FeatureConfig::default(),
+            axis_providers: vec![
+                Arc::new(DefaultBehaviorAxisProvider),
+                Arc::new(DefaultEngagementAxisProvider),
+            ],
+            signer: None,
+            consent_token: None,
+            differential_privacy: None,
+            include_raw_events: false,
+            local_timezone: local_timezone(),
+            keyboard_enabled: true,
+            mouse_enabled: true,
+        }
+    }
+
+    /// Set the session ID for generated snapshots.
+    ///
+    /// The session ID is recorded in `meta.session_id` and embedded in the
+    /// source ID, so readings can be grouped by session without parsing `meta`.
+    pub fn with_session_id(mut self, session_id: String) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Set the feature parameters recorded in snapshot metadata.
+    ///
+    /// This should match the `FeatureConfig` passed to `compute_features`.
+    pub fn with_feature_config(mut self, feature_config: FeatureConfig) -> Self {
+        self.feature_config = feature_config;
+        self
+    }
+
+    /// Register an additional axis provider.
+    ///
+    /// Its readings are added to every snapshot built afterwards, after those
+    /// of the built-in providers.
+    pub fn register_axis_provider(mut self, provider: Box<dyn AxisProvider>) -> Self {
+        self.axis_providers.push(Arc::from(provider));
+        self
+    }
+
+    /// Sign every snapshot built afterwards, adding `meta.signature`.
+    pub fn with_signer(mut self, signer: SnapshotSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Record the user's consent token in every snapshot built afterwards.
+    pub fn with_consent_token(mut self, consent_token: String) -> Self {
+        self.consent_token = Some(consent_token);
+        self
+    }
+
+    /// Add Laplace noise to the axis scores of every snapshot built afterwards.
+    ///
+    /// The exact values that `meta` would otherwise carry
+    /// ([`EXACT_VALUE_META_KEYS`], and `total_events` in session summaries)
+    /// are left out. Session summaries average already-noised scores and get
+    /// no extra noise.
+    pub fn with_differential_privacy(mut self, config: DifferentialPrivacyConfig) -> Self {
+        self.differential_privacy = Some(config);
+        self
+    }
+
+    /// Embed each window's raw timing events in `meta.raw_events` of every
+    /// snapshot built afterwards, so features can be recomputed later.
+    ///
+    /// The events hold timestamps, key-down flags and movement magnitudes
+    /// only, but make snapshots much larger.
+    pub fn with_raw_events(mut self, include_raw_events: bool) -> Self {
+        self.include_raw_events = include_raw_events;
+        self
+    }
+
+    /// Set the IANA time zone recorded in `meta.local_timezone`.
+    ///
+    /// By default this is the system time zone when the builder was created,
+    /// so every snapshot of a session reports the same zone.
+    pub fn with_local_timezone(mut self, local_timezone: Option<String>) -> Self {
+        self.local_timezone = local_timezone;
+        self
+    }
+
+    /// Declare which input sources the collector captures.
+    ///
+    /// Axes of a disabled source are reported with no score and the note
+    /// `source not enabled`, and the snapshot source is marked degraded.
+    pub fn with_enabled_sources(mut self, keyboard: bool, mouse: bool) -> Self {
+        self.keyboard_enabled = keyboard;
+        self.mouse_enabled = mouse;
+        self
+    }
+
+    /// Get the privacy declaration for generated snapshots.
+    fn privacy(&self) -> HsiPrivacy {
+        HsiPrivacy {
+            consent_token: self.consent_token.clone(),
+            differential_privacy_epsilon: self.differential_privacy.map(|dp| dp.epsilon),
+            ..HsiPrivacy::default()
+        }
+    }
+
+    /// Get the instance ID.
+    pub fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    /// Get the ID of the keyboard/mouse source cited by generated snapshots.
+    ///
+    /// This is `s_<session_id>_keyboard_mouse_<instance_id>` when a session ID
+    /// is set and `s_keyboard_mouse_<instance_id>` otherwise.
+    pub fn source_id(&self) -> String {
+        match self.session_id {
+            Some(ref session_id) => format!("s_{session_id}_keyboard_mouse_{}", self.instance_id),
+            None => format!("s_keyboard_mouse_{}", self.instance_id),
+        }
+    }
+
+    /// Build an HSI 1.0 compliant snapshot from a window and its computed features.
+    ///
+    /// `resolution` is the window duration tag reported by the `WindowManager`
+    /// and is embedded in the window label (e.g. `"60s"`). A `delta` from a
+    /// `FeatureDeltaTracker` is included in `meta` as `feature_delta`.
+    ///
+    /// This is a wrapper over [`HsiSnapshotBuilder`] that adds the agent's
+    /// source, providers and metadata, then applies noise and signing.
+    pub fn build(
+        &self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+        resolution: Duration,
+        delta: Option<&FeatureDelta>,
+    ) -> HsiSnapshot {
+        let data_quality = features.data_quality.unwrap_or_else(|| {
+            DataQuality::from_event_counts(window.keyboard_events.len(), window.mouse_events.len())
+        });
+
+        let mut builder = HsiSnapshotBuilder::new(window)
+            .with_resolution(resolution)
+            .with_instance_id(self.instance_id)
+            .with_source(self.source_id(), window_quality(window))
+            .with_data_quality(data_quality)
+            .with_enabled_sources(self.keyboard_enabled, self.mouse_enabled);
+        for provider in &self.axis_providers {
+            builder = builder.with_provider_axes(provider.as_ref(), features);
+        }
+        for (key, value) in self.window_meta(window, features, resolution, data_quality, delta) {
+            builder = builder.with_meta(key, value);
+        }
+        let mut snapshot = builder.with_privacy(self.privacy()).into_snapshot();
+        snapshot.record_axis_schema_hash();
+
+        debug_assert!(
+            validate_snapshot(&snapshot).is_empty(),
+            "built snapshot violates HSI 1.0: {:?}",
+            validate_snapshot(&snapshot)
+        );
+
+        if let (Some(dp), Some(axes)) = (self.differential_privacy, snapshot.axes.as_mut()) {
+            dp.apply(axes, &mut rand::thread_rng());
+        }
+        if let Some(ref signer) = self.signer {
+            signer.sign(&mut snapshot);
+        }
+        snapshot
+    }
+
+    /// Metadata recorded in `meta` of each window snapshot.
+    fn window_meta(
+        &self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+        resolution: Duration,
+        data_quality: DataQuality,
+        delta: Option<&FeatureDelta>,
+    ) -> HashMap<String, serde_json::Value> {
+        let mut meta = HashMap::new();
+        meta.insert(
+            "keyboard_events".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(window.keyboard_events.len())),
+        );
+        meta.insert(
+            "mouse_events".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(window.mouse_events.len())),
+        );
+        meta.insert(
+            "duration_secs".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(window.duration_secs())
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "window_resolution_secs".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(resolution.num_seconds())),
+        );
+        meta.insert(
+            "is_session_start".to_string(),
+            serde_json::Value::Bool(window.is_session_start),
+        );
+        meta.insert(
+            "platform".to_string(),
+            serde_json::Value::String(window.source_platform().as_str().to_string()),
+        );
+        meta.insert(
+            "session_window_index".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(window.session_window_index)),
+        );
+        meta.insert(
+            "pause_threshold_ms".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(
+                self.feature_config.pause_threshold_ms,
+            )),
+        );
+        if let Some(ref session_id) = self.session_id {
+            meta.insert(
+                "session_id".to_string(),
+                serde_json::Value::String(session_id.clone()),
+            );
+        }
+        if let Some(ref local_timezone) = self.local_timezone {
+            meta.insert(
+                "local_timezone".to_string(),
+                serde_json::Value::String(local_timezone.clone()),
+            );
+        }
+        // Include raw feature values in meta for transparency
+        meta.insert(
+            "raw_typing_rate".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.keyboard.typing_rate)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "pause_count".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(features.keyboard.pause_count)),
+        );
+        meta.insert(
+            "raw_mouse_activity_rate".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.mouse.mouse_activity_rate)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "raw_mean_velocity".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.mouse.mean_velocity)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "raw_click_rate".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.mouse.click_rate)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "typing_tap_count".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(features.keyboard.typing_tap_count)),
+        );
+        meta.insert(
+            "navigation_key_count".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(
+                features.keyboard.navigation_key_count,
+            )),
+        );
+        // Too wide for axis readings, so only kept in meta
+        meta.insert(
+            "pause_histogram".to_string(),
+            serde_json::json!(features.keyboard.pause_histogram),
+        );
+        meta.insert(
+            "keyboard_scroll_rate".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.keyboard.keyboard_scroll_rate)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        meta.insert(
+            "idle_time_ms".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(features.mouse.idle_time_ms)),
+        );
+        meta.insert(
+            "deep_focus_block".to_string(),
+            serde_json::Value::Bool(features.behavioral.deep_focus_block),
+        );
+        meta.insert(
+            "rhythm_class".to_string(),
+            serde_json::Value::String(features.rhythm_class.as_str().to_string()),
+        );
+        meta.insert(
+            "mouse_class".to_string(),
+            serde_json::Value::String(features.mouse_class.as_str().to_string()),
+        );
+        meta.insert(
+            "data_quality".to_string(),
+            serde_json::Value::String(data_quality.as_str().to_string()),
+        );
+        meta.insert(
+            "burstiness".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(features.behavioral.burstiness)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        if let Some(delta) = delta {
+            meta.insert(
+                "feature_delta".to_string(),
+                serde_json::to_value(delta).unwrap_or(serde_json::Value::Null),
+            );
+        }
+        if self.include_raw_events {
+            meta.insert(
+                RAW_EVENTS_META_KEY.to_string(),
+                serde_json::to_value(window).unwrap_or(serde_json::Value::Null),
+            );
+        }
+        // Exact values would undo the noise on the axis scores
+        if self.differential_privacy.is_some() {
+            meta.retain(|key, _| !EXACT_VALUE_META_KEYS.contains(&key.as_str()));
+        }
+
+        meta
+    }
+
+    /// Build a snapshot and feed it to a deep focus tracker.
+    ///
+    /// The snapshot `meta` is annotated with the tracker's block state after
+    /// this window has been observed.
+    pub fn build_with_focus_tracker(
+        &self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+        resolution: Duration,
+        delta: Option<&FeatureDelta>,
+        tracker: &mut DeepFocusTracker,
+    ) -> HsiSnapshot {
+        let mut snapshot = self.build(window, features, resolution, delta);
+        tracker.observe(&snapshot);
+        tracker.annotate(&mut snapshot);
+        snapshot
+    }
+
+    /// Build a single snapshot summarizing a completed session.
+    ///
+    /// Each axis score is the mean across all windows, except percentile axes
+    /// which use the median. The summary window spans from the earliest window
+    /// start to the latest window end. Snapshots should come from a single
+    /// window resolution, otherwise events are counted more than once.
+    pub fn build_session_summary(&self, snapshots: Vec<HsiSnapshot>) -> HsiSnapshot {
+        let computed_at = Utc::now();
+        let window_id = format!("w_session_{}", computed_at.timestamp_millis());
+
+        // Session span across all window definitions
+        let mut session_start: Option<DateTime<FixedOffset>> = None;
+        let mut session_end: Option<DateTime<FixedOffset>> = None;
+        for hsi_window in snapshots.iter().flat_map(|s| s.windows.values()) {
+            if let Ok(start) = DateTime::parse_from_rfc3339(&hsi_window.start) {
+                session_start = Some(session_start.map_or(start, |s| s.min(start)));
+            }
+            if let Ok(end) = DateTime::parse_from_rfc3339(&hsi_window.end) {
+                session_end = Some(session_end.map_or(end, |e| e.max(end)));
+            }
+        }
+        let start = session_start.map_or(computed_at, |t| t.with_timezone(&Utc));
+        let end = session_end.map_or(computed_at, |t| t.with_timezone(&Utc));
+
+        let mut windows = HashMap::new();
+        windows.insert(
+            window_id.clone(),
+            HsiWindow {
+                start: start.to_rfc3339(),
+                end: end.to_rfc3339(),
+                label: Some(HsiWindowLabel::SessionSummary),
+                resolution_secs: None,
+            },
+        );
+
+        // Average each domain's axes across the session windows
+        let source_id = self.source_id();
+        let summarize = |domain: fn(&HsiAxes) -> Option<&HsiAxesDomain>| {
+            summarize_domain(
+                snapshots
+                    .iter()
+                    .filter_map(|s| s.axes.as_ref())
+                    .filter_map(domain),
+                &window_id,
+                &source_id,
+            )
+        };
+        let behavior = summarize(|axes| axes.behavior.as_ref());
+        let engagement = summarize(|axes| axes.engagement.as_ref());
+
+        // Source quality is the mean of the per-window source qualities
+        let qualities: Vec<f64> = snapshots
+            .iter()
+            .filter_map(|s| s.sources.as_ref())
+            .flat_map(|sources| sources.values().map(|source| source.quality))
+            .collect();
+        let quality = mean(&qualities).unwrap_or(0.0);
+        let platform = snapshots
+            .iter()
+            .filter_map(|s| s.sources.as_ref())
+            .flat_map(|sources| sources.values())
+            .find_map(|source| source.platform);
+        let mut sources = HashMap::new();
+        sources.insert(
+            source_id.clone(),
+            HsiSource {
+                source_type: HsiSourceType::Sensor,
+                quality,
+                degraded: snapshots.is_empty(),
+                notes: Some("Aggregated across all session windows".to_string()),
+                platform,
+            },
+        );
+
+        // Build metadata
+        let meta_count = |snapshot: &HsiSnapshot, key: &str| {
+            snapshot
+                .meta
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+        };
+        let total_events: u64 = snapshots
+            .iter()
+            .map(|s| meta_count(s, "keyboard_events") + meta_count(s, "mouse_events"))
+            .sum();
+        let session_duration_secs = (end - start).num_milliseconds() as f64 / 1000.0;
+
+        let mut meta = HashMap::new();
+        meta.insert(
+            "window_count".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(snapshots.len())),
+        );
+        meta.insert(
+            "session_duration_secs".to_string(),
+            serde_json::Value::Number(
+                serde_json::Number::from_f64(session_duration_secs)
+                    .unwrap_or(serde_json::Number::from(0)),
+            ),
+        );
+        if self.differential_privacy.is_none() {
+            meta.insert(
+                "total_events".to_string(),
+                serde_json::Value::Number(serde_json::Number::from(total_events)),
+            );
+        }
+        if let Some(ref session_id) = self.session_id {
+            meta.insert(
+                "session_id".to_string(),
+                serde_json::Value::String(session_id.clone()),
+            );
+        }
+        if let Some(ref local_timezone) = self.local_timezone {
+            meta.insert(
+                "local_timezone".to_string(),
+                serde_json::Value::String(local_timezone.clone()),
+            );
+        }
+
+        let mut snapshot = HsiSnapshot {
+            hsi_version: HSI_VERSION.to_string(),
+            observed_at_utc: end.to_rfc3339(),
+            computed_at_utc: computed_at.to_rfc3339(),
+            producer: HsiProducer {
+                name: PRODUCER_NAME.to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                instance_id: Some(self.instance_id.to_string()),
+            },
+            window_ids: vec![window_id],
+            windows,
+            source_ids: Some(vec![source_id]),
+            sources: Some(sources),
+            axes: Some(HsiAxes {
+                affect: None,
+                engagement: (!engagement.readings.is_empty()).then_some(engagement),
+                behavior: Some(behavior),
+            }),
+            privacy: self.privacy(),
+            meta: Some(meta),
+        };
+        snapshot.record_axis_schema_hash();
+
+        if let Some(ref signer) = self.signer {
+            signer.sign(&mut snapshot);
+        }
+        snapshot
+    }
+
+    /// Build and serialize an HSI snapshot to JSON.
+    pub fn build_json(
+        &self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+        resolution: Duration,
+        delta: Option<&FeatureDelta>,
+    ) -> String {
+        let snapshot = self.build(window, features, resolution, delta);
+        serde_json::to_string_pretty(&snapshot).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl Default for HsiBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mean and spread of one axis across a set of snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisStatistics {
+    /// Axis name
+    pub axis: String,
+    /// Number of windows with a score for this axis
+    pub count: usize,
+    /// Mean score
+    pub mean: f64,
+    /// Median score
+    pub median: f64,
+    /// Population standard deviation of the score
+    pub std_dev: f64,
+    /// Direction of the first reading seen for this axis
+    pub direction: Option<HsiDirection>,
+}
+
+/// Compute per-axis mean, median and standard deviation, in first-seen axis
+/// order.
+///
+/// Readings without a score are ignored.
+pub fn axis_statistics(snapshots: &[HsiSnapshot]) -> Vec<AxisStatistics> {
+    let mut order: Vec<(&str, Option<HsiDirection>)> = Vec::new();
+    let mut scores: HashMap<&str, Vec<f64>> = HashMap::new();
+    for reading in snapshots.iter().flat_map(|s| s.readings()) {
+        let Some(score) = reading.score else {
+            continue;
+        };
+        scores
+            .entry(reading.axis.as_str())
+            .or_insert_with(|| {
+                order.push((reading.axis.as_str(), reading.direction));
+                Vec::new()
+            })
+            .push(score);
+    }
+
+    order
+        .into_iter()
+        .map(|(axis, direction)| {
+            let values = &scores[axis];
+            let average = mean(values).unwrap_or(0.0);
+            let variance =
+                values.iter().map(|v| (v - average).powi(2)).sum::<f64>() / values.len() as f64;
+            AxisStatistics {
+                axis: axis.to_string(),
+                count: values.len(),
+                mean: average,
+                median: median(values).unwrap_or(0.0),
+                std_dev: variance.sqrt(),
+                direction,
+            }
+        })
+        .collect()
+}
+
+/// Summarize one axes domain over a session's windows.
+///
+/// Each axis score is the mean across windows (the median for percentile
+/// axes), keeping the order in which axes were first seen.
+fn summarize_domain<'a>(
+    domains: impl Iterator<Item = &'a HsiAxesDomain>,
+    window_id: &str,
+    source_id: &str,
+) -> HsiAxesDomain {
+    // Collect per-axis scores and confidences, preserving first-seen order
+    let mut axis_order: Vec<&HsiAxisReading> = Vec::new();
+    let mut axis_values: HashMap<&str, (Vec<f64>, Vec<f64>)> = HashMap::new();
+    for reading in domains.flat_map(|domain| domain.readings.iter()) {
+        let (scores, confidences) = axis_values.entry(&reading.axis).or_insert_with(|| {
+            axis_order.push(reading);
+            (Vec::new(), Vec::new())
+        });
+        if let Some(score) = reading.score {
+            scores.push(score);
+        }
+        confidences.push(reading.confidence);
+    }
+
+    let readings = axis_order
+        .into_iter()
+        .map(|template| {
+            let (scores, confidences) = &axis_values[template.axis.as_str()];
+            let score = if is_percentile_axis(&template.axis) {
+                median(scores)
+            } else {
+                mean(scores)
+            };
+            HsiAxisReading {
+                axis: template.axis.clone(),
+                score,
+                confidence: mean(confidences).unwrap_or(0.0),
+                window_id: window_id.to_string(),
+                direction: template.direction,
+                unit: template.unit.clone(),
+                evidence_source_ids: Some(vec![source_id.to_string()]),
+                notes: template.notes.clone(),
+            }
+        })
+        .collect();
+
+    HsiAxesDomain { readings }
+}
+
+/// Whether an axis reports a distribution percentile (e.g. `iki_p50`).
+///
+/// Percentile axes are summarized by their median rather than their mean.
+fn is_percentile_axis(axis: &str) -> bool {
+    axis.rsplit_once("_p")
+        .is_some_and(|(_, suffix)| !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Arithmetic mean, or `None` for an empty slice.
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Median, or `None` for an empty slice.
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::types::{KeyboardEvent, MouseEvent, SensorEvent};
+    use crate::core::delta::FeatureDeltaTracker;
+    use crate::core::features::compute_features;
+    use crate::core::rhythm::TypingRhythmClass;
+
+    #[test]
+    fn test_hsi_builder_instance_id() {
+        let builder1 = HsiBuilder::new();
+        let builder2 = HsiBuilder::new();
+        assert_ne!(builder1.instance_id(), builder2.instance_id());
+    }
+
+    #[test]
+    fn test_hsi_snapshot_creation() {
+        let builder = HsiBuilder::new();
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+
+        assert_eq!(snapshot.hsi_version, HSI_VERSION);
+        assert_eq!(snapshot.producer.name, PRODUCER_NAME);
+        assert!(!snapshot.privacy.contains_pii);
+        assert!(snapshot.privacy.derived_metrics_allowed);
+        assert_eq!(snapshot.privacy.consent_token, None);
+    }
+
+    #[test]
+    fn test_consent_token_in_privacy() {
+        let builder = HsiBuilder::new().with_consent_token("consent-123".to_string());
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        assert_eq!(
+            snapshot.privacy.consent_token.as_deref(),
+            Some("consent-123")
+        );
+
+        let summary = builder.build_session_summary(vec![snapshot]);
+        assert_eq!(
+            summary.privacy.consent_token.as_deref(),
+            Some("consent-123")
+        );
+    }
+
+    #[test]
+    fn test_differential_privacy_noise() {
+        let dp = DifferentialPrivacyConfig {
+            epsilon: 0.5,
+            sensitivity: 1.0,
+        };
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let exact = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let noisy = HsiBuilder::new().with_differential_privacy(dp).build(
+            &window,
+            &features,
+            Duration::seconds(10),
+            None,
+        );
+
+        assert_eq!(exact.privacy.differential_privacy_epsilon, None);
+        assert_eq!(noisy.privacy.differential_privacy_epsilon, Some(0.5));
+        let exact_scores: Vec<f64> = exact.readings().filter_map(|r| r.score).collect();
+        let noisy_scores: Vec<f64> = noisy.readings().filter_map(|r| r.score).collect();
+        assert_eq!(exact_scores.len(), noisy_scores.len());
+        assert!(noisy_scores.iter().all(|s| (0.0..=1.0).contains(s)));
+        assert_ne!(exact_scores, noisy_scores);
+    }
+
+    #[test]
+    fn test_differential_privacy_omits_exact_meta() {
+        let dp = DifferentialPrivacyConfig {
+            epsilon: 0.5,
+            sensitivity: 1.0,
+        };
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        features.keyboard.typing_rate = 3.25;
+        features.mouse.mean_velocity = 140.0;
+        let delta = FeatureDelta::default();
+        let builder = HsiBuilder::new().with_raw_events(true);
+
+        let exact = builder.build(&window, &features, Duration::seconds(10), Some(&delta));
+        let exact_meta = exact.meta.as_ref().unwrap();
+        for key in EXACT_VALUE_META_KEYS {
+            assert!(exact_meta.contains_key(*key), "{key} missing without DP");
+        }
+
+        let noisy = builder.with_differential_privacy(dp).build(
+            &window,
+            &features,
+            Duration::seconds(10),
+            Some(&delta),
+        );
+        let noisy_meta = noisy.meta.as_ref().unwrap();
+        for key in EXACT_VALUE_META_KEYS {
+            assert!(!noisy_meta.contains_key(*key), "{key} kept with DP");
+        }
+        assert!(noisy_meta.contains_key("duration_secs"));
+        let summary = HsiBuilder::new()
+            .with_differential_privacy(dp)
+            .build_session_summary(vec![noisy]);
+        assert!(!summary.meta.unwrap().contains_key("total_events"));
+    }
+
+    #[test]
+    fn test_hsi_1_0_compliance() {
+        let builder = HsiBuilder::new();
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
 
         // Check required top-level fields
         assert_eq!(snapshot.hsi_version, "1.0");
@@ -606,13 +1659,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_snapshot_builder() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = HsiSnapshotBuilder::new(&window)
+            .with_source("s_test", 0.5)
+            .with_behavior_axes(&features)
+            .with_engagement_axes(&features)
+            .with_meta("note", serde_json::json!("calibration"))
+            .with_privacy(HsiPrivacy::default())
+            .build()
+            .unwrap();
+
+        assert_eq!(snapshot.source_ids, Some(vec!["s_test".to_string()]));
+        assert!(snapshot.find_reading("typing_rate").is_some());
+        let reading = snapshot.readings().next().unwrap();
+        assert_eq!(
+            reading.evidence_source_ids,
+            Some(vec!["s_test".to_string()])
+        );
+        assert_eq!(snapshot.meta.unwrap()["note"], "calibration");
+        let window_id = &snapshot.window_ids[0];
+        assert_eq!(
+            snapshot.windows[window_id].label,
+            Some(HsiWindowLabel::Custom("10s".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_builder_rejects_invalid_steps() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+
+        let result = HsiSnapshotBuilder::new(&window)
+            .with_source("s_test", 1.5)
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::QualityOutOfRange {
+                source_id: "s_test".to_string(),
+                quality: 1.5,
+            }
+        );
+
+        let privacy = HsiPrivacy {
+            contains_pii: true,
+            ..HsiPrivacy::default()
+        };
+        let result = HsiSnapshotBuilder::new(&window)
+            .with_source("s_test", 0.5)
+            .with_privacy(privacy)
+            .build();
+        assert_eq!(result.unwrap_err(), ValidationError::PiiViolation);
+    }
+
     #[test]
     fn test_hsi_json_serialization() {
         let builder = HsiBuilder::new();
         let window = EventWindow::new(Utc::now(), Duration::seconds(10));
-        let features = compute_features(&window);
+        let features = compute_features(&window, &FeatureConfig::default());
 
-        let json = builder.build_json(&window, &features);
+        let json = builder.build_json(&window, &features, Duration::seconds(10), None);
 
         // Verify JSON contains required fields
         assert!(json.contains("hsi_version"));
@@ -629,9 +1737,9 @@ mod tests {
     fn test_source_quality_calculation() {
         let builder = HsiBuilder::new();
         let window = EventWindow::new(Utc::now(), Duration::seconds(10));
-        let features = compute_features(&window);
+        let features = compute_features(&window, &FeatureConfig::default());
 
-        let snapshot = builder.build(&window, &features);
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
 
         let sources = snapshot.sources.as_ref().unwrap();
         let source = sources.values().next().unwrap();
@@ -640,4 +1748,503 @@ mod tests {
         assert!(source.quality < 0.5);
         assert!(source.degraded);
     }
+
+    #[test]
+    fn test_source_id_includes_session_id() {
+        let builder = HsiBuilder::new();
+        assert_eq!(
+            builder.source_id(),
+            format!("s_keyboard_mouse_{}", builder.instance_id())
+        );
+
+        let builder = HsiBuilder::new().with_session_id("SESS-42".to_string());
+        let source_id = format!("s_SESS-42_keyboard_mouse_{}", builder.instance_id());
+        assert_eq!(builder.source_id(), source_id);
+
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        assert_eq!(snapshot.source_ids, Some(vec![source_id.clone()]));
+        assert!(snapshot.sources.as_ref().unwrap().contains_key(&source_id));
+        let behavior = snapshot.axes.as_ref().unwrap().behavior.as_ref().unwrap();
+        for reading in &behavior.readings {
+            assert_eq!(reading.evidence_source_ids, Some(vec![source_id.clone()]));
+        }
+
+        let summary = builder.build_session_summary(vec![snapshot]);
+        assert_eq!(summary.source_ids, Some(vec![source_id]));
+    }
+
+    #[test]
+    fn test_single_source_window_is_degraded() {
+        let builder = HsiBuilder::new();
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        for _ in 0..20 {
+            window.add_event(SensorEvent::Keyboard(KeyboardEvent::new(true)));
+        }
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let source = snapshot.sources.as_ref().unwrap().values().next().unwrap();
+        assert!(source.degraded);
+        assert_eq!(source.notes.as_deref(), Some("No mouse events in window"));
+        assert_eq!(
+            snapshot.meta.unwrap()["data_quality"],
+            serde_json::json!("KEYBOARD_ONLY")
+        );
+
+        for _ in 0..20 {
+            window.add_event(SensorEvent::Mouse(MouseEvent::movement(3.0, 4.0)));
+        }
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let source = snapshot.sources.as_ref().unwrap().values().next().unwrap();
+        assert!(!source.degraded);
+        assert_eq!(source.notes, None);
+    }
+
+    #[test]
+    fn test_disabled_source_axes_have_no_score() {
+        let builder = HsiBuilder::new().with_enabled_sources(true, false);
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        for _ in 0..20 {
+            window.add_event(SensorEvent::Keyboard(KeyboardEvent::new(true)));
+        }
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        assert!(validate_snapshot(&snapshot).is_empty());
+        let source = snapshot.sources.as_ref().unwrap().values().next().unwrap();
+        assert!(source.degraded);
+        assert_eq!(source.notes.as_deref(), Some("Active sources: keyboard"));
+
+        let reading = |axis: &str| {
+            snapshot
+                .readings()
+                .find(|reading| reading.axis == axis)
+                .unwrap()
+                .clone()
+        };
+        let idle = reading("idle_ratio");
+        assert_eq!(idle.score, None);
+        assert_eq!(idle.notes.as_deref(), Some("source not enabled"));
+        assert!(reading("typing_rate").score.is_some());
+        assert!(reading("focus_continuity").score.is_some());
+    }
+
+    #[test]
+    fn test_snapshot_records_platform() {
+        let builder = HsiBuilder::new();
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut event = KeyboardEvent::new(true);
+        event.source_platform = Platform::Windows;
+        window.add_event(SensorEvent::Keyboard(event));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let meta = snapshot.meta.as_ref().unwrap();
+        assert_eq!(meta["platform"], "windows");
+        let source = snapshot.sources.as_ref().unwrap().values().next().unwrap();
+        assert_eq!(source.platform, Some(Platform::Windows));
+
+        let summary = builder.build_session_summary(vec![snapshot]);
+        let source = summary.sources.as_ref().unwrap().values().next().unwrap();
+        assert_eq!(source.platform, Some(Platform::Windows));
+    }
+
+    #[test]
+    fn test_window_label_includes_resolution() {
+        let builder = HsiBuilder::new();
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(60));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(60), None);
+        let hsi_window = snapshot.windows.values().next().unwrap();
+        assert_eq!(
+            hsi_window.label,
+            Some(HsiWindowLabel::Custom("60s".to_string()))
+        );
+        assert_eq!(hsi_window.resolution_secs, Some(60));
+
+        // Session starts keep the well-known label; the resolution has its own field
+        window.is_session_start = true;
+        let snapshot = builder.build(&window, &features, Duration::seconds(60), None);
+        let json = serde_json::to_value(&snapshot).unwrap();
+        let window_id = &snapshot.window_ids[0];
+        assert_eq!(json["windows"][window_id]["label"], "session_start");
+        assert_eq!(json["windows"][window_id]["resolution_secs"], 60);
+        assert_eq!(
+            snapshot.windows[window_id].label,
+            Some(HsiWindowLabel::SessionStart)
+        );
+
+        let summary = builder.build_session_summary(vec![snapshot]);
+        let summary_window = summary.windows.values().next().unwrap();
+        assert_eq!(summary_window.resolution_secs, None);
+    }
+
+    #[test]
+    fn test_window_ids_unique_across_resolutions() {
+        let mut manager =
+            crate::core::windowing::WindowManager::with_resolutions(vec![10, 60], 300, None);
+        manager.process_event(SensorEvent::Keyboard(KeyboardEvent::new(true)));
+        manager.flush();
+
+        // Both windows start with the same event and complete in one pass
+        let builder = HsiBuilder::new();
+        let ids: Vec<String> = manager
+            .take_completed_windows()
+            .iter()
+            .map(|(resolution, window)| {
+                let features = compute_features(window, &FeatureConfig::default());
+                let snapshot = builder.build(window, &features, *resolution, None);
+                let window_id = snapshot.window_ids[0].clone();
+                assert!(snapshot.readings().all(|r| r.window_id == window_id));
+                window_id
+            })
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert!(ids.iter().any(|id| id.starts_with("w_10s_")));
+        assert!(ids.iter().any(|id| id.starts_with("w_60s_")));
+    }
+
+    #[test]
+    fn test_axis_schema_hash() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        assert_eq!(
+            snapshot.axis_schema_hash(),
+            Some(HsiSnapshot::CURRENT_AXIS_SCHEMA_HASH)
+        );
+        assert_eq!(
+            axis_schema_hash(["b", "a", "b"]),
+            axis_schema_hash(["a", "b"])
+        );
+
+        let summary = HsiBuilder::new().build_session_summary(vec![snapshot]);
+        assert_eq!(
+            summary.axis_schema_hash(),
+            Some(HsiSnapshot::CURRENT_AXIS_SCHEMA_HASH)
+        );
+    }
+
+    #[test]
+    fn test_merge_snapshots() {
+        let start = Utc::now();
+        let window = EventWindow::new(start, Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let sensor = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let mut extension =
+            HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let behavior = extension.axes.as_mut().unwrap().behavior.as_mut().unwrap();
+        let typing = behavior
+            .readings
+            .iter_mut()
+            .find(|reading| reading.axis == "typing_rate")
+            .unwrap();
+        typing.score = Some(0.9);
+        typing.confidence = 1.0;
+        extension
+            .meta
+            .as_mut()
+            .unwrap()
+            .insert("note".to_string(), serde_json::json!("extension"));
+
+        let merged = sensor.merge(&extension).unwrap();
+        assert_eq!(merged.source_ids.as_ref().unwrap().len(), 2);
+        assert_eq!(merged.sources.as_ref().unwrap().len(), 2);
+        assert_eq!(merged.readings().count(), sensor.readings().count());
+        assert_eq!(merged.axis_score("typing_rate"), Some(0.9));
+        assert_eq!(merged.meta.as_ref().unwrap()["note"], "extension");
+        assert!(validate_snapshot(&merged).is_empty());
+
+        let later = EventWindow::new(start + Duration::seconds(60), Duration::seconds(10));
+        let later = HsiBuilder::new().build(&later, &features, Duration::seconds(10), None);
+        assert_eq!(
+            sensor.merge(&later).unwrap_err(),
+            MergeError::NonOverlappingWindows
+        );
+
+        extension.hsi_version = "2.0".to_string();
+        assert!(matches!(
+            sensor.merge(&extension),
+            Err(MergeError::IncompatibleVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_window_label_round_trip() {
+        for label in ["session_start", "session_summary", "deep_focus_end", "60s"] {
+            let parsed: HsiWindowLabel = serde_json::from_value(label.into()).unwrap();
+            assert_eq!(parsed.as_str(), label);
+        }
+        assert_eq!(
+            HsiWindowLabel::from("session_start:10s".to_string()),
+            HsiWindowLabel::SessionStart
+        );
+        assert_eq!(
+            HsiWindowLabel::from("sesion_start".to_string()),
+            HsiWindowLabel::Custom("sesion_start".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pause_threshold_in_meta() {
+        let feature_config = FeatureConfig {
+            pause_threshold_ms: 250,
+            ..FeatureConfig::default()
+        };
+        let builder = HsiBuilder::new().with_feature_config(feature_config.clone());
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &feature_config);
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let meta = snapshot.meta.unwrap();
+        assert_eq!(meta["pause_threshold_ms"], serde_json::json!(250));
+    }
+
+    #[test]
+    fn test_pause_histogram_in_meta() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        features.keyboard.pause_histogram = [3, 2, 1, 0, 1];
+
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        assert!(snapshot.axis_score("pause_histogram").is_none());
+        let meta = snapshot.meta.unwrap();
+        assert_eq!(meta["pause_histogram"], serde_json::json!([3, 2, 1, 0, 1]));
+    }
+
+    #[test]
+    fn test_raw_events_in_meta() {
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        window.add_event(SensorEvent::Keyboard(KeyboardEvent::new(true)));
+        window.add_event(SensorEvent::Mouse(MouseEvent::movement(3.0, 4.0)));
+        let features = compute_features(&window, &FeatureConfig::default());
+
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        assert!(snapshot.raw_events().is_none());
+
+        let snapshot = HsiBuilder::new().with_raw_events(true).build(
+            &window,
+            &features,
+            Duration::seconds(10),
+            None,
+        );
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let decoded: HsiSnapshot = serde_json::from_str(&json).unwrap();
+        let raw = decoded.raw_events().unwrap();
+        assert_eq!(raw.start, window.start);
+        assert_eq!(raw.end, window.end);
+        assert_eq!(raw.keyboard_events.len(), 1);
+        assert_eq!(raw.mouse_events.len(), 1);
+        assert_eq!(raw.mouse_events[0].delta_magnitude, Some(5.0));
+    }
+
+    #[test]
+    fn test_local_timezone_in_meta() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let builder = HsiBuilder::new().with_local_timezone(Some("America/New_York".to_string()));
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let meta = snapshot.meta.as_ref().unwrap();
+        assert_eq!(meta["local_timezone"], "America/New_York");
+
+        let summary = builder.build_session_summary(vec![snapshot]);
+        assert_eq!(summary.meta.unwrap()["local_timezone"], "America/New_York");
+
+        let snapshot = HsiBuilder::new().with_local_timezone(None).build(
+            &window,
+            &features,
+            Duration::seconds(10),
+            None,
+        );
+        assert!(!snapshot.meta.unwrap().contains_key("local_timezone"));
+    }
+
+    #[test]
+    fn test_rhythm_class_in_meta() {
+        let builder = HsiBuilder::new();
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        assert_eq!(features.rhythm_class, TypingRhythmClass::Sparse);
+
+        features.rhythm_class = TypingRhythmClass::SteadyFlow;
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let meta = snapshot.meta.unwrap();
+        assert_eq!(meta["rhythm_class"], serde_json::json!("STEADY_FLOW"));
+        assert_eq!(meta["mouse_class"], serde_json::json!("IDLE"));
+    }
+
+    #[test]
+    fn test_feature_delta_in_meta() {
+        let builder = HsiBuilder::new();
+        let start = Utc::now();
+        let mut tracker = FeatureDeltaTracker::new();
+
+        let mut first = EventWindow::new(start, Duration::seconds(10));
+        first.is_session_start = true;
+        let mut features = compute_features(&first, &FeatureConfig::default());
+        let delta = tracker.observe(&first, &features);
+        let snapshot = builder.build(&first, &features, Duration::seconds(10), Some(&delta));
+        let meta = snapshot.meta.unwrap();
+        assert_eq!(
+            meta["feature_delta"]["typing_rate_delta"],
+            serde_json::Value::Null
+        );
+
+        let second = EventWindow::new(start + Duration::seconds(10), Duration::seconds(10));
+        features.keyboard.typing_rate += 2.0;
+        let delta = tracker.observe(&second, &features);
+        let snapshot = builder.build(&second, &features, Duration::seconds(10), Some(&delta));
+        let meta = snapshot.meta.unwrap();
+        assert_eq!(
+            meta["feature_delta"]["typing_rate_delta"],
+            serde_json::json!(2.0)
+        );
+        assert_eq!(
+            meta["feature_delta"]["pause_count_delta"],
+            serde_json::json!(0)
+        );
+
+        // Without a delta the field is omitted
+        let snapshot = builder.build(&second, &features, Duration::seconds(10), None);
+        assert!(!snapshot.meta.unwrap().contains_key("feature_delta"));
+    }
+
+    #[test]
+    fn test_iki_percentile_axes() {
+        let builder = HsiBuilder::new();
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        features.keyboard.iki_p50_ms = 500.0;
+        features.keyboard.iki_p95_ms = 5000.0;
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let readings = &snapshot.axes.unwrap().behavior.unwrap().readings;
+        let score = |axis: &str| {
+            readings
+                .iter()
+                .find(|r| r.axis == axis)
+                .and_then(|r| r.score)
+                .unwrap()
+        };
+
+        assert_eq!(score("iki_p25"), 0.0);
+        assert_eq!(score("iki_p50"), 0.25);
+        assert_eq!(score("iki_p95"), 1.0); // Capped at 2000ms
+    }
+
+    #[test]
+    fn test_engagement_axes_populated() {
+        let builder = HsiBuilder::new();
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        features.keyboard.typing_rate = 3.0;
+        features.mouse.mouse_activity_rate = 1.0;
+
+        let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+        let engagement = snapshot.axes.as_ref().unwrap().engagement.as_ref().unwrap();
+        let axes: Vec<&str> = engagement
+            .readings
+            .iter()
+            .map(|r| r.axis.as_str())
+            .collect();
+        assert_eq!(
+            axes,
+            vec![
+                "interaction_persistence",
+                "reengagement_rate",
+                "input_modality_ratio"
+            ]
+        );
+        assert_eq!(snapshot.axis_score("input_modality_ratio"), Some(0.75));
+        for reading in &engagement.readings {
+            assert_eq!(reading.window_id, snapshot.window_ids[0]);
+            assert!(reading.direction.is_some());
+        }
+    }
+
+    #[test]
+    fn test_session_summary() {
+        let builder = HsiBuilder::new();
+        let start = Utc::now();
+        let mut snapshots = Vec::new();
+        for (i, typing_rate) in [2.0, 4.0, 9.0].into_iter().enumerate() {
+            let window = EventWindow::new(
+                start + Duration::seconds(10 * i as i64),
+                Duration::seconds(10),
+            );
+            let mut features = compute_features(&window, &FeatureConfig::default());
+            features.keyboard.typing_rate = typing_rate;
+            features.keyboard.iki_p50_ms = [200.0, 400.0, 2000.0][i];
+            snapshots.push(builder.build(&window, &features, Duration::seconds(10), None));
+        }
+
+        let summary = builder.build_session_summary(snapshots);
+
+        let hsi_window = summary.windows.values().next().unwrap();
+        assert_eq!(hsi_window.label, Some(HsiWindowLabel::SessionSummary));
+        assert_eq!(hsi_window.start, start.to_rfc3339());
+        assert_eq!(hsi_window.end, (start + Duration::seconds(30)).to_rfc3339());
+
+        // Typing rate is averaged, IKI percentile uses the median
+        let typing_rate = summary.axis_score("typing_rate").unwrap();
+        assert!((typing_rate - 0.5).abs() < 1e-9);
+        assert_eq!(summary.axis_score("iki_p50"), Some(0.2));
+        assert!(summary
+            .axes
+            .as_ref()
+            .and_then(|axes| axes.engagement.as_ref())
+            .is_some_and(|domain| domain.readings.len() == 3));
+
+        let meta = summary.meta.unwrap();
+        assert_eq!(meta["window_count"], serde_json::json!(3));
+        assert_eq!(meta["session_duration_secs"], serde_json::json!(30.0));
+        assert_eq!(meta["total_events"], serde_json::json!(0));
+    }
+
+    #[test]
+    fn test_percentile_axis_detection() {
+        assert!(is_percentile_axis("iki_p95"));
+        assert!(is_percentile_axis("mouse_velocity_p25"));
+        assert!(!is_percentile_axis("typing_rate"));
+        assert!(!is_percentile_axis("deep_focus_p"));
+    }
+
+    #[test]
+    fn test_axis_statistics() {
+        let builder = HsiBuilder::new();
+        let start = Utc::now();
+        let snapshots: Vec<HsiSnapshot> = [2.0, 4.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, typing_rate)| {
+                let window = EventWindow::new(
+                    start + Duration::seconds(10 * i as i64),
+                    Duration::seconds(10),
+                );
+                let mut features = compute_features(&window, &FeatureConfig::default());
+                features.keyboard.typing_rate = typing_rate;
+                builder.build(&window, &features, Duration::seconds(10), None)
+            })
+            .collect();
+
+        let stats = axis_statistics(&snapshots);
+        let typing = stats.iter().find(|s| s.axis == "typing_rate").unwrap();
+        assert_eq!(typing.count, 2);
+        assert!((typing.mean - 0.3).abs() < 1e-9);
+        assert!((typing.median - 0.3).abs() < 1e-9);
+        assert!((typing.std_dev - 0.1).abs() < 1e-9);
+        assert_eq!(typing.direction, Some(HsiDirection::HigherIsMore));
+
+        assert!(!snapshots[0].is_session_summary());
+        assert!(builder
+            .build_session_summary(snapshots)
+            .is_session_summary());
+    }
 }
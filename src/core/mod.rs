@@ -3,15 +3,42 @@
 //! This module contains:
 //! - Window management for collecting events into time windows
 //! - Feature computation from event windows
+//! - Typing rhythm and mouse behavior classification
 //! - HSI snapshot building for export
+//! - Pluggable axis providers for snapshot readings
+//! - Cross-window deep focus tracking
+//! - Window-to-window feature deltas
+//! - HSI 1.0 conformance validation
 
+pub mod axes;
+pub mod delta;
 pub mod features;
+pub mod focus;
 pub mod hsi;
+pub mod privacy;
+pub mod rhythm;
+pub mod session;
+pub mod validator;
 pub mod windowing;
 
 // Re-export commonly used types
+pub use axes::{
+    axis_input_source, AxisDomain, AxisProvider, DefaultBehaviorAxisProvider,
+    DefaultEngagementAxisProvider, InputSource,
+};
+pub use delta::{FeatureDelta, FeatureDeltaTracker};
 pub use features::{
-    compute_features, BehavioralSignals, KeyboardFeatures, MouseFeatures, WindowFeatures,
+    compute_features, compute_features_parallel, BehavioralSignals, DataQuality, FeatureConfig,
+    InputModality, KeyboardFeatures, MouseFeatures, WindowFeatures, DEFAULT_PAUSE_THRESHOLD_MS,
+    IKI_ENTROPY_BINS, PAUSE_HISTOGRAM_BUCKETS,
+};
+pub use focus::DeepFocusTracker;
+pub use hsi::{
+    axis_statistics, AxisStatistics, HsiBuilder, HsiPrivacy, HsiSnapshot, HsiSnapshotBuilder,
+    HsiWindowLabel, MergeError, HSI_VERSION, PRODUCER_NAME,
 };
-pub use hsi::{HsiBuilder, HsiSnapshot, HSI_VERSION, PRODUCER_NAME};
-pub use windowing::{EventWindow, WindowManager};
+pub use privacy::DifferentialPrivacyConfig;
+pub use rhythm::{classify_mouse_behavior, classify_rhythm, MouseBehaviorClass, TypingRhythmClass};
+pub use session::{TypingSession, TypingSessionDetector};
+pub use validator::{validate_snapshot, ValidationError};
+pub use windowing::{AdaptiveWindowConfig, EventWindow, WindowManager};
@@ -0,0 +1,111 @@
+//! Differential privacy for axis readings.
+//!
+//! With differential privacy enabled, every axis score gets Laplace noise
+//! with scale `sensitivity / epsilon` before it is written to a snapshot, so
+//! exact feature values cannot be reconstructed from aggregate datasets.
+//! Smaller `epsilon` means more noise and stronger privacy.
+
+use crate::core::hsi::HsiAxes;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Laplace mechanism parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifferentialPrivacyConfig {
+    /// Privacy budget per reading (must be positive)
+    pub epsilon: f64,
+    /// Largest change one user's data can make to a score (must be positive)
+    pub sensitivity: f64,
+}
+
+impl DifferentialPrivacyConfig {
+    /// Check that `epsilon` and `sensitivity` are positive and finite.
+    pub fn is_valid(&self) -> bool {
+        self.epsilon.is_finite()
+            && self.epsilon > 0.0
+            && self.sensitivity.is_finite()
+            && self.sensitivity > 0.0
+    }
+
+    /// Get the scale of the Laplace noise.
+    pub fn scale(&self) -> f64 {
+        self.sensitivity / self.epsilon
+    }
+
+    /// Add noise to a 0-1 score, clipping the result to 0-1.
+    pub fn add_noise<R: Rng + ?Sized>(&self, score: f64, rng: &mut R) -> f64 {
+        (score + laplace_noise(self.scale(), rng)).clamp(0.0, 1.0)
+    }
+
+    /// Add noise to every score in `axes`.
+    pub fn apply<R: Rng + ?Sized>(&self, axes: &mut HsiAxes, rng: &mut R) {
+        let domains = [&mut axes.affect, &mut axes.engagement, &mut axes.behavior];
+        for domain in domains.into_iter().flatten() {
+            for reading in &mut domain.readings {
+                if let Some(score) = reading.score {
+                    reading.score = Some(self.add_noise(score, rng));
+                }
+            }
+        }
+    }
+}
+
+/// Sample from a zero-centred Laplace distribution by inverse transform.
+fn laplace_noise<R: Rng + ?Sized>(scale: f64, rng: &mut R) -> f64 {
+    // u in (-0.5, 0.5); -0.5 would give an infinite sample
+    let u = loop {
+        let u: f64 = rng.gen_range(-0.5..0.5);
+        if u > -0.5 {
+            break u;
+        }
+    };
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_laplace_noise_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f64> = (0..20_000).map(|_| laplace_noise(0.1, &mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let mean_abs = samples.iter().map(|s| s.abs()).sum::<f64>() / samples.len() as f64;
+
+        // A Laplace(0, b) sample has mean 0 and mean absolute value b
+        assert!(mean.abs() < 0.01, "mean {mean}");
+        assert!((mean_abs - 0.1).abs() < 0.01, "mean_abs {mean_abs}");
+    }
+
+    #[test]
+    fn test_noisy_scores_stay_in_range() {
+        let config = DifferentialPrivacyConfig {
+            epsilon: 0.5,
+            sensitivity: 1.0,
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        for score in [0.0, 0.5, 1.0] {
+            for _ in 0..1_000 {
+                assert!((0.0..=1.0).contains(&config.add_noise(score, &mut rng)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let valid = DifferentialPrivacyConfig {
+            epsilon: 1.0,
+            sensitivity: 0.1,
+        };
+        assert!(valid.is_valid());
+        assert!((valid.scale() - 0.1).abs() < 1e-12);
+        assert!(!DifferentialPrivacyConfig {
+            epsilon: 0.0,
+            ..valid
+        }
+        .is_valid());
+    }
+}
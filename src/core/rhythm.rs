@@ -0,0 +1,235 @@
+//! Coarse classification of typing rhythm and mouse behavior.
+//!
+//! Raw inter-key interval and cursor statistics are hard to read at a glance.
+//! This module maps a window's keyboard and mouse features onto small sets of
+//! named patterns using fixed decision-tree thresholds.
+
+use crate::core::features::{KeyboardFeatures, MouseFeatures};
+use serde::{Deserialize, Serialize};
+
+/// Typing rate (keys/sec) below which a window is `Sparse`.
+const SPARSE_MAX_TYPING_RATE: f64 = 0.5;
+
+/// Minimum burst index for `FastBurst`.
+const FAST_BURST_MIN_BURST_INDEX: f64 = 0.5;
+
+/// Minimum typing rate (keys/sec) for `FastBurst`.
+const FAST_BURST_MIN_TYPING_RATE: f64 = 4.0;
+
+/// Gap ratio above which typing is `Hesitant`.
+const HESITANT_MIN_GAP_RATIO: f64 = 0.3;
+
+/// Minimum cadence stability for `SteadyFlow`.
+const STEADY_MIN_CADENCE_STABILITY: f64 = 0.6;
+
+/// Gap ratio below which typing can be `SteadyFlow`.
+const STEADY_MAX_GAP_RATIO: f64 = 0.15;
+
+/// Idle ratio above which a window is `Idle`.
+const IDLE_MIN_IDLE_RATIO: f64 = 0.9;
+
+/// Micro-adjustment ratio at or above which movement is `Precise`.
+const PRECISE_MIN_MICRO_ADJUSTMENT_RATIO: f64 = 0.5;
+
+/// Minimum acceleration spikes for `Searching`.
+const SEARCHING_MIN_ACCELERATION_SPIKES: u32 = 3;
+
+/// Minimum velocity standard deviation for `Searching`.
+const SEARCHING_MIN_VELOCITY_VARIABILITY: f64 = 30.0;
+
+/// Typing rhythm pattern of a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TypingRhythmClass {
+    /// Rapid keystrokes in tight clusters
+    FastBurst,
+    /// Regular cadence with few gaps
+    SteadyFlow,
+    /// Frequent gaps between keystrokes
+    Hesitant,
+    /// Little or no typing
+    #[default]
+    Sparse,
+    /// No single pattern dominates
+    Mixed,
+}
+
+impl TypingRhythmClass {
+    /// Get the wire name of this class (e.g. `FAST_BURST`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TypingRhythmClass::FastBurst => "FAST_BURST",
+            TypingRhythmClass::SteadyFlow => "STEADY_FLOW",
+            TypingRhythmClass::Hesitant => "HESITANT",
+            TypingRhythmClass::Sparse => "SPARSE",
+            TypingRhythmClass::Mixed => "MIXED",
+        }
+    }
+}
+
+/// Classify the typing rhythm of a window from its keyboard features.
+pub fn classify_rhythm(features: &KeyboardFeatures) -> TypingRhythmClass {
+    if features.typing_rate < SPARSE_MAX_TYPING_RATE {
+        TypingRhythmClass::Sparse
+    } else if features.burst_index >= FAST_BURST_MIN_BURST_INDEX
+        && features.typing_rate >= FAST_BURST_MIN_TYPING_RATE
+    {
+        TypingRhythmClass::FastBurst
+    } else if features.typing_gap_ratio > HESITANT_MIN_GAP_RATIO {
+        TypingRhythmClass::Hesitant
+    } else if features.typing_cadence_stability >= STEADY_MIN_CADENCE_STABILITY
+        && features.typing_gap_ratio < STEADY_MAX_GAP_RATIO
+    {
+        TypingRhythmClass::SteadyFlow
+    } else {
+        TypingRhythmClass::Mixed
+    }
+}
+
+/// Mouse behavior mode of a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MouseBehaviorClass {
+    /// Mostly small, deliberate adjustments
+    Precise,
+    /// Erratic speed changes, as when hunting for a target
+    Searching,
+    /// Little or no mouse movement
+    #[default]
+    Idle,
+    /// Sustained, larger movements
+    Kinetic,
+}
+
+impl MouseBehaviorClass {
+    /// Get the wire name of this class (e.g. `PRECISE`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MouseBehaviorClass::Precise => "PRECISE",
+            MouseBehaviorClass::Searching => "SEARCHING",
+            MouseBehaviorClass::Idle => "IDLE",
+            MouseBehaviorClass::Kinetic => "KINETIC",
+        }
+    }
+}
+
+/// Classify the mouse behavior of a window from its mouse features.
+///
+/// A window without any mouse movement is `Idle`.
+pub fn classify_mouse_behavior(features: &MouseFeatures) -> MouseBehaviorClass {
+    if features.mouse_activity_rate <= 0.0 || features.idle_ratio > IDLE_MIN_IDLE_RATIO {
+        MouseBehaviorClass::Idle
+    } else if features.micro_adjustment_ratio >= PRECISE_MIN_MICRO_ADJUSTMENT_RATIO {
+        MouseBehaviorClass::Precise
+    } else if features.acceleration_spikes >= SEARCHING_MIN_ACCELERATION_SPIKES
+        && features.velocity_variability >= SEARCHING_MIN_VELOCITY_VARIABILITY
+    {
+        MouseBehaviorClass::Searching
+    } else {
+        MouseBehaviorClass::Kinetic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyboard(
+        typing_rate: f64,
+        burst_index: f64,
+        cadence_stability: f64,
+        gap_ratio: f64,
+    ) -> KeyboardFeatures {
+        KeyboardFeatures {
+            typing_rate,
+            burst_index,
+            typing_cadence_stability: cadence_stability,
+            typing_gap_ratio: gap_ratio,
+            ..KeyboardFeatures::default()
+        }
+    }
+
+    #[test]
+    fn test_classify_rhythm() {
+        assert_eq!(
+            classify_rhythm(&KeyboardFeatures::default()),
+            TypingRhythmClass::Sparse
+        );
+        assert_eq!(
+            classify_rhythm(&keyboard(6.0, 0.7, 0.4, 0.05)),
+            TypingRhythmClass::FastBurst
+        );
+        assert_eq!(
+            classify_rhythm(&keyboard(2.0, 0.1, 0.8, 0.5)),
+            TypingRhythmClass::Hesitant
+        );
+        assert_eq!(
+            classify_rhythm(&keyboard(3.0, 0.2, 0.8, 0.05)),
+            TypingRhythmClass::SteadyFlow
+        );
+        assert_eq!(
+            classify_rhythm(&keyboard(3.0, 0.2, 0.4, 0.2)),
+            TypingRhythmClass::Mixed
+        );
+    }
+
+    #[test]
+    fn test_rhythm_class_wire_name() {
+        for class in [
+            TypingRhythmClass::FastBurst,
+            TypingRhythmClass::SteadyFlow,
+            TypingRhythmClass::Hesitant,
+            TypingRhythmClass::Sparse,
+            TypingRhythmClass::Mixed,
+        ] {
+            assert_eq!(
+                serde_json::to_value(class).unwrap(),
+                serde_json::json!(class.as_str())
+            );
+        }
+    }
+
+    fn mouse(
+        idle_ratio: f64,
+        micro_adjustment_ratio: f64,
+        velocity_variability: f64,
+        acceleration_spikes: u32,
+    ) -> MouseFeatures {
+        MouseFeatures {
+            mouse_activity_rate: 5.0,
+            idle_ratio,
+            micro_adjustment_ratio,
+            velocity_variability,
+            acceleration_spikes,
+            ..MouseFeatures::default()
+        }
+    }
+
+    #[test]
+    fn test_mostly_idle_window_is_idle() {
+        assert_eq!(
+            classify_mouse_behavior(&mouse(0.95, 0.8, 50.0, 10)),
+            MouseBehaviorClass::Idle
+        );
+        assert_eq!(
+            classify_mouse_behavior(&MouseFeatures::default()),
+            MouseBehaviorClass::Idle
+        );
+    }
+
+    #[test]
+    fn test_classify_mouse_behavior() {
+        assert_eq!(
+            classify_mouse_behavior(&mouse(0.2, 0.7, 5.0, 0)),
+            MouseBehaviorClass::Precise
+        );
+        assert_eq!(
+            classify_mouse_behavior(&mouse(0.2, 0.2, 45.0, 6)),
+            MouseBehaviorClass::Searching
+        );
+        assert_eq!(
+            classify_mouse_behavior(&mouse(0.1, 0.1, 20.0, 1)),
+            MouseBehaviorClass::Kinetic
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Detection of sustained typing sessions.
+//!
+//! A single window only says whether the user typed steadily for a few
+//! seconds. `TypingSessionDetector` coalesces runs of consecutive windows
+//! with high `session_continuity` into one `TypingSession`, answering
+//! questions like "the user typed continuously for 8 minutes, then stopped".
+
+use crate::core::features::WindowFeatures;
+use crate::core::windowing::EventWindow;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Consecutive typing windows needed before a run counts as a session.
+pub const DEFAULT_MIN_CONTINUITY_WINDOWS: u32 = 6;
+
+/// `session_continuity` above which a window counts as sustained typing.
+pub const DEFAULT_CONTINUITY_THRESHOLD: f64 = 0.5;
+
+/// A run of consecutive windows of sustained typing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypingSession {
+    /// Start of the first window
+    pub start: DateTime<Utc>,
+    /// End of the last window
+    pub end: DateTime<Utc>,
+    /// Number of windows in the session
+    pub window_count: u32,
+    /// Mean typing rate (keys/sec) over the session's windows
+    pub mean_typing_rate: f64,
+    /// Mean `session_continuity` over the session's windows
+    pub mean_session_continuity: f64,
+}
+
+impl TypingSession {
+    /// Get the time from the start of the first window to the end of the last.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Coalesces consecutive sustained-typing windows into typing sessions.
+///
+/// Windows must be fed in order and from a single window resolution.
+#[derive(Debug, Clone)]
+pub struct TypingSessionDetector {
+    /// Windows needed before a run counts as a session
+    min_continuity_windows: u32,
+    /// `session_continuity` above which a window counts as sustained typing
+    continuity_threshold: f64,
+    /// The run of typing windows in progress
+    run: Option<TypingSession>,
+    /// End of the previous window, to detect gaps
+    last_end: Option<DateTime<Utc>>,
+    /// Length of the previous window, to detect gaps
+    last_duration: Duration,
+}
+
+impl Default for TypingSessionDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_CONTINUITY_WINDOWS, DEFAULT_CONTINUITY_THRESHOLD)
+    }
+}
+
+impl TypingSessionDetector {
+    /// Create a detector requiring `min_continuity_windows` consecutive windows
+    /// with `session_continuity` above `continuity_threshold`.
+    pub fn new(min_continuity_windows: u32, continuity_threshold: f64) -> Self {
+        Self {
+            min_continuity_windows: min_continuity_windows.max(1),
+            continuity_threshold,
+            run: None,
+            last_end: None,
+            last_duration: Duration::zero(),
+        }
+    }
+
+    /// Feed the next completed window.
+    ///
+    /// Returns the typing session that this window ends, if any. A session
+    /// ends at a low-activity window, a new session boundary, or a gap of at
+    /// least one window length with no window.
+    pub fn observe(
+        &mut self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+    ) -> Option<TypingSession> {
+        let is_gap = self
+            .last_end
+            .is_some_and(|last_end| window.start - last_end >= self.last_duration);
+        let mut finished = if window.is_session_start || is_gap {
+            self.take_run()
+        } else {
+            None
+        };
+
+        let continuity = features.keyboard.session_continuity;
+        if continuity > self.continuity_threshold {
+            let typing_rate = features.keyboard.typing_rate;
+            match self.run {
+                Some(ref mut run) => {
+                    let count = f64::from(run.window_count);
+                    run.mean_typing_rate =
+                        (run.mean_typing_rate * count + typing_rate) / (count + 1.0);
+                    run.mean_session_continuity =
+                        (run.mean_session_continuity * count + continuity) / (count + 1.0);
+                    run.window_count += 1;
+                    run.end = window.end;
+                }
+                None => {
+                    self.run = Some(TypingSession {
+                        start: window.start,
+                        end: window.end,
+                        window_count: 1,
+                        mean_typing_rate: typing_rate,
+                        mean_session_continuity: continuity,
+                    });
+                }
+            }
+        } else if finished.is_none() {
+            finished = self.take_run();
+        }
+
+        self.last_end = Some(window.end);
+        self.last_duration = window.end - window.start;
+        finished
+    }
+
+    /// Get the typing session in progress, once it is long enough to count.
+    pub fn current_session(&self) -> Option<&TypingSession> {
+        self.run
+            .as_ref()
+            .filter(|run| run.window_count >= self.min_continuity_windows)
+    }
+
+    /// End the run in progress (e.g. when collection stops) and return it if
+    /// it is long enough to count.
+    pub fn finish(&mut self) -> Option<TypingSession> {
+        self.last_end = None;
+        self.take_run()
+    }
+
+    fn take_run(&mut self) -> Option<TypingSession> {
+        self.run
+            .take()
+            .filter(|run| run.window_count >= self.min_continuity_windows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: DateTime<Utc>, offset_secs: i64) -> EventWindow {
+        EventWindow::new(
+            start + Duration::seconds(offset_secs),
+            Duration::seconds(10),
+        )
+    }
+
+    fn features(typing_rate: f64, session_continuity: f64) -> WindowFeatures {
+        let mut features = WindowFeatures::default();
+        features.keyboard.typing_rate = typing_rate;
+        features.keyboard.session_continuity = session_continuity;
+        features
+    }
+
+    #[test]
+    fn test_session_ends_at_low_activity_window() {
+        let mut detector = TypingSessionDetector::new(3, 0.5);
+        let start = Utc::now();
+
+        for (i, rate) in [2.0, 4.0, 6.0].into_iter().enumerate() {
+            assert_eq!(
+                detector.observe(&window(start, i as i64 * 10), &features(rate, 0.8)),
+                None
+            );
+        }
+        assert_eq!(
+            detector.current_session().map(TypingSession::duration),
+            Some(Duration::seconds(30))
+        );
+
+        let session = detector
+            .observe(&window(start, 30), &features(0.2, 0.1))
+            .unwrap();
+        assert_eq!(session.window_count, 3);
+        assert_eq!(session.start, start);
+        assert_eq!(session.end, start + Duration::seconds(30));
+        assert!((session.mean_typing_rate - 4.0).abs() < 1e-9);
+        assert!((session.mean_session_continuity - 0.8).abs() < 1e-9);
+        assert_eq!(detector.current_session(), None);
+    }
+
+    #[test]
+    fn test_short_runs_are_not_sessions() {
+        let mut detector = TypingSessionDetector::new(3, 0.5);
+        let start = Utc::now();
+
+        detector.observe(&window(start, 0), &features(3.0, 0.9));
+        detector.observe(&window(start, 10), &features(3.0, 0.9));
+        assert_eq!(detector.current_session(), None);
+        assert_eq!(
+            detector.observe(&window(start, 20), &features(0.0, 0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gap_ends_session() {
+        let mut detector = TypingSessionDetector::new(2, 0.5);
+        let start = Utc::now();
+
+        detector.observe(&window(start, 0), &features(3.0, 0.9));
+        detector.observe(&window(start, 10), &features(3.0, 0.9));
+
+        // No window for a full window length: the next typing window starts a new run
+        let session = detector
+            .observe(&window(start, 35), &features(3.0, 0.9))
+            .unwrap();
+        assert_eq!(session.window_count, 2);
+        assert_eq!(detector.current_session(), None);
+
+        detector.observe(&window(start, 45), &features(3.0, 0.9));
+        assert_eq!(detector.finish().map(|s| s.window_count), Some(2));
+    }
+}
@@ -0,0 +1,318 @@
+//! HSI 1.0 conformance checks for snapshots.
+//!
+//! `validate_snapshot` reports every problem it finds rather than stopping at
+//! the first, so a single pass over an exported file shows all issues.
+
+use crate::core::hsi::{HsiAxisReading, HsiSnapshot, HSI_VERSION};
+use chrono::DateTime;
+
+/// A way in which a snapshot does not conform to HSI 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// `hsi_version` is not the supported version
+    UnsupportedVersion { version: String },
+    /// A required field is empty
+    MissingRequiredField { field: String },
+    /// A timestamp field is not valid RFC3339
+    InvalidTimestamp { field: String, value: String },
+    /// A window ends before it starts
+    InvalidWindowRange { window_id: String },
+    /// An entry of `window_ids` has no definition in `windows`
+    UndefinedWindow { window_id: String },
+    /// An axis reading refers to a window the snapshot does not declare
+    InvalidWindowId {
+        axis: String,
+        axis_window_id: String,
+        snapshot_window_ids: Vec<String>,
+    },
+    /// An axis name is not lower_snake_case
+    InvalidAxisName { axis: String },
+    /// An axis score is outside 0-1
+    ScoreOutOfRange { axis: String, score: f64 },
+    /// An axis confidence is outside 0-1
+    ConfidenceOutOfRange { axis: String, confidence: f64 },
+    /// A source quality is outside 0-1
+    QualityOutOfRange { source_id: String, quality: f64 },
+    /// An axis reading cites a source the snapshot does not declare
+    UnknownSource { axis: String, source_id: String },
+    /// The privacy block declares that the payload contains PII
+    PiiViolation,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::UnsupportedVersion { version } => {
+                write!(
+                    f,
+                    "Unsupported hsi_version {version:?} (expected {HSI_VERSION:?})"
+                )
+            }
+            ValidationError::MissingRequiredField { field } => {
+                write!(f, "Missing required field: {field}")
+            }
+            ValidationError::InvalidTimestamp { field, value } => {
+                write!(f, "Invalid RFC3339 timestamp in {field}: {value:?}")
+            }
+            ValidationError::InvalidWindowRange { window_id } => {
+                write!(f, "Window {window_id} ends before it starts")
+            }
+            ValidationError::UndefinedWindow { window_id } => {
+                write!(
+                    f,
+                    "Window {window_id} is listed in window_ids but not defined"
+                )
+            }
+            ValidationError::InvalidWindowId {
+                axis,
+                axis_window_id,
+                snapshot_window_ids,
+            } => write!(
+                f,
+                "Axis {axis} refers to window {axis_window_id}, not one of {snapshot_window_ids:?}"
+            ),
+            ValidationError::InvalidAxisName { axis } => {
+                write!(f, "Axis name {axis:?} is not lower_snake_case")
+            }
+            ValidationError::ScoreOutOfRange { axis, score } => {
+                write!(f, "Axis {axis} score {score} is outside 0-1")
+            }
+            ValidationError::ConfidenceOutOfRange { axis, confidence } => {
+                write!(f, "Axis {axis} confidence {confidence} is outside 0-1")
+            }
+            ValidationError::QualityOutOfRange { source_id, quality } => {
+                write!(f, "Source {source_id} quality {quality} is outside 0-1")
+            }
+            ValidationError::UnknownSource { axis, source_id } => {
+                write!(f, "Axis {axis} cites undeclared source {source_id}")
+            }
+            ValidationError::PiiViolation => write!(f, "privacy.contains_pii must be false"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Check a snapshot against the HSI 1.0 specification.
+///
+/// Returns an empty list for a conforming snapshot.
+pub fn validate_snapshot(snapshot: &HsiSnapshot) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if snapshot.hsi_version != HSI_VERSION {
+        errors.push(ValidationError::UnsupportedVersion {
+            version: snapshot.hsi_version.clone(),
+        });
+    }
+
+    check_timestamp(&mut errors, "observed_at_utc", &snapshot.observed_at_utc);
+    check_timestamp(&mut errors, "computed_at_utc", &snapshot.computed_at_utc);
+
+    if snapshot.producer.name.is_empty() {
+        errors.push(missing("producer.name"));
+    }
+    if snapshot.producer.version.is_empty() {
+        errors.push(missing("producer.version"));
+    }
+
+    // Windows
+    if snapshot.window_ids.is_empty() {
+        errors.push(missing("window_ids"));
+    }
+    for window_id in &snapshot.window_ids {
+        if !snapshot.windows.contains_key(window_id) {
+            errors.push(ValidationError::UndefinedWindow {
+                window_id: window_id.clone(),
+            });
+        }
+    }
+    let mut window_ids: Vec<&String> = snapshot.windows.keys().collect();
+    window_ids.sort();
+    for window_id in window_ids {
+        let window = &snapshot.windows[window_id];
+        let start = check_timestamp(
+            &mut errors,
+            &format!("windows.{window_id}.start"),
+            &window.start,
+        );
+        let end = check_timestamp(
+            &mut errors,
+            &format!("windows.{window_id}.end"),
+            &window.end,
+        );
+        if let (Some(start), Some(end)) = (start, end) {
+            if end < start {
+                errors.push(ValidationError::InvalidWindowRange {
+                    window_id: window_id.clone(),
+                });
+            }
+        }
+    }
+
+    // Sources
+    if let Some(ref sources) = snapshot.sources {
+        let mut source_ids: Vec<&String> = sources.keys().collect();
+        source_ids.sort();
+        for source_id in source_ids {
+            let quality = sources[source_id].quality;
+            if !(0.0..=1.0).contains(&quality) {
+                errors.push(ValidationError::QualityOutOfRange {
+                    source_id: source_id.clone(),
+                    quality,
+                });
+            }
+        }
+    }
+
+    // Axis readings
+    for reading in snapshot.readings() {
+        check_reading(&mut errors, reading);
+        if !snapshot.window_ids.contains(&reading.window_id) {
+            errors.push(ValidationError::InvalidWindowId {
+                axis: reading.axis.clone(),
+                axis_window_id: reading.window_id.clone(),
+                snapshot_window_ids: snapshot.window_ids.clone(),
+            });
+        }
+        for source_id in reading.evidence_source_ids.iter().flatten() {
+            let declared = snapshot
+                .sources
+                .as_ref()
+                .map(|sources| sources.contains_key(source_id))
+                .unwrap_or(false);
+            if !declared {
+                errors.push(ValidationError::UnknownSource {
+                    axis: reading.axis.clone(),
+                    source_id: source_id.clone(),
+                });
+            }
+        }
+    }
+
+    if snapshot.privacy.contains_pii {
+        errors.push(ValidationError::PiiViolation);
+    }
+
+    errors
+}
+
+/// Check the parts of an axis reading that do not depend on the rest of the
+/// snapshot: its name, score and confidence.
+pub(crate) fn check_reading(errors: &mut Vec<ValidationError>, reading: &HsiAxisReading) {
+    if !is_lower_snake_case(&reading.axis) {
+        errors.push(ValidationError::InvalidAxisName {
+            axis: reading.axis.clone(),
+        });
+    }
+    if let Some(score) = reading.score {
+        if !(0.0..=1.0).contains(&score) {
+            errors.push(ValidationError::ScoreOutOfRange {
+                axis: reading.axis.clone(),
+                score,
+            });
+        }
+    }
+    if !(0.0..=1.0).contains(&reading.confidence) {
+        errors.push(ValidationError::ConfidenceOutOfRange {
+            axis: reading.axis.clone(),
+            confidence: reading.confidence,
+        });
+    }
+}
+
+pub(crate) fn missing(field: &str) -> ValidationError {
+    ValidationError::MissingRequiredField {
+        field: field.to_string(),
+    }
+}
+
+/// Parse an RFC3339 timestamp field, recording an error if it is empty or invalid.
+fn check_timestamp(
+    errors: &mut Vec<ValidationError>,
+    field: &str,
+    value: &str,
+) -> Option<DateTime<chrono::FixedOffset>> {
+    if value.is_empty() {
+        errors.push(missing(field));
+        return None;
+    }
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(timestamp) => Some(timestamp),
+        Err(_) => {
+            errors.push(ValidationError::InvalidTimestamp {
+                field: field.to_string(),
+                value: value.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Check that a name is non-empty lower_snake_case (`[a-z][a-z0-9_]*`).
+fn is_lower_snake_case(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hsi::HsiBuilder;
+    use crate::test_utils::sample_snapshot;
+
+    #[test]
+    fn test_builder_output_is_valid() {
+        let snapshot = sample_snapshot();
+        assert_eq!(validate_snapshot(&snapshot), Vec::new());
+
+        let summary = HsiBuilder::new().build_session_summary(vec![snapshot]);
+        assert_eq!(validate_snapshot(&summary), Vec::new());
+    }
+
+    #[test]
+    fn test_detects_violations() {
+        let mut snapshot = sample_snapshot();
+        snapshot.privacy.contains_pii = true;
+        snapshot.observed_at_utc = "yesterday".to_string();
+        let behavior = snapshot
+            .axes
+            .as_mut()
+            .and_then(|axes| axes.behavior.as_mut())
+            .unwrap();
+        behavior.readings[0].score = Some(1.5);
+        behavior.readings[1].window_id = "w_other".to_string();
+        behavior.readings[2].axis = "TypingRate".to_string();
+        let axis = behavior.readings[0].axis.clone();
+
+        let errors = validate_snapshot(&snapshot);
+
+        assert!(errors.contains(&ValidationError::PiiViolation));
+        assert!(errors.contains(&ValidationError::InvalidTimestamp {
+            field: "observed_at_utc".to_string(),
+            value: "yesterday".to_string(),
+        }));
+        assert!(errors.contains(&ValidationError::ScoreOutOfRange { axis, score: 1.5 }));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            ValidationError::InvalidWindowId { axis_window_id, .. } if axis_window_id == "w_other"
+        )));
+        assert!(errors.contains(&ValidationError::InvalidAxisName {
+            axis: "TypingRate".to_string(),
+        }));
+        assert_eq!(errors.len(), 5);
+    }
+
+    #[test]
+    fn test_undefined_window() {
+        let mut snapshot = sample_snapshot();
+        snapshot.window_ids.push("w_missing".to_string());
+
+        assert_eq!(
+            validate_snapshot(&snapshot),
+            vec![ValidationError::UndefinedWindow {
+                window_id: "w_missing".to_string(),
+            }]
+        );
+    }
+}
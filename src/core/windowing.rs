@@ -2,9 +2,12 @@
 //!
 //! Events are collected into fixed-duration windows (default 10 seconds)
 //! for feature extraction. Session boundaries are detected based on gaps.
+//! In adaptive mode, windows close early once they hold enough events.
+//! In clock-aligned mode, windows start on multiples of their duration since
+//! the Unix epoch, so windows from different sessions line up.
 
-use crate::collector::types::{KeyboardEvent, MouseEvent, SensorEvent};
-use chrono::{DateTime, Duration, Utc};
+use crate::collector::types::{KeyboardEvent, MouseEvent, Platform, SensorEvent, TouchEvent};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A time window containing collected events.
@@ -18,8 +21,14 @@ pub struct EventWindow {
     pub keyboard_events: Vec<KeyboardEvent>,
     /// Mouse events in this window
     pub mouse_events: Vec<MouseEvent>,
+    /// Touch events in this window
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub touch_events: Vec<TouchEvent>,
     /// Whether this window marks the start of a new session
     pub is_session_start: bool,
+    /// Position of this window within its session (0 for the first window)
+    #[serde(default)]
+    pub session_window_index: u32,
 }
 
 impl EventWindow {
@@ -30,7 +39,9 @@ impl EventWindow {
             end: start + duration,
             keyboard_events: Vec::new(),
             mouse_events: Vec::new(),
+            touch_events: Vec::new(),
             is_session_start: false,
+            session_window_index: 0,
         }
     }
 
@@ -44,58 +55,251 @@ impl EventWindow {
         match event {
             SensorEvent::Keyboard(e) => self.keyboard_events.push(e),
             SensorEvent::Mouse(e) => self.mouse_events.push(e),
+            SensorEvent::Touch(e) => self.touch_events.push(e),
         }
     }
 
     /// Check if the window has any events.
     pub fn is_empty(&self) -> bool {
-        self.keyboard_events.is_empty() && self.mouse_events.is_empty()
+        self.keyboard_events.is_empty()
+            && self.mouse_events.is_empty()
+            && self.touch_events.is_empty()
     }
 
     /// Get the total number of events in this window.
     pub fn event_count(&self) -> usize {
-        self.keyboard_events.len() + self.mouse_events.len()
+        self.keyboard_events.len() + self.mouse_events.len() + self.touch_events.len()
     }
 
     /// Get the duration of this window in seconds.
     pub fn duration_secs(&self) -> f64 {
         (self.end - self.start).num_milliseconds() as f64 / 1000.0
     }
+
+    /// Get the platform that captured this window's events.
+    ///
+    /// An empty window reports the platform this binary was built for.
+    pub fn source_platform(&self) -> Platform {
+        self.keyboard_events
+            .first()
+            .map(|e| e.source_platform)
+            .or_else(|| self.mouse_events.first().map(|e| e.source_platform))
+            .unwrap_or_else(Platform::current)
+    }
+}
+
+impl Extend<SensorEvent> for EventWindow {
+    /// Add events in bulk, as with [`EventWindow::add_event`].
+    fn extend<I: IntoIterator<Item = SensorEvent>>(&mut self, events: I) {
+        for event in events {
+            self.add_event(event);
+        }
+    }
+}
+
+impl FromIterator<SensorEvent> for EventWindow {
+    /// Build a window starting at the first event's timestamp and ending just
+    /// after the latest event. An empty window starts and ends now.
+    fn from_iter<I: IntoIterator<Item = SensorEvent>>(events: I) -> Self {
+        let mut events = events.into_iter().peekable();
+        let start = events.peek().map_or_else(Utc::now, SensorEvent::timestamp);
+        let mut window = EventWindow::new(start, Duration::zero());
+        for event in events {
+            let event_end = event.timestamp() + Duration::milliseconds(1);
+            if event_end > window.end {
+                window.end = event_end;
+            }
+            window.add_event(event);
+        }
+        window
+    }
+}
+
+/// Bounds for adaptively sized windows.
+///
+/// A window closes after `max_secs`, or as soon as it holds `target_events`
+/// events, whichever comes first, but never before `min_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptiveWindowConfig {
+    /// Shortest window duration in seconds
+    pub min_secs: u64,
+    /// Longest window duration in seconds
+    pub max_secs: u64,
+    /// Number of events after which a window may close early
+    pub target_events: usize,
 }
 
 /// Manages the collection of events into time windows.
+///
+/// A manager can track several window resolutions at once (e.g. 10s, 60s and
+/// 300s). Each resolution keeps its own current window, and every event is
+/// added to all of them.
 pub struct WindowManager {
-    /// Duration of each window
-    window_duration: Duration,
+    /// One slot per configured window resolution
+    resolutions: Vec<ResolutionSlot>,
     /// Gap threshold for session boundaries
     session_gap_threshold: Duration,
-    /// Current window being filled
-    current_window: Option<EventWindow>,
-    /// Completed windows ready for processing
-    completed_windows: Vec<EventWindow>,
+    /// Completed windows ready for processing, tagged with their resolution
+    completed_windows: Vec<(Duration, EventWindow)>,
+    /// Windows with fewer events than this are dropped instead of completed
+    min_events: Option<usize>,
+    /// Early-close bounds when windows are sized adaptively
+    adaptive: Option<AdaptiveWindowConfig>,
+    /// Whether windows start on wall-clock boundaries instead of at the first event
+    clock_aligned: bool,
+    /// Events dropped because their clock-aligned window had passed, not yet taken
+    late_events: u64,
     /// Timestamp of the last event received
     last_event_time: Option<DateTime<Utc>>,
 }
 
+/// The current window for a single resolution.
+struct ResolutionSlot {
+    /// Duration of each window at this resolution
+    duration: Duration,
+    /// Current window being filled
+    current_window: Option<EventWindow>,
+    /// Index within the current session of the next window at this resolution
+    current_session_window_index: u32,
+}
+
 impl WindowManager {
     /// Create a new window manager with the given window duration.
-    pub fn new(window_duration_secs: u64, session_gap_threshold_secs: u64) -> Self {
+    ///
+    /// Windows with fewer than `min_events` events are silently dropped.
+    pub fn new(
+        window_duration_secs: u64,
+        session_gap_threshold_secs: u64,
+        min_events: Option<usize>,
+    ) -> Self {
+        Self::with_resolutions(
+            vec![window_duration_secs],
+            session_gap_threshold_secs,
+            min_events,
+        )
+    }
+
+    /// Create a window manager that maintains one window per resolution.
+    ///
+    /// Duplicate durations are ignored. Windows with fewer than `min_events`
+    /// events are silently dropped.
+    pub fn with_resolutions(
+        window_durations_secs: Vec<u64>,
+        session_gap_threshold_secs: u64,
+        min_events: Option<usize>,
+    ) -> Self {
+        let mut durations = window_durations_secs;
+        durations.sort_unstable();
+        durations.dedup();
+
         Self {
-            window_duration: Duration::seconds(window_duration_secs as i64),
+            resolutions: durations
+                .into_iter()
+                .map(|secs| ResolutionSlot {
+                    duration: Duration::seconds(secs as i64),
+                    current_window: None,
+                    current_session_window_index: 0,
+                })
+                .collect(),
             session_gap_threshold: Duration::seconds(session_gap_threshold_secs as i64),
-            current_window: None,
             completed_windows: Vec::new(),
+            min_events,
+            adaptive: None,
+            clock_aligned: false,
+            late_events: 0,
             last_event_time: None,
         }
     }
 
-    /// Process an incoming event.
+    /// Create a window manager with adaptively sized windows.
+    ///
+    /// Windows are tagged with the `max_secs` resolution but record their
+    /// actual span in `start` and `end`. Windows with fewer than `min_events`
+    /// events are silently dropped.
+    pub fn adaptive(
+        adaptive: AdaptiveWindowConfig,
+        session_gap_threshold_secs: u64,
+        min_events: Option<usize>,
+    ) -> Self {
+        let mut manager = Self::with_resolutions(
+            vec![adaptive.max_secs],
+            session_gap_threshold_secs,
+            min_events,
+        );
+        manager.adaptive = Some(adaptive);
+        manager
+    }
+
+    /// Get the configured window resolutions, shortest first.
+    pub fn resolutions(&self) -> Vec<Duration> {
+        self.resolutions.iter().map(|slot| slot.duration).collect()
+    }
+
+    /// Change the window resolutions without dropping collected events.
+    ///
+    /// Resolutions that are kept continue their current window. Windows of
+    /// removed resolutions are completed at their original duration, and new
+    /// resolutions start with the next event.
+    pub fn set_resolutions(&mut self, window_durations_secs: Vec<u64>) {
+        let mut durations = window_durations_secs;
+        durations.sort_unstable();
+        durations.dedup();
+
+        let mut previous = std::mem::take(&mut self.resolutions);
+        for secs in durations {
+            let duration = Duration::seconds(secs as i64);
+            let slot = match previous.iter().position(|slot| slot.duration == duration) {
+                Some(index) => previous.remove(index),
+                None => ResolutionSlot {
+                    duration,
+                    current_window: None,
+                    current_session_window_index: 0,
+                },
+            };
+            self.resolutions.push(slot);
+        }
+
+        for mut slot in previous {
+            slot.complete_into(&mut self.completed_windows, self.min_events);
+        }
+    }
+
+    /// Change the gap that separates sessions, effective from the next event.
+    pub fn set_session_gap_threshold(&mut self, session_gap_threshold_secs: u64) {
+        self.session_gap_threshold = Duration::seconds(session_gap_threshold_secs as i64);
+    }
+
+    /// Start new windows on multiples of their duration since the Unix epoch.
+    ///
+    /// With a 10-second window, windows start at :00, :10, :20 and so on.
+    /// Takes effect from the next window. Ignored in adaptive mode, where
+    /// windows close at arbitrary times.
+    ///
+    /// Each event then belongs to exactly one window per resolution. Events
+    /// that arrive out of order, after their window has been completed, are
+    /// dropped and counted in [`Self::take_late_events`] instead of being
+    /// added to a later window.
+    pub fn set_clock_aligned(&mut self, clock_aligned: bool) {
+        self.clock_aligned = clock_aligned;
+    }
+
+    /// Get the number of late events dropped in clock-aligned mode since the
+    /// last call.
+    ///
+    /// An event counts once even if it was only too late for some of the
+    /// resolutions; it is still added to the windows that cover it.
+    pub fn take_late_events(&mut self) -> u64 {
+        std::mem::take(&mut self.late_events)
+    }
+
+    /// Process an incoming event, owned or boxed as received from the collector.
     ///
     /// This will:
     /// 1. Detect session boundaries based on gaps
     /// 2. Create new windows as needed
     /// 3. Complete windows when their time expires
-    pub fn process_event(&mut self, event: SensorEvent) {
+    pub fn process_event(&mut self, event: impl Into<SensorEvent>) {
+        let event = event.into();
         let event_time = event.timestamp();
 
         // Check for session boundary (gap in events)
@@ -105,45 +309,112 @@ impl WindowManager {
             true // First event starts a session
         };
 
-        // If this is a new session, complete the current window
-        if is_new_session && self.current_window.is_some() {
-            self.complete_current_window();
-        }
+        let clock_aligned = self.clock_aligned && self.adaptive.is_none();
+        let is_out_of_order = self.last_event_time.is_some_and(|last| event_time < last);
+        let mut is_late = false;
+
+        // Every resolution but the last gets a copy; the last takes the event
+        let mut event = Some(event);
+        let last_slot = self.resolutions.len().saturating_sub(1);
+        for (slot_index, slot) in self.resolutions.iter_mut().enumerate() {
+            // An out-of-order event whose aligned window was already completed
+            // cannot be added to it, and must not be added to a later one
+            let window_passed = slot
+                .current_window
+                .as_ref()
+                .map_or(true, |window| event_time < window.start);
+            if clock_aligned && is_out_of_order && window_passed {
+                is_late = true;
+                continue;
+            }
 
-        // Ensure we have a current window
-        if self.current_window.is_none() {
-            let mut window = EventWindow::new(event_time, self.window_duration);
-            window.is_session_start = is_new_session;
-            self.current_window = Some(window);
-        }
+            // If this is a new session, complete the current window and
+            // restart the window count
+            if is_new_session {
+                slot.complete_into(&mut self.completed_windows, self.min_events);
+                slot.current_session_window_index = 0;
+            }
 
-        // Check if the event falls outside the current window
-        let window = self.current_window.as_ref().unwrap();
-        if event_time >= window.end {
-            // Complete the current window and create a new one
-            self.complete_current_window();
+            // Check if the event falls outside the current window
+            if let Some(ref window) = slot.current_window {
+                if event_time >= window.end {
+                    slot.complete_into(&mut self.completed_windows, self.min_events);
+                }
+            }
 
-            // Align the new window to the event time
-            let mut window = EventWindow::new(event_time, self.window_duration);
-            window.is_session_start = is_new_session;
-            self.current_window = Some(window);
+            // Ensure we have a current window, starting at the event time or the
+            // wall-clock boundary before it
+            let duration = slot.duration;
+            let start = if clock_aligned {
+                aligned_start(event_time, duration)
+            } else {
+                event_time
+            };
+            let next_index = &mut slot.current_session_window_index;
+            let window = slot.current_window.get_or_insert_with(|| {
+                let mut window = EventWindow::new(start, duration);
+                window.is_session_start = is_new_session;
+                // Windows later dropped for `min_events` still take an index
+                window.session_window_index = *next_index;
+                *next_index += 1;
+                window
+            });
+
+            // Add the event to the current window
+            let event = if slot_index == last_slot {
+                event.take()
+            } else {
+                event.clone()
+            };
+            if let Some(event) = event {
+                window.add_event(event);
+            }
+
+            // An adaptive window that reached its target closes early, but not before its minimum
+            if let Some(adaptive) = self.adaptive {
+                if window.event_count() >= adaptive.target_events {
+                    let min_secs = adaptive.min_secs.min(adaptive.max_secs);
+                    let min_end = window.start + Duration::seconds(min_secs as i64);
+                    if event_time >= min_end {
+                        window.end = event_time + Duration::milliseconds(1);
+                        slot.complete_into(&mut self.completed_windows, self.min_events);
+                    } else {
+                        window.end = min_end;
+                    }
+                }
+            }
         }
 
-        // Add the event to the current window
-        if let Some(ref mut window) = self.current_window {
-            window.add_event(event);
+        if is_late {
+            self.late_events += 1;
         }
+        // Out-of-order events do not move the clock back
+        self.last_event_time = Some(
+            self.last_event_time
+                .map_or(event_time, |last| last.max(event_time)),
+        );
+    }
 
-        self.last_event_time = Some(event_time);
+    /// Process a batch of recorded events, such as a replayed session.
+    ///
+    /// Events are sorted by timestamp first, so they need not be in order.
+    pub fn replay_events(&mut self, events: impl IntoIterator<Item = SensorEvent>) {
+        let mut events: Vec<SensorEvent> = events.into_iter().collect();
+        events.sort_by_key(SensorEvent::timestamp);
+        for event in events {
+            self.process_event(event);
+        }
     }
 
-    /// Force completion of the current window (e.g., on pause or stop).
+    /// Force completion of the current windows (e.g., on pause or stop).
     pub fn flush(&mut self) {
-        self.complete_current_window();
+        for slot in &mut self.resolutions {
+            slot.complete_into(&mut self.completed_windows, self.min_events);
+        }
     }
 
-    /// Get and remove completed windows.
-    pub fn take_completed_windows(&mut self) -> Vec<EventWindow> {
+    /// Get and remove completed windows, tagged with their resolution.
+    pub fn take_completed_windows(&mut self) -> Vec<(Duration, EventWindow)> {
         std::mem::take(&mut self.completed_windows)
     }
 
@@ -157,22 +428,44 @@ impl WindowManager {
         self.completed_windows.len()
     }
 
-    /// Complete the current window and move it to completed.
-    fn complete_current_window(&mut self) {
-        if let Some(window) = self.current_window.take() {
-            // Only keep non-empty windows
-            if !window.is_empty() {
-                self.completed_windows.push(window);
+    /// Check and complete any current windows that have expired.
+    pub fn check_window_expiry(&mut self) {
+        let now = Utc::now();
+        for slot in &mut self.resolutions {
+            if let Some(ref window) = slot.current_window {
+                if now >= window.end {
+                    slot.complete_into(&mut self.completed_windows, self.min_events);
+                }
             }
         }
     }
+}
+
+/// Round a timestamp down to a multiple of `duration` since the Unix epoch.
+fn aligned_start(time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    let step = duration.num_milliseconds();
+    if step <= 0 {
+        return time;
+    }
+    let millis = time.timestamp_millis();
+    Utc.timestamp_millis_opt(millis - millis.rem_euclid(step))
+        .single()
+        .unwrap_or(time)
+}
 
-    /// Check and complete the current window if it has expired.
-    pub fn check_window_expiry(&mut self) {
-        let now = Utc::now();
-        if let Some(ref window) = self.current_window {
-            if now >= window.end {
-                self.complete_current_window();
+impl ResolutionSlot {
+    /// Complete the current window and move it to `completed`.
+    ///
+    /// Empty windows, and windows below `min_events`, are discarded.
+    fn complete_into(
+        &mut self,
+        completed: &mut Vec<(Duration, EventWindow)>,
+        min_events: Option<usize>,
+    ) {
+        if let Some(window) = self.current_window.take() {
+            let min_events = min_events.unwrap_or(1).max(1);
+            if window.event_count() >= min_events {
+                completed.push((self.duration, window));
             }
         }
     }
@@ -205,12 +498,16 @@ mod tests {
 
     #[test]
     fn test_window_manager_basic() {
-        let mut manager = WindowManager::new(10, 300);
+        let mut manager = WindowManager::new(10, 300, None);
 
-        // Process some keyboard events
-        for _ in 0..5 {
+        // Process some keyboard events, boxed as from the collector channel or owned
+        for i in 0..5 {
             let event = SensorEvent::Keyboard(crate::collector::types::KeyboardEvent::new(true));
-            manager.process_event(event);
+            if i % 2 == 0 {
+                manager.process_event(Box::new(event));
+            } else {
+                manager.process_event(event);
+            }
         }
 
         // Window shouldn't be complete yet
@@ -222,6 +519,256 @@ mod tests {
 
         let windows = manager.take_completed_windows();
         assert_eq!(windows.len(), 1);
-        assert_eq!(windows[0].keyboard_events.len(), 5);
+        assert_eq!(windows[0].0, Duration::seconds(10));
+        assert_eq!(windows[0].1.keyboard_events.len(), 5);
+    }
+
+    #[test]
+    fn test_window_manager_multi_resolution() {
+        let mut manager = WindowManager::with_resolutions(vec![300, 10, 60, 10], 300, None);
+        assert_eq!(
+            manager.resolutions(),
+            vec![
+                Duration::seconds(10),
+                Duration::seconds(60),
+                Duration::seconds(300)
+            ]
+        );
+
+        let start = Utc::now();
+        for offset_secs in [0, 5, 15, 70] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = start + Duration::seconds(offset_secs);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+        manager.flush();
+
+        let windows = manager.take_completed_windows();
+        let count_at = |secs: i64| {
+            windows
+                .iter()
+                .filter(|(res, _)| *res == Duration::seconds(secs))
+                .count()
+        };
+
+        // 10s: [0, 5], [15], [70]; 60s: [0, 5, 15], [70]; 300s: [0, 5, 15, 70]
+        assert_eq!(count_at(10), 3);
+        assert_eq!(count_at(60), 2);
+        assert_eq!(count_at(300), 1);
+
+        let (_, long_window) = windows
+            .iter()
+            .find(|(res, _)| *res == Duration::seconds(300))
+            .unwrap();
+        assert_eq!(long_window.keyboard_events.len(), 4);
+        assert!(long_window.is_session_start);
+    }
+
+    #[test]
+    fn test_session_window_index() {
+        let mut manager = WindowManager::new(10, 60, None);
+
+        let start = Utc::now();
+        // Session 1: three windows; session 2 after a 5 minute gap: one window
+        for offset_secs in [0, 15, 25, 325] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = start + Duration::seconds(offset_secs);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+        manager.flush();
+
+        let indices: Vec<(u32, bool)> = manager
+            .take_completed_windows()
+            .iter()
+            .map(|(_, window)| (window.session_window_index, window.is_session_start))
+            .collect();
+        assert_eq!(indices, vec![(0, true), (1, false), (2, false), (0, true)]);
+    }
+
+    #[test]
+    fn test_window_manager_min_events() {
+        let mut manager = WindowManager::new(10, 300, Some(5));
+
+        // A single accidental key press is dropped
+        manager.process_event(SensorEvent::Keyboard(
+            crate::collector::types::KeyboardEvent::new(true),
+        ));
+        manager.flush();
+        assert!(!manager.has_completed_windows());
+
+        // A window meeting the threshold is kept
+        for _ in 0..5 {
+            manager.process_event(SensorEvent::Keyboard(
+                crate::collector::types::KeyboardEvent::new(true),
+            ));
+        }
+        manager.flush();
+        assert_eq!(manager.completed_window_count(), 1);
+    }
+
+    #[test]
+    fn test_adaptive_window_closes_at_target() {
+        let adaptive = AdaptiveWindowConfig {
+            min_secs: 2,
+            max_secs: 30,
+            target_events: 3,
+        };
+        let mut manager = WindowManager::adaptive(adaptive, 300, None);
+        assert_eq!(manager.resolutions(), vec![Duration::seconds(30)]);
+
+        // Burst: the target is reached after the minimum, closing the window at the third event
+        let start = Utc::now();
+        for offset_ms in [0, 1000, 2500, 3000] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = start + Duration::milliseconds(offset_ms);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].0, Duration::seconds(30));
+        assert_eq!(windows[0].1.event_count(), 3);
+        assert_eq!(windows[0].1.duration_secs(), 2.501);
+
+        // Lull: a window below the target runs for the full maximum
+        let mut event = crate::collector::types::KeyboardEvent::new(true);
+        event.timestamp = start + Duration::seconds(40);
+        manager.process_event(SensorEvent::Keyboard(event));
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].1.event_count(), 1);
+        assert_eq!(windows[0].1.duration_secs(), 30.0);
+    }
+
+    #[test]
+    fn test_adaptive_window_respects_minimum() {
+        let adaptive = AdaptiveWindowConfig {
+            min_secs: 5,
+            max_secs: 30,
+            target_events: 2,
+        };
+        let mut manager = WindowManager::adaptive(adaptive, 300, None);
+
+        // The target is reached after 1s; the window still lasts the 5s minimum
+        let start = Utc::now();
+        for offset_secs in [0, 1, 3, 6] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = start + Duration::seconds(offset_secs);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].1.event_count(), 3);
+        assert_eq!(windows[0].1.duration_secs(), 5.0);
+    }
+
+    #[test]
+    fn test_clock_aligned_windows() {
+        let mut manager = WindowManager::new(10, 300, None);
+        manager.set_clock_aligned(true);
+
+        let base = Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+        for offset_ms in [3_250, 9_900, 12_000] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = base + Duration::milliseconds(offset_ms);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+
+        // The first window starts at :00 although the first event came at :03.25
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].1.start, base);
+        assert_eq!(windows[0].1.end, base + Duration::seconds(10));
+        assert_eq!(windows[0].1.event_count(), 2);
+
+        manager.flush();
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows[0].1.start, base + Duration::seconds(10));
+    }
+
+    #[test]
+    fn test_clock_aligned_late_event() {
+        let mut manager = WindowManager::with_resolutions(vec![10, 60], 300, None);
+        manager.set_clock_aligned(true);
+
+        let base = Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+        // :08 arrives after :12 started the :10 window
+        for offset_ms in [3_000, 12_000, 8_000, 14_000] {
+            let mut event = crate::collector::types::KeyboardEvent::new(true);
+            event.timestamp = base + Duration::milliseconds(offset_ms);
+            manager.process_event(SensorEvent::Keyboard(event));
+        }
+        manager.flush();
+
+        let windows = manager.take_completed_windows();
+        let short: Vec<&EventWindow> = windows
+            .iter()
+            .filter(|(res, _)| *res == Duration::seconds(10))
+            .map(|(_, window)| window)
+            .collect();
+        // The late event is not counted in the :10 window
+        assert_eq!(short[0].start, base);
+        assert_eq!(short[0].event_count(), 1);
+        assert_eq!(short[1].start, base + Duration::seconds(10));
+        assert_eq!(short[1].event_count(), 2);
+
+        // The 60s window still covers it
+        let (_, long) = windows
+            .iter()
+            .find(|(res, _)| *res == Duration::seconds(60))
+            .unwrap();
+        assert_eq!(long.event_count(), 4);
+        assert_eq!(manager.take_late_events(), 1);
+        assert_eq!(manager.take_late_events(), 0);
+
+        // Nor does a late event reopen a window that was already flushed
+        let mut event = crate::collector::types::KeyboardEvent::new(true);
+        event.timestamp = base + Duration::seconds(9);
+        manager.process_event(SensorEvent::Keyboard(event));
+        manager.flush();
+        assert!(!manager.has_completed_windows());
+        assert_eq!(manager.take_late_events(), 1);
+    }
+
+    #[test]
+    fn test_window_from_events() {
+        let start = Utc::now();
+        let events: Vec<SensorEvent> = [0, 2000, 1000]
+            .into_iter()
+            .map(|offset_ms| {
+                let mut event = KeyboardEvent::new(true);
+                event.timestamp = start + Duration::milliseconds(offset_ms);
+                SensorEvent::Keyboard(event)
+            })
+            .collect();
+
+        let mut window: EventWindow = events.iter().cloned().collect();
+        assert_eq!(window.start, start);
+        assert_eq!(window.end, start + Duration::milliseconds(2001));
+        assert_eq!(window.keyboard_events.len(), 3);
+
+        window.extend(vec![SensorEvent::Mouse(MouseEvent::movement(3.0, 4.0))]);
+        assert_eq!(window.mouse_events.len(), 1);
+        assert_eq!(window.event_count(), 4);
+    }
+
+    #[test]
+    fn test_replay_events_in_timestamp_order() {
+        let mut manager = WindowManager::new(10, 300, None);
+        let start = Utc::now();
+
+        // Out of order: the 12s event must not close the first window early
+        let events = [12, 0, 5].into_iter().map(|offset_secs| {
+            let mut event = KeyboardEvent::new(true);
+            event.timestamp = start + Duration::seconds(offset_secs);
+            SensorEvent::Keyboard(event)
+        });
+        manager.replay_events(events);
+        manager.flush();
+
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].1.start, start);
+        assert_eq!(windows[0].1.keyboard_events.len(), 2);
+        assert_eq!(windows[1].1.keyboard_events.len(), 1);
     }
 }
@@ -0,0 +1,1370 @@
+//! Reading and writing exported HSI snapshot files.
+//!
+//! Session and export files are plain JSON by default. When
+//! `compress_exports` is enabled they are gzip-compressed and carry an
+//! additional `.gz` suffix, which is also how they are recognized on read.
+//!
+//! With `streaming_export` enabled, snapshots are instead appended to a
+//! `.jsonl` session file as each window completes, so a crash loses at most
+//! the window in progress.
+//!
+//! For statistical analysis, snapshots can also be flattened into CSV with
+//! one row per window. With the `proto` feature, they can be written as a
+//! compact stream of length-delimited Protocol Buffers messages.
+
+use crate::core::hsi::{axis_statistics, HsiBuilder, HsiSnapshot, HsiWindowLabel};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// File suffix appended to compressed exports.
+pub const GZIP_SUFFIX: &str = ".gz";
+
+/// Build an export file name, adding the gzip suffix when compressing.
+///
+/// `stem` and `extension` are joined with a dot, e.g. `session_x` and `json`
+/// become `session_x.json` or `session_x.json.gz`.
+pub fn export_file_name(stem: &str, extension: &str, compress: bool) -> String {
+    if compress {
+        format!("{stem}.{extension}{GZIP_SUFFIX}")
+    } else {
+        format!("{stem}.{extension}")
+    }
+}
+
+/// Values substituted into an `export_filename_pattern`.
+#[derive(Debug, Clone)]
+pub struct FileNameVars<'a> {
+    /// When the file is written
+    pub at: DateTime<Utc>,
+    /// ID of the agent instance writing the file
+    pub instance_id: &'a str,
+    /// ID of the collection session
+    pub session_id: &'a str,
+    /// Number of snapshots in the file
+    pub snapshot_count: usize,
+}
+
+/// Render an export file name pattern into a file stem.
+///
+/// Supports `{timestamp}` (`20240115_143000`), `{date}` (`20240115`),
+/// `{time}` (`143000`), `{instance_id}`, `{session_id}` and
+/// `{snapshot_count}`; times are UTC. Unknown variables are left as written.
+/// Path separators are replaced with `_` so the file stays in its directory.
+pub fn render_file_name_pattern(pattern: &str, vars: &FileNameVars) -> String {
+    let mut rendered = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open..];
+        let Some(close) = after.find('}') else {
+            rest = after;
+            break;
+        };
+        let value = match &after[1..close] {
+            "timestamp" => vars.at.format("%Y%m%d_%H%M%S").to_string(),
+            "date" => vars.at.format("%Y%m%d").to_string(),
+            "time" => vars.at.format("%H%M%S").to_string(),
+            "instance_id" => vars.instance_id.to_string(),
+            "session_id" => vars.session_id.to_string(),
+            "snapshot_count" => vars.snapshot_count.to_string(),
+            _ => after[..=close].to_string(),
+        };
+        rendered.push_str(&value);
+        rest = &after[close + 1..];
+    }
+    rendered.push_str(rest);
+    rendered.replace(['/', '\\'], "_")
+}
+
+/// Extensions of session files, longest first.
+const SESSION_EXTENSIONS: [&str; 4] = [".json.gz", ".jsonl.gz", ".json", ".jsonl"];
+
+/// Suffix added to the stem of flux-enriched session files.
+pub const ENRICHED_SUFFIX: &str = "_enriched";
+
+/// One piece of a parsed file name pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternPart {
+    Literal(String),
+    /// `{timestamp}`: `YYYYMMDD_HHMMSS`
+    Timestamp,
+    /// `{date}`: `YYYYMMDD`
+    Date,
+    /// `{time}`: `HHMMSS`
+    Time,
+    /// `{snapshot_count}`
+    Number,
+    /// `{instance_id}` or `{session_id}`: any text without dots
+    Text,
+}
+
+/// Values captured from a file name by a [`FileNamePattern`].
+#[derive(Debug, Default)]
+struct PatternCaptures<'a> {
+    timestamp: Option<&'a str>,
+    date: Option<&'a str>,
+    time: Option<&'a str>,
+}
+
+/// An `export_filename_pattern` parsed to recognize the session files it names.
+///
+/// A session file name is a rendered pattern, optionally followed by
+/// [`ENRICHED_SUFFIX`], with a `.json`, `.jsonl`, `.json.gz` or `.jsonl.gz`
+/// extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileNamePattern {
+    parts: Vec<PatternPart>,
+}
+
+impl FileNamePattern {
+    /// Parse a pattern in the syntax of [`render_file_name_pattern`].
+    pub fn new(pattern: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = pattern;
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let after = &rest[open..];
+            let Some(close) = after.find('}') else {
+                rest = after;
+                break;
+            };
+            let part = match &after[1..close] {
+                "timestamp" => PatternPart::Timestamp,
+                "date" => PatternPart::Date,
+                "time" => PatternPart::Time,
+                "snapshot_count" => PatternPart::Number,
+                "instance_id" | "session_id" => PatternPart::Text,
+                _ => {
+                    literal.push_str(&after[..=close]);
+                    rest = &after[close + 1..];
+                    continue;
+                }
+            };
+            if !literal.is_empty() {
+                parts.push(PatternPart::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(part);
+            rest = &after[close + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(PatternPart::Literal(literal));
+        }
+        // Rendering replaces path separators, so matching must too
+        for part in &mut parts {
+            if let PatternPart::Literal(text) = part {
+                *text = text.replace(['/', '\\'], "_");
+            }
+        }
+        Self { parts }
+    }
+
+    /// Check whether `file_name` names a session file written with this pattern.
+    pub fn matches(&self, file_name: &str) -> bool {
+        self.captures(file_name).is_some()
+    }
+
+    /// Get the time encoded in a session file name by `{timestamp}`, or by
+    /// `{date}` and `{time}` (midnight if the pattern has no time).
+    ///
+    /// Returns `None` if the name does not match or the pattern has no date.
+    pub fn timestamp(&self, file_name: &str) -> Option<DateTime<Utc>> {
+        let captures = self.captures(file_name)?;
+        if let Some(timestamp) = captures.timestamp {
+            return NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S")
+                .ok()
+                .map(|naive| naive.and_utc());
+        }
+        let date = NaiveDate::parse_from_str(captures.date?, "%Y%m%d").ok()?;
+        let time = match captures.time {
+            Some(time) => NaiveTime::parse_from_str(time, "%H%M%S").ok()?,
+            None => NaiveTime::MIN,
+        };
+        Some(date.and_time(time).and_utc())
+    }
+
+    /// Match a file name, returning the captured date and time values.
+    fn captures<'a>(&self, file_name: &'a str) -> Option<PatternCaptures<'a>> {
+        let stem = SESSION_EXTENSIONS
+            .iter()
+            .find_map(|ext| file_name.strip_suffix(ext))?;
+        let mut captures = PatternCaptures::default();
+        if match_parts(&self.parts, stem, &mut captures) {
+            return Some(captures);
+        }
+        let stem = stem.strip_suffix(ENRICHED_SUFFIX)?;
+        let mut captures = PatternCaptures::default();
+        match_parts(&self.parts, stem, &mut captures).then_some(captures)
+    }
+}
+
+/// Match all of `text` against `parts`, backtracking over variable-length parts.
+fn match_parts<'a>(
+    parts: &[PatternPart],
+    text: &'a str,
+    captures: &mut PatternCaptures<'a>,
+) -> bool {
+    let Some((part, rest)) = parts.split_first() else {
+        return text.is_empty();
+    };
+    let digits = |len: usize| {
+        text.get(..len)
+            .filter(|head| head.bytes().all(|b| b.is_ascii_digit()))
+    };
+    match part {
+        PatternPart::Literal(literal) => text
+            .strip_prefix(literal.as_str())
+            .is_some_and(|tail| match_parts(rest, tail, captures)),
+        PatternPart::Timestamp => {
+            let Some(head) = text.get(..15) else {
+                return false;
+            };
+            let well_formed = head.bytes().enumerate().all(|(i, b)| {
+                if i == 8 {
+                    b == b'_'
+                } else {
+                    b.is_ascii_digit()
+                }
+            });
+            if !well_formed || !match_parts(rest, &text[15..], captures) {
+                return false;
+            }
+            captures.timestamp = Some(head);
+            true
+        }
+        PatternPart::Date | PatternPart::Time => {
+            let len = if *part == PatternPart::Date { 8 } else { 6 };
+            let Some(head) = digits(len) else {
+                return false;
+            };
+            if !match_parts(rest, &text[len..], captures) {
+                return false;
+            }
+            if *part == PatternPart::Date {
+                captures.date = Some(head);
+            } else {
+                captures.time = Some(head);
+            }
+            true
+        }
+        PatternPart::Number | PatternPart::Text => {
+            let accepts = |c: char| match part {
+                PatternPart::Number => c.is_ascii_digit(),
+                _ => c != '.',
+            };
+            for (i, c) in text.char_indices() {
+                if !accepts(c) {
+                    break;
+                }
+                let end = i + c.len_utf8();
+                if match_parts(rest, &text[end..], captures) {
+                    return true;
+                }
+            }
+            false
+        }
+    }
+}
+
+/// Check whether a path names a gzip-compressed export.
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension().map(|e| e == "gz").unwrap_or(false)
+}
+
+/// Check whether a path names a session snapshot file.
+///
+/// Bulk session files are `.json` or `.json.gz`; streamed session files are
+/// `.jsonl` files named by `pattern`.
+pub fn is_snapshot_file(path: &Path, pattern: &FileNamePattern) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| {
+            name.ends_with(".json")
+                || name.ends_with(".json.gz")
+                || (name.ends_with(".jsonl") && pattern.matches(name))
+        })
+        .unwrap_or(false)
+}
+
+/// Check whether a path names a JSON Lines file (`.jsonl` or `.jsonl.gz`).
+pub fn is_jsonl(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.trim_end_matches(GZIP_SUFFIX).ends_with(".jsonl"))
+        .unwrap_or(false)
+}
+
+/// Write raw export bytes, gzip-compressing them when requested.
+pub fn write_bytes(path: &Path, bytes: &[u8], compress: bool) -> std::io::Result<()> {
+    if compress {
+        let file = std::fs::File::create(path)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(bytes)?;
+        encoder.finish()?;
+        Ok(())
+    } else {
+        std::fs::write(path, bytes)
+    }
+}
+
+/// Read an export file as text, decompressing it if it has a `.gz` suffix.
+pub fn read_to_string(path: &Path) -> std::io::Result<String> {
+    if is_compressed(path) {
+        let file = std::fs::File::open(path)?;
+        let mut content = String::new();
+        GzDecoder::new(file).read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Serialize snapshots as a pretty JSON array and write them to `path`.
+pub fn write_snapshots(
+    path: &Path,
+    snapshots: &[HsiSnapshot],
+    compress: bool,
+) -> Result<(), ExportError> {
+    let json = serde_json::to_string_pretty(snapshots)
+        .map_err(|e| ExportError::SerializeError(e.to_string()))?;
+    write_bytes(path, json.as_bytes(), compress).map_err(|e| ExportError::IoError(e.to_string()))
+}
+
+/// Read snapshots from a JSON array or JSON Lines file, decompressing it if needed.
+///
+/// A truncated trailing line in a JSON Lines file (e.g. from a crash
+/// mid-write) is skipped rather than failing the whole file.
+pub fn read_snapshots(path: &Path) -> Result<Vec<HsiSnapshot>, ExportError> {
+    let content = read_to_string(path).map_err(|e| ExportError::IoError(e.to_string()))?;
+    if is_jsonl(path) {
+        let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+        let mut snapshots = Vec::with_capacity(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str(line) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(_) if i + 1 == lines.len() => break,
+                Err(e) => return Err(ExportError::ParseError(e.to_string())),
+            }
+        }
+        Ok(snapshots)
+    } else {
+        serde_json::from_str(&content).map_err(|e| ExportError::ParseError(e.to_string()))
+    }
+}
+
+/// Window metadata columns at the start of each CSV row.
+pub const CSV_META_COLUMNS: &[&str] = &[
+    "observed_at_utc",
+    "session_start",
+    "window_duration_secs",
+    "keyboard_events",
+    "mouse_events",
+    "rhythm_class",
+    "mouse_class",
+    "raw_typing_rate",
+    "raw_mean_velocity",
+    "raw_click_rate",
+    "typing_tap_count",
+    "navigation_key_count",
+    "idle_time_ms",
+    "deep_focus_block",
+];
+
+/// Axis score columns of each CSV row, in the order the default providers emit them.
+pub const CSV_AXIS_COLUMNS: &[&str] = &[
+    "typing_rate",
+    "typing_burstiness",
+    "session_continuity",
+    "idle_ratio",
+    "focus_continuity",
+    "interaction_rhythm",
+    "motor_stability",
+    "friction",
+    "typing_cadence_stability",
+    "typing_gap_ratio",
+    "typing_interaction_intensity",
+    "keyboard_scroll_rate",
+    "burstiness",
+    "iki_p25",
+    "iki_p50",
+    "iki_p75",
+    "iki_p95",
+    "iki_entropy",
+    "iki_autocorrelation",
+    "mouse_velocity_p25",
+    "mouse_velocity_p50",
+    "mouse_velocity_p75",
+    "mouse_velocity_p95",
+    "scroll_reversal_rate",
+    "click_hold_mean",
+    "click_hold_cv",
+    "middle_click_rate",
+    "scroll_click_ratio",
+    "interaction_persistence",
+    "reengagement_rate",
+    "input_modality_ratio",
+];
+
+/// Build the CSV header row matching [`snapshot_to_csv_row`].
+pub fn csv_header() -> String {
+    CSV_META_COLUMNS
+        .iter()
+        .chain(CSV_AXIS_COLUMNS)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Flatten a window snapshot into a CSV row.
+///
+/// Metadata comes from the snapshot's `meta` block and axis columns hold
+/// the axis scores. Missing values and unavailable scores are left empty.
+pub fn snapshot_to_csv_row(snapshot: &HsiSnapshot) -> String {
+    let meta_value = |column: &str| {
+        // Columns are named after the window, some meta keys are not
+        let key = match column {
+            "session_start" => "is_session_start",
+            "window_duration_secs" => "duration_secs",
+            other => other,
+        };
+        match snapshot.meta.as_ref().and_then(|meta| meta.get(key)) {
+            Some(serde_json::Value::String(s)) => csv_field(s),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        }
+    };
+
+    let mut fields = vec![csv_field(&snapshot.observed_at_utc)];
+    fields.extend(
+        CSV_META_COLUMNS[1..]
+            .iter()
+            .map(|column| meta_value(column)),
+    );
+    fields.extend(CSV_AXIS_COLUMNS.iter().map(|axis| {
+        snapshot
+            .axis_score(axis)
+            .map(|score| score.to_string())
+            .unwrap_or_default()
+    }));
+    fields.join(",")
+}
+
+/// Render window snapshots as CSV, with a header row and one row per window.
+///
+/// Session summary snapshots are skipped.
+pub fn snapshots_to_csv(snapshots: &[HsiSnapshot]) -> String {
+    let mut csv = csv_header();
+    csv.push('\n');
+    for snapshot in snapshots.iter().filter(|s| !s.is_session_summary()) {
+        csv.push_str(&snapshot_to_csv_row(snapshot));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Encode snapshots as a stream of length-delimited protobuf messages.
+#[cfg(feature = "proto")]
+pub fn snapshots_to_proto_stream(snapshots: &[HsiSnapshot]) -> Vec<u8> {
+    use prost::Message;
+
+    let mut bytes = Vec::new();
+    for snapshot in snapshots {
+        // Encoding into a Vec cannot run out of space
+        let _ = snapshot.to_proto().encode_length_delimited(&mut bytes);
+    }
+    bytes
+}
+
+/// Decode a stream of length-delimited protobuf messages into snapshots.
+#[cfg(feature = "proto")]
+pub fn read_proto_stream(mut bytes: &[u8]) -> Result<Vec<HsiSnapshot>, ExportError> {
+    use prost::Message;
+
+    let mut snapshots = Vec::new();
+    while !bytes.is_empty() {
+        let message = crate::proto::HsiSnapshot::decode_length_delimited(&mut bytes)
+            .map_err(|e| ExportError::ParseError(e.to_string()))?;
+        snapshots.push(
+            HsiSnapshot::from_proto(message).map_err(|e| ExportError::ParseError(e.to_string()))?,
+        );
+    }
+    Ok(snapshots)
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse an ISO 8601 date or date-time used to select snapshots.
+///
+/// Accepts RFC3339 (`2024-01-15T14:30:00Z`, `2024-01-15T14:30:00+01:00`),
+/// a date-time without offset, taken as UTC (`2024-01-15T14:30:00`), or a
+/// date, meaning midnight UTC (`2024-01-15`).
+pub fn parse_datetime(value: &str) -> Result<DateTime<Utc>, ExportError> {
+    let value = value.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Ok(t.and_utc());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    Err(ExportError::ParseError(format!(
+        "Invalid date/time {value:?}: expected ISO 8601, e.g. 2024-01-15 or 2024-01-15T14:30:00Z"
+    )))
+}
+
+/// Keep the snapshots observed in `[since, until)`.
+///
+/// Either bound may be open. Snapshots whose `observed_at_utc` cannot be
+/// parsed are dropped when a bound is given.
+pub fn filter_by_observed_at(
+    snapshots: Vec<HsiSnapshot>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Vec<HsiSnapshot> {
+    if since.is_none() && until.is_none() {
+        return snapshots;
+    }
+    snapshots
+        .into_iter()
+        .filter(|s| {
+            let Ok(observed) = DateTime::parse_from_rfc3339(&s.observed_at_utc) else {
+                return false;
+            };
+            let observed = observed.with_timezone(&Utc);
+            !since.is_some_and(|since| observed < since)
+                && !until.is_some_and(|until| observed >= until)
+        })
+        .collect()
+}
+
+/// Combine snapshots from several session files into chronological order.
+///
+/// Snapshots are sorted by `observed_at_utc` (the sort is stable, so ties
+/// keep their input order). With `deduplicate`, only the first snapshot for
+/// each primary window is kept, where a window is identified by its ID,
+/// label and resolution. Returns the merged snapshots and the number of
+/// duplicates removed.
+pub fn merge_snapshots(
+    mut snapshots: Vec<HsiSnapshot>,
+    deduplicate: bool,
+) -> (Vec<HsiSnapshot>, usize) {
+    snapshots.sort_by_cached_key(|s| {
+        DateTime::parse_from_rfc3339(&s.observed_at_utc)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+    });
+
+    if !deduplicate {
+        return (snapshots, 0);
+    }
+
+    let before = snapshots.len();
+    let mut seen = std::collections::HashSet::new();
+    snapshots.retain(|s| match s.window_ids.first() {
+        Some(window_id) => {
+            // Older files gave every resolution of one flush the same ID
+            let window = s.windows.get(window_id);
+            let label = window
+                .and_then(|w| w.label.as_ref())
+                .map(|label| label.as_str().to_string());
+            let resolution_secs = window.and_then(|w| w.resolution_secs);
+            seen.insert((window_id.clone(), label, resolution_secs))
+        }
+        None => true,
+    });
+    let removed = before - snapshots.len();
+    (snapshots, removed)
+}
+
+/// Granularity for grouping snapshots into aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateBy {
+    /// One aggregate per UTC calendar day
+    Day,
+    /// One aggregate per UTC hour
+    Hour,
+    /// One aggregate per recording session
+    Session,
+}
+
+impl AggregateBy {
+    /// Get the name of this granularity (e.g. `day`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AggregateBy::Day => "day",
+            AggregateBy::Hour => "hour",
+            AggregateBy::Session => "session",
+        }
+    }
+
+    /// Window label given to the aggregates (e.g. `aggregate_day`).
+    pub fn window_label(&self) -> HsiWindowLabel {
+        HsiWindowLabel::Custom(format!("aggregate_{}", self.as_str()))
+    }
+
+    /// Key of the group a snapshot falls into, or `None` if it has no
+    /// parseable observation time.
+    fn group_key(&self, snapshot: &HsiSnapshot) -> Option<String> {
+        let observed = DateTime::parse_from_rfc3339(&snapshot.observed_at_utc)
+            .ok()?
+            .with_timezone(&Utc);
+        Some(match self {
+            AggregateBy::Day => observed.format("%Y-%m-%d").to_string(),
+            AggregateBy::Hour => observed.format("%Y-%m-%dT%H:00:00Z").to_string(),
+            AggregateBy::Session => snapshot
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.get("session_id"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        })
+    }
+}
+
+impl std::str::FromStr for AggregateBy {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "day" => Ok(AggregateBy::Day),
+            "hour" => Ok(AggregateBy::Hour),
+            "session" => Ok(AggregateBy::Session),
+            other => Err(ExportError::ParseError(format!(
+                "Unknown aggregation '{other}' (expected day, hour or session)"
+            ))),
+        }
+    }
+}
+
+/// Group snapshots by `by` and build one aggregate snapshot per group.
+///
+/// Each aggregate is a session summary of its group (mean score per axis,
+/// median for percentile axes) whose window is labelled
+/// [`AggregateBy::window_label`]. Its `meta.axis_statistics` holds the mean,
+/// median, standard deviation, window count and direction of every axis, so
+/// that `higher_is_less` axes still read as "higher is worse" after
+/// aggregation. Session summaries in the input are skipped so windows are not
+/// counted twice. Aggregates are returned in chronological order.
+///
+/// `builder` should not have a signer, since aggregates are modified after
+/// they are built.
+pub fn aggregate_snapshots(
+    snapshots: Vec<HsiSnapshot>,
+    by: AggregateBy,
+    builder: &HsiBuilder,
+) -> Vec<HsiSnapshot> {
+    let (snapshots, _) = merge_snapshots(snapshots, false);
+
+    let mut groups: Vec<(String, Vec<HsiSnapshot>)> = Vec::new();
+    for snapshot in snapshots {
+        if snapshot.is_session_summary() {
+            continue;
+        }
+        let Some(key) = by.group_key(&snapshot) else {
+            continue;
+        };
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(snapshot),
+            None => groups.push((key, vec![snapshot])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, group)| {
+            let statistics: serde_json::Map<String, serde_json::Value> = axis_statistics(&group)
+                .into_iter()
+                .map(|stats| {
+                    let value = serde_json::json!({
+                        "count": stats.count,
+                        "mean": stats.mean,
+                        "median": stats.median,
+                        "std_dev": stats.std_dev,
+                        "direction": stats.direction.map(|d| d.as_str()),
+                    });
+                    (stats.axis, value)
+                })
+                .collect();
+
+            // Summary window IDs are only unique per millisecond, so name
+            // each aggregate window after its group instead
+            let mut aggregate = builder.build_session_summary(group);
+            let window_id = format!("w_aggregate_{}_{key}", by.as_str());
+            aggregate.windows = aggregate
+                .windows
+                .into_values()
+                .map(|mut window| {
+                    window.label = Some(by.window_label());
+                    (window_id.clone(), window)
+                })
+                .collect();
+            aggregate.window_ids = vec![window_id.clone()];
+            for domain in aggregate.axes.iter_mut().flat_map(|axes| {
+                [&mut axes.affect, &mut axes.engagement, &mut axes.behavior]
+                    .into_iter()
+                    .flatten()
+            }) {
+                for reading in &mut domain.readings {
+                    reading.window_id = window_id.clone();
+                }
+            }
+            let meta = aggregate.meta.get_or_insert_with(Default::default);
+            meta.insert(
+                "aggregate_by".to_string(),
+                serde_json::Value::String(by.as_str().to_string()),
+            );
+            meta.insert("aggregate_key".to_string(), serde_json::Value::String(key));
+            meta.insert(
+                "axis_statistics".to_string(),
+                serde_json::Value::Object(statistics),
+            );
+            aggregate
+        })
+        .collect()
+}
+
+/// Write snapshots to `path` in the format implied by its name.
+///
+/// `.jsonl` files get one snapshot per line, anything else a pretty JSON
+/// array; a `.gz` suffix compresses the output.
+pub fn write_snapshot_file(path: &Path, snapshots: &[HsiSnapshot]) -> Result<(), ExportError> {
+    let compress = is_compressed(path);
+    if !is_jsonl(path) {
+        return write_snapshots(path, snapshots, compress);
+    }
+
+    let mut content = String::new();
+    for snapshot in snapshots {
+        content.push_str(
+            &serde_json::to_string(snapshot)
+                .map_err(|e| ExportError::SerializeError(e.to_string()))?,
+        );
+        content.push('\n');
+    }
+    write_bytes(path, content.as_bytes(), compress).map_err(|e| ExportError::IoError(e.to_string()))
+}
+
+/// List the session snapshot files in a directory, streamed files being
+/// recognized by `pattern`.
+pub fn snapshot_files(dir: &Path, pattern: &FileNamePattern) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| is_snapshot_file(p, pattern))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Overview of a single session file.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionFileSummary {
+    /// File name within the export directory
+    pub file_name: String,
+    /// Number of snapshots in the file (including any session summary)
+    pub snapshot_count: usize,
+    /// Earliest window start (RFC3339)
+    pub start: Option<String>,
+    /// Latest window end (RFC3339)
+    pub end: Option<String>,
+    /// Keyboard and mouse events across all windows
+    pub total_events: u64,
+    /// File size on disk in bytes
+    pub size_bytes: u64,
+}
+
+impl SessionFileSummary {
+    /// Summarize already-loaded snapshots from the given file.
+    pub fn from_snapshots(path: &Path, snapshots: &[HsiSnapshot]) -> Self {
+        let mut start: Option<DateTime<Utc>> = None;
+        let mut end: Option<DateTime<Utc>> = None;
+        for window in snapshots.iter().flat_map(|s| s.windows.values()) {
+            if let Ok(t) = DateTime::parse_from_rfc3339(&window.start) {
+                let t = t.with_timezone(&Utc);
+                start = Some(start.map_or(t, |s| s.min(t)));
+            }
+            if let Ok(t) = DateTime::parse_from_rfc3339(&window.end) {
+                let t = t.with_timezone(&Utc);
+                end = Some(end.map_or(t, |e| e.max(t)));
+            }
+        }
+
+        // Per-window event counts; session summaries carry no such counts
+        let meta_count = |snapshot: &HsiSnapshot, key: &str| {
+            snapshot
+                .meta
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+        };
+        let total_events = snapshots
+            .iter()
+            .map(|s| meta_count(s, "keyboard_events") + meta_count(s, "mouse_events"))
+            .sum();
+
+        Self {
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            snapshot_count: snapshots.len(),
+            start: start.map(|t| t.to_rfc3339()),
+            end: end.map(|t| t.to_rfc3339()),
+            total_events,
+            size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        }
+    }
+
+    /// Read and summarize a session file.
+    pub fn from_file(path: &Path) -> Result<Self, ExportError> {
+        let snapshots = read_snapshots(path)?;
+        Ok(Self::from_snapshots(path, &snapshots))
+    }
+}
+
+/// List the session files named by `pattern` in a directory, oldest first.
+///
+/// Files are ordered by the time in their name, then by name.
+pub fn session_files(dir: &Path, pattern: &FileNamePattern) -> Vec<PathBuf> {
+    let mut files: Vec<(Option<DateTime<Utc>>, PathBuf)> = snapshot_files(dir, pattern)
+        .into_iter()
+        .filter_map(|p| {
+            let name = p.file_name()?.to_str()?;
+            if !pattern.matches(name) {
+                return None;
+            }
+            Some((pattern.timestamp(name), p))
+        })
+        .collect();
+    files.sort();
+    files.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Appends snapshots to a JSON Lines file as they are produced.
+///
+/// The file is opened lazily on the first append, so sessions that never
+/// complete a window leave no file behind. Each line is flushed immediately.
+pub struct ExportWriter {
+    /// Destination file path
+    path: PathBuf,
+    /// Open file handle, created on first append
+    file: Option<File>,
+    /// Number of snapshots written so far
+    lines_written: usize,
+}
+
+impl ExportWriter {
+    /// Create a writer for the given `.jsonl` path without opening it yet.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            lines_written: 0,
+        }
+    }
+
+    /// Append one snapshot as a single JSON line.
+    pub fn append(&mut self, snapshot: &HsiSnapshot) -> Result<(), ExportError> {
+        let mut line = serde_json::to_string(snapshot)
+            .map_err(|e| ExportError::SerializeError(e.to_string()))?;
+        line.push('\n');
+
+        let file = match self.file {
+            Some(ref mut file) => file,
+            None => {
+                if let Some(parent) = self.path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| ExportError::IoError(e.to_string()))?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .map_err(|e| ExportError::IoError(e.to_string()))?;
+                self.file.insert(file)
+            }
+        };
+
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| ExportError::IoError(e.to_string()))?;
+        self.lines_written += 1;
+        Ok(())
+    }
+
+    /// Get the destination file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the number of snapshots written so far.
+    pub fn lines_written(&self) -> usize {
+        self.lines_written
+    }
+}
+
+/// Export errors.
+#[derive(Debug)]
+pub enum ExportError {
+    IoError(String),
+    ParseError(String),
+    SerializeError(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::IoError(e) => write!(f, "IO error: {e}"),
+            ExportError::ParseError(e) => write!(f, "Parse error: {e}"),
+            ExportError::SerializeError(e) => write!(f, "Serialize error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use crate::test_utils::sample_snapshot;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(
+            export_file_name("session_1", "json", false),
+            "session_1.json"
+        );
+        assert_eq!(
+            export_file_name("session_1", "json", true),
+            "session_1.json.gz"
+        );
+        let pattern = FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN);
+        assert!(is_snapshot_file(Path::new("session_1.json.gz"), &pattern));
+        assert!(is_snapshot_file(
+            Path::new("session_20240115_143000.jsonl"),
+            &pattern
+        ));
+        assert!(!is_snapshot_file(Path::new("export_1.jsonl"), &pattern));
+    }
+
+    #[test]
+    fn test_snapshots_to_csv() {
+        let snapshot = sample_snapshot();
+        let summary = HsiBuilder::new().build_session_summary(vec![snapshot.clone()]);
+
+        let csv = snapshots_to_csv(&[snapshot.clone(), summary]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let header: Vec<&str> = lines[0].split(',').collect();
+        let row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(header.len(), row.len());
+        let column = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(column("observed_at_utc"), snapshot.observed_at_utc);
+        assert_eq!(column("session_start"), "false");
+        assert_eq!(column("window_duration_secs"), "10.0");
+        assert_eq!(column("keyboard_events"), "0");
+        assert_eq!(column("rhythm_class"), "SPARSE");
+        assert_eq!(
+            column("typing_rate"),
+            snapshot.axis_score("typing_rate").unwrap().to_string()
+        );
+    }
+
+    #[cfg(feature = "proto")]
+    #[test]
+    fn test_proto_stream_round_trip() {
+        let snapshots = vec![sample_snapshot(), sample_snapshot()];
+        let bytes = snapshots_to_proto_stream(&snapshots);
+        let decoded = read_proto_stream(&bytes).unwrap();
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].observed_at_utc, snapshots[1].observed_at_utc);
+        assert_eq!(
+            decoded[1].axis_score("typing_rate"),
+            snapshots[1].axis_score("typing_rate")
+        );
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_session_file_summary() {
+        let dir = std::env::temp_dir().join(format!("synheart-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session_20240115_143210.json");
+        std::fs::write(dir.join("export_20240115_143210.json"), "[]").unwrap();
+
+        let start = Utc::now();
+        let builder = HsiBuilder::new();
+        let snapshots: Vec<HsiSnapshot> = (0..2)
+            .map(|i| {
+                let window =
+                    EventWindow::new(start + Duration::seconds(10 * i), Duration::seconds(10));
+                let features = compute_features(&window, &FeatureConfig::default());
+                builder.build(&window, &features, Duration::seconds(10), None)
+            })
+            .collect();
+        write_snapshots(&path, &snapshots, false).unwrap();
+
+        let pattern = FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN);
+        assert_eq!(session_files(&dir, &pattern), vec![path.clone()]);
+
+        let summary = SessionFileSummary::from_file(&path).unwrap();
+        assert_eq!(summary.file_name, "session_20240115_143210.json");
+        assert_eq!(summary.snapshot_count, 2);
+        assert_eq!(summary.start, Some(start.to_rfc3339()));
+        assert_eq!(
+            summary.end,
+            Some((start + Duration::seconds(20)).to_rfc3339())
+        );
+        assert_eq!(summary.total_events, 0);
+        assert!(summary.size_bytes > 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_gzip_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("synheart-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(export_file_name("session", "json", true));
+
+        let snapshot = sample_snapshot();
+        write_snapshots(&path, std::slice::from_ref(&snapshot), true).unwrap();
+
+        // The file on disk is gzip data, not plain JSON
+        let raw = std::fs::read(&path).unwrap();
+        assert_eq!(&raw[..2], &[0x1f, 0x8b]);
+
+        let restored = read_snapshots(&path).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&restored[0]).unwrap(),
+            serde_json::to_value(&snapshot).unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_writer_appends_lines() {
+        let dir = std::env::temp_dir().join(format!("synheart-export-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("session_20240115_143000.jsonl");
+
+        let mut writer = ExportWriter::new(path.clone());
+        assert!(!path.exists()); // Opened lazily
+
+        writer.append(&sample_snapshot()).unwrap();
+        writer.append(&sample_snapshot()).unwrap();
+        assert_eq!(writer.lines_written(), 2);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        let pattern = FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN);
+        assert!(is_snapshot_file(&path, &pattern));
+        assert_eq!(read_snapshots(&path).unwrap().len(), 2);
+
+        // A truncated trailing line is skipped
+        std::fs::write(&path, format!("{content}{{\"hsi_version\"")).unwrap();
+        assert_eq!(read_snapshots(&path).unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_file_name_pattern() {
+        let vars = FileNameVars {
+            at: Utc.with_ymd_and_hms(2024, 1, 15, 14, 30, 0).unwrap(),
+            instance_id: "inst",
+            session_id: "sess/1",
+            snapshot_count: 42,
+        };
+        assert_eq!(
+            render_file_name_pattern("session_{timestamp}", &vars),
+            "session_20240115_143000"
+        );
+        assert_eq!(
+            render_file_name_pattern("{date}-{time}_{instance_id}_{snapshot_count}", &vars),
+            "20240115-143000_inst_42"
+        );
+        assert_eq!(
+            render_file_name_pattern("{session_id}_{unknown}_{", &vars),
+            "sess_1_{unknown}_{"
+        );
+    }
+
+    #[test]
+    fn test_file_name_pattern_matches_rendered_names() {
+        let vars = FileNameVars {
+            at: Utc.with_ymd_and_hms(2024, 1, 15, 14, 30, 0).unwrap(),
+            instance_id: "inst",
+            session_id: "sess/1",
+            snapshot_count: 42,
+        };
+        for raw in [
+            "session_{timestamp}",
+            "{date}-{time}_{session_id}",
+            "{instance_id}_{timestamp}_{snapshot_count}",
+            "{date}/{session_id}",
+        ] {
+            let pattern = FileNamePattern::new(raw);
+            let stem = render_file_name_pattern(raw, &vars);
+            for name in [
+                format!("{stem}.json"),
+                format!("{stem}.json.gz"),
+                format!("{stem}.jsonl"),
+                format!("{stem}_enriched.json"),
+            ] {
+                assert!(pattern.matches(&name), "{raw} should match {name}");
+            }
+            assert!(!pattern.matches(&format!("{stem}.export.json")), "{raw}");
+            assert!(!pattern.matches(&format!("{stem}.csv")), "{raw}");
+        }
+
+        let pattern = FileNamePattern::new("{date}-{time}_{session_id}");
+        assert_eq!(
+            pattern.timestamp("20240115-143000_abc.jsonl"),
+            Some(vars.at)
+        );
+        assert!(!pattern.matches("session_20240115_143000.json"));
+        assert!(!pattern.matches("2024011-143000_abc.json"));
+
+        let pattern = FileNamePattern::new("{date}_{session_id}");
+        assert_eq!(
+            pattern.timestamp("20240115_abc.json"),
+            Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap())
+        );
+        let pattern = FileNamePattern::new("capture_{session_id}");
+        assert!(pattern.matches("capture_abc.json"));
+        assert_eq!(pattern.timestamp("capture_abc.json"), None);
+    }
+
+    #[test]
+    fn test_session_files_custom_pattern() {
+        let dir = std::env::temp_dir().join(format!("synheart-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "20240116-090000_b.jsonl",
+            "20240115-143000_a.json",
+            "20240115-143000_a.export.json",
+            "session_20240101_000000.json",
+        ] {
+            std::fs::write(dir.join(name), "[]").unwrap();
+        }
+
+        let pattern = FileNamePattern::new("{date}-{time}_{session_id}");
+        assert_eq!(
+            session_files(&dir, &pattern),
+            vec![
+                dir.join("20240115-143000_a.json"),
+                dir.join("20240116-090000_b.jsonl"),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 15, 14, 30, 0).unwrap();
+        assert_eq!(parse_datetime("2024-01-15T14:30:00Z").unwrap(), expected);
+        assert_eq!(
+            parse_datetime("2024-01-15T15:30:00+01:00").unwrap(),
+            expected
+        );
+        assert_eq!(parse_datetime("2024-01-15T14:30:00").unwrap(), expected);
+        assert_eq!(
+            parse_datetime("2024-01-15").unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()
+        );
+
+        let error = parse_datetime("yesterday").unwrap_err();
+        assert!(error.to_string().contains("\"yesterday\""));
+    }
+
+    #[test]
+    fn test_filter_by_observed_at() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 14, 30, 0).unwrap();
+        let builder = HsiBuilder::new();
+        let snapshots: Vec<HsiSnapshot> = (0..3)
+            .map(|i| {
+                let window =
+                    EventWindow::new(start + Duration::seconds(10 * i), Duration::seconds(10));
+                let features = compute_features(&window, &FeatureConfig::default());
+                builder.build(&window, &features, Duration::seconds(10), None)
+            })
+            .collect();
+
+        // Observed at the window ends: start + 10s, 20s and 30s
+        let all = filter_by_observed_at(snapshots.clone(), None, None);
+        assert_eq!(all.len(), 3);
+        let since =
+            filter_by_observed_at(snapshots.clone(), Some(start + Duration::seconds(20)), None);
+        assert_eq!(since.len(), 2);
+        let range = filter_by_observed_at(
+            snapshots,
+            Some(start + Duration::seconds(10)),
+            Some(start + Duration::seconds(30)),
+        );
+        assert_eq!(range.len(), 2);
+        assert_eq!(
+            range[1].observed_at_utc,
+            (start + Duration::seconds(20)).to_rfc3339()
+        );
+    }
+
+    #[test]
+    fn test_merge_snapshots() {
+        let start = Utc::now();
+        let builder = HsiBuilder::new();
+        let snapshots: Vec<HsiSnapshot> = (0..3)
+            .map(|i| {
+                let window =
+                    EventWindow::new(start + Duration::seconds(10 * i), Duration::seconds(10));
+                let features = compute_features(&window, &FeatureConfig::default());
+                let mut snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+                snapshot.window_ids = vec![format!("w_{i}")];
+                snapshot
+            })
+            .collect();
+
+        // Second file overlaps the first and is out of order
+        let input = vec![
+            snapshots[2].clone(),
+            snapshots[0].clone(),
+            snapshots[1].clone(),
+            snapshots[2].clone(),
+        ];
+
+        let (merged, removed) = merge_snapshots(input.clone(), false);
+        assert_eq!(removed, 0);
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[0].window_ids, vec!["w_0"]);
+
+        let (merged, removed) = merge_snapshots(input, true);
+        assert_eq!(removed, 1);
+        let ids: Vec<&str> = merged.iter().map(|s| s.window_ids[0].as_str()).collect();
+        assert_eq!(ids, vec!["w_0", "w_1", "w_2"]);
+
+        let dir = std::env::temp_dir().join(format!("synheart-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("merged.jsonl.gz");
+        write_snapshot_file(&path, &merged).unwrap();
+        assert_eq!(read_snapshots(&path).unwrap().len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_keeps_each_resolution_of_a_flush() {
+        let start = Utc::now();
+        let builder = HsiBuilder::new();
+        let snapshots: Vec<HsiSnapshot> = [10, 60]
+            .into_iter()
+            .map(|secs| {
+                let window = EventWindow::new(start, Duration::seconds(secs));
+                let features = compute_features(&window, &FeatureConfig::default());
+                builder.build(&window, &features, Duration::seconds(secs), None)
+            })
+            .collect();
+
+        let (merged, removed) = merge_snapshots(snapshots.clone(), true);
+        assert_eq!(removed, 0);
+        assert_eq!(merged.len(), 2);
+
+        // Files written before window IDs included the resolution
+        let legacy: Vec<HsiSnapshot> = snapshots
+            .into_iter()
+            .map(|mut snapshot| {
+                let window = snapshot.windows.drain().next().unwrap().1;
+                snapshot.window_ids = vec!["w_1705327930000".to_string()];
+                snapshot
+                    .windows
+                    .insert("w_1705327930000".to_string(), window);
+                snapshot
+            })
+            .collect();
+        let input = [legacy.clone(), legacy].concat();
+        let (merged, removed) = merge_snapshots(input, true);
+        assert_eq!(removed, 2);
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn test_aggregate_snapshots() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 14, 59, 40).unwrap();
+        let builder = HsiBuilder::new();
+        // Observed at 14:59:50, 15:00:00 and 15:00:10
+        let snapshots: Vec<HsiSnapshot> = [2.0, 4.0, 6.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, typing_rate)| {
+                let window = EventWindow::new(
+                    start + Duration::seconds(10 * i as i64),
+                    Duration::seconds(10),
+                );
+                let mut features = compute_features(&window, &FeatureConfig::default());
+                features.keyboard.typing_rate = typing_rate;
+                builder.build(&window, &features, Duration::seconds(10), None)
+            })
+            .collect();
+
+        assert_eq!("Hour".parse::<AggregateBy>().unwrap(), AggregateBy::Hour);
+        assert!("week".parse::<AggregateBy>().is_err());
+
+        let mut input = snapshots.clone();
+        input.push(builder.build_session_summary(snapshots.clone()));
+        let days = aggregate_snapshots(input, AggregateBy::Day, &HsiBuilder::new());
+        assert_eq!(days.len(), 1);
+        let day = &days[0];
+        let window = day.windows.values().next().unwrap();
+        assert_eq!(window.label.as_ref().unwrap().as_str(), "aggregate_day");
+        assert_eq!(day.window_ids, vec!["w_aggregate_day_2024-01-15"]);
+        let meta = day.meta.as_ref().unwrap();
+        assert_eq!(meta["window_count"], 3);
+        assert_eq!(meta["aggregate_key"], "2024-01-15");
+        let typing = &meta["axis_statistics"]["typing_rate"];
+        assert!((typing["mean"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+        assert!((typing["median"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(typing["count"], 3);
+        assert_eq!(typing["direction"], "higher_is_more");
+        assert!((day.axis_score("typing_rate").unwrap() - 0.4).abs() < 1e-9);
+
+        let hours = aggregate_snapshots(snapshots, AggregateBy::Hour, &HsiBuilder::new());
+        let keys: Vec<&str> = hours
+            .iter()
+            .map(|h| h.meta.as_ref().unwrap()["aggregate_key"].as_str().unwrap())
+            .collect();
+        assert_eq!(keys, vec!["2024-01-15T14:00:00Z", "2024-01-15T15:00:00Z"]);
+    }
+}
@@ -3,7 +3,9 @@
 //! This module bridges the gap between the sensor agent's keyboard/mouse
 //! events and the behavior module's event types.
 
-use crate::collector::types::{KeyboardEvent, MouseEvent, MouseEventType};
+use crate::collector::types::{
+    KeyboardEvent, KeyboardEventType, MouseEvent, MouseEventType, Platform, SensorEvent,
+};
 use crate::core::windowing::EventWindow;
 use synheart_flux::behavior::types::{ScrollEvent, TapEvent, TypingEvent};
 use synheart_flux::behavior::{BehaviorEvent, BehaviorEventType, BehaviorSession};
@@ -35,7 +37,7 @@ impl SensorBehaviorAdapter {
     pub fn convert(&self, session_id: &str, window: &EventWindow) -> BehaviorSession {
         let mut events = Vec::new();
 
-        // Convert keyboard events to typing events
+        // Convert keyboard events to typing (or keyboard scroll) events
         for kb_event in &window.keyboard_events {
             if kb_event.is_key_down {
                 events.push(self.keyboard_to_behavior(kb_event));
@@ -63,7 +65,27 @@ impl SensorBehaviorAdapter {
     }
 
     /// Convert a keyboard event to a typing behavior event.
+    ///
+    /// Navigation keys (arrows, page up/down, home/end) become scroll events
+    /// instead, so keyboard scrolling does not inflate flux typing metrics.
     fn keyboard_to_behavior(&self, kb: &KeyboardEvent) -> BehaviorEvent {
+        if kb.event_type == KeyboardEventType::NavigationKey {
+            return BehaviorEvent {
+                timestamp: kb.timestamp,
+                event_type: BehaviorEventType::Scroll,
+                scroll: Some(ScrollEvent {
+                    velocity: None, // Key presses have no scroll magnitude
+                    direction: None,
+                    direction_reversal: false,
+                }),
+                tap: None,
+                swipe: None,
+                interruption: None,
+                typing: None,
+                app_switch: None,
+            };
+        }
+
         BehaviorEvent {
             timestamp: kb.timestamp,
             event_type: BehaviorEventType::Typing,
@@ -114,7 +136,9 @@ impl SensorBehaviorAdapter {
                     app_switch: None,
                 })
             }
-            MouseEventType::LeftClick | MouseEventType::RightClick => {
+            MouseEventType::LeftClick
+            | MouseEventType::RightClick
+            | MouseEventType::MiddleClick => {
                 Some(BehaviorEvent {
                     timestamp: mouse.timestamp,
                     event_type: BehaviorEventType::Tap,
@@ -129,7 +153,11 @@ impl SensorBehaviorAdapter {
                     app_switch: None,
                 })
             }
-            MouseEventType::Scroll => {
+            // Releases only carry hold duration, which flux does not consume
+            MouseEventType::LeftRelease | MouseEventType::RightRelease => None,
+            // Pinch zoom has no flux equivalent
+            MouseEventType::TrackpadPinch => None,
+            MouseEventType::Scroll | MouseEventType::TrackpadSwipe => {
                 Some(BehaviorEvent {
                     timestamp: mouse.timestamp,
                     event_type: BehaviorEventType::Scroll,
@@ -162,6 +190,43 @@ pub fn convert_to_behavior_session(
     adapter.convert(session_id, window)
 }
 
+/// Rebuild an event window from a behavior session, e.g. one ingested
+/// from the Chrome extension.
+///
+/// Typing events become key presses, taps become left clicks and scrolls
+/// become wheel scrolls. Flux scrolls carry a speed but no direction, so
+/// scrolls are recorded as downward. Other event types have no sensor
+/// equivalent and are skipped.
+pub fn behavior_session_to_window(session: &BehaviorSession) -> EventWindow {
+    let mut window = EventWindow::new(session.start_time, session.end_time - session.start_time);
+    for behavior in &session.events {
+        let event = match behavior.event_type {
+            BehaviorEventType::Typing => {
+                let mut event = KeyboardEvent::new(true);
+                event.timestamp = behavior.timestamp;
+                event.source_platform = Platform::Unknown;
+                SensorEvent::Keyboard(event)
+            }
+            BehaviorEventType::Tap => {
+                let mut event = MouseEvent::click(true);
+                event.timestamp = behavior.timestamp;
+                event.source_platform = Platform::Unknown;
+                SensorEvent::Mouse(event)
+            }
+            BehaviorEventType::Scroll => {
+                let velocity = behavior.scroll.as_ref().and_then(|s| s.velocity);
+                let mut event = MouseEvent::scroll(0.0, velocity.unwrap_or(0.0));
+                event.timestamp = behavior.timestamp;
+                event.source_platform = Platform::Unknown;
+                SensorEvent::Mouse(event)
+            }
+            _ => continue,
+        };
+        window.add_event(event);
+    }
+    window
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,4 +247,70 @@ mod tests {
         assert_eq!(session.session_id, "test-session");
         assert!(session.events.is_empty());
     }
+
+    #[test]
+    fn test_behavior_session_round_trip() {
+        let adapter = SensorBehaviorAdapter::with_defaults();
+        let start = Utc::now();
+        let mut window = EventWindow::new(start, Duration::seconds(10));
+        for (offset_ms, event) in [
+            (100, SensorEvent::Keyboard(KeyboardEvent::new(true))),
+            (200, SensorEvent::Keyboard(KeyboardEvent::new(false))),
+            (300, SensorEvent::Mouse(MouseEvent::click(true))),
+            (400, SensorEvent::Mouse(MouseEvent::scroll(0.0, 5.0))),
+        ] {
+            let mut event = event;
+            match &mut event {
+                SensorEvent::Keyboard(e) => e.timestamp = start + Duration::milliseconds(offset_ms),
+                SensorEvent::Mouse(e) => e.timestamp = start + Duration::milliseconds(offset_ms),
+                SensorEvent::Touch(_) => unreachable!(),
+            }
+            window.add_event(event);
+        }
+
+        let session = adapter.convert("test-session", &window);
+        let rebuilt = behavior_session_to_window(&session);
+        assert_eq!(rebuilt.start, window.start);
+        assert_eq!(rebuilt.end, window.end);
+        // Key releases are not part of the session
+        assert_eq!(rebuilt.keyboard_events.len(), 1);
+        assert_eq!(
+            rebuilt.keyboard_events[0].timestamp,
+            start + Duration::milliseconds(100)
+        );
+        let types: Vec<MouseEventType> =
+            rebuilt.mouse_events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![MouseEventType::LeftClick, MouseEventType::Scroll]
+        );
+    }
+
+    #[test]
+    fn test_navigation_keys_become_scroll_events() {
+        let adapter = SensorBehaviorAdapter::with_defaults();
+        let mut window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        window.keyboard_events.push(KeyboardEvent::new(true));
+        window.keyboard_events.push(KeyboardEvent::navigation(true));
+        window
+            .keyboard_events
+            .push(KeyboardEvent::navigation(false));
+
+        let session = adapter.convert("test-session", &window);
+        assert_eq!(session.events.len(), 2);
+        let typing = session
+            .events
+            .iter()
+            .filter(|e| matches!(e.event_type, BehaviorEventType::Typing))
+            .count();
+        let scrolls: Vec<&BehaviorEvent> = session
+            .events
+            .iter()
+            .filter(|e| matches!(e.event_type, BehaviorEventType::Scroll))
+            .collect();
+        assert_eq!(typing, 1);
+        assert_eq!(scrolls.len(), 1);
+        assert!(scrolls[0].typing.is_none());
+        assert!(scrolls[0].scroll.is_some());
+    }
 }
@@ -19,15 +19,15 @@
 //! ```ignore
 //! use synheart_sensor_agent::flux::SensorFluxProcessor;
 //!
-//! // Create processor with baseline tracking
-//! let mut processor = SensorFluxProcessor::new(20); // 20 session baseline window
+//! // Create processor with baseline tracking, sharing the agent's HSI builder
+//! let mut processor = SensorFluxProcessor::new_with_builder(hsi_builder.clone(), 20);
 //!
 //! // Process a window and get enriched output
-//! // processor.process_window(&window, &features, base_snapshot)
+//! // processor.process_window(&window, &features)
 //! ```
 
 mod adapter;
 mod processor;
 
-pub use adapter::{convert_to_behavior_session, SensorBehaviorAdapter};
-pub use processor::{EnrichedSnapshot, SensorFluxProcessor};
+pub use adapter::{behavior_session_to_window, convert_to_behavior_session, SensorBehaviorAdapter};
+pub use processor::{EnrichedSnapshot, ProcessorState, SensorFluxProcessor, WindowFlux};
@@ -4,10 +4,13 @@
 //! baseline tracking and HSI enrichment for sensor agent data.
 
 use crate::core::features::WindowFeatures;
-use crate::core::hsi::HsiSnapshot;
+use crate::core::focus::DeepFocusTracker;
+use crate::core::hsi::{HsiBuilder, HsiSnapshot};
 use crate::core::windowing::EventWindow;
 use crate::flux::adapter::SensorBehaviorAdapter;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use synheart_flux::behavior::BehaviorProcessor;
 use synheart_flux::ComputeError;
 
@@ -24,6 +27,86 @@ pub struct EnrichedSnapshot {
     pub baseline: Option<BaselineInfo>,
 }
 
+impl EnrichedSnapshot {
+    /// Flatten the snapshot into a single-level record for querying.
+    ///
+    /// The record holds `observed_at_utc`, every scalar `base.meta` entry,
+    /// each axis score keyed by axis name (null when unavailable), and the
+    /// flux and baseline metrics prefixed with `flux_` and `baseline_`.
+    /// Axis scores take precedence over `meta` entries of the same name.
+    pub fn to_flat_record(&self) -> HashMap<String, serde_json::Value> {
+        let mut record = HashMap::new();
+        record.insert(
+            "observed_at_utc".to_string(),
+            serde_json::Value::String(self.base.observed_at_utc.clone()),
+        );
+
+        // Nested meta values (e.g. feature_delta) do not fit a flat record
+        for (key, value) in self.base.meta.iter().flatten() {
+            if !value.is_object() && !value.is_array() {
+                record.insert(key.clone(), value.clone());
+            }
+        }
+
+        for reading in self.base.readings() {
+            record.insert(reading.axis.clone(), serde_json::json!(reading.score));
+        }
+
+        let prefixed = [
+            ("flux_", serde_json::to_value(&self.flux_behavior)),
+            ("baseline_", serde_json::to_value(&self.baseline)),
+        ];
+        for (prefix, value) in prefixed {
+            if let Ok(serde_json::Value::Object(fields)) = value {
+                for (key, value) in fields {
+                    record.insert(format!("{prefix}{key}"), value);
+                }
+            }
+        }
+        record
+    }
+}
+
+/// Flux output for a window whose snapshot was built by the caller.
+///
+/// Returned by [`SensorFluxProcessor::process_snapshot`]; attach it to the
+/// snapshot with [`Self::attach`].
+#[derive(Debug, Clone)]
+pub struct WindowFlux {
+    /// Flux-computed behavioral metrics (if available)
+    pub flux_behavior: Option<FluxBehaviorMetrics>,
+    /// Baseline information (if available)
+    pub baseline: Option<BaselineInfo>,
+}
+
+impl WindowFlux {
+    /// Combine the flux output with its window's snapshot.
+    pub fn attach(self, base: HsiSnapshot) -> EnrichedSnapshot {
+        EnrichedSnapshot {
+            base,
+            flux_behavior: self.flux_behavior,
+            baseline: self.baseline,
+        }
+    }
+
+    /// Enrich a session's snapshots with the flux output of their windows.
+    ///
+    /// `fluxes` runs parallel to `snapshots`. Each snapshot with flux output
+    /// is moved into its enriched snapshot; snapshots without one (`None`,
+    /// or past the end of `fluxes`) are dropped. This lets the caller keep a
+    /// single copy of every snapshot until the session ends.
+    pub fn attach_all(
+        snapshots: Vec<HsiSnapshot>,
+        fluxes: Vec<Option<WindowFlux>>,
+    ) -> Vec<EnrichedSnapshot> {
+        snapshots
+            .into_iter()
+            .zip(fluxes)
+            .filter_map(|(snapshot, flux)| flux.map(|flux| flux.attach(snapshot)))
+            .collect()
+    }
+}
+
 /// Flux-computed behavioral metrics.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FluxBehaviorMetrics {
@@ -41,7 +124,7 @@ pub struct FluxBehaviorMetrics {
     pub scroll_jitter_rate: f64,
     /// Interaction intensity
     pub interaction_intensity: f64,
-    /// Deep focus block count
+    /// Deep focus block count (from the sensor's `DeepFocusTracker` when available)
     pub deep_focus_blocks: u32,
 }
 
@@ -58,12 +141,44 @@ pub struct BaselineInfo {
     pub sessions_in_baseline: u32,
 }
 
+/// Persisted state of a [`SensorFluxProcessor`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorState {
+    /// Sessions processed so far, so session IDs continue after a restart
+    pub session_count: usize,
+    /// Rolling baselines as saved by the flux `BehaviorProcessor`
+    pub baselines_json: String,
+    /// Serialized behavior sessions in the baseline, oldest first
+    #[serde(default)]
+    pub recent_sessions: Vec<String>,
+}
+
+/// State of a [`SensorFluxProcessor`] captured before a batch.
+struct StagedState {
+    baselines_json: String,
+    focus_tracker: DeepFocusTracker,
+    sessions_in_baseline: u32,
+    recent_sessions: VecDeque<String>,
+    session_count: usize,
+}
+
 /// Sensor-aware Flux processor for baseline tracking and HSI enrichment.
 pub struct SensorFluxProcessor {
     /// Internal behavior processor
     processor: BehaviorProcessor,
     /// Adapter for converting sensor events
     adapter: SensorBehaviorAdapter,
+    /// Builder for the base snapshots
+    builder: HsiBuilder,
+    /// Deep focus blocks across the processed windows
+    focus_tracker: DeepFocusTracker,
+    /// Number of sessions in the rolling baseline
+    baseline_window_sessions: usize,
+    /// Sessions in the baseline as of the last processed window
+    sessions_in_baseline: u32,
+    /// Serialized behavior sessions in the baseline, oldest first, kept so
+    /// the baseline can be recomputed when its window shrinks
+    recent_sessions: VecDeque<String>,
     /// Session counter
     session_count: usize,
 }
@@ -75,9 +190,24 @@ impl SensorFluxProcessor {
     ///
     /// * `baseline_window_sessions` - Number of sessions to include in rolling baseline (default: 20)
     pub fn new(baseline_window_sessions: usize) -> Self {
+        Self::new_with_builder(HsiBuilder::new(), baseline_window_sessions)
+    }
+
+    /// Create a processor that builds its base snapshots with `builder`.
+    ///
+    /// Only [`Self::process_window`] and [`Self::process_windows_batch`]
+    /// build snapshots. Callers that already build a snapshot for each window
+    /// should use [`Self::new`] and [`Self::process_snapshot`] instead, so the
+    /// window is not built (and noised) twice.
+    pub fn new_with_builder(builder: HsiBuilder, baseline_window_sessions: usize) -> Self {
         Self {
             processor: BehaviorProcessor::with_baseline_window(baseline_window_sessions),
             adapter: SensorBehaviorAdapter::with_defaults(),
+            builder,
+            focus_tracker: DeepFocusTracker::new(),
+            baseline_window_sessions,
+            sessions_in_baseline: 0,
+            recent_sessions: VecDeque::new(),
             session_count: 0,
         }
     }
@@ -85,57 +215,170 @@ impl SensorFluxProcessor {
     /// Create with a custom device ID.
     pub fn with_device_id(baseline_window_sessions: usize, device_id: &str) -> Self {
         Self {
-            processor: BehaviorProcessor::with_baseline_window(baseline_window_sessions),
             adapter: SensorBehaviorAdapter::new(device_id.to_string(), "UTC".to_string()),
-            session_count: 0,
+            ..Self::new(baseline_window_sessions)
         }
     }
 
     /// Process a window and return an enriched snapshot with flux metrics.
     ///
-    /// This converts the sensor window to a behavior session, processes it
-    /// through the flux pipeline, and returns an enriched snapshot.
+    /// This builds the base snapshot for the window, converts the window to a
+    /// behavior session, processes it through the flux pipeline, and returns
+    /// the enriched snapshot. The base snapshot's resolution is the window
+    /// duration and it carries no `feature_delta`.
     pub fn process_window(
         &mut self,
         window: &EventWindow,
-        _features: &WindowFeatures,
-        base_snapshot: HsiSnapshot,
+        features: &WindowFeatures,
     ) -> Result<EnrichedSnapshot, ComputeError> {
         self.session_count += 1;
-        let session_id = format!("sensor-{}", self.session_count);
+        let session_json = session_json(&self.adapter, self.session_count, window)?;
+        self.enrich(window, features, &session_json)
+    }
+
+    /// Process a window whose snapshot has already been built.
+    ///
+    /// The window is run through the flux pipeline like
+    /// [`Self::process_window`] and `snapshot` gets its `baseline_coverage`,
+    /// but the snapshot stays with the caller: [`WindowFlux::attach`] turns
+    /// it into the enriched snapshot once the caller is done with it. Both
+    /// then carry the same axis scores (including any differential privacy
+    /// noise) and deep focus state, without a second build or a copy.
+    pub fn process_snapshot(
+        &mut self,
+        window: &EventWindow,
+        snapshot: &mut HsiSnapshot,
+    ) -> Result<WindowFlux, ComputeError> {
+        self.session_count += 1;
+        let session_json = session_json(&self.adapter, self.session_count, window)?;
+        let mut flux = self.run_flux(&session_json)?;
+        self.annotate(snapshot, &mut flux);
+        Ok(flux)
+    }
 
-        // Convert window to behavior session
-        let session = self.adapter.convert(&session_id, window);
+    /// Process a batch of windows, returning enriched snapshots in input order.
+    ///
+    /// Windows are converted and serialized in parallel up front; the flux
+    /// pipeline then runs over them in order, so each window's baseline
+    /// includes the windows before it, as with repeated
+    /// [`Self::process_window`] calls. The batch is all or nothing: on the
+    /// first error the baseline, session counter and deep focus state are
+    /// restored to what they were before the batch.
+    pub fn process_windows_batch(
+        &mut self,
+        windows: &[(EventWindow, WindowFeatures)],
+    ) -> Result<Vec<EnrichedSnapshot>, ComputeError> {
+        let first_session = self.session_count + 1;
+        let adapter = &self.adapter;
+        let session_jsons: Vec<Result<String, ComputeError>> = windows
+            .par_iter()
+            .enumerate()
+            .map(|(i, (window, _))| session_json(adapter, first_session + i, window))
+            .collect();
 
-        // Serialize session to JSON for flux processing
-        let session_json = serde_json::to_string(&session)
-            .map_err(|e| ComputeError::EncodingError(e.to_string()))?;
+        let staged = self.stage()?;
+        let mut enriched = Vec::with_capacity(windows.len());
+        for ((window, features), session_json) in windows.iter().zip(session_jsons) {
+            self.session_count += 1;
+            let result =
+                session_json.and_then(|session_json| self.enrich(window, features, &session_json));
+            match result {
+                Ok(snapshot) => enriched.push(snapshot),
+                Err(e) => {
+                    self.restore(staged)?;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(enriched)
+    }
 
-        // Process through flux
-        let hsi_json = self.processor.process(&session_json)?;
+    /// Capture the state a batch changes, so it can be rolled back.
+    fn stage(&self) -> Result<StagedState, ComputeError> {
+        Ok(StagedState {
+            baselines_json: self.processor.save_baselines()?,
+            focus_tracker: self.focus_tracker.clone(),
+            sessions_in_baseline: self.sessions_in_baseline,
+            recent_sessions: self.recent_sessions.clone(),
+            session_count: self.session_count,
+        })
+    }
 
-        // Extract metrics from HSI JSON
-        let (flux_behavior, baseline) = extract_flux_metrics_from_json(&hsi_json)?;
+    /// Roll back to state captured by [`Self::stage`].
+    fn restore(&mut self, staged: StagedState) -> Result<(), ComputeError> {
+        let mut processor = BehaviorProcessor::with_baseline_window(self.baseline_window_sessions);
+        processor.load_baselines(&staged.baselines_json)?;
+        self.processor = processor;
+        self.focus_tracker = staged.focus_tracker;
+        self.sessions_in_baseline = staged.sessions_in_baseline;
+        self.recent_sessions = staged.recent_sessions;
+        self.session_count = staged.session_count;
+        Ok(())
+    }
 
-        Ok(EnrichedSnapshot {
-            base: base_snapshot,
+    /// Run a serialized behavior session through flux and build its enriched snapshot.
+    fn enrich(
+        &mut self,
+        window: &EventWindow,
+        features: &WindowFeatures,
+        session_json: &str,
+    ) -> Result<EnrichedSnapshot, ComputeError> {
+        let mut flux = self.run_flux(session_json)?;
+        let mut base_snapshot = self.builder.build_with_focus_tracker(
+            window,
+            features,
+            window.end - window.start,
+            None,
+            &mut self.focus_tracker,
+        );
+        self.annotate(&mut base_snapshot, &mut flux);
+        Ok(flux.attach(base_snapshot))
+    }
+
+    /// Run a serialized behavior session through flux and extract its metrics.
+    fn run_flux(&mut self, session_json: &str) -> Result<WindowFlux, ComputeError> {
+        let hsi_json = self.processor.process(session_json)?;
+        let (flux_behavior, baseline) = extract_flux_metrics_from_json(&hsi_json)?;
+        if let Some(ref baseline) = baseline {
+            self.sessions_in_baseline = baseline.sessions_in_baseline;
+        }
+        self.recent_sessions.push_back(session_json.to_string());
+        while self.recent_sessions.len() > self.baseline_window_sessions {
+            self.recent_sessions.pop_front();
+        }
+        Ok(WindowFlux {
             flux_behavior,
             baseline,
         })
     }
 
+    /// Reconcile a window's snapshot with the flux metrics computed for it.
+    fn annotate(&self, snapshot: &mut HsiSnapshot, flux: &mut WindowFlux) {
+        // Prefer the cross-window deep focus count tracked by the sensor
+        let tracked_blocks = snapshot
+            .meta
+            .as_ref()
+            .and_then(|m| m.get("deep_focus_blocks_completed"))
+            .and_then(|v| v.as_u64());
+        if let (Some(metrics), Some(blocks)) = (flux.flux_behavior.as_mut(), tracked_blocks) {
+            metrics.deep_focus_blocks = blocks as u32;
+        }
+
+        // Unsigned meta entry, so signatures stay valid
+        snapshot.meta.get_or_insert_with(Default::default).insert(
+            "baseline_coverage".to_string(),
+            serde_json::json!(self.baseline_coverage()),
+        );
+    }
+
     /// Process a window without enrichment (just baseline update).
     ///
     /// This updates the baseline without returning enriched output.
     pub fn update_baseline(&mut self, window: &EventWindow) -> Result<(), ComputeError> {
         self.session_count += 1;
-        let session_id = format!("sensor-{}", self.session_count);
-        let session = self.adapter.convert(&session_id, window);
-
-        let session_json = serde_json::to_string(&session)
-            .map_err(|e| ComputeError::EncodingError(e.to_string()))?;
+        let session_json = session_json(&self.adapter, self.session_count, window)?;
 
-        let _ = self.processor.process(&session_json)?;
+        self.run_flux(&session_json)?;
         Ok(())
     }
 
@@ -149,6 +392,95 @@ impl SensorFluxProcessor {
         self.processor.load_baselines(json)
     }
 
+    /// Save the baselines and session counter to JSON for persistence.
+    pub fn save_state(&self) -> Result<String, ComputeError> {
+        let state = ProcessorState {
+            session_count: self.session_count,
+            baselines_json: self.processor.save_baselines()?,
+            recent_sessions: self.recent_sessions.iter().cloned().collect(),
+        };
+        serde_json::to_string(&state).map_err(|e| ComputeError::EncodingError(e.to_string()))
+    }
+
+    /// Restore the baselines and session counter saved by [`Self::save_state`].
+    ///
+    /// Baselines saved by [`Self::save_baselines`] are also accepted; the
+    /// session counter is then left unchanged.
+    pub fn load_state(&mut self, json: &str) -> Result<(), ComputeError> {
+        match serde_json::from_str::<ProcessorState>(json) {
+            Ok(state) => {
+                self.processor.load_baselines(&state.baselines_json)?;
+                self.session_count = state.session_count;
+                self.recent_sessions = state.recent_sessions.into();
+                Ok(())
+            }
+            Err(_) => self.processor.load_baselines(json),
+        }
+    }
+
+    /// Change the number of sessions in the rolling baseline.
+    ///
+    /// When growing the window, the accumulated baselines are carried over,
+    /// so no sessions are lost. When shrinking it, the baseline is recomputed
+    /// from the newest `new_size` sessions, dropping the oldest; if fewer
+    /// sessions than that are known (e.g. state saved by an older version),
+    /// the accumulated baselines are carried over instead. On error the
+    /// current baseline is kept unchanged.
+    pub fn resize_baseline_window(&mut self, new_size: usize) -> Result<(), ComputeError> {
+        if new_size == self.baseline_window_sessions {
+            return Ok(());
+        }
+        let mut processor = BehaviorProcessor::with_baseline_window(new_size);
+        let mut sessions_in_baseline = self.sessions_in_baseline;
+        if new_size < self.baseline_window_sessions && self.recent_sessions.len() >= new_size {
+            let skip = self.recent_sessions.len() - new_size;
+            for session_json in self.recent_sessions.iter().skip(skip) {
+                let hsi_json = processor.process(session_json)?;
+                if let (_, Some(baseline)) = extract_flux_metrics_from_json(&hsi_json)? {
+                    sessions_in_baseline = baseline.sessions_in_baseline;
+                }
+            }
+            self.recent_sessions.drain(..skip);
+        } else {
+            processor.load_baselines(&self.processor.save_baselines()?)?;
+        }
+        self.processor = processor;
+        self.baseline_window_sessions = new_size;
+        self.sessions_in_baseline = sessions_in_baseline;
+        Ok(())
+    }
+
+    /// Get the number of sessions in the rolling baseline.
+    pub fn baseline_window(&self) -> usize {
+        self.baseline_window_sessions
+    }
+
+    /// Get the number of sessions in the baseline as of the last processed window.
+    pub fn baseline_sessions(&self) -> u32 {
+        self.sessions_in_baseline
+    }
+
+    /// Get how full the rolling baseline is (0.0 to 1.0).
+    ///
+    /// Deviations from the baseline are unavailable or unreliable until the
+    /// baseline holds `baseline_window` sessions.
+    pub fn baseline_coverage(&self) -> f64 {
+        if self.baseline_window_sessions == 0 {
+            return 1.0;
+        }
+        (self.sessions_in_baseline as f64 / self.baseline_window_sessions as f64).min(1.0)
+    }
+
+    /// Check whether the rolling baseline is full.
+    pub fn is_baseline_ready(&self) -> bool {
+        self.baseline_coverage() >= 1.0
+    }
+
+    /// Get the builder used for base snapshots.
+    pub fn builder(&self) -> &HsiBuilder {
+        &self.builder
+    }
+
     /// Get the number of sessions processed.
     pub fn session_count(&self) -> usize {
         self.session_count
@@ -215,6 +547,24 @@ fn extract_flux_metrics_from_json(
     Ok((flux_behavior, baseline))
 }
 
+/// Convert a window to the flux behavior session `sensor-<session_number>`, as JSON.
+///
+/// Fails for windows that do not end after they start.
+fn session_json(
+    adapter: &SensorBehaviorAdapter,
+    session_number: usize,
+    window: &EventWindow,
+) -> Result<String, ComputeError> {
+    if window.end <= window.start {
+        return Err(ComputeError::EncodingError(format!(
+            "window starting {} has no duration",
+            window.start.to_rfc3339()
+        )));
+    }
+    let session = adapter.convert(&format!("sensor-{session_number}"), window);
+    serde_json::to_string(&session).map_err(|e| ComputeError::EncodingError(e.to_string()))
+}
+
 impl Default for SensorFluxProcessor {
     fn default() -> Self {
         Self::new(20)
@@ -224,6 +574,9 @@ impl Default for SensorFluxProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collector::types::{KeyboardEvent, SensorEvent};
+    use crate::core::features::{compute_features, FeatureConfig};
+    use chrono::{DateTime, Duration, Utc};
 
     #[test]
     fn test_processor_creation() {
@@ -231,9 +584,270 @@ mod tests {
         assert_eq!(processor.session_count(), 0);
     }
 
+    #[test]
+    fn test_resize_baseline_window() {
+        let mut processor = SensorFluxProcessor::new(20);
+        processor.resize_baseline_window(5).unwrap();
+        assert_eq!(processor.baseline_window(), 5);
+        assert_eq!(processor.session_count(), 0);
+    }
+
+    /// A window with `keys` evenly spaced key presses, `index` windows after a fixed start.
+    fn typing_window(index: i64, keys: i64) -> EventWindow {
+        let start = DateTime::parse_from_rfc3339("2024-01-15T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::seconds(10 * index);
+        let mut window = EventWindow::new(start, Duration::seconds(10));
+        for k in 0..keys {
+            let timestamp = start + Duration::milliseconds(k * 10_000 / keys);
+            for is_key_down in [true, false] {
+                let mut event = KeyboardEvent::new(is_key_down);
+                event.timestamp = timestamp;
+                window.add_event(SensorEvent::Keyboard(event));
+            }
+        }
+        window
+    }
+
+    /// Process a final window and return its baseline.
+    fn next_baseline(processor: &mut SensorFluxProcessor) -> BaselineInfo {
+        let window = typing_window(10, 35);
+        let features = compute_features(&window, &FeatureConfig::default());
+        processor
+            .process_window(&window, &features)
+            .unwrap()
+            .baseline
+            .unwrap()
+    }
+
+    #[test]
+    fn test_shrinking_baseline_window_drops_oldest_sessions() {
+        let windows: Vec<EventWindow> = (0..5).map(|i| typing_window(i, 5 + 20 * i)).collect();
+        let mut processor = SensorFluxProcessor::new(5);
+        for window in &windows {
+            processor.update_baseline(window).unwrap();
+        }
+        assert_eq!(processor.recent_sessions.len(), 5);
+
+        processor.resize_baseline_window(3).unwrap();
+        assert_eq!(processor.baseline_window(), 3);
+        assert_eq!(processor.session_count(), 5);
+        assert_eq!(processor.recent_sessions.len(), 3);
+        assert!(processor.baseline_sessions() <= 3);
+
+        // Same baseline as a processor that only ever saw the newest three sessions
+        let mut expected = SensorFluxProcessor::new(3);
+        expected.session_count = 2;
+        for window in &windows[2..] {
+            expected.update_baseline(window).unwrap();
+        }
+        assert_eq!(processor.baseline_sessions(), expected.baseline_sessions());
+        let shrunk = next_baseline(&mut processor);
+        let fresh = next_baseline(&mut expected);
+        assert_eq!(shrunk.sessions_in_baseline, fresh.sessions_in_baseline);
+        assert_eq!(shrunk.distraction, fresh.distraction);
+        assert_eq!(shrunk.focus, fresh.focus);
+    }
+
+    #[test]
+    fn test_growing_baseline_window_keeps_sessions() {
+        let windows: Vec<EventWindow> = (0..3).map(|i| typing_window(i, 5 + 20 * i)).collect();
+        let mut processor = SensorFluxProcessor::new(3);
+        for window in &windows {
+            processor.update_baseline(window).unwrap();
+        }
+        let sessions_before = processor.baseline_sessions();
+
+        processor.resize_baseline_window(10).unwrap();
+        assert_eq!(processor.baseline_window(), 10);
+        assert_eq!(processor.baseline_sessions(), sessions_before);
+
+        // Same baseline as a processor that had the larger window all along
+        let mut expected = SensorFluxProcessor::new(10);
+        for window in &windows {
+            expected.update_baseline(window).unwrap();
+        }
+        let grown = next_baseline(&mut processor);
+        let fresh = next_baseline(&mut expected);
+        assert!(grown.sessions_in_baseline >= sessions_before);
+        assert_eq!(grown.sessions_in_baseline, fresh.sessions_in_baseline);
+        assert_eq!(grown.distraction, fresh.distraction);
+        assert_eq!(processor.recent_sessions.len(), 4);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut processor = SensorFluxProcessor::new(20);
+        processor.session_count = 12;
+        let json = processor.save_state().unwrap();
+
+        let mut restored = SensorFluxProcessor::new(20);
+        restored.load_state(&json).unwrap();
+        assert_eq!(restored.session_count(), 12);
+
+        // Bare baselines from earlier versions still load
+        let mut legacy = SensorFluxProcessor::new(20);
+        legacy
+            .load_state(&processor.save_baselines().unwrap())
+            .unwrap();
+        assert_eq!(legacy.session_count(), 0);
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        let mut processor = SensorFluxProcessor::new(20);
+        let enriched = processor.process_windows_batch(&[]).unwrap();
+        assert!(enriched.is_empty());
+        assert_eq!(processor.session_count(), 0);
+    }
+
+    #[test]
+    fn test_failed_batch_leaves_baseline_unchanged() {
+        let mut processor = SensorFluxProcessor::new(5);
+        processor.update_baseline(&typing_window(0, 10)).unwrap();
+        let sessions_before = processor.baseline_sessions();
+
+        // The third window fails after the first two went through flux
+        let mut batch: Vec<(EventWindow, WindowFeatures)> = (1..4)
+            .map(|i| {
+                let window = typing_window(i, 5 + 20 * i);
+                let features = compute_features(&window, &FeatureConfig::default());
+                (window, features)
+            })
+            .collect();
+        batch[2].0.end = batch[2].0.start;
+        assert!(processor.process_windows_batch(&batch).is_err());
+
+        assert_eq!(processor.session_count(), 1);
+        assert_eq!(processor.baseline_sessions(), sessions_before);
+        assert_eq!(processor.recent_sessions.len(), 1);
+
+        // The next window sees the same baseline as if the batch never ran
+        let mut expected = SensorFluxProcessor::new(5);
+        expected.update_baseline(&typing_window(0, 10)).unwrap();
+        let after_failure = next_baseline(&mut processor);
+        let untouched = next_baseline(&mut expected);
+        assert_eq!(
+            after_failure.sessions_in_baseline,
+            untouched.sessions_in_baseline
+        );
+        assert_eq!(after_failure.distraction, untouched.distraction);
+    }
+
+    #[test]
+    fn test_baseline_coverage() {
+        let mut processor = SensorFluxProcessor::new(20);
+        assert_eq!(processor.baseline_coverage(), 0.0);
+        assert!(!processor.is_baseline_ready());
+
+        processor.sessions_in_baseline = 7;
+        assert!((processor.baseline_coverage() - 0.35).abs() < 1e-9);
+
+        processor.sessions_in_baseline = 25;
+        assert_eq!(processor.baseline_coverage(), 1.0);
+        assert!(processor.is_baseline_ready());
+    }
+
     #[test]
     fn test_processor_with_device_id() {
         let processor = SensorFluxProcessor::with_device_id(20, "test-device");
         assert_eq!(processor.session_count(), 0);
     }
+
+    #[test]
+    fn test_to_flat_record() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let base = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let enriched = EnrichedSnapshot {
+            base: base.clone(),
+            flux_behavior: None,
+            baseline: Some(BaselineInfo {
+                distraction: Some(0.4),
+                focus: None,
+                distraction_deviation_pct: None,
+                sessions_in_baseline: 3,
+            }),
+        };
+
+        let record = enriched.to_flat_record();
+        assert_eq!(
+            record["observed_at_utc"],
+            serde_json::json!(base.observed_at_utc)
+        );
+        assert_eq!(record["keyboard_events"], serde_json::json!(0));
+        assert_eq!(
+            record["typing_rate"],
+            serde_json::json!(base.axis_score("typing_rate"))
+        );
+        assert_eq!(
+            record["baseline_sessions_in_baseline"],
+            serde_json::json!(3)
+        );
+        assert!(record.values().all(|v| !v.is_object() && !v.is_array()));
+        assert!(!record.contains_key("flux_distraction_score"));
+    }
+
+    #[test]
+    fn test_process_snapshot_reuses_snapshot() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new()
+            .with_session_id("SESS-1".to_string())
+            .build(&window, &features, Duration::seconds(10), None);
+
+        let mut processor = SensorFluxProcessor::new(20);
+        let mut annotated = snapshot.clone();
+        let flux = processor.process_snapshot(&window, &mut annotated).unwrap();
+        let enriched = flux.attach(annotated);
+        assert_eq!(processor.session_count(), 1);
+        assert_eq!(enriched.base.source_ids, snapshot.source_ids);
+        let scores = |s: &HsiSnapshot| s.readings().map(|r| r.score).collect::<Vec<_>>();
+        assert_eq!(scores(&enriched.base), scores(&snapshot));
+        assert_eq!(enriched.base.computed_at_utc, snapshot.computed_at_utc);
+        assert_eq!(
+            enriched.base.meta.as_ref().unwrap()["baseline_coverage"],
+            serde_json::json!(processor.baseline_coverage())
+        );
+    }
+
+    #[test]
+    fn test_attach_all_moves_snapshots() {
+        let start = Utc::now();
+        let builder = HsiBuilder::new();
+        let snapshots: Vec<HsiSnapshot> = (0..3)
+            .map(|i| {
+                let window =
+                    EventWindow::new(start + Duration::seconds(i * 10), Duration::seconds(10));
+                let features = compute_features(&window, &FeatureConfig::default());
+                builder.build(&window, &features, Duration::seconds(10), None)
+            })
+            .collect();
+        let observed: Vec<String> = snapshots
+            .iter()
+            .map(|s| s.observed_at_utc.clone())
+            .collect();
+        let flux = || WindowFlux {
+            flux_behavior: None,
+            baseline: None,
+        };
+
+        // The third snapshot (e.g. the session summary) has no flux entry
+        let enriched = WindowFlux::attach_all(snapshots, vec![None, Some(flux())]);
+        assert_eq!(enriched.len(), 1);
+        assert_eq!(enriched[0].base.observed_at_utc, observed[1]);
+    }
+
+    #[test]
+    fn test_processor_with_builder() {
+        let builder = HsiBuilder::new().with_session_id("SESS-1".to_string());
+        let instance_id = builder.instance_id();
+        let processor = SensorFluxProcessor::new_with_builder(builder, 20);
+        assert_eq!(processor.builder().instance_id(), instance_id);
+        assert_eq!(
+            processor.builder().source_id(),
+            format!("s_SESS-1_keyboard_mouse_{instance_id}")
+        );
+    }
 }
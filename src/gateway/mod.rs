@@ -0,0 +1,1078 @@
+//! Gateway client for syncing HSI snapshots to synheart-core-gateway.
+//!
+//! This module provides integration with the local synheart-core-gateway
+//! for real-time HSI processing via synheart-flux.
+
+use crate::core::HsiSnapshot;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+mod queue;
+#[cfg(feature = "gateway")]
+mod ws;
+
+pub use queue::PersistentQueue;
+#[cfg(feature = "gateway")]
+pub use ws::WsGatewayClient;
+
+/// Gateway host used when none is configured.
+pub const DEFAULT_GATEWAY_HOST: &str = "127.0.0.1";
+
+/// Default number of sync attempts (including the first) before giving up.
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first sync retry, in milliseconds.
+pub const DEFAULT_RETRY_INITIAL_DELAY_MS: u64 = 500;
+
+/// Default upper bound on the delay between retries, in milliseconds.
+pub const DEFAULT_RETRY_MAX_DELAY_MS: u64 = 30_000;
+
+/// How snapshots are delivered to the gateway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GatewayTransport {
+    /// Batched HTTP POSTs every sync interval
+    #[default]
+    Http,
+    /// Persistent WebSocket connection, one frame per snapshot
+    WebSocket,
+}
+
+impl std::str::FromStr for GatewayTransport {
+    type Err = GatewayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "http" => Ok(GatewayTransport::Http),
+            "websocket" | "ws" => Ok(GatewayTransport::WebSocket),
+            other => Err(GatewayError::Config(format!(
+                "Unknown gateway transport '{other}' (expected http or websocket)"
+            ))),
+        }
+    }
+}
+
+/// How snapshot batches are encoded in HTTP sync requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentType {
+    /// JSON request bodies
+    #[default]
+    Json,
+    /// Protocol Buffers request bodies (`BehavioralSession` message)
+    Protobuf,
+}
+
+impl ContentType {
+    /// Get the MIME type sent in the `Content-Type` header.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ContentType::Json => "application/json",
+            ContentType::Protobuf => "application/x-protobuf",
+        }
+    }
+}
+
+impl std::str::FromStr for ContentType {
+    type Err = GatewayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(ContentType::Json),
+            "protobuf" | "proto" => Ok(ContentType::Protobuf),
+            other => Err(GatewayError::Config(format!(
+                "Unknown gateway content type '{other}' (expected json or protobuf)"
+            ))),
+        }
+    }
+}
+
+/// Gateway configuration.
+#[derive(Debug, Clone)]
+pub struct GatewayConfig {
+    /// Gateway host (default: 127.0.0.1)
+    pub host: String,
+    /// Gateway port
+    pub port: u16,
+    /// Bearer authentication token
+    pub token: String,
+    /// Maximum number of sync attempts, including the first
+    pub retry_max_attempts: u32,
+    /// Delay before the first retry; doubles on each subsequent attempt
+    pub retry_initial_delay_ms: u64,
+    /// Upper bound on the delay between retries
+    pub retry_max_delay_ms: u64,
+    /// Whether to connect over HTTPS
+    pub tls: bool,
+    /// PEM CA bundle to trust in addition to the system roots (e.g. a self-signed gateway)
+    pub ca_cert_path: Option<PathBuf>,
+    /// Snapshot delivery transport
+    pub transport: GatewayTransport,
+    /// Encoding of HTTP sync request bodies
+    pub content_type: ContentType,
+    /// Device ID reported to the gateway (a random ID per client if None)
+    pub device_id: Option<String>,
+}
+
+impl GatewayConfig {
+    /// Create a new gateway configuration.
+    pub fn new(host: impl Into<String>, port: u16, token: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            token: token.into(),
+            retry_max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+            retry_initial_delay_ms: DEFAULT_RETRY_INITIAL_DELAY_MS,
+            retry_max_delay_ms: DEFAULT_RETRY_MAX_DELAY_MS,
+            tls: false,
+            ca_cert_path: None,
+            transport: GatewayTransport::Http,
+            content_type: ContentType::Json,
+            device_id: None,
+        }
+    }
+
+    /// Set the gateway host.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Report a fixed device ID, e.g. from [`load_or_create_device_id`].
+    pub fn with_device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    /// Set the encoding of HTTP sync request bodies.
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Set the snapshot delivery transport.
+    pub fn with_transport(mut self, transport: GatewayTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Connect over HTTPS, optionally trusting an extra PEM CA bundle.
+    pub fn with_tls(mut self, ca_cert_path: Option<PathBuf>) -> Self {
+        self.tls = true;
+        self.ca_cert_path = ca_cert_path;
+        self
+    }
+
+    /// Set the retry policy for failed syncs.
+    pub fn with_retry(mut self, max_attempts: u32, initial_delay_ms: u64) -> Self {
+        self.retry_max_attempts = max_attempts;
+        self.retry_initial_delay_ms = initial_delay_ms;
+        self
+    }
+
+    /// Set the upper bound on the delay between retries.
+    pub fn with_max_retry_delay(mut self, max_delay_ms: u64) -> Self {
+        self.retry_max_delay_ms = max_delay_ms;
+        self
+    }
+
+    /// Get the backoff delay before retry number `retry` (starting at 1).
+    ///
+    /// The delay doubles with each retry and has ±20% jitter applied so that
+    /// multiple agents do not retry in lockstep. It never exceeds
+    /// `retry_max_delay_ms`, so a long outage keeps being retried at that pace.
+    pub fn retry_delay(&self, retry: u32) -> std::time::Duration {
+        let base = self
+            .retry_initial_delay_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(32));
+        // Uniform jitter factor in [0.8, 1.2]
+        let unit = (uuid::Uuid::new_v4().as_u128() % 1_000_001) as f64 / 1_000_000.0;
+        let jitter = 0.8 + 0.4 * unit;
+        let delay = ((base as f64 * jitter).round() as u64).min(self.retry_max_delay_ms);
+        std::time::Duration::from_millis(delay)
+    }
+
+    /// Load configuration from SyniLife runtime directory.
+    ///
+    /// This is [`GatewayConfig::from_dir`] with
+    /// `~/Library/Application Support/SyniLife/runtime/` on macOS and
+    /// `<data dir>/SyniLife/runtime/` on Linux.
+    pub fn from_runtime_dir() -> Result<Self, GatewayError> {
+        Self::from_dir(&Self::default_runtime_dir()?)
+    }
+
+    /// Load configuration from the gateway files in `dir`.
+    ///
+    /// Reads the port from `gateway.port` and the token from `gateway.token`.
+    /// Optional files: `gateway.host` names the host (default
+    /// [`DEFAULT_GATEWAY_HOST`]), `gateway.tls` containing `true` or `false`
+    /// selects HTTPS, and `gateway.ca_cert` holds the path of a PEM CA bundle
+    /// to trust (relative to `dir`), which implies HTTPS. Errors name the
+    /// exact files that were read.
+    pub fn from_dir(dir: &Path) -> Result<Self, GatewayError> {
+        let port_path = dir.join("gateway.port");
+        let token_path = dir.join("gateway.token");
+        let tls_path = dir.join("gateway.tls");
+        let host_path = dir.join("gateway.host");
+        let ca_cert_path = dir.join("gateway.ca_cert");
+
+        let port_str = std::fs::read_to_string(&port_path).map_err(|e| {
+            GatewayError::Config(format!(
+                "Failed to read gateway port from {port_path:?}: {e} \
+                 (expected {port_path:?} and {token_path:?})"
+            ))
+        })?;
+
+        let port: u16 = port_str.trim().parse().map_err(|e| {
+            GatewayError::Config(format!(
+                "Invalid port number '{}' in {port_path:?}: {e}",
+                port_str.trim()
+            ))
+        })?;
+
+        let token = std::fs::read_to_string(&token_path)
+            .map_err(|e| {
+                GatewayError::Config(format!(
+                    "Failed to read gateway token from {token_path:?}: {e} \
+                     (expected {port_path:?} and {token_path:?})"
+                ))
+            })?
+            .trim()
+            .to_string();
+
+        let tls = match std::fs::read_to_string(&tls_path) {
+            Ok(content) => parse_tls_flag(&content, &tls_path)?,
+            Err(_) => false,
+        };
+
+        let read_optional = |path: &Path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|content| content.trim().to_string())
+                .filter(|content| !content.is_empty())
+        };
+        let host = read_optional(&host_path).unwrap_or_else(|| DEFAULT_GATEWAY_HOST.to_string());
+        let ca_cert = read_optional(&ca_cert_path).map(|path| dir.join(path));
+
+        let config = Self::new(host, port, token);
+        Ok(if tls || ca_cert.is_some() {
+            config.with_tls(ca_cert)
+        } else {
+            config
+        })
+    }
+
+    /// Get the default directory holding the gateway port and token files.
+    pub fn default_runtime_dir() -> Result<PathBuf, GatewayError> {
+        Ok(Self::default_state_dir()?.join("runtime"))
+    }
+
+    /// Get the default SyniLife state directory.
+    fn default_state_dir() -> Result<PathBuf, GatewayError> {
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(home) = dirs::home_dir() {
+                return Ok(home.join("Library/Application Support/SyniLife"));
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(data_dir) = dirs::data_dir() {
+                return Ok(data_dir.join("SyniLife"));
+            }
+        }
+
+        Err(GatewayError::Config(
+            "Could not determine SyniLife state directory".to_string(),
+        ))
+    }
+
+    /// Get the full gateway URL.
+    pub fn url(&self) -> String {
+        let scheme = if self.tls { "https" } else { "http" };
+        format!("{scheme}://{}:{}", self.host, self.port)
+    }
+
+    /// Get the behavioral ingest endpoint URL.
+    pub fn ingest_url(&self) -> String {
+        format!("{}/v1/ingest/behavioral", self.url())
+    }
+
+    /// Get the health check endpoint URL.
+    pub fn health_url(&self) -> String {
+        format!("{}/health", self.url())
+    }
+
+    /// Get the WebSocket streaming endpoint URL.
+    pub fn stream_url(&self) -> String {
+        let scheme = if self.tls { "wss" } else { "ws" };
+        format!("{scheme}://{}:{}/v1/stream", self.host, self.port)
+    }
+
+    /// Build an HTTP client for this gateway.
+    ///
+    /// Invalid certificates are always rejected; a configured `ca_cert_path`
+    /// is added as an extra trusted root.
+    #[cfg(feature = "gateway")]
+    pub fn http_client(&self) -> Result<reqwest::Client, GatewayError> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .danger_accept_invalid_certs(false);
+
+        if let Some(ref ca_path) = self.ca_cert_path {
+            let pem = std::fs::read(ca_path).map_err(|e| {
+                GatewayError::Config(format!("Failed to read CA bundle {ca_path:?}: {e}"))
+            })?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| GatewayError::Config(format!("Invalid CA bundle {ca_path:?}: {e}")))?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        builder
+            .build()
+            .map_err(|e| GatewayError::Config(format!("Failed to create HTTP client: {e}")))
+    }
+}
+
+/// Parse the contents of a `gateway.tls` runtime file.
+fn parse_tls_flag(content: &str, path: &Path) -> Result<bool, GatewayError> {
+    match content.trim().to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" | "" => Ok(false),
+        other => Err(GatewayError::Config(format!(
+            "Invalid gateway.tls value '{other}' in {path:?} (expected true or false)"
+        ))),
+    }
+}
+
+/// Load the device ID kept in `path`, creating the file on first use.
+///
+/// The file holds a UUID generated once, so the device keeps the same
+/// identity in the gateway across restarts. The ID is the full UUID, so it
+/// neither reveals the hostname nor risks collisions between devices.
+pub fn load_or_create_device_id(path: &Path) -> Result<String, GatewayError> {
+    let uuid = match std::fs::read_to_string(path) {
+        Ok(content) => uuid::Uuid::parse_str(content.trim())
+            .map_err(|e| GatewayError::Config(format!("Invalid device ID in {path:?}: {e}")))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let uuid = uuid::Uuid::new_v4();
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    GatewayError::Config(format!(
+                        "Failed to create device ID directory {parent:?}: {e}"
+                    ))
+                })?;
+            }
+            std::fs::write(path, format!("{uuid}\n")).map_err(|e| {
+                GatewayError::Config(format!("Failed to write device ID {path:?}: {e}"))
+            })?;
+            uuid
+        }
+        Err(e) => {
+            return Err(GatewayError::Config(format!(
+                "Failed to read device ID {path:?}: {e}"
+            )))
+        }
+    };
+    Ok(uuid.to_string())
+}
+
+/// Gateway client error types.
+#[derive(Debug)]
+pub enum GatewayError {
+    /// Configuration error
+    Config(String),
+    /// Network/HTTP error
+    Network(String),
+    /// Server returned an error response
+    Server { status: u16, message: String },
+    /// JSON serialization error
+    Serialization(String),
+    /// Local file I/O error (e.g. the persistent queue)
+    Io(String),
+}
+
+impl std::fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GatewayError::Config(msg) => write!(f, "Gateway config error: {msg}"),
+            GatewayError::Network(msg) => write!(f, "Gateway network error: {msg}"),
+            GatewayError::Server { status, message } => {
+                write!(f, "Gateway server error ({status}): {message}")
+            }
+            GatewayError::Serialization(msg) => write!(f, "Gateway serialization error: {msg}"),
+            GatewayError::Io(msg) => write!(f, "Gateway I/O error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+impl GatewayError {
+    /// Whether the failure is transient and the request is worth retrying.
+    ///
+    /// Network errors and 5xx server responses are retryable; client errors
+    /// and configuration, serialization or local I/O failures are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GatewayError::Network(_) => true,
+            GatewayError::Server { status, .. } => *status >= 500,
+            GatewayError::Config(_) | GatewayError::Serialization(_) | GatewayError::Io(_) => false,
+        }
+    }
+}
+
+/// Session payload for the behavioral ingest endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct BehavioralSession {
+    /// Session containing HSI snapshots
+    pub session: SessionPayload,
+}
+
+impl BehavioralSession {
+    /// Convert this session into its protobuf message.
+    pub fn to_proto(&self) -> crate::proto::BehavioralSession {
+        let session = &self.session;
+        crate::proto::BehavioralSession {
+            session: Some(crate::proto::SessionPayload {
+                session_id: session.session_id.clone(),
+                device_id: session.device_id.clone(),
+                timezone: session.timezone.clone(),
+                start_time: session.start_time.clone(),
+                end_time: session.end_time.clone(),
+                snapshots: session
+                    .snapshots
+                    .iter()
+                    .map(HsiSnapshot::to_proto)
+                    .collect(),
+                meta: Some(crate::proto::SessionMeta {
+                    source: session.meta.source.clone(),
+                    version: session.meta.version.clone(),
+                    snapshot_count: session.meta.snapshot_count as u64,
+                }),
+            }),
+        }
+    }
+}
+
+/// Session payload structure matching core-gateway expectations.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPayload {
+    /// Session identifier
+    pub session_id: String,
+    /// Device identifier
+    pub device_id: String,
+    /// Timezone
+    pub timezone: String,
+    /// Session start time (RFC3339)
+    pub start_time: String,
+    /// Session end time (RFC3339)
+    pub end_time: String,
+    /// HSI snapshots as events
+    pub snapshots: Vec<HsiSnapshot>,
+    /// Metadata
+    pub meta: SessionMeta,
+}
+
+/// Session metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMeta {
+    /// Source identifier
+    pub source: String,
+    /// Version
+    pub version: String,
+    /// Snapshot count
+    pub snapshot_count: usize,
+}
+
+/// Gateway response from the behavioral ingest endpoint.
+///
+/// Every field may be omitted, and unknown fields are ignored, so older and
+/// newer gateways can both be read.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayResponse {
+    /// Timestamp of processing (empty if the gateway omits it)
+    #[serde(default)]
+    pub timestamp: String,
+    /// Flux payload (if processed)
+    #[serde(default)]
+    pub flux_payload: Option<serde_json::Value>,
+    /// HSI state summary
+    #[serde(default)]
+    pub state: Option<HsiState>,
+}
+
+/// HSI state summary from gateway.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HsiState {
+    /// Focus level
+    #[serde(default)]
+    pub focus: Option<String>,
+    /// Load level
+    #[serde(default)]
+    pub load: Option<String>,
+    /// Recovery level
+    #[serde(default)]
+    pub recovery: Option<String>,
+}
+
+impl std::fmt::Display for HsiState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let focus = self.focus.as_deref().unwrap_or("unknown");
+        let load = self.load.as_deref().unwrap_or("unknown");
+        let recovery = self.recovery.as_deref().unwrap_or("unknown");
+        write!(f, "focus: {focus}, load: {load}, recovery: {recovery}")
+    }
+}
+
+/// Gateway client for syncing with synheart-core-gateway.
+#[cfg(feature = "gateway")]
+pub struct GatewayClient {
+    config: GatewayConfig,
+    client: reqwest::Client,
+    device_id: String,
+}
+
+#[cfg(feature = "gateway")]
+impl GatewayClient {
+    /// Create a new gateway client.
+    ///
+    /// Fails if the configured CA bundle cannot be loaded.
+    pub fn new(config: GatewayConfig) -> Result<Self, GatewayError> {
+        let client = config.http_client()?;
+        let device_id = config
+            .device_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        Ok(Self {
+            config,
+            client,
+            device_id,
+        })
+    }
+
+    /// Create a new gateway client from runtime directory configuration.
+    pub fn from_runtime() -> Result<Self, GatewayError> {
+        let config = GatewayConfig::from_runtime_dir()?;
+        Self::new(config)
+    }
+
+    /// Test connection to the gateway.
+    pub async fn test_connection(&self) -> Result<bool, GatewayError> {
+        let response = self
+            .client
+            .get(self.config.health_url())
+            .send()
+            .await
+            .map_err(|e| GatewayError::Network(e.to_string()))?;
+
+        Ok(response.status().is_success())
+    }
+
+    /// Sync HSI snapshots to the gateway.
+    pub async fn sync_snapshots(
+        &self,
+        snapshots: &[HsiSnapshot],
+        session_id: &str,
+    ) -> Result<GatewayResponse, GatewayError> {
+        if snapshots.is_empty() {
+            return Err(GatewayError::Config("No snapshots to sync".to_string()));
+        }
+
+        // Build session payload
+        let start_time = snapshots
+            .first()
+            .map(|s| s.observed_at_utc.clone())
+            .unwrap_or_default();
+        let end_time = snapshots
+            .last()
+            .map(|s| s.computed_at_utc.clone())
+            .unwrap_or_default();
+
+        let timezone = chrono_tz::Tz::UTC.to_string();
+
+        let session = BehavioralSession {
+            session: SessionPayload {
+                session_id: session_id.to_string(),
+                device_id: self.device_id.clone(),
+                timezone,
+                start_time,
+                end_time,
+                snapshots: snapshots.to_vec(),
+                meta: SessionMeta {
+                    source: "synheart-sensor-agent".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    snapshot_count: snapshots.len(),
+                },
+            },
+        };
+
+        let body = match self.config.content_type {
+            ContentType::Json => serde_json::to_vec(&session)
+                .map_err(|e| GatewayError::Serialization(e.to_string()))?,
+            ContentType::Protobuf => {
+                use prost::Message;
+                session.to_proto().encode_to_vec()
+            }
+        };
+
+        let response = self
+            .client
+            .post(self.config.ingest_url())
+            .header("Authorization", format!("Bearer {}", self.config.token))
+            .header("Content-Type", self.config.content_type.mime_type())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| GatewayError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(GatewayError::Server {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        let gateway_response: GatewayResponse = response
+            .json()
+            .await
+            .map_err(|e| GatewayError::Serialization(e.to_string()))?;
+
+        Ok(gateway_response)
+    }
+
+    /// Get the device ID.
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Get the gateway configuration.
+    pub fn config(&self) -> &GatewayConfig {
+        &self.config
+    }
+}
+
+/// Blocking gateway client for use in synchronous contexts.
+///
+/// Snapshots awaiting sync are held in a `PersistentQueue` when one is
+/// attached, or in memory otherwise.
+#[cfg(feature = "gateway")]
+pub struct BlockingGatewayClient {
+    inner: GatewayClient,
+    runtime: tokio::runtime::Runtime,
+    queue: Option<PersistentQueue>,
+    pending: Vec<HsiSnapshot>,
+}
+
+#[cfg(feature = "gateway")]
+impl BlockingGatewayClient {
+    /// Create a new blocking gateway client.
+    pub fn new(config: GatewayConfig) -> Result<Self, GatewayError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| GatewayError::Config(format!("Failed to create runtime: {e}")))?;
+
+        Ok(Self {
+            inner: GatewayClient::new(config)?,
+            runtime,
+            queue: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Attach a disk-backed queue for unsent snapshots.
+    ///
+    /// Snapshots persisted by a previous run are loaded with the queue and
+    /// will be included in the next `sync_pending` call.
+    pub fn with_queue(mut self, queue: Option<PersistentQueue>) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    /// Create a new blocking gateway client from runtime directory configuration.
+    pub fn from_runtime() -> Result<Self, GatewayError> {
+        let config = GatewayConfig::from_runtime_dir()?;
+        Self::new(config)
+    }
+
+    /// Test connection to the gateway.
+    pub fn test_connection(&self) -> Result<bool, GatewayError> {
+        self.runtime.block_on(self.inner.test_connection())
+    }
+
+    /// Sync HSI snapshots to the gateway.
+    ///
+    /// Retryable failures (network errors and 5xx responses) are retried with
+    /// exponential backoff, up to `retry_max_attempts` attempts in total.
+    pub fn sync_snapshots(
+        &self,
+        snapshots: &[HsiSnapshot],
+        session_id: &str,
+    ) -> Result<GatewayResponse, GatewayError> {
+        let config = self.inner.config();
+        let max_attempts = config.retry_max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match self
+                .runtime
+                .block_on(self.inner.sync_snapshots(snapshots, session_id))
+            {
+                Err(e) if e.is_retryable() && attempt < max_attempts => {
+                    std::thread::sleep(config.retry_delay(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Queue a snapshot for the next `sync_pending` call.
+    pub fn enqueue(&mut self, snapshot: HsiSnapshot) -> Result<(), GatewayError> {
+        match self.queue {
+            Some(ref mut queue) => queue.push(snapshot),
+            None => {
+                self.pending.push(snapshot);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the number of snapshots awaiting sync.
+    pub fn pending_count(&self) -> usize {
+        match self.queue {
+            Some(ref queue) => queue.len(),
+            None => self.pending.len(),
+        }
+    }
+
+    /// Sync all queued snapshots, clearing the queue on success.
+    ///
+    /// On failure the snapshots stay queued for the next attempt.
+    pub fn sync_pending(&mut self, session_id: &str) -> Result<GatewayResponse, GatewayError> {
+        let snapshots = match self.queue {
+            Some(ref queue) => queue.snapshots(),
+            None => std::mem::take(&mut self.pending),
+        };
+
+        match self.sync_snapshots(&snapshots, session_id) {
+            Ok(response) => {
+                if let Some(ref mut queue) = self.queue {
+                    queue.clear()?;
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                if self.queue.is_none() {
+                    self.pending = snapshots;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Get the device ID.
+    pub fn device_id(&self) -> &str {
+        self.inner.device_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_config_url() {
+        let config = GatewayConfig::new("127.0.0.1", 8080, "test-token");
+        assert_eq!(config.url(), "http://127.0.0.1:8080");
+        assert_eq!(
+            config.ingest_url(),
+            "http://127.0.0.1:8080/v1/ingest/behavioral"
+        );
+        assert_eq!(config.health_url(), "http://127.0.0.1:8080/health");
+    }
+
+    #[test]
+    fn test_gateway_config_tls_url() {
+        let config = GatewayConfig::new("gateway.local", 8443, "test-token").with_tls(None);
+        assert_eq!(config.url(), "https://gateway.local:8443");
+        assert_eq!(
+            config.ingest_url(),
+            "https://gateway.local:8443/v1/ingest/behavioral"
+        );
+    }
+
+    #[test]
+    fn test_gateway_stream_url_and_transport() {
+        let config = GatewayConfig::new("127.0.0.1", 8080, "test-token");
+        assert_eq!(config.transport, GatewayTransport::Http);
+        assert_eq!(config.stream_url(), "ws://127.0.0.1:8080/v1/stream");
+        assert_eq!(
+            config.with_tls(None).stream_url(),
+            "wss://127.0.0.1:8080/v1/stream"
+        );
+
+        assert_eq!(
+            "websocket".parse::<GatewayTransport>().unwrap(),
+            GatewayTransport::WebSocket
+        );
+        assert_eq!(
+            "HTTP".parse::<GatewayTransport>().unwrap(),
+            GatewayTransport::Http
+        );
+        assert!("grpc".parse::<GatewayTransport>().is_err());
+    }
+
+    #[test]
+    fn test_content_type() {
+        let config = GatewayConfig::new("127.0.0.1", 8080, "test-token");
+        assert_eq!(config.content_type, ContentType::Json);
+        assert_eq!(
+            config.with_content_type(ContentType::Protobuf).content_type,
+            ContentType::Protobuf
+        );
+
+        assert_eq!(
+            "Protobuf".parse::<ContentType>().unwrap(),
+            ContentType::Protobuf
+        );
+        assert_eq!(ContentType::Json.mime_type(), "application/json");
+        assert!("xml".parse::<ContentType>().is_err());
+    }
+
+    #[test]
+    fn test_session_to_proto() {
+        use crate::core::features::{compute_features, FeatureConfig};
+        use crate::core::hsi::HsiBuilder;
+        use crate::core::windowing::EventWindow;
+        use chrono::{Duration, Utc};
+
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+        let session = BehavioralSession {
+            session: SessionPayload {
+                session_id: "SESS-test".to_string(),
+                device_id: "sensor-test".to_string(),
+                timezone: "UTC".to_string(),
+                start_time: snapshot.observed_at_utc.clone(),
+                end_time: snapshot.computed_at_utc.clone(),
+                snapshots: vec![snapshot],
+                meta: SessionMeta {
+                    source: "synheart-sensor-agent".to_string(),
+                    version: "0.0.0".to_string(),
+                    snapshot_count: 1,
+                },
+            },
+        };
+
+        let payload = session.to_proto().session.unwrap();
+        assert_eq!(payload.session_id, "SESS-test");
+        assert_eq!(payload.snapshots.len(), 1);
+        assert_eq!(payload.meta.unwrap().snapshot_count, 1);
+    }
+
+    #[test]
+    fn test_parse_tls_flag() {
+        let path = Path::new("gateway.tls");
+        assert!(parse_tls_flag("true\n", path).unwrap());
+        assert!(parse_tls_flag("TRUE", path).unwrap());
+        assert!(!parse_tls_flag("false", path).unwrap());
+        assert!(parse_tls_flag("yes", path).is_err());
+    }
+
+    #[test]
+    fn test_gateway_config_from_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("synheart-gateway-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let error = GatewayConfig::from_dir(&dir).unwrap_err().to_string();
+        assert!(error.contains(&format!("{:?}", dir.join("gateway.port"))));
+        assert!(error.contains(&format!("{:?}", dir.join("gateway.token"))));
+
+        std::fs::write(dir.join("gateway.port"), "8080\n").unwrap();
+        std::fs::write(dir.join("gateway.token"), "secret\n").unwrap();
+        std::fs::write(dir.join("gateway.tls"), "true").unwrap();
+        let config = GatewayConfig::from_dir(&dir).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.token, "secret");
+        assert_eq!(config.host, DEFAULT_GATEWAY_HOST);
+        assert!(config.tls);
+        assert_eq!(config.ca_cert_path, None);
+
+        // A CA bundle enables TLS on its own and resolves relative to the dir
+        std::fs::remove_file(dir.join("gateway.tls")).unwrap();
+        std::fs::write(dir.join("gateway.host"), "gateway.lan\n").unwrap();
+        std::fs::write(dir.join("gateway.ca_cert"), "ca.pem\n").unwrap();
+        let config = GatewayConfig::from_dir(&dir).unwrap();
+        assert_eq!(config.url(), "https://gateway.lan:8080");
+        assert_eq!(config.ca_cert_path, Some(dir.join("ca.pem")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_device_id_is_stable() {
+        let path = std::env::temp_dir()
+            .join(format!("synheart-device-{}", uuid::Uuid::new_v4()))
+            .join("device_id");
+
+        let first = load_or_create_device_id(&path).unwrap();
+        assert!(uuid::Uuid::parse_str(&first).is_ok());
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), first);
+        assert_eq!(load_or_create_device_id(&path).unwrap(), first);
+
+        std::fs::write(&path, "not a uuid").unwrap();
+        assert!(load_or_create_device_id(&path).is_err());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[cfg(feature = "gateway")]
+    #[test]
+    fn test_missing_ca_bundle_is_config_error() {
+        let config = GatewayConfig::new("gateway.local", 8443, "test-token")
+            .with_tls(Some(PathBuf::from("/nonexistent/ca.pem")));
+        assert!(matches!(config.http_client(), Err(GatewayError::Config(_))));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_with_jitter() {
+        let config = GatewayConfig::new("127.0.0.1", 8080, "test-token").with_retry(5, 1000);
+        for (retry, base) in [(1, 1000.0), (2, 2000.0), (3, 4000.0)] {
+            let delay = config.retry_delay(retry).as_millis() as f64;
+            assert!(delay >= base * 0.8 && delay <= base * 1.2, "delay {delay}");
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let config = GatewayConfig::new("127.0.0.1", 8080, "test-token")
+            .with_retry(50, 1000)
+            .with_max_retry_delay(5000);
+        for retry in [4, 10, 40] {
+            assert!(config.retry_delay(retry).as_millis() <= 5000);
+        }
+        assert!(config.retry_delay(3).as_millis() >= 3200);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(GatewayError::Network("refused".to_string()).is_retryable());
+        assert!(GatewayError::Server {
+            status: 503,
+            message: String::new()
+        }
+        .is_retryable());
+        assert!(!GatewayError::Server {
+            status: 401,
+            message: String::new()
+        }
+        .is_retryable());
+        assert!(!GatewayError::Config("bad".to_string()).is_retryable());
+        assert!(!GatewayError::Io("disk full".to_string()).is_retryable());
+    }
+
+    /// Minimal HTTP server that answers every request with the given status.
+    ///
+    /// Returns the bound port and a counter of requests received.
+    #[cfg(feature = "gateway")]
+    fn mock_gateway(
+        status_line: &'static str,
+    ) -> (u16, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // Read headers, then the body so the client sees a clean response
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 {status_line}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+
+        (port, requests)
+    }
+
+    #[cfg(feature = "gateway")]
+    #[test]
+    fn test_sync_retries_on_503() {
+        use crate::core::features::{compute_features, FeatureConfig};
+        use crate::core::hsi::HsiBuilder;
+        use crate::core::windowing::EventWindow;
+        use chrono::{Duration, Utc};
+        use std::sync::atomic::Ordering;
+
+        let (port, requests) = mock_gateway("503 Service Unavailable");
+        let config = GatewayConfig::new("127.0.0.1", port, "test-token").with_retry(3, 1);
+        let client = BlockingGatewayClient::new(config).unwrap();
+
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+
+        let result = client.sync_snapshots(&[snapshot], "SESS-test");
+        assert!(matches!(
+            result,
+            Err(GatewayError::Server { status: 503, .. })
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_hsi_state_display() {
+        let state = HsiState {
+            focus: Some("high".to_string()),
+            load: Some("moderate".to_string()),
+            recovery: None,
+        };
+        let display = format!("{state}");
+        assert!(display.contains("high"));
+        assert!(display.contains("moderate"));
+    }
+
+    #[test]
+    fn test_gateway_response_minimal() {
+        let response: GatewayResponse = serde_json::from_str("{}").unwrap();
+        assert!(response.timestamp.is_empty());
+        assert!(response.flux_payload.is_none());
+        assert!(response.state.is_none());
+
+        let json = r#"{"state": {"focus": "high", "trend": "rising"}, "extra": 1}"#;
+        let response: GatewayResponse = serde_json::from_str(json).unwrap();
+        let state = response.state.unwrap();
+        assert_eq!(state.focus.as_deref(), Some("high"));
+        assert!(state.load.is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Disk-backed queue of snapshots awaiting gateway sync.
+//!
+//! Snapshots are appended to a JSON Lines file as they are queued, so they
+//! survive crashes and gateway outages. Once the queue is full, snapshots are
+//! still appended and the oldest lines are only dropped from the file when it
+//! is compacted, so each push costs one append. The file is truncated once
+//! the queue has been synced.
+
+use super::GatewayError;
+use crate::core::HsiSnapshot;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Append-only, size-bounded queue of unsent snapshots.
+pub struct PersistentQueue {
+    /// Backing JSON Lines file
+    path: PathBuf,
+    /// Maximum number of queued snapshots; the oldest are dropped beyond this
+    max_len: usize,
+    /// Queued snapshots, oldest first
+    snapshots: VecDeque<HsiSnapshot>,
+    /// Lines in the backing file, including dropped snapshots not yet compacted
+    lines_on_disk: usize,
+}
+
+impl PersistentQueue {
+    /// Open a queue at `path`, loading any snapshots persisted by a previous run.
+    ///
+    /// Unparseable lines (e.g. a write cut short by a crash) are skipped. If
+    /// more than `max_len` snapshots are on disk, only the newest are kept.
+    pub fn open(path: impl Into<PathBuf>, max_len: usize) -> Result<Self, GatewayError> {
+        let path = path.into();
+        let mut queue = Self {
+            path,
+            max_len: max_len.max(1),
+            snapshots: VecDeque::new(),
+            lines_on_disk: 0,
+        };
+
+        if queue.path.exists() {
+            let content = std::fs::read_to_string(&queue.path).map_err(|e| {
+                GatewayError::Io(format!("Failed to read queue {:?}: {e}", queue.path))
+            })?;
+            queue.snapshots = content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+            queue.lines_on_disk = content.lines().count();
+            if queue.snapshots.len() > queue.max_len {
+                let excess = queue.snapshots.len() - queue.max_len;
+                queue.snapshots.drain(..excess);
+                queue.rewrite()?;
+            }
+        }
+
+        Ok(queue)
+    }
+
+    /// Add a snapshot to the back of the queue and persist it.
+    ///
+    /// The snapshot is appended as a single line. When the queue is full the
+    /// oldest snapshot is dropped from memory; its line stays in the file
+    /// (and is skipped on reopen) until the file holds twice `max_len` lines
+    /// and is compacted.
+    pub fn push(&mut self, snapshot: HsiSnapshot) -> Result<(), GatewayError> {
+        let line = to_line(&snapshot)?;
+        if self.snapshots.len() >= self.max_len {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+
+        if self.lines_on_disk >= self.max_len.saturating_mul(2) {
+            return self.rewrite();
+        }
+        self.ensure_parent()?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| GatewayError::Io(format!("Failed to write queue {:?}: {e}", self.path)))?;
+        self.lines_on_disk += 1;
+        Ok(())
+    }
+
+    /// Remove all snapshots and truncate the backing file.
+    pub fn clear(&mut self) -> Result<(), GatewayError> {
+        self.snapshots.clear();
+        self.rewrite()
+    }
+
+    /// Get the queued snapshots, oldest first.
+    pub fn snapshots(&self) -> Vec<HsiSnapshot> {
+        self.snapshots.iter().cloned().collect()
+    }
+
+    /// Get the number of queued snapshots.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Check whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Get the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replace the backing file with the current queue contents.
+    fn rewrite(&mut self) -> Result<(), GatewayError> {
+        let mut content = String::new();
+        for snapshot in &self.snapshots {
+            content.push_str(&to_line(snapshot)?);
+        }
+        self.ensure_parent()?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| GatewayError::Io(format!("Failed to write queue {:?}: {e}", self.path)))?;
+        self.lines_on_disk = self.snapshots.len();
+        Ok(())
+    }
+
+    fn ensure_parent(&self) -> Result<(), GatewayError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                GatewayError::Io(format!("Failed to create queue directory {parent:?}: {e}"))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize a snapshot as one newline-terminated JSON line.
+fn to_line(snapshot: &HsiSnapshot) -> Result<String, GatewayError> {
+    let mut line =
+        serde_json::to_string(snapshot).map_err(|e| GatewayError::Serialization(e.to_string()))?;
+    line.push('\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use chrono::{Duration, Utc};
+
+    fn snapshot_at(offset_secs: i64) -> HsiSnapshot {
+        let window = EventWindow::new(
+            Utc::now() + Duration::seconds(offset_secs),
+            Duration::seconds(10),
+        );
+        let features = compute_features(&window, &FeatureConfig::default());
+        HsiBuilder::new().build(&window, &features, Duration::seconds(10), None)
+    }
+
+    #[test]
+    fn test_queue_persists_and_drops_oldest() {
+        let dir = std::env::temp_dir().join(format!("synheart-queue-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("pending_snapshots.jsonl");
+
+        let snapshots: Vec<HsiSnapshot> = (0..3).map(|i| snapshot_at(i * 10)).collect();
+        let mut queue = PersistentQueue::open(&path, 2).unwrap();
+        for snapshot in &snapshots {
+            queue.push(snapshot.clone()).unwrap();
+        }
+        assert_eq!(queue.len(), 2);
+
+        // Reopening loads the newest two snapshots from disk
+        let reopened = PersistentQueue::open(&path, 2).unwrap();
+        let observed: Vec<String> = reopened
+            .snapshots()
+            .into_iter()
+            .map(|s| s.observed_at_utc)
+            .collect();
+        assert_eq!(
+            observed,
+            vec![
+                snapshots[1].observed_at_utc.clone(),
+                snapshots[2].observed_at_utc.clone()
+            ]
+        );
+
+        queue.clear().unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        assert!(PersistentQueue::open(&path, 2).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_full_queue_appends_and_compacts() {
+        let dir = std::env::temp_dir().join(format!("synheart-queue-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("pending_snapshots.jsonl");
+        let line_count = || std::fs::read_to_string(&path).unwrap().lines().count();
+
+        let snapshots: Vec<HsiSnapshot> = (0..6).map(|i| snapshot_at(i * 10)).collect();
+        let mut queue = PersistentQueue::open(&path, 2).unwrap();
+        for snapshot in &snapshots[..4] {
+            queue.push(snapshot.clone()).unwrap();
+        }
+        // Pushes to a full queue append instead of rewriting
+        assert_eq!(queue.len(), 2);
+        assert_eq!(line_count(), 4);
+
+        // At twice the capacity the file is compacted to the queued snapshots
+        queue.push(snapshots[4].clone()).unwrap();
+        assert_eq!(line_count(), 2);
+        queue.push(snapshots[5].clone()).unwrap();
+        assert_eq!(line_count(), 3);
+
+        let reopened = PersistentQueue::open(&path, 2).unwrap();
+        let observed: Vec<String> = reopened
+            .snapshots()
+            .into_iter()
+            .map(|s| s.observed_at_utc)
+            .collect();
+        assert_eq!(
+            observed,
+            vec![
+                snapshots[4].observed_at_utc.clone(),
+                snapshots[5].observed_at_utc.clone()
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
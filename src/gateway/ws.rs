@@ -0,0 +1,355 @@
+//! WebSocket streaming transport for the gateway.
+//!
+//! Instead of batching snapshots and posting them every sync interval, the
+//! WebSocket transport keeps a persistent connection to `/v1/stream` and
+//! sends each snapshot as a JSON text frame as soon as it is produced.
+
+use super::{GatewayConfig, GatewayError, PersistentQueue};
+use crate::core::HsiSnapshot;
+use futures_util::SinkExt;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Number of snapshots that can wait in the channel to the worker.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Maximum number of unsent snapshots the worker holds while disconnected;
+/// the oldest are dropped beyond this.
+const MAX_BACKLOG: usize = 10_000;
+
+/// How long to wait for the WebSocket handshake before retrying.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Streaming gateway client backed by a persistent WebSocket connection.
+///
+/// The connection is owned by a background thread, so `send` never blocks
+/// the caller. While disconnected the worker keeps unsent snapshots and
+/// reconnects with capped exponential backoff (using the config's retry
+/// delay). Snapshots still unsent when the client closes are written to the
+/// attached [`PersistentQueue`] and sent first on the next connection.
+pub struct WsGatewayClient {
+    sender: Option<Sender<HsiSnapshot>>,
+    worker: Option<std::thread::JoinHandle<()>>,
+}
+
+impl WsGatewayClient {
+    /// Start the background worker and begin connecting to the gateway.
+    pub fn connect(config: GatewayConfig) -> Result<Self, GatewayError> {
+        Self::connect_with_queue(config, None)
+    }
+
+    /// Start the background worker with a disk-backed queue for snapshots
+    /// that could not be sent before shutdown.
+    ///
+    /// Snapshots persisted by a previous run are sent before new ones.
+    pub fn connect_with_queue(
+        config: GatewayConfig,
+        queue: Option<PersistentQueue>,
+    ) -> Result<Self, GatewayError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| GatewayError::Config(format!("Failed to create runtime: {e}")))?;
+
+        let (sender, receiver) = channel(CHANNEL_CAPACITY);
+        let worker = std::thread::Builder::new()
+            .name("gateway-ws".to_string())
+            .spawn(move || runtime.block_on(run_worker(config, receiver, queue)))
+            .map_err(|e| GatewayError::Config(format!("Failed to start WebSocket worker: {e}")))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    /// Queue a snapshot to be sent as soon as the connection allows.
+    ///
+    /// Fails without blocking if the worker has fallen too far behind.
+    pub fn send(&self, snapshot: HsiSnapshot) -> Result<(), GatewayError> {
+        self.sender
+            .as_ref()
+            .ok_or_else(|| GatewayError::Network("WebSocket client closed".to_string()))?
+            .try_send(snapshot)
+            .map_err(|e| match e {
+                TrySendError::Full(_) => {
+                    GatewayError::Network("WebSocket send buffer full".to_string())
+                }
+                TrySendError::Closed(_) => {
+                    GatewayError::Network("WebSocket worker stopped".to_string())
+                }
+            })
+    }
+
+    /// Flush queued snapshots and close the connection.
+    ///
+    /// If the gateway is unreachable at this point, unsent snapshots are
+    /// written to the persistent queue (or dropped if there is none).
+    pub fn close(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // Dropping the sender tells the worker to drain and exit
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for WsGatewayClient {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Open an authenticated WebSocket connection to the gateway stream endpoint.
+async fn open_stream(config: &GatewayConfig) -> Result<WsStream, GatewayError> {
+    let mut request = config
+        .stream_url()
+        .into_client_request()
+        .map_err(|e| GatewayError::Config(e.to_string()))?;
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", config.token)
+            .parse()
+            .map_err(|_| GatewayError::Config("Invalid gateway token".to_string()))?,
+    );
+
+    let (stream, _) =
+        tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(request))
+            .await
+            .map_err(|_| {
+                GatewayError::Network(format!(
+                    "Connection timed out after {}s",
+                    CONNECT_TIMEOUT.as_secs()
+                ))
+            })?
+            .map_err(|e| GatewayError::Network(e.to_string()))?;
+    Ok(stream)
+}
+
+/// Add a snapshot to the back of the backlog, dropping the oldest if full.
+fn buffer(backlog: &mut VecDeque<HsiSnapshot>, snapshot: HsiSnapshot) {
+    if backlog.len() >= MAX_BACKLOG {
+        backlog.pop_front();
+    }
+    backlog.push_back(snapshot);
+}
+
+/// Drive `future` to completion while moving snapshots from the channel into
+/// the backlog, so the channel never fills while the worker waits.
+///
+/// Returns `None` as soon as the channel closes.
+async fn buffering<F: Future>(
+    future: F,
+    receiver: &mut Receiver<HsiSnapshot>,
+    backlog: &mut VecDeque<HsiSnapshot>,
+) -> Option<F::Output> {
+    tokio::pin!(future);
+    loop {
+        tokio::select! {
+            output = &mut future => return Some(output),
+            snapshot = receiver.recv() => match snapshot {
+                Some(snapshot) => buffer(backlog, snapshot),
+                None => return None,
+            },
+        }
+    }
+}
+
+/// Persist the unsent backlog at shutdown.
+///
+/// The backlog already holds everything the queue held when the worker
+/// started, so the queue is replaced rather than appended to.
+fn spill(queue: Option<&mut PersistentQueue>, backlog: VecDeque<HsiSnapshot>) {
+    if backlog.is_empty() {
+        return;
+    }
+    let Some(queue) = queue else {
+        eprintln!(
+            "[Gateway] Dropping {} unsent snapshots at shutdown",
+            backlog.len()
+        );
+        return;
+    };
+    let count = backlog.len();
+    let result = queue
+        .clear()
+        .and_then(|_| backlog.into_iter().try_for_each(|s| queue.push(s)));
+    match result {
+        Ok(()) => eprintln!(
+            "[Gateway] Saved {count} unsent snapshots to {:?}",
+            queue.path()
+        ),
+        Err(e) => eprintln!("[Gateway] Could not save unsent snapshots: {e}"),
+    }
+}
+
+/// Forward snapshots from the channel to the gateway until the channel closes.
+async fn run_worker(
+    config: GatewayConfig,
+    mut receiver: Receiver<HsiSnapshot>,
+    mut queue: Option<PersistentQueue>,
+) {
+    // Snapshots not yet sent, oldest first, starting with those left by a previous run
+    let mut backlog: VecDeque<HsiSnapshot> = queue
+        .as_ref()
+        .map(|queue| queue.snapshots().into())
+        .unwrap_or_default();
+    let mut attempt = 0;
+
+    loop {
+        // Stop waiting on an unreachable gateway as soon as the client closes
+        let Some(connected) = buffering(open_stream(&config), &mut receiver, &mut backlog).await
+        else {
+            spill(queue.as_mut(), backlog);
+            return;
+        };
+        let mut stream = match connected {
+            Ok(stream) => {
+                attempt = 0;
+                stream
+            }
+            Err(e) => {
+                attempt += 1;
+                eprintln!("[Gateway] WebSocket connect failed (attempt {attempt}): {e}");
+                let delay = tokio::time::sleep(config.retry_delay(attempt));
+                if buffering(delay, &mut receiver, &mut backlog)
+                    .await
+                    .is_none()
+                {
+                    spill(queue.as_mut(), backlog);
+                    return;
+                }
+                continue;
+            }
+        };
+
+        loop {
+            let snapshot = match backlog.pop_front() {
+                Some(snapshot) => snapshot,
+                None => match receiver.recv().await {
+                    Some(snapshot) => snapshot,
+                    None => {
+                        let _ = stream.close(None).await;
+                        return;
+                    }
+                },
+            };
+            let frame = match serde_json::to_string(&snapshot) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    eprintln!("[Gateway] Could not serialize snapshot: {e}");
+                    continue;
+                }
+            };
+
+            if let Err(e) = stream.send(Message::Text(frame.into())).await {
+                eprintln!("[Gateway] WebSocket disconnected: {e}");
+                backlog.push_front(snapshot);
+                break;
+            }
+
+            // Everything restored from the queue has now been sent
+            if backlog.is_empty() {
+                if let Some(queue) = queue.as_mut().filter(|queue| !queue.is_empty()) {
+                    if let Err(e) = queue.clear() {
+                        eprintln!("[Gateway] Could not clear queue: {e}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use chrono::{Duration, Utc};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_ws_client_streams_snapshots() {
+        // Local WebSocket server that forwards the first text frame it receives
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        listener.set_nonblocking(true).unwrap();
+        let (frames_tx, frames_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                while let Some(Ok(message)) = ws.next().await {
+                    if let Message::Text(text) = message {
+                        frames_tx.send(text.as_str().to_string()).unwrap();
+                    }
+                }
+            });
+        });
+
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+
+        let config = GatewayConfig::new("127.0.0.1", port, "test-token").with_retry(3, 10);
+        let client = WsGatewayClient::connect(config).unwrap();
+        client.send(snapshot.clone()).unwrap();
+
+        let frame = frames_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap();
+        let received: HsiSnapshot = serde_json::from_str(&frame).unwrap();
+        assert_eq!(received.observed_at_utc, snapshot.observed_at_utc);
+
+        client.close();
+    }
+
+    #[test]
+    fn test_unsent_snapshots_saved_to_queue_on_close() {
+        // A port nothing listens on
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let dir = std::env::temp_dir().join(format!("synheart-ws-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("pending_snapshots.jsonl");
+
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+
+        // A long backoff must not hold up close
+        let config = GatewayConfig::new("127.0.0.1", port, "test-token").with_retry(3, 60_000);
+        let queue = PersistentQueue::open(&path, 100).unwrap();
+        let client = WsGatewayClient::connect_with_queue(config, Some(queue)).unwrap();
+        client.send(snapshot.clone()).unwrap();
+        client.send(snapshot).unwrap();
+        let started = std::time::Instant::now();
+        client.close();
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let queue = PersistentQueue::open(&path, 100).unwrap();
+        assert_eq!(queue.len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,459 @@
+//! Control channel between the CLI and a running agent.
+//!
+//! The agent listens on a Unix domain socket (`agent.sock` in the data
+//! directory) or, on Windows, a named pipe. Each connection carries a single
+//! newline-terminated JSON command such as `{"cmd": "pause"}` and receives a
+//! single JSON response line.
+//!
+//! Commands are forwarded to the agent's main loop, which applies them and
+//! replies, so the response always reflects the agent's actual state.
+
+use crate::config::Config;
+use crate::watch::WindowReadout;
+use chrono::{DateTime, Utc};
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::sync::oneshot;
+
+/// Named pipe used instead of a socket file on Windows.
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\synheart-sensor-agent";
+
+/// How long the agent has to answer a command before the connection is closed.
+const RESPONSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Upper bound on a single command line, in bytes.
+const MAX_COMMAND_LEN: u64 = 4096;
+
+/// A command sent to the running agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum IpcCommand {
+    /// Stop collecting until resumed
+    Pause,
+    /// Resume collecting
+    Resume,
+    /// Report the current state
+    Status,
+}
+
+/// The agent's reply to a command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcResponse {
+    /// Whether the command was applied
+    pub ok: bool,
+    /// Whether collection is paused after the command
+    pub paused: bool,
+    /// Error description when `ok` is false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Length in seconds of the typing session in progress, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typing_session_secs: Option<u64>,
+    /// Sessions in the flux rolling baseline, if flux is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_sessions: Option<u32>,
+    /// Size of the flux rolling baseline, if flux is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_window: Option<usize>,
+    /// When the last keyboard or mouse event arrived, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// Features of the latest completed window, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_window: Option<WindowReadout>,
+}
+
+impl IpcResponse {
+    /// Successful response reporting the current pause state.
+    pub fn status(paused: bool) -> Self {
+        Self {
+            ok: true,
+            paused,
+            error: None,
+            typing_session_secs: None,
+            baseline_sessions: None,
+            baseline_window: None,
+            last_event_at: None,
+            latest_window: None,
+        }
+    }
+
+    /// Report the length of the typing session in progress.
+    pub fn with_typing_session_secs(mut self, typing_session_secs: Option<u64>) -> Self {
+        self.typing_session_secs = typing_session_secs;
+        self
+    }
+
+    /// Report when the last event arrived.
+    pub fn with_last_event_at(mut self, last_event_at: Option<DateTime<Utc>>) -> Self {
+        self.last_event_at = last_event_at;
+        self
+    }
+
+    /// Report the features of the latest completed window.
+    pub fn with_latest_window(mut self, latest_window: Option<WindowReadout>) -> Self {
+        self.latest_window = latest_window;
+        self
+    }
+
+    /// Report how many of the flux baseline's sessions have been collected.
+    pub fn with_baseline(mut self, baseline_sessions: u32, baseline_window: usize) -> Self {
+        self.baseline_sessions = Some(baseline_sessions);
+        self.baseline_window = Some(baseline_window);
+        self
+    }
+}
+
+/// A command received by the server, waiting for the main loop to answer it.
+pub struct IpcRequest {
+    /// The command to apply
+    pub command: IpcCommand,
+    /// Channel back to the connection handler
+    reply: oneshot::Sender<IpcResponse>,
+}
+
+impl IpcRequest {
+    /// Send the response to the client.
+    pub fn respond(self, response: IpcResponse) {
+        // The client may have given up already; nothing to do then
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Get the IPC endpoint for the given configuration.
+///
+/// This is the socket path on Unix and the named pipe path on Windows.
+pub fn endpoint(config: &Config) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let _ = config;
+        PathBuf::from(PIPE_NAME)
+    }
+
+    #[cfg(not(windows))]
+    {
+        config.ipc_socket_path()
+    }
+}
+
+/// Listens for control commands from other processes.
+///
+/// Connections are accepted on a background thread; received commands are
+/// queued for the main loop, which polls them with [`IpcServer::try_recv`].
+pub struct IpcServer {
+    /// Socket or pipe path
+    endpoint: PathBuf,
+    /// Commands waiting to be handled
+    requests: Receiver<IpcRequest>,
+}
+
+impl IpcServer {
+    /// Start listening at `endpoint`.
+    ///
+    /// On Unix a stale socket file left by a crashed agent is replaced. If
+    /// another agent is still listening, this fails with
+    /// [`IpcError::AlreadyRunning`].
+    pub fn bind(endpoint: &Path) -> Result<Self, IpcError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| IpcError::Io(format!("Failed to create runtime: {e}")))?;
+
+        let (sender, requests) = crossbeam_channel::unbounded();
+        let listener = {
+            let _guard = runtime.enter();
+            listener::bind(endpoint)?
+        };
+
+        std::thread::Builder::new()
+            .name("ipc-server".to_string())
+            .spawn(move || runtime.block_on(listener::serve(listener, sender)))
+            .map_err(|e| IpcError::Io(format!("Failed to start IPC server: {e}")))?;
+
+        Ok(Self {
+            endpoint: endpoint.to_path_buf(),
+            requests,
+        })
+    }
+
+    /// Get the next pending command, if any, without blocking.
+    pub fn try_recv(&self) -> Option<IpcRequest> {
+        self.requests.try_recv().ok()
+    }
+
+    /// Get the socket or pipe path.
+    pub fn endpoint(&self) -> &Path {
+        &self.endpoint
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.endpoint);
+    }
+}
+
+/// Read one command from a connection, hand it to the main loop and write the reply.
+async fn handle_connection<S>(stream: S, sender: Sender<IpcRequest>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = String::new();
+    let mut reader =
+        tokio::io::BufReader::new(tokio::io::AsyncReadExt::take(reader, MAX_COMMAND_LEN));
+    if reader.read_line(&mut line).await.is_err() {
+        return;
+    }
+
+    let response = match serde_json::from_str::<IpcCommand>(line.trim()) {
+        Ok(command) => {
+            let (reply, response) = oneshot::channel();
+            if sender.send(IpcRequest { command, reply }).is_err() {
+                return;
+            }
+            match tokio::time::timeout(RESPONSE_TIMEOUT, response).await {
+                Ok(Ok(response)) => response,
+                _ => return,
+            }
+        }
+        Err(e) => IpcResponse {
+            ok: false,
+            paused: false,
+            error: Some(format!("Invalid command: {e}")),
+            typing_session_secs: None,
+            baseline_sessions: None,
+            baseline_window: None,
+            last_event_at: None,
+            latest_window: None,
+        },
+    };
+
+    if let Ok(mut json) = serde_json::to_string(&response) {
+        json.push('\n');
+        let _ = writer.write_all(json.as_bytes()).await;
+        let _ = writer.shutdown().await;
+    }
+}
+
+#[cfg(unix)]
+mod listener {
+    use super::{handle_connection, IpcError, IpcRequest};
+    use crossbeam_channel::Sender;
+    use std::path::Path;
+    use tokio::net::UnixListener;
+
+    pub(super) fn bind(endpoint: &Path) -> Result<UnixListener, IpcError> {
+        if endpoint.exists() {
+            if std::os::unix::net::UnixStream::connect(endpoint).is_ok() {
+                return Err(IpcError::AlreadyRunning);
+            }
+            // Left behind by an agent that did not shut down cleanly
+            std::fs::remove_file(endpoint).map_err(|e| IpcError::Io(e.to_string()))?;
+        }
+        if let Some(parent) = endpoint.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| IpcError::Io(e.to_string()))?;
+        }
+        UnixListener::bind(endpoint).map_err(|e| IpcError::Io(e.to_string()))
+    }
+
+    pub(super) async fn serve(listener: UnixListener, sender: Sender<IpcRequest>) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_connection(stream, sender.clone()));
+                }
+                Err(e) => eprintln!("[IPC] Accept failed: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod listener {
+    use super::{handle_connection, IpcError, IpcRequest};
+    use crossbeam_channel::Sender;
+    use std::path::{Path, PathBuf};
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+    pub(super) struct PipeListener {
+        name: PathBuf,
+        next: NamedPipeServer,
+    }
+
+    pub(super) fn bind(endpoint: &Path) -> Result<PipeListener, IpcError> {
+        let next = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(endpoint)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::PermissionDenied => IpcError::AlreadyRunning,
+                _ => IpcError::Io(e.to_string()),
+            })?;
+        Ok(PipeListener {
+            name: endpoint.to_path_buf(),
+            next,
+        })
+    }
+
+    pub(super) async fn serve(mut listener: PipeListener, sender: Sender<IpcRequest>) {
+        loop {
+            if let Err(e) = listener.next.connect().await {
+                eprintln!("[IPC] Accept failed: {e}");
+                continue;
+            }
+            // Create the next instance before handing off the connected one
+            let next = match ServerOptions::new().create(&listener.name) {
+                Ok(next) => next,
+                Err(e) => {
+                    eprintln!("[IPC] Could not create pipe instance: {e}");
+                    return;
+                }
+            };
+            let connected = std::mem::replace(&mut listener.next, next);
+            tokio::spawn(handle_connection(connected, sender.clone()));
+        }
+    }
+}
+
+/// Send a command to the agent listening at `endpoint` and wait for its reply.
+///
+/// Returns [`IpcError::NotRunning`] when no agent is listening.
+pub fn send_command(endpoint: &Path, command: IpcCommand) -> Result<IpcResponse, IpcError> {
+    #[cfg(unix)]
+    let stream = {
+        let stream =
+            std::os::unix::net::UnixStream::connect(endpoint).map_err(|_| IpcError::NotRunning)?;
+        stream
+            .set_read_timeout(Some(RESPONSE_TIMEOUT))
+            .map_err(|e| IpcError::Io(e.to_string()))?;
+        stream
+    };
+
+    #[cfg(windows)]
+    let stream = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(endpoint)
+        .map_err(|_| IpcError::NotRunning)?;
+
+    exchange(stream, command)
+}
+
+/// Write a command line and read back the response line.
+fn exchange<S>(mut stream: S, command: IpcCommand) -> Result<IpcResponse, IpcError>
+where
+    S: std::io::Read + Write,
+{
+    let mut request =
+        serde_json::to_string(&command).map_err(|e| IpcError::Protocol(e.to_string()))?;
+    request.push('\n');
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|_| stream.flush())
+        .map_err(|e| IpcError::Io(e.to_string()))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| IpcError::Io(e.to_string()))?;
+    if line.trim().is_empty() {
+        return Err(IpcError::Protocol(
+            "Agent closed the connection".to_string(),
+        ));
+    }
+    serde_json::from_str(line.trim()).map_err(|e| IpcError::Protocol(e.to_string()))
+}
+
+/// IPC errors.
+#[derive(Debug)]
+pub enum IpcError {
+    /// No agent is listening at the endpoint
+    NotRunning,
+    /// Another agent is already listening at the endpoint
+    AlreadyRunning,
+    /// Socket or pipe I/O failed
+    Io(String),
+    /// The peer sent something that is not a valid message
+    Protocol(String),
+}
+
+impl std::fmt::Display for IpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpcError::NotRunning => write!(f, "No running agent found"),
+            IpcError::AlreadyRunning => write!(f, "Another agent is already running"),
+            IpcError::Io(msg) => write!(f, "IPC I/O error: {msg}"),
+            IpcError::Protocol(msg) => write!(f, "IPC protocol error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_serde() {
+        assert_eq!(
+            serde_json::from_str::<IpcCommand>(r#"{"cmd": "pause"}"#).unwrap(),
+            IpcCommand::Pause
+        );
+        assert_eq!(
+            serde_json::to_string(&IpcCommand::Status).unwrap(),
+            r#"{"cmd":"status"}"#
+        );
+        assert!(serde_json::from_str::<IpcCommand>(r#"{"cmd": "reboot"}"#).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_server_round_trip() {
+        let dir = std::env::temp_dir().join(format!("synheart-ipc-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("agent.sock");
+        let server = IpcServer::bind(&path).unwrap();
+
+        assert!(matches!(
+            IpcServer::bind(&path),
+            Err(IpcError::AlreadyRunning)
+        ));
+
+        // Stand-in for the agent's main loop
+        let agent = std::thread::spawn(move || {
+            let mut paused = false;
+            let mut handled = 0;
+            while handled < 2 {
+                match server.try_recv() {
+                    Some(request) => {
+                        if request.command == IpcCommand::Pause {
+                            paused = true;
+                        }
+                        request.respond(IpcResponse::status(paused));
+                        handled += 1;
+                    }
+                    None => std::thread::sleep(std::time::Duration::from_millis(10)),
+                }
+            }
+        });
+
+        let response = send_command(&path, IpcCommand::Pause).unwrap();
+        assert_eq!(response, IpcResponse::status(true));
+        let response = send_command(&path, IpcCommand::Status).unwrap();
+        assert!(response.ok && response.paused);
+
+        agent.join().unwrap();
+        assert!(!path.exists()); // Socket removed when the server is dropped
+        assert!(matches!(
+            send_command(&path, IpcCommand::Status),
+            Err(IpcError::NotRunning)
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
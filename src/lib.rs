@@ -46,8 +46,18 @@
 
 pub mod collector;
 pub mod config;
+pub mod consent;
 pub mod core;
+pub mod export;
+pub mod ipc;
+pub mod reload;
+pub mod retention;
+pub mod service;
+pub mod signing;
+pub mod store;
+pub mod test_utils;
 pub mod transparency;
+pub mod watch;
 
 #[cfg(feature = "flux")]
 pub mod flux;
@@ -55,23 +65,35 @@ pub mod flux;
 #[cfg(feature = "gateway")]
 pub mod gateway;
 
+#[cfg(feature = "proto")]
+pub mod proto;
+
 #[cfg(feature = "server")]
 pub mod server;
 
 // Re-export key types at crate root for convenience
 pub use collector::{Collector, CollectorConfig, CollectorError, SensorEvent};
-pub use config::{Config, SourceConfig};
-pub use core::{compute_features, HsiBuilder, HsiSnapshot, WindowFeatures, WindowManager};
+pub use config::{Config, FluxConfig, GatewayConnectionConfig, SourceConfig};
+pub use core::{
+    compute_features, FeatureConfig, HsiBuilder, HsiSnapshot, HsiSnapshotBuilder, WindowFeatures,
+    WindowManager,
+};
+pub use store::{SnapshotStore, StorageBackend};
 pub use transparency::{SharedTransparencyLog, TransparencyLog, TransparencyStats};
 
 // Flux re-exports (when enabled)
 #[cfg(feature = "flux")]
 pub use flux::{EnrichedSnapshot, SensorFluxProcessor};
 
+// SQLite store re-exports (when enabled)
+#[cfg(feature = "sqlite")]
+pub use store::SqliteSnapshotStore;
+
 // Gateway re-exports (when enabled)
 #[cfg(feature = "gateway")]
 pub use gateway::{
-    BlockingGatewayClient, GatewayClient, GatewayConfig, GatewayError, GatewayResponse,
+    load_or_create_device_id, BlockingGatewayClient, ContentType, GatewayClient, GatewayConfig,
+    GatewayError, GatewayResponse, GatewayTransport, PersistentQueue, WsGatewayClient,
 };
 
 // Server re-exports (when enabled)
@@ -102,6 +124,8 @@ pub const PRIVACY_DECLARATION: &str = r#"
 ║                                                                  ║
 ║  All data is processed locally. Raw events are discarded         ║
 ║  after feature extraction (every 10 seconds).                    ║
+║  Near-idle windows (below the configured minimum event count)    ║
+║  are dropped without computing or keeping any data.              ║
 ║                                                                  ║
 ║  You can view collection statistics anytime with:                ║
 ║    synheart-sensor status                                        ║
@@ -118,5 +142,6 @@ mod tests {
         assert!(PRIVACY_DECLARATION.contains("PRIVACY"));
         assert!(PRIVACY_DECLARATION.contains("NEVER CAPTURE"));
         assert!(PRIVACY_DECLARATION.contains("keys you press"));
+        assert!(PRIVACY_DECLARATION.contains("Near-idle windows"));
     }
 }
@@ -2,23 +2,38 @@
 //!
 //! Privacy-first behavioral sensor for research.
 
-use chrono::Utc;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use synheart_sensor_agent::{
     collector::{check_permission, Collector, CollectorConfig, SensorEvent},
-    config::{Config, SourceConfig},
-    core::{compute_features, HsiBuilder, HsiSnapshot, WindowManager},
-    transparency::create_shared_log_with_persistence,
+    config::{Config, ConfigFormat, SourceConfig, DEFAULT_EXPORT_FILENAME_PATTERN},
+    consent,
+    core::{
+        axis_statistics, compute_features, compute_features_parallel, validate_snapshot,
+        DeepFocusTracker, EventWindow, FeatureConfig, FeatureDeltaTracker, HsiBuilder, HsiSnapshot,
+        TypingSession, TypingSessionDetector, WindowFeatures, WindowManager,
+    },
+    export::{self, FileNamePattern},
+    ipc::{self, IpcCommand, IpcError, IpcResponse, IpcServer},
+    reload::{self, ReloadSignal},
+    retention, service,
+    signing::SnapshotSigner,
+    store::{SnapshotStore, StorageBackend},
+    transparency::{create_shared_log_with_persistence, describe_elapsed},
+    watch::{self, WindowReadout},
     PRIVACY_DECLARATION, VERSION,
 };
 
 #[cfg(feature = "gateway")]
-use synheart_sensor_agent::{BlockingGatewayClient, GatewayConfig};
+use synheart_sensor_agent::{
+    load_or_create_device_id, BlockingGatewayClient, ContentType, GatewayConfig, GatewayTransport,
+    PersistentQueue, WsGatewayClient,
+};
 
 #[derive(Parser)]
 #[command(name = "synheart-sensor")]
@@ -34,7 +49,7 @@ struct Cli {
 enum Commands {
     /// Start capturing behavioral data
     Start {
-        /// Input sources to capture (keyboard, mouse, or all)
+        /// Input sources to capture (keyboard, mouse, touch, or all)
         #[arg(long, default_value = "all")]
         sources: String,
 
@@ -46,25 +61,58 @@ enum Commands {
         #[arg(long)]
         flux: bool,
 
-        /// Baseline window size (number of sessions for rolling baseline)
-        #[arg(long, default_value = "20")]
-        baseline_window: usize,
+        /// Baseline window size (number of sessions for rolling baseline; defaults to flux.baseline_window)
+        #[arg(long)]
+        baseline_window: Option<usize>,
 
         /// Enable gateway sync (requires gateway feature)
         #[arg(long)]
         gateway: bool,
 
         /// Gateway port (auto-detected from runtime dir if not specified)
-        #[arg(long)]
+        #[arg(long, env = "SYNHEART_GATEWAY_PORT")]
         gateway_port: Option<u16>,
 
         /// Gateway token (auto-detected from runtime dir if not specified)
-        #[arg(long)]
+        #[arg(long, env = "SYNHEART_GATEWAY_TOKEN", hide_env_values = true)]
         gateway_token: Option<String>,
 
+        /// Directory holding gateway.port, gateway.token and gateway.tls
+        /// (defaults to the SyniLife runtime dir)
+        #[arg(long, env = "SYNHEART_GATEWAY_CONFIG_DIR")]
+        gateway_config_dir: Option<PathBuf>,
+
+        /// Gateway host (overrides gateway.host in the config file and runtime dir)
+        #[arg(long)]
+        gateway_host: Option<String>,
+
+        /// Connect to the gateway over TLS
+        #[arg(long)]
+        gateway_tls: bool,
+
+        /// PEM CA bundle to trust for the gateway certificate (implies --gateway-tls)
+        #[arg(long)]
+        gateway_ca_cert: Option<PathBuf>,
+
         /// Sync interval in seconds (how often to sync to gateway)
         #[arg(long, default_value = "10")]
         sync_interval: u64,
+
+        /// Gateway transport (http or websocket)
+        #[arg(long, default_value = "http")]
+        gateway_transport: String,
+
+        /// Encoding of HTTP gateway sync requests (json or protobuf)
+        #[arg(long, default_value = "json")]
+        gateway_content_type: String,
+
+        /// Serve live transparency stats on localhost at this port (requires transparency-server feature)
+        #[arg(long)]
+        transparency_port: Option<u16>,
+
+        /// Consent to collection without the interactive prompt on first run
+        #[arg(long)]
+        yes_i_consent: bool,
     },
 
     /// Start HTTP server to receive behavioral data from Chrome extension
@@ -79,12 +127,24 @@ enum Commands {
         gateway_host: String,
 
         /// Gateway port
-        #[arg(long, default_value = "8080")]
+        #[arg(long, default_value = "8080", env = "SYNHEART_GATEWAY_PORT")]
         gateway_port: u16,
 
         /// Gateway auth token
-        #[arg(long)]
+        #[arg(long, env = "SYNHEART_GATEWAY_TOKEN", hide_env_values = true)]
         gateway_token: String,
+
+        /// Origin allowed to make cross-origin requests (repeatable; replaces the defaults)
+        #[arg(long = "cors-origin")]
+        cors_origins: Vec<String>,
+
+        /// Bearer token required on POST /ingest
+        #[arg(long)]
+        ingest_token: Option<String>,
+
+        /// POST /ingest requests allowed per minute, shared by all local clients (0 disables the limit)
+        #[arg(long, default_value_t = synheart_sensor_agent::server::DEFAULT_MAX_INGEST_RPM)]
+        max_ingest_rpm: u32,
     },
 
     /// Pause data collection
@@ -96,6 +156,13 @@ enum Commands {
     /// Show current collection status
     Status,
 
+    /// Show the latest window's features in a live terminal dashboard
+    Watch {
+        /// Seconds between refreshes
+        #[arg(long, default_value = "2")]
+        interval_secs: u64,
+    },
+
     /// Display privacy declaration
     Privacy,
 
@@ -105,13 +172,124 @@ enum Commands {
         #[arg(long, short)]
         output: Option<PathBuf>,
 
-        /// Export format (json or jsonl)
+        /// Export format (json, jsonl, csv, proto or flat-json)
         #[arg(long, default_value = "json")]
         format: String,
+
+        /// Only export snapshots observed at or after this ISO 8601 date or time
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only export snapshots observed before this ISO 8601 date or time
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Export one aggregate snapshot per day, hour or session instead of
+        /// every window
+        #[arg(long, value_name = "day|hour|session")]
+        aggregate_by: Option<String>,
+    },
+
+    /// List recorded sessions
+    Sessions {
+        /// Emit the listing as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the contents of a session file
+    Inspect {
+        /// Session file (absolute, or relative to the export directory)
+        file: PathBuf,
+
+        /// Show the full snapshot at this index instead of the summary
+        #[arg(long)]
+        window_index: Option<usize>,
+
+        /// Output format (text or csv)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Delete a session file
+    Delete {
+        /// Session file (absolute, or relative to the export directory)
+        file: PathBuf,
+
+        /// Delete without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Merge several session files into one, in chronological order
+    Merge {
+        /// Session files to merge
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output file (`.jsonl` for JSON Lines, `.gz` suffix to compress)
+        #[arg(long, short)]
+        output: PathBuf,
+
+        /// Drop snapshots whose window (ID, label and resolution) was already seen
+        #[arg(long)]
+        deduplicate: bool,
+    },
+
+    /// Recompute features for a session file recorded with raw events
+    Replay {
+        /// Session file (absolute, or relative to the export directory)
+        file: PathBuf,
+
+        /// Output file (defaults to `<session>_replayed.json` in the export directory)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check a session file for HSI 1.0 conformance
+    Validate {
+        /// Session file (absolute, or relative to the export directory)
+        file: PathBuf,
+    },
+
+    /// Delete session files older than the retention period
+    Prune {
+        /// Retention period in days (defaults to `retention_days` from the config)
+        #[arg(long)]
+        days: Option<u64>,
+    },
+
+    /// Run the agent at login (LaunchAgent, systemd user unit or scheduled task)
+    InstallService {
+        /// Remove the service instead of installing it
+        #[arg(long)]
+        uninstall: bool,
+
+        /// Consent to collection without the interactive prompt
+        #[arg(long)]
+        yes_i_consent: bool,
+    },
+
+    /// Show or change configuration
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigCommand>,
     },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Change one setting in the config file
+    Set(ConfigSet),
+}
 
-    /// Show configuration
-    Config,
+#[derive(Args)]
+struct ConfigSet {
+    /// Setting in dot notation (e.g. `sources.keyboard`, `window_duration`)
+    key: String,
+
+    /// New value (booleans accept true/false/1/0; `null` clears optional settings)
+    value: String,
 }
 
 fn main() {
@@ -126,7 +304,15 @@ fn main() {
             gateway,
             gateway_port,
             gateway_token,
+            gateway_config_dir,
+            gateway_host,
+            gateway_tls,
+            gateway_ca_cert,
             sync_interval,
+            gateway_transport,
+            gateway_content_type,
+            transparency_port,
+            yes_i_consent,
         } => {
             cmd_start(
                 &sources,
@@ -136,7 +322,15 @@ fn main() {
                 gateway,
                 gateway_port,
                 gateway_token,
+                gateway_config_dir,
+                gateway_host,
+                gateway_tls,
+                gateway_ca_cert,
                 sync_interval,
+                &gateway_transport,
+                &gateway_content_type,
+                transparency_port,
+                yes_i_consent,
             );
         }
         #[cfg(feature = "server")]
@@ -145,8 +339,19 @@ fn main() {
             gateway_host,
             gateway_port,
             gateway_token,
+            cors_origins,
+            ingest_token,
+            max_ingest_rpm,
         } => {
-            cmd_serve(port, &gateway_host, gateway_port, &gateway_token);
+            cmd_serve(
+                port,
+                &gateway_host,
+                gateway_port,
+                &gateway_token,
+                cors_origins,
+                ingest_token,
+                max_ingest_rpm,
+            );
         }
         Commands::Pause => {
             cmd_pause();
@@ -157,15 +362,70 @@ fn main() {
         Commands::Status => {
             cmd_status();
         }
+        Commands::Watch { interval_secs } => {
+            cmd_watch(interval_secs);
+        }
         Commands::Privacy => {
             cmd_privacy();
         }
-        Commands::Export { output, format } => {
-            cmd_export(output, &format);
+        Commands::Export {
+            output,
+            format,
+            since,
+            until,
+            aggregate_by,
+        } => {
+            cmd_export(
+                output,
+                &format,
+                since.as_deref(),
+                until.as_deref(),
+                aggregate_by.as_deref(),
+            );
+        }
+        Commands::Sessions { json } => {
+            cmd_sessions(json);
         }
-        Commands::Config => {
+        Commands::Inspect {
+            file,
+            window_index,
+            format,
+        } => {
+            cmd_inspect(&file, window_index, &format);
+        }
+        Commands::Delete { file, yes } => {
+            cmd_delete(&file, yes);
+        }
+        Commands::Merge {
+            inputs,
+            output,
+            deduplicate,
+        } => {
+            cmd_merge(&inputs, &output, deduplicate);
+        }
+        Commands::Replay { file, output } => {
+            cmd_replay(&file, output.as_deref());
+        }
+        Commands::Validate { file } => {
+            cmd_validate(&file);
+        }
+        Commands::Prune { days } => {
+            cmd_prune(days);
+        }
+        Commands::InstallService {
+            uninstall,
+            yes_i_consent,
+        } => {
+            cmd_install_service(uninstall, yes_i_consent);
+        }
+        Commands::Config { action: None } => {
             cmd_config();
         }
+        Commands::Config {
+            action: Some(ConfigCommand::Set(ConfigSet { key, value })),
+        } => {
+            cmd_config_set(&key, &value);
+        }
     }
 }
 
@@ -173,17 +433,28 @@ fn main() {
 #[allow(clippy::too_many_arguments)]
 fn cmd_start(
     sources: &str,
-    _foreground: bool,
+    foreground: bool,
     enable_flux: bool,
-    baseline_window: usize,
+    baseline_window: Option<usize>,
     enable_gateway: bool,
     gateway_port: Option<u16>,
     gateway_token: Option<String>,
+    gateway_config_dir: Option<PathBuf>,
+    gateway_host: Option<String>,
+    gateway_tls: bool,
+    gateway_ca_cert: Option<PathBuf>,
     sync_interval: u64,
+    gateway_transport: &str,
+    gateway_content_type: &str,
+    transparency_port: Option<u16>,
+    yes_i_consent: bool,
 ) {
     println!("Synheart Sensor Agent v{VERSION}");
     println!();
 
+    // Collection requires recorded consent, asked for on first run
+    let consent_token = obtain_consent(yes_i_consent);
+
     // Check for Input Monitoring permission
     if !check_permission() {
         eprintln!("Error: Input Monitoring permission not granted.");
@@ -204,9 +475,31 @@ fn cmd_start(
     }
 
     // Load or create configuration
-    let config = Config::load().unwrap_or_default();
-    if let Err(e) = config.ensure_directories() {
-        eprintln!("Warning: Could not create directories: {e}");
+    let mut config = Config::load().unwrap_or_default();
+    let baseline_window = baseline_window.unwrap_or(config.flux.baseline_window);
+    let session_id = format!("SESS-{}", Utc::now().timestamp_millis());
+    let session_data_path = match config.ensure_session_directories(&session_id) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Warning: Could not create directories: {e}");
+            config.session_data_path(&session_id)
+        }
+    };
+
+    // Apply the data retention policy to previous sessions
+    if let Some(retention_days) = config.retention_days {
+        match retention::enforce_retention_policy(
+            &config.export_path,
+            retention_days,
+            &FileNamePattern::new(&config.export_filename_pattern),
+        ) {
+            Ok(deleted) if !deleted.is_empty() => println!(
+                "Deleted {} session file(s) older than {retention_days} days",
+                deleted.len()
+            ),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: Could not apply retention policy: {e}"),
+        }
     }
 
     println!("Starting collection...");
@@ -227,6 +520,9 @@ fn cmd_start(
         }
     );
     println!("  Window duration: {}s", config.window_duration.as_secs());
+    if config.session_scoped_data {
+        println!("  Session data: {session_data_path:?}");
+    }
 
     // Show flux status
     #[cfg(feature = "flux")]
@@ -242,24 +538,28 @@ fn cmd_start(
 
     // Show gateway status
     #[cfg(feature = "gateway")]
-    let gateway_client = if enable_gateway {
-        match create_gateway_client(gateway_port, gateway_token) {
-            Ok(client) => {
-                println!("  Gateway sync: enabled (interval: {sync_interval}s)");
-                println!("  Device ID: {}", client.device_id());
-
-                // Test connection
-                match client.test_connection() {
-                    Ok(true) => println!("  Gateway connection: OK"),
-                    Ok(false) => {
-                        eprintln!("Warning: Gateway health check failed");
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Could not connect to gateway: {e}");
-                    }
+    let gateway_config = if enable_gateway {
+        // CLI flags override the config file's gateway section
+        let connection = synheart_sensor_agent::GatewayConnectionConfig {
+            host: gateway_host.or_else(|| config.gateway.host.clone()),
+            tls: gateway_tls || config.gateway.tls,
+            ca_cert_path: gateway_ca_cert.or_else(|| config.gateway.ca_cert_path.clone()),
+        };
+        match create_gateway_config(
+            gateway_port,
+            gateway_token,
+            gateway_config_dir.as_deref(),
+            &connection,
+            gateway_transport,
+            gateway_content_type,
+        ) {
+            Ok(gateway_config) => match load_or_create_device_id(&config.device_id_path()) {
+                Ok(device_id) => Some(gateway_config.with_device_id(device_id)),
+                Err(e) => {
+                    eprintln!("Warning: {e}; using a new device ID for this run");
+                    Some(gateway_config)
                 }
-                Some(client)
-            }
+            },
             Err(e) => {
                 eprintln!("Warning: Gateway initialization failed: {e}");
                 eprintln!("Continuing without gateway sync.");
@@ -271,6 +571,65 @@ fn cmd_start(
         None
     };
 
+    // WebSocket transport streams each snapshot as soon as it completes
+    #[cfg(feature = "gateway")]
+    let ws_client = match gateway_config {
+        Some(ref gateway_config) if gateway_config.transport == GatewayTransport::WebSocket => {
+            match WsGatewayClient::connect_with_queue(
+                gateway_config.clone(),
+                open_pending_queue(&config),
+            ) {
+                Ok(client) => {
+                    println!(
+                        "  Gateway streaming: enabled ({})",
+                        gateway_config.stream_url()
+                    );
+                    Some(client)
+                }
+                Err(e) => {
+                    eprintln!("Warning: Gateway initialization failed: {e}");
+                    eprintln!("Continuing without gateway sync.");
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // HTTP transport batches snapshots every sync interval
+    #[cfg(feature = "gateway")]
+    let mut gateway_client = match gateway_config {
+        Some(gateway_config) if gateway_config.transport == GatewayTransport::Http => {
+            match BlockingGatewayClient::new(gateway_config) {
+                Ok(client) => {
+                    println!("  Gateway sync: enabled (interval: {sync_interval}s)");
+                    println!("  Device ID: {}", client.device_id());
+
+                    // Persist unsent snapshots so they survive outages and restarts
+                    let client = client.with_queue(open_pending_queue(&config));
+
+                    // Test connection
+                    match client.test_connection() {
+                        Ok(true) => println!("  Gateway connection: OK"),
+                        Ok(false) => {
+                            eprintln!("Warning: Gateway health check failed");
+                        }
+                        Err(e) => {
+                            eprintln!("Warning: Could not connect to gateway: {e}");
+                        }
+                    }
+                    Some(client)
+                }
+                Err(e) => {
+                    eprintln!("Warning: Gateway initialization failed: {e}");
+                    eprintln!("Continuing without gateway sync.");
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
     #[cfg(not(feature = "gateway"))]
     if enable_gateway {
         eprintln!("Warning: --gateway flag ignored (gateway feature not enabled at compile time)");
@@ -282,38 +641,87 @@ fn cmd_start(
 
     // Set up transparency log
     let transparency_log =
-        create_shared_log_with_persistence(config.data_path.join("transparency.json"));
+        create_shared_log_with_persistence(config.transparency_log_path(&session_id));
+
+    // Serve live transparency stats for auditing while collection runs
+    #[cfg(feature = "transparency-server")]
+    if let Some(port) = transparency_port {
+        let log = transparency_log.clone();
+        thread::spawn(move || {
+            if let Err(e) = synheart_sensor_agent::transparency::serve_transparency(log, port) {
+                eprintln!("Warning: Transparency server stopped: {e}");
+            }
+        });
+        println!("Transparency stats: http://127.0.0.1:{port}/transparency");
+    }
+    #[cfg(not(feature = "transparency-server"))]
+    if transparency_port.is_some() {
+        eprintln!(
+            "Warning: --transparency-port ignored (transparency-server feature not enabled at compile time)"
+        );
+    }
 
     // Create collector
     let collector_config = CollectorConfig {
         capture_keyboard: source_config.keyboard,
         capture_mouse: source_config.mouse,
+        capture_touch: source_config.touch,
+        ..CollectorConfig::default()
     };
     let mut collector = Collector::new(collector_config);
 
     // Create window manager
-    let mut window_manager = WindowManager::new(
-        config.window_duration.as_secs(),
-        config.session_gap_threshold_secs,
-    );
+    let mut window_manager = create_window_manager(&config);
+
+    // Feature computation parameters
+    let feature_config = config.feature_config.clone();
 
     // Create HSI builder
-    let hsi_builder = HsiBuilder::new();
+    let hsi_builder = HsiBuilder::new()
+        .with_session_id(session_id.clone())
+        .with_feature_config(feature_config.clone())
+        .with_consent_token(consent_token)
+        .with_raw_events(config.include_raw_events_in_export)
+        .with_enabled_sources(source_config.keyboard, source_config.mouse);
+    let mut hsi_builder = configure_snapshot_privacy(hsi_builder, &config);
     println!("Instance ID: {}", hsi_builder.instance_id());
 
+    // Track deep focus streaks and feature changes across consecutive windows
+    let mut focus_tracker = DeepFocusTracker::new();
+    let mut delta_tracker = FeatureDeltaTracker::new();
+    let mut typing_detector = TypingSessionDetector::default();
+    let mut latest_window: Option<WindowReadout> = None;
+
     // Storage for completed snapshots
     let mut snapshots: Vec<HsiSnapshot> = Vec::new();
 
+    // Persist snapshots to a database instead of session files when configured
+    let snapshot_store = open_snapshot_store(&config);
+
+    // Append snapshots to disk as they complete when streaming export is enabled
+    let mut export_writer = if config.streaming_export && snapshot_store.is_none() {
+        // Named when the session starts, before any snapshot is written
+        let stem = session_file_stem(&config, &hsi_builder, &session_id, 0);
+        let path = config
+            .export_path
+            .join(export::export_file_name(&stem, "jsonl", false));
+        println!("Streaming snapshots to {path:?}");
+        Some(export::ExportWriter::new(path))
+    } else {
+        None
+    };
+
     // Initialize flux processor if enabled
     #[cfg(feature = "flux")]
     let mut flux_processor = if enable_flux {
+        // Enriches the snapshots built below rather than building its own
         let mut processor = synheart_sensor_agent::flux::SensorFluxProcessor::new(baseline_window);
 
-        // Try to load existing baselines
-        let baselines_path = config.data_path.join("flux_baselines.json");
+        // Try to load existing baselines and session counter
+        let baselines_path = session_data_path.join("flux_baselines.json");
         if baselines_path.exists() {
-            if let Ok(baselines_json) = std::fs::read_to_string(&baselines_path) {
-                match processor.load_baselines(&baselines_json) {
+            if let Ok(state_json) = std::fs::read_to_string(&baselines_path) {
+                match processor.load_state(&state_json) {
                     Ok(_) => println!("Loaded existing baselines from {baselines_path:?}"),
                     Err(e) => eprintln!("Warning: Could not load baselines: {e}"),
                 }
@@ -325,19 +733,31 @@ fn cmd_start(
         None
     };
 
-    // Storage for enriched snapshots (when flux is enabled)
+    // Flux output of each window in `snapshots`, attached to the snapshots
+    // when the session ends so each snapshot is only held once
     #[cfg(feature = "flux")]
-    let mut enriched_snapshots: Vec<synheart_sensor_agent::flux::EnrichedSnapshot> = Vec::new();
+    let mut window_fluxes: Vec<Option<synheart_sensor_agent::flux::WindowFlux>> = Vec::new();
 
     // Set up Ctrl+C handler
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
     ctrlc_handler(r);
 
-    // Support pause/resume from another process by polling the config file.
+    // Support pause/resume from another process over the control socket.
     // If paused at startup, wait until resumed before starting the collector.
     let mut paused = config.paused;
-    let mut last_config_check = std::time::Instant::now();
+    let ipc_server = match IpcServer::bind(&ipc::endpoint(&config)) {
+        Ok(server) => Some(server),
+        Err(IpcError::AlreadyRunning) => {
+            eprintln!("Error: Another agent is already running.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Warning: Could not open control socket: {e}");
+            eprintln!("`synheart-sensor pause/resume` will not reach this agent.");
+            None
+        }
+    };
 
     if paused {
         println!("Collection is currently paused.");
@@ -350,45 +770,148 @@ fn cmd_start(
 
     // Gateway sync state
     #[cfg(feature = "gateway")]
-    let mut pending_sync_snapshots: Vec<HsiSnapshot> = Vec::new();
-    #[cfg(feature = "gateway")]
     let mut last_gateway_sync = std::time::Instant::now();
-    #[cfg(feature = "gateway")]
-    let session_id = format!("SESS-{}", Utc::now().timestamp_millis());
+
+    // In the foreground, SIGHUP reloads the config without a restart
+    let reload_signal = if foreground {
+        match ReloadSignal::install() {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                eprintln!("Warning: Could not install SIGHUP handler: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     // Main event loop
-    let receiver = collector.receiver().clone();
+    let mut receiver = collector.receiver().clone();
     let mut last_window_check = std::time::Instant::now();
 
     while running.load(Ordering::SeqCst) {
-        // Periodically reload config so `synheart-sensor pause/resume` can control a running agent.
-        if last_config_check.elapsed() >= Duration::from_secs(1) {
-            if let Ok(cfg) = Config::load() {
-                if cfg.paused != paused {
-                    paused = cfg.paused;
-
-                    if paused {
-                        println!();
-                        println!("Pausing collection...");
-                        collector.stop();
+        // Apply commands from `synheart-sensor pause/resume/status`
+        while let Some(request) = ipc_server.as_ref().and_then(IpcServer::try_recv) {
+            let requested = match request.command {
+                IpcCommand::Pause => Some(true),
+                IpcCommand::Resume => Some(false),
+                IpcCommand::Status => None,
+            };
+
+            if let Some(requested) = requested.filter(|&p| p != paused) {
+                paused = requested;
+
+                if paused {
+                    println!();
+                    println!("Pausing collection...");
+                    collector.stop();
+
+                    // Flush any in-progress window and drop partial data.
+                    window_manager.flush();
+                    let _ = window_manager.take_completed_windows();
+
+                    // Drain any queued events.
+                    while receiver.try_recv().is_ok() {}
+                } else {
+                    println!();
+                    println!("Resuming collection...");
+                    if let Err(e) = collector.start() {
+                        eprintln!("Error resuming collector: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
 
-                        // Flush any in-progress window and drop partial data.
-                        window_manager.flush();
-                        let _ = window_manager.take_completed_windows();
+            let typing_session_secs = typing_detector
+                .current_session()
+                .map(|session| session.duration().num_seconds().max(0) as u64);
+            #[allow(unused_mut)]
+            let mut response = IpcResponse::status(paused)
+                .with_typing_session_secs(typing_session_secs)
+                .with_last_event_at(transparency_log.stats().last_event_at())
+                .with_latest_window(latest_window.clone());
+            #[cfg(feature = "flux")]
+            if let Some(ref processor) = flux_processor {
+                response = response
+                    .with_baseline(processor.baseline_sessions(), processor.baseline_window());
+            }
+            request.respond(response);
+        }
 
-                        // Drain any queued events.
-                        while receiver.try_recv().is_ok() {}
+        if reload_signal.as_ref().is_some_and(ReloadSignal::take) {
+            match Config::load() {
+                Ok(reloaded) => {
+                    let changes = reload::apply_config(&mut config, &reloaded, &mut window_manager);
+                    println!();
+                    if changes.is_empty() {
+                        println!("Configuration reloaded (no changes applied)");
                     } else {
-                        println!();
-                        println!("Resuming collection...");
-                        if let Err(e) = collector.start() {
-                            eprintln!("Error resuming collector: {e}");
-                            std::process::exit(1);
+                        println!("Configuration reloaded:");
+                    }
+                    if changes.window_duration {
+                        println!("  Window duration: {}s", config.window_duration.as_secs());
+                    }
+                    if changes.session_gap_threshold {
+                        println!(
+                            "  Session gap threshold: {}s",
+                            config.session_gap_threshold_secs
+                        );
+                    }
+                    if changes.compress_exports {
+                        println!("  Compress exports: {}", config.compress_exports);
+                    }
+                    if changes.flux_baseline_window {
+                        println!(
+                            "  Flux baseline window: {} sessions",
+                            config.flux.baseline_window
+                        );
+                        #[cfg(feature = "flux")]
+                        if let Some(ref mut processor) = flux_processor {
+                            if let Err(e) =
+                                processor.resize_baseline_window(config.flux.baseline_window)
+                            {
+                                eprintln!("Warning: Could not resize flux baseline window: {e}");
+                            }
+                        }
+                    }
+                    if changes.sources {
+                        println!(
+                            "  Sources: keyboard {}, mouse {}",
+                            config.sources.keyboard, config.sources.mouse
+                        );
+
+                        // Hand events already captured to the windows before switching
+                        collector.stop();
+                        while let Ok(event) = receiver.try_recv() {
+                            match *event {
+                                SensorEvent::Keyboard(_) => {
+                                    transparency_log.record_keyboard_event()
+                                }
+                                SensorEvent::Mouse(_) => transparency_log.record_mouse_event(),
+                                SensorEvent::Touch(_) => {}
+                            }
+                            window_manager.process_event(event);
+                        }
+
+                        collector = Collector::new(CollectorConfig {
+                            capture_keyboard: config.sources.keyboard,
+                            capture_mouse: config.sources.mouse,
+                            capture_touch: config.sources.touch,
+                            ..CollectorConfig::default()
+                        });
+                        receiver = collector.receiver().clone();
+                        hsi_builder = hsi_builder
+                            .with_enabled_sources(config.sources.keyboard, config.sources.mouse);
+                        if !paused {
+                            if let Err(e) = collector.start() {
+                                eprintln!("Error restarting collector: {e}");
+                                std::process::exit(1);
+                            }
                         }
                     }
                 }
+                Err(e) => eprintln!("Warning: Could not reload config: {e}"),
             }
-            last_config_check = std::time::Instant::now();
         }
 
         if paused {
@@ -400,9 +923,10 @@ fn cmd_start(
         match receiver.recv_timeout(Duration::from_millis(100)) {
             Ok(event) => {
                 // Update transparency log
-                match &event {
+                match *event {
                     SensorEvent::Keyboard(_) => transparency_log.record_keyboard_event(),
                     SensorEvent::Mouse(_) => transparency_log.record_mouse_event(),
+                    SensorEvent::Touch(_) => {}
                 }
 
                 // Add to window
@@ -421,19 +945,50 @@ fn cmd_start(
             }
         }
 
+        // Account for events lost to a full event buffer
+        let dropped = collector.take_dropped_events();
+        if dropped > 0 {
+            transparency_log.record_events_dropped(dropped);
+        }
+        // Out-of-order events whose clock-aligned window had already completed
+        let late = window_manager.take_late_events();
+        if late > 0 {
+            eprintln!("Dropped {late} late event(s) whose window had already completed");
+            transparency_log.record_late_events_dropped(late);
+        }
+        for restarted_at in collector.take_restarts() {
+            println!("Collector restarted at {}", restarted_at.to_rfc3339());
+            transparency_log.record_collector_restart(restarted_at);
+        }
+
         // Process completed windows
-        for window in window_manager.take_completed_windows() {
-            let features = compute_features(&window);
-            let snapshot = hsi_builder.build(&window, &features);
+        let completed = window_manager.take_completed_windows();
+        let completed_features = compute_window_features(&completed, &feature_config);
+        for ((resolution, window), features) in completed.into_iter().zip(completed_features) {
+            let delta = delta_tracker.observe(&window, &features);
+            if let Some(session) = typing_detector.observe(&window, &features) {
+                print_typing_session(&session);
+            }
+            latest_window = Some(WindowReadout::from_features(&window, &features));
+            #[allow(unused_mut)]
+            let mut snapshot = hsi_builder.build_with_focus_tracker(
+                &window,
+                &features,
+                resolution,
+                Some(&delta),
+                &mut focus_tracker,
+            );
 
             transparency_log.record_window_completed();
 
             // Process with flux if enabled
             #[cfg(feature = "flux")]
+            let mut window_flux = None;
+            #[cfg(feature = "flux")]
             if let Some(ref mut processor) = flux_processor {
-                match processor.process_window(&window, &features, snapshot.clone()) {
-                    Ok(enriched) => {
-                        let baseline_info = if let Some(ref baseline) = enriched.baseline {
+                match processor.process_snapshot(&window, &mut snapshot) {
+                    Ok(flux) => {
+                        let baseline_info = if let Some(ref baseline) = flux.baseline {
                             format!(
                                 " | baseline: {} sessions, dev: {:.1}%",
                                 baseline.sessions_in_baseline,
@@ -443,10 +998,10 @@ fn cmd_start(
                             String::new()
                         };
 
-                        let flux_info = if let Some(ref flux) = enriched.flux_behavior {
+                        let flux_info = if let Some(ref metrics) = flux.flux_behavior {
                             format!(
                                 " | distraction: {:.2}, focus: {:.2}",
-                                flux.distraction_score, flux.focus_hint
+                                metrics.distraction_score, metrics.focus_hint
                             )
                         } else {
                             String::new()
@@ -460,7 +1015,7 @@ fn cmd_start(
                             flux_info,
                             baseline_info
                         );
-                        enriched_snapshots.push(enriched);
+                        window_flux = Some(flux);
                     }
                     Err(e) => {
                         eprintln!("Warning: Flux processing failed: {e}");
@@ -489,38 +1044,56 @@ fn cmd_start(
                 window.mouse_events.len()
             );
 
-            snapshots.push(snapshot.clone());
+            if let Some(ref mut writer) = export_writer {
+                match writer.append(&snapshot) {
+                    Ok(()) => transparency_log.record_snapshot_exported(),
+                    Err(e) => eprintln!("Error streaming snapshot: {e}"),
+                }
+            }
+            if let Some(ref store) = snapshot_store {
+                match store.insert(&snapshot) {
+                    Ok(()) => transparency_log.record_snapshot_exported(),
+                    Err(e) => eprintln!("Error storing snapshot: {e}"),
+                }
+            }
 
             // Add to gateway sync buffer
             #[cfg(feature = "gateway")]
-            if gateway_client.is_some() {
-                pending_sync_snapshots.push(snapshot);
+            if let Some(ref mut client) = gateway_client {
+                if let Err(e) = client.enqueue(snapshot.clone()) {
+                    eprintln!("[Gateway] Could not queue snapshot: {e}");
+                }
+            }
+            #[cfg(feature = "gateway")]
+            if let Some(ref client) = ws_client {
+                if let Err(e) = client.send(snapshot.clone()) {
+                    eprintln!("[Gateway] Could not stream snapshot: {e}");
+                }
             }
+
+            #[cfg(feature = "flux")]
+            window_fluxes.push(window_flux);
+            snapshots.push(snapshot);
         }
 
         // Sync to gateway if enabled and interval has passed
         #[cfg(feature = "gateway")]
-        if let Some(ref client) = gateway_client {
+        if let Some(ref mut client) = gateway_client {
             if last_gateway_sync.elapsed() >= Duration::from_secs(sync_interval)
-                && !pending_sync_snapshots.is_empty()
+                && client.pending_count() > 0
             {
-                match client.sync_snapshots(&pending_sync_snapshots, &session_id) {
+                let pending_count = client.pending_count();
+                match client.sync_pending(&session_id) {
                     Ok(response) => {
+                        transparency_log.record_gateway_sync(pending_count, true);
                         if let Some(state) = response.state {
-                            println!(
-                                "[Gateway] Synced {} snapshots | HSI: {}",
-                                pending_sync_snapshots.len(),
-                                state
-                            );
+                            println!("[Gateway] Synced {pending_count} snapshots | HSI: {state}");
                         } else {
-                            println!(
-                                "[Gateway] Synced {} snapshots",
-                                pending_sync_snapshots.len()
-                            );
+                            println!("[Gateway] Synced {pending_count} snapshots");
                         }
-                        pending_sync_snapshots.clear();
                     }
                     Err(e) => {
+                        transparency_log.record_gateway_sync(pending_count, false);
                         eprintln!("[Gateway] Sync failed: {e}");
                         // Keep snapshots for retry
                     }
@@ -532,14 +1105,13 @@ fn cmd_start(
 
     // Final gateway sync before exit
     #[cfg(feature = "gateway")]
-    if let Some(ref client) = gateway_client {
-        if !pending_sync_snapshots.is_empty() {
-            println!(
-                "Syncing remaining {} snapshots to gateway...",
-                pending_sync_snapshots.len()
-            );
-            match client.sync_snapshots(&pending_sync_snapshots, &session_id) {
+    if let Some(ref mut client) = gateway_client {
+        if client.pending_count() > 0 {
+            let pending_count = client.pending_count();
+            println!("Syncing remaining {pending_count} snapshots to gateway...");
+            match client.sync_pending(&session_id) {
                 Ok(response) => {
+                    transparency_log.record_gateway_sync(pending_count, true);
                     if let Some(state) = response.state {
                         println!("[Gateway] Final sync complete | HSI: {state}");
                     } else {
@@ -547,11 +1119,16 @@ fn cmd_start(
                     }
                 }
                 Err(e) => {
+                    transparency_log.record_gateway_sync(pending_count, false);
                     eprintln!("[Gateway] Final sync failed: {e}");
                 }
             }
         }
     }
+    #[cfg(feature = "gateway")]
+    if let Some(client) = ws_client {
+        client.close();
+    }
 
     // Stop collection
     println!();
@@ -560,57 +1137,117 @@ fn cmd_start(
 
     // Flush remaining window
     window_manager.flush();
-    for window in window_manager.take_completed_windows() {
-        let features = compute_features(&window);
-        let snapshot = hsi_builder.build(&window, &features);
+    let completed = window_manager.take_completed_windows();
+    let completed_features = compute_window_features(&completed, &feature_config);
+    for ((resolution, window), features) in completed.into_iter().zip(completed_features) {
+        let delta = delta_tracker.observe(&window, &features);
+        if let Some(session) = typing_detector.observe(&window, &features) {
+            print_typing_session(&session);
+        }
+        let snapshot = hsi_builder.build_with_focus_tracker(
+            &window,
+            &features,
+            resolution,
+            Some(&delta),
+            &mut focus_tracker,
+        );
         transparency_log.record_window_completed();
+        if let Some(ref mut writer) = export_writer {
+            match writer.append(&snapshot) {
+                Ok(()) => transparency_log.record_snapshot_exported(),
+                Err(e) => eprintln!("Error streaming snapshot: {e}"),
+            }
+        }
+        if let Some(ref store) = snapshot_store {
+            match store.insert(&snapshot) {
+                Ok(()) => transparency_log.record_snapshot_exported(),
+                Err(e) => eprintln!("Error storing snapshot: {e}"),
+            }
+        }
         snapshots.push(snapshot);
     }
+    if let Some(session) = typing_detector.finish() {
+        print_typing_session(&session);
+    }
 
     // Save transparency log
     if let Err(e) = transparency_log.save() {
         eprintln!("Warning: Could not save transparency log: {e}");
     }
 
+    // Finish the streamed session file; the bulk write below is skipped
+    if let Some(ref mut writer) = export_writer {
+        if !snapshots.is_empty() {
+            let summary = hsi_builder.build_session_summary(snapshots.clone());
+            match writer.append(&summary) {
+                Ok(()) => transparency_log.record_snapshot_exported(),
+                Err(e) => eprintln!("Error streaming session summary: {e}"),
+            }
+            println!(
+                "Streamed {} snapshots to {:?}",
+                writer.lines_written(),
+                writer.path()
+            );
+        }
+    }
+
+    // Add the session summary to the database; the bulk write below is skipped
+    if let Some(ref store) = snapshot_store {
+        if !snapshots.is_empty() {
+            let summary = hsi_builder.build_session_summary(snapshots.clone());
+            match store.insert(&summary) {
+                Ok(()) => transparency_log.record_snapshot_exported(),
+                Err(e) => eprintln!("Error storing session summary: {e}"),
+            }
+            println!(
+                "Stored {} snapshots in {:?}",
+                snapshots.len() + 1,
+                config.snapshot_db_path()
+            );
+        }
+    }
+
     // Export snapshots
-    if !snapshots.is_empty() {
-        let export_path = config.export_path.join(format!(
-            "session_{}.json",
-            Utc::now().format("%Y%m%d_%H%M%S")
+    if export_writer.is_none() && snapshot_store.is_none() && !snapshots.is_empty() {
+        // Append a rolled-up view of the whole session
+        snapshots.push(hsi_builder.build_session_summary(snapshots.clone()));
+
+        let stem = session_file_stem(&config, &hsi_builder, &session_id, snapshots.len());
+        let export_path = config.export_path.join(export::export_file_name(
+            &stem,
+            "json",
+            config.compress_exports,
         ));
 
         if let Some(parent) = export_path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
 
-        match serde_json::to_string_pretty(&snapshots) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&export_path, json) {
-                    eprintln!("Error writing snapshots: {e}");
-                } else {
-                    println!(
-                        "Exported {} snapshots to {:?}",
-                        snapshots.len(),
-                        export_path
-                    );
-                    for _ in &snapshots {
-                        transparency_log.record_snapshot_exported();
-                    }
+        match export::write_snapshots(&export_path, &snapshots, config.compress_exports) {
+            Ok(()) => {
+                println!(
+                    "Exported {} snapshots to {:?}",
+                    snapshots.len(),
+                    export_path
+                );
+                for _ in &snapshots {
+                    transparency_log.record_snapshot_exported();
                 }
             }
             Err(e) => {
-                eprintln!("Error serializing snapshots: {e}");
+                eprintln!("Error writing snapshots: {e}");
             }
         }
     }
 
     // Export enriched snapshots if flux was enabled
     #[cfg(feature = "flux")]
+    let enriched_snapshots =
+        synheart_sensor_agent::flux::WindowFlux::attach_all(snapshots, window_fluxes);
+    #[cfg(feature = "flux")]
     if !enriched_snapshots.is_empty() {
-        let enriched_path = config.export_path.join(format!(
-            "session_{}_enriched.json",
-            Utc::now().format("%Y%m%d_%H%M%S")
-        ));
+        let stem = session_file_stem(&config, &hsi_builder, &session_id, enriched_snapshots.len());
+        let enriched_path = config.export_path.join(format!("{stem}_enriched.json"));
 
         if let Some(parent) = enriched_path.parent() {
             let _ = std::fs::create_dir_all(parent);
@@ -633,12 +1270,12 @@ fn cmd_start(
             }
         }
 
-        // Save baselines for next session
+        // Save baselines and session counter for next session
         if let Some(ref processor) = flux_processor {
-            let baselines_path = config.data_path.join("flux_baselines.json");
-            match processor.save_baselines() {
-                Ok(baselines_json) => {
-                    if let Err(e) = std::fs::write(&baselines_path, baselines_json) {
+            let baselines_path = session_data_path.join("flux_baselines.json");
+            match processor.save_state() {
+                Ok(state_json) => {
+                    if let Err(e) = std::fs::write(&baselines_path, state_json) {
                         eprintln!("Error saving baselines: {e}");
                     } else {
                         println!("Saved baselines to {baselines_path:?}");
@@ -658,7 +1295,15 @@ fn cmd_start(
 
 /// Start HTTP server for receiving behavioral data from Chrome extension
 #[cfg(feature = "server")]
-fn cmd_serve(port: u16, gateway_host: &str, gateway_port: u16, gateway_token: &str) {
+fn cmd_serve(
+    port: u16,
+    gateway_host: &str,
+    gateway_port: u16,
+    gateway_token: &str,
+    cors_origins: Vec<String>,
+    ingest_token: Option<String>,
+    max_ingest_rpm: u32,
+) {
     use synheart_sensor_agent::gateway::GatewayConfig;
     use synheart_sensor_agent::server::ServerConfig;
 
@@ -677,7 +1322,16 @@ fn cmd_serve(port: u16, gateway_host: &str, gateway_port: u16, gateway_token: &s
 
     // Create server config
     let gateway_config = GatewayConfig::new(gateway_host, gateway_port, gateway_token.to_string());
-    let server_config = ServerConfig::new(port, gateway_config, config.data_path.clone());
+    let mut server_config = ServerConfig::new(port, gateway_config, config.data_path.clone());
+    if !cors_origins.is_empty() {
+        server_config = server_config.with_cors_origins(cors_origins);
+    }
+    if let Some(token) = ingest_token {
+        println!("  Ingest authentication: Bearer token");
+        server_config = server_config.with_ingest_token(token);
+    }
+    server_config =
+        server_config.with_max_ingest_rpm((max_ingest_rpm > 0).then_some(max_ingest_rpm));
 
     // Set up runtime
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
@@ -723,40 +1377,217 @@ fn cmd_serve(port: u16, gateway_host: &str, gateway_port: u16, gateway_token: &s
 }
 
 fn cmd_pause() {
-    let mut config = Config::load().unwrap_or_default();
-    config.paused = true;
-    if let Err(e) = config.save() {
-        eprintln!("Error saving config: {e}");
-        std::process::exit(1);
+    if set_paused(true) {
+        println!("Collection paused. Use 'synheart-sensor resume' to continue.");
+    } else {
+        println!("Agent is not running; it will start paused.");
     }
-    println!("Collection paused. Use 'synheart-sensor resume' to continue.");
 }
 
 fn cmd_resume() {
-    let mut config = Config::load().unwrap_or_default();
-    config.paused = false;
-    if let Err(e) = config.save() {
-        eprintln!("Error saving config: {e}");
-        std::process::exit(1);
+    if set_paused(false) {
+        println!("Collection resumed.");
+    } else {
+        println!("Agent is not running; it will collect when started.");
     }
-    println!("Collection resumed.");
 }
 
-fn cmd_status() {
-    let config = Config::load().unwrap_or_default();
+/// Create the window manager configured by `config`.
+fn create_window_manager(config: &Config) -> WindowManager {
+    let mut window_manager = match config.adaptive_window {
+        Some(adaptive) => WindowManager::adaptive(
+            adaptive,
+            config.session_gap_threshold_secs,
+            config.min_events_per_window,
+        ),
+        None => WindowManager::new(
+            config.window_duration.as_secs(),
+            config.session_gap_threshold_secs,
+            config.min_events_per_window,
+        ),
+    };
+    window_manager.set_clock_aligned(config.clock_aligned_windows);
+    window_manager
+}
 
-    println!("Synheart Sensor Agent Status");
-    println!("============================");
-    println!();
+/// Render the configured file name pattern for this session's files.
+fn session_file_stem(
+    config: &Config,
+    hsi_builder: &HsiBuilder,
+    session_id: &str,
+    snapshot_count: usize,
+) -> String {
+    export::render_file_name_pattern(
+        &config.export_filename_pattern,
+        &export::FileNameVars {
+            at: Utc::now(),
+            instance_id: &hsi_builder.instance_id().to_string(),
+            session_id,
+            snapshot_count,
+        },
+    )
+}
 
-    // Check permission
-    let has_permission = check_permission();
-    println!(
-        "Input Monitoring Permission: {}",
-        if has_permission {
-            "Granted ✓"
-        } else {
-            "Not Granted ✗"
+/// Apply the configured snapshot signing and differential privacy to a builder.
+fn configure_snapshot_privacy(mut hsi_builder: HsiBuilder, config: &Config) -> HsiBuilder {
+    if let Some(ref key_path) = config.signing_key_path {
+        match SnapshotSigner::from_file(key_path) {
+            Ok(signer) => {
+                hsi_builder = hsi_builder.with_signer(signer);
+                println!("Snapshot signing: enabled");
+            }
+            Err(e) => eprintln!("Warning: Snapshot signing disabled ({key_path:?}): {e}"),
+        }
+    }
+    match config.differential_privacy {
+        Some(dp) if dp.is_valid() => {
+            hsi_builder = hsi_builder.with_differential_privacy(dp);
+            println!(
+                "Differential privacy: enabled (epsilon {}, noise scale {})",
+                dp.epsilon,
+                dp.scale()
+            );
+        }
+        Some(_) => eprintln!(
+            "Warning: Differential privacy disabled (epsilon and sensitivity must be positive)"
+        ),
+        None => {}
+    }
+    hsi_builder
+}
+
+/// Compute features for windows completed together, concurrently if configured.
+fn compute_window_features(
+    completed: &[(chrono::Duration, EventWindow)],
+    feature_config: &FeatureConfig,
+) -> Vec<WindowFeatures> {
+    if feature_config.parallel {
+        let windows: Vec<&EventWindow> = completed.iter().map(|(_, window)| window).collect();
+        compute_features_parallel(&windows, feature_config)
+    } else {
+        completed
+            .iter()
+            .map(|(_, window)| compute_features(window, feature_config))
+            .collect()
+    }
+}
+
+/// Report a completed typing session.
+fn print_typing_session(session: &TypingSession) {
+    let secs = session.duration().num_seconds();
+    println!(
+        "Typing session: {}m {:02}s ({} windows, {:.1} keys/s, continuity {:.2})",
+        secs / 60,
+        secs % 60,
+        session.window_count,
+        session.mean_typing_rate,
+        session.mean_session_continuity
+    );
+}
+
+/// Get the stored consent token, asking for consent first if none is recorded.
+///
+/// Exits if the user declines, or if there is no terminal to ask on (e.g.
+/// when started as a service) and `--yes-i-consent` was not passed.
+fn obtain_consent(yes_i_consent: bool) -> String {
+    let path = Config::consent_token_path();
+    if let Some(token) = consent::load_consent_token(&path) {
+        return token;
+    }
+
+    if !yes_i_consent && !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        eprintln!("Error: Consent to collection has not been recorded, and there is no terminal to ask on.");
+        eprintln!("Run `synheart-sensor start` interactively once, or pass --yes-i-consent.");
+        std::process::exit(1);
+    }
+
+    println!("{PRIVACY_DECLARATION}");
+    if !yes_i_consent {
+        print!("I understand and consent [y/N] ");
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err()
+            || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        {
+            eprintln!("Consent not given. Collection was not started.");
+            std::process::exit(1);
+        }
+    }
+
+    match consent::grant_consent(&path) {
+        Ok(token) => {
+            println!("Consent recorded in {path:?}");
+            println!();
+            token
+        }
+        Err(e) => {
+            eprintln!("Error: Could not record consent: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Pause or resume the running agent over its control socket.
+///
+/// When no agent is running the state is saved in the config file instead,
+/// so it applies to the next start. Returns whether a running agent was reached.
+fn set_paused(paused: bool) -> bool {
+    let config = Config::load().unwrap_or_default();
+    let command = if paused {
+        IpcCommand::Pause
+    } else {
+        IpcCommand::Resume
+    };
+
+    match ipc::send_command(&ipc::endpoint(&config), command) {
+        Ok(response) if response.ok => return true,
+        Ok(response) => {
+            eprintln!(
+                "Error: Agent rejected command: {}",
+                response.error.unwrap_or_default()
+            );
+            std::process::exit(1);
+        }
+        Err(IpcError::NotRunning) => {}
+        Err(e) => {
+            eprintln!("Error contacting agent: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    // Save from the file alone so environment overrides are not persisted
+    let mut config = Config::load_file().unwrap_or_default();
+    config.paused = paused;
+    if let Err(e) = config.save() {
+        eprintln!("Error saving config: {e}");
+        std::process::exit(1);
+    }
+    false
+}
+
+fn cmd_status() {
+    let config = Config::load().unwrap_or_default();
+
+    println!("Synheart Sensor Agent Status");
+    println!("============================");
+    println!();
+
+    // Check permission (consent and, on macOS, Input Monitoring)
+    let has_permission = check_permission();
+    println!(
+        "Collection Permission: {}",
+        if has_permission {
+            "Granted ✓"
+        } else {
+            "Not Granted ✗"
+        }
+    );
+    println!(
+        "Collection Consent: {}",
+        if consent::load_consent_token(&Config::consent_token_path()).is_some() {
+            "Given ✓"
+        } else {
+            "Not Given ✗ (asked on next start)"
         }
     );
     println!();
@@ -780,95 +1611,291 @@ fn cmd_status() {
         }
     );
     println!("  Window duration: {}s", config.window_duration.as_secs());
-    println!("  Paused: {}", config.paused);
+    match ipc::send_command(&ipc::endpoint(&config), IpcCommand::Status) {
+        Ok(response) => {
+            println!("  Agent: running");
+            println!("  Paused: {}", response.paused);
+            match response.typing_session_secs {
+                Some(secs) => println!("  Typing session: {}m {:02}s", secs / 60, secs % 60),
+                None => println!("  Typing session: none"),
+            }
+            match response.last_event_at {
+                Some(at) => println!("  Last event: {}", describe_elapsed(Utc::now() - at)),
+                None => println!("  Last event: none"),
+            }
+            if let (Some(sessions), Some(window)) =
+                (response.baseline_sessions, response.baseline_window)
+            {
+                if sessions as usize >= window {
+                    println!("  Flux baseline: ready");
+                } else {
+                    println!("  Flux baseline: warming up ({sessions}/{window} sessions)");
+                }
+            }
+        }
+        Err(_) => {
+            println!("  Agent: not running");
+            println!("  Paused: {}", config.paused);
+        }
+    }
     println!();
 
-    // Load and show transparency stats if available
-    let stats_path = config.data_path.join("transparency.json");
-    if stats_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&stats_path) {
-            if let Ok(stats) = serde_json::from_str::<serde_json::Value>(&content) {
-                println!("Cumulative Statistics:");
-                if let Some(kb) = stats.get("keyboard_events") {
-                    println!("  Keyboard events: {kb}");
-                }
-                if let Some(mouse) = stats.get("mouse_events") {
-                    println!("  Mouse events: {mouse}");
-                }
-                if let Some(windows) = stats.get("windows_completed") {
-                    println!("  Windows completed: {windows}");
-                }
-                if let Some(snapshots) = stats.get("snapshots_exported") {
-                    println!("  Snapshots exported: {snapshots}");
-                }
+    // Load and show transparency stats if available, summed across session logs
+    let mut totals: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+    for stats_path in config.transparency_log_paths() {
+        let Ok(content) = std::fs::read_to_string(&stats_path) else {
+            continue;
+        };
+        let Ok(serde_json::Value::Object(stats)) = serde_json::from_str(&content) else {
+            continue;
+        };
+        for (key, value) in stats {
+            if let Some(value) = value.as_u64() {
+                *totals.entry(key).or_default() += value;
             }
         }
-    } else {
+    }
+    if !totals.is_empty() {
+        println!("Cumulative Statistics:");
+        for (key, label) in [
+            ("keyboard_events", "Keyboard events"),
+            ("mouse_events", "Mouse events"),
+            ("windows_completed", "Windows completed"),
+            ("snapshots_exported", "Snapshots exported"),
+            ("snapshots_deleted", "Snapshots deleted"),
+            ("late_events_dropped", "Late events dropped"),
+        ] {
+            if let Some(count) = totals.get(key) {
+                println!("  {label}: {count}");
+            }
+        }
+        match totals.get("events_dropped") {
+            Some(&dropped) if dropped > 0 => {
+                println!("  ⚠ Events dropped: {dropped}");
+                println!("    Some windows are incomplete because the event buffer was full.");
+            }
+            Some(dropped) => println!("  Events dropped: {dropped}"),
+            None => {}
+        }
+    } else if !config.session_scoped_data {
         println!("No previous session data found.");
     }
+
+    // Session-scoped data directories
+    if config.session_scoped_data {
+        let session_dirs = config.session_directories();
+        if session_dirs.is_empty() {
+            println!("No session data directories found.");
+        } else {
+            println!("Session Data Directories ({}):", session_dirs.len());
+            for dir in session_dirs {
+                println!("  {}", dir.display());
+            }
+        }
+    }
+}
+
+fn cmd_watch(interval_secs: u64) {
+    let config = Config::load().unwrap_or_default();
+    let interval = Duration::from_secs(interval_secs.max(1));
+
+    loop {
+        let (source, readout) = latest_window_readout(&config);
+        print!(
+            "{}{}",
+            watch::CLEAR_SCREEN,
+            watch::render_dashboard(readout.as_ref(), &source, Utc::now())
+        );
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        thread::sleep(interval);
+    }
+}
+
+/// Get the latest window's features from the running agent, or else from the
+/// most recent streaming session file. Also returns where they came from.
+fn latest_window_readout(config: &Config) -> (String, Option<WindowReadout>) {
+    if let Ok(response) = ipc::send_command(&ipc::endpoint(config), IpcCommand::Status) {
+        return ("running agent".to_string(), response.latest_window);
+    }
+    let pattern = FileNamePattern::new(&config.export_filename_pattern);
+    match watch::latest_stream_file(&config.export_path, &pattern) {
+        Some(path) => match watch::read_latest(&path) {
+            Ok(readout) => (path.display().to_string(), readout),
+            Err(e) => (format!("{} ({e})", path.display()), None),
+        },
+        None => (
+            "agent not running and no streaming session file found".to_string(),
+            None,
+        ),
+    }
 }
 
 fn cmd_privacy() {
     println!("{PRIVACY_DECLARATION}");
 }
 
-fn cmd_export(output: Option<PathBuf>, format: &str) {
+fn cmd_export(
+    output: Option<PathBuf>,
+    format: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    aggregate_by: Option<&str>,
+) {
     let config = Config::load().unwrap_or_default();
     let export_dir = output.unwrap_or(config.export_path.clone());
+    let pattern = FileNamePattern::new(&config.export_filename_pattern);
 
-    // Find all session files
-    let session_files: Vec<PathBuf> = std::fs::read_dir(&export_dir)
-        .map(|entries| {
-            entries
-                .filter_map(|e| e.ok())
-                .map(|e| e.path())
-                .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
-                .collect()
-        })
-        .unwrap_or_default();
+    let aggregate_by = match aggregate_by.map(str::parse::<export::AggregateBy>) {
+        Some(Ok(by)) => Some(by),
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
 
-    if session_files.is_empty() {
-        println!("No session data found in {export_dir:?}");
-        println!("Run 'synheart-sensor start' to begin collecting data.");
-        return;
+    let parse_bound = |value: Option<&str>| match value.map(export::parse_datetime) {
+        Some(Ok(t)) => Some(t),
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    let since = parse_bound(since);
+    let until = parse_bound(until);
+    if let (Some(since), Some(until)) = (since, until) {
+        if since >= until {
+            eprintln!("Error: --since ({since}) must be before --until ({until})");
+            std::process::exit(1);
+        }
     }
 
-    println!(
-        "Found {} session file(s) in {:?}",
-        session_files.len(),
-        export_dir
-    );
+    if format == "flat-json" {
+        if since.is_some() || until.is_some() {
+            eprintln!("Warning: --since and --until are ignored for flat-json exports");
+        }
+        if aggregate_by.is_some() {
+            eprintln!("Warning: --aggregate-by is ignored for flat-json exports");
+        }
+        export_flat_json(&export_dir, &pattern, config.compress_exports);
+        return;
+    }
 
-    // Combine all snapshots
-    let mut all_snapshots: Vec<HsiSnapshot> = Vec::new();
-    for file in &session_files {
-        if let Ok(content) = std::fs::read_to_string(file) {
-            if let Ok(snapshots) = serde_json::from_str::<Vec<HsiSnapshot>>(&content) {
-                all_snapshots.extend(snapshots);
+    // Combine all snapshots, from the database when configured or else session files
+    let all_snapshots: Vec<HsiSnapshot> = if let Some(store) = open_snapshot_store(&config) {
+        match store.query_range(DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC) {
+            Ok(snapshots) => {
+                println!(
+                    "Read {} snapshot(s) from {:?}",
+                    snapshots.len(),
+                    config.snapshot_db_path()
+                );
+                snapshots
+            }
+            Err(e) => {
+                eprintln!("Error reading snapshot store: {e}");
+                return;
             }
         }
+    } else {
+        match read_session_files(&export_dir, &pattern) {
+            Some(snapshots) => snapshots,
+            None => return,
+        }
+    };
+
+    let total = all_snapshots.len();
+    let all_snapshots = export::filter_by_observed_at(all_snapshots, since, until);
+    if all_snapshots.len() == total {
+        println!("Total snapshots: {total}");
+    } else {
+        println!(
+            "Total snapshots: {} in the selected time range (of {total})",
+            all_snapshots.len()
+        );
     }
 
-    println!("Total snapshots: {}", all_snapshots.len());
+    let all_snapshots = match aggregate_by {
+        Some(by) => {
+            let aggregates = export::aggregate_snapshots(all_snapshots, by, &HsiBuilder::new());
+            println!(
+                "Aggregated into {} {} group(s)",
+                aggregates.len(),
+                by.as_str()
+            );
+            aggregates
+        }
+        None => all_snapshots,
+    };
 
     // Export based on format
-    let output_path = export_dir.join(format!(
-        "export_{}.{}",
-        Utc::now().format("%Y%m%d_%H%M%S"),
-        if format == "jsonl" { "jsonl" } else { "json" }
+    let _ = std::fs::create_dir_all(&export_dir);
+    // Session files are named by the pattern; keep exports distinguishable so
+    // retention, `sessions` and `watch` do not mistake them for sessions. The
+    // `.export` suffix makes the stem fall outside what the pattern matches.
+    let stem = if config.export_filename_pattern == DEFAULT_EXPORT_FILENAME_PATTERN {
+        format!("export_{}", Utc::now().format("%Y%m%d_%H%M%S"))
+    } else {
+        let rendered = export::render_file_name_pattern(
+            &config.export_filename_pattern,
+            &export::FileNameVars {
+                at: Utc::now(),
+                instance_id: "all",
+                session_id: "all",
+                snapshot_count: all_snapshots.len(),
+            },
+        );
+        format!("{rendered}.export")
+    };
+    let output_path = export_dir.join(export::export_file_name(
+        &stem,
+        match format {
+            "jsonl" => "jsonl",
+            "csv" => "csv",
+            "proto" => "pb",
+            _ => "json",
+        },
+        config.compress_exports,
     ));
 
-    let result = if format == "jsonl" {
+    let result = if format == "proto" {
+        // Length-delimited protobuf stream
+        #[cfg(feature = "proto")]
+        {
+            export::write_bytes(
+                &output_path,
+                &export::snapshots_to_proto_stream(&all_snapshots),
+                config.compress_exports,
+            )
+        }
+        #[cfg(not(feature = "proto"))]
+        {
+            eprintln!("Error: proto format requires the proto feature at compile time");
+            return;
+        }
+    } else if format == "csv" {
+        // One row per window, for analysis in R or notebooks
+        export::write_bytes(
+            &output_path,
+            export::snapshots_to_csv(&all_snapshots).as_bytes(),
+            config.compress_exports,
+        )
+    } else if format == "jsonl" {
         // JSON Lines format
         let lines: Vec<String> = all_snapshots
             .iter()
             .filter_map(|s| serde_json::to_string(s).ok())
             .collect();
-        std::fs::write(&output_path, lines.join("\n"))
+        export::write_bytes(
+            &output_path,
+            lines.join("\n").as_bytes(),
+            config.compress_exports,
+        )
     } else {
         // Pretty JSON format
         match serde_json::to_string_pretty(&all_snapshots) {
-            Ok(json) => std::fs::write(&output_path, json),
+            Ok(json) => export::write_bytes(&output_path, json.as_bytes(), config.compress_exports),
             Err(e) => {
                 eprintln!("Error serializing: {e}");
                 return;
@@ -882,6 +1909,610 @@ fn cmd_export(output: Option<PathBuf>, format: &str) {
     }
 }
 
+/// Export the flux-enriched session files as flat JSON records, one per window.
+#[cfg(feature = "flux")]
+fn export_flat_json(export_dir: &Path, pattern: &FileNamePattern, compress: bool) {
+    use synheart_sensor_agent::flux::EnrichedSnapshot;
+
+    let enriched_files: Vec<PathBuf> = export::snapshot_files(export_dir, pattern)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("_enriched.json") && pattern.matches(name))
+        })
+        .collect();
+    if enriched_files.is_empty() {
+        println!("No enriched session data found in {export_dir:?}");
+        println!("Run 'synheart-sensor start --flux' to collect enriched snapshots.");
+        return;
+    }
+
+    // serde_json maps keep keys sorted, so every record lists its fields in the same order
+    let mut records: Vec<serde_json::Map<String, serde_json::Value>> = Vec::new();
+    for file in &enriched_files {
+        let snapshots = std::fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                serde_json::from_str::<Vec<EnrichedSnapshot>>(&content).map_err(|e| e.to_string())
+            });
+        match snapshots {
+            Ok(snapshots) => records.extend(
+                snapshots
+                    .iter()
+                    .map(|snapshot| snapshot.to_flat_record().into_iter().collect()),
+            ),
+            Err(e) => eprintln!("Warning: Skipping {file:?}: {e}"),
+        }
+    }
+    println!("Total records: {}", records.len());
+
+    let _ = std::fs::create_dir_all(export_dir);
+    let output_path = export_dir.join(export::export_file_name(
+        &format!("export_flat_{}", Utc::now().format("%Y%m%d_%H%M%S")),
+        "json",
+        compress,
+    ));
+    let result = match serde_json::to_string_pretty(&records) {
+        Ok(json) => export::write_bytes(&output_path, json.as_bytes(), compress),
+        Err(e) => {
+            eprintln!("Error serializing: {e}");
+            return;
+        }
+    };
+    match result {
+        Ok(_) => println!("Exported to {output_path:?}"),
+        Err(e) => eprintln!("Error writing export: {e}"),
+    }
+}
+
+#[cfg(not(feature = "flux"))]
+fn export_flat_json(_export_dir: &Path, _pattern: &FileNamePattern, _compress: bool) {
+    eprintln!("Error: flat-json format requires the flux feature at compile time");
+}
+
+/// Read and combine all session files (plain, gzip-compressed or streamed).
+///
+/// Returns `None` after printing a hint when no session files are found.
+fn read_session_files(export_dir: &Path, pattern: &FileNamePattern) -> Option<Vec<HsiSnapshot>> {
+    let session_files = export::snapshot_files(export_dir, pattern);
+
+    if session_files.is_empty() {
+        println!("No session data found in {export_dir:?}");
+        println!("Run 'synheart-sensor start' to begin collecting data.");
+        return None;
+    }
+
+    println!(
+        "Found {} session file(s) in {:?}",
+        session_files.len(),
+        export_dir
+    );
+
+    let mut all_snapshots: Vec<HsiSnapshot> = Vec::new();
+    for file in &session_files {
+        if let Ok(snapshots) = export::read_snapshots(file) {
+            all_snapshots.extend(snapshots);
+        }
+    }
+    Some(all_snapshots)
+}
+
+/// Open the configured snapshot store, if the SQLite backend is selected.
+///
+/// Returns `None` for the JSON backend, or if the store cannot be opened.
+fn open_snapshot_store(config: &Config) -> Option<Box<dyn SnapshotStore>> {
+    if config.storage_backend != StorageBackend::Sqlite {
+        return None;
+    }
+
+    #[cfg(feature = "sqlite")]
+    {
+        match synheart_sensor_agent::SqliteSnapshotStore::open(&config.snapshot_db_path()) {
+            Ok(store) => Some(Box::new(store)),
+            Err(e) => {
+                eprintln!("Warning: Could not open snapshot database: {e}");
+                eprintln!("Falling back to JSON session files.");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    {
+        eprintln!(
+            "Warning: sqlite storage backend ignored (sqlite feature not enabled at compile time)"
+        );
+        None
+    }
+}
+
+fn cmd_sessions(json: bool) {
+    let config = Config::load().unwrap_or_default();
+
+    let pattern = FileNamePattern::new(&config.export_filename_pattern);
+    let summaries: Vec<export::SessionFileSummary> =
+        export::session_files(&config.export_path, &pattern)
+            .iter()
+            .filter_map(|path| match export::SessionFileSummary::from_file(path) {
+                Ok(summary) => Some(summary),
+                Err(e) => {
+                    eprintln!("Warning: Skipping {path:?}: {e}");
+                    None
+                }
+            })
+            .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summaries).unwrap_or_else(|_| "[]".to_string())
+        );
+        return;
+    }
+
+    if summaries.is_empty() {
+        println!("No sessions found in {:?}", config.export_path);
+        println!("Run 'synheart-sensor start' to begin collecting data.");
+        return;
+    }
+
+    println!(
+        "{:<36} {:>9} {:<25} {:<25} {:>8} {:>10}",
+        "FILE", "SNAPSHOTS", "START", "END", "EVENTS", "SIZE"
+    );
+    for summary in &summaries {
+        println!(
+            "{:<36} {:>9} {:<25} {:<25} {:>8} {:>10}",
+            summary.file_name,
+            summary.snapshot_count,
+            summary.start.as_deref().map(short_timestamp).unwrap_or("-"),
+            summary.end.as_deref().map(short_timestamp).unwrap_or("-"),
+            summary.total_events,
+            format_bytes(summary.size_bytes)
+        );
+    }
+    println!();
+    println!("{} session(s) in {:?}", summaries.len(), config.export_path);
+}
+
+/// Trim sub-second precision from an RFC3339 timestamp for display.
+fn short_timestamp(timestamp: &str) -> &str {
+    timestamp.get(..19).unwrap_or(timestamp)
+}
+
+/// Format a byte count with a binary unit suffix.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn cmd_inspect(file: &Path, window_index: Option<usize>, format: &str) {
+    let config = Config::load().unwrap_or_default();
+    let path = resolve_session_file(&config, file);
+
+    let snapshots = match export::read_snapshots(&path) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            eprintln!("Error reading {path:?}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Restrict to a single snapshot when an index is given
+    let selected: Vec<(usize, &HsiSnapshot)> = match window_index {
+        Some(index) => match snapshots.get(index) {
+            Some(snapshot) => vec![(index, snapshot)],
+            None => {
+                eprintln!(
+                    "Window index {index} out of range ({} snapshots in file)",
+                    snapshots.len()
+                );
+                std::process::exit(1);
+            }
+        },
+        None => snapshots.iter().enumerate().collect(),
+    };
+
+    if format == "csv" {
+        println!("window_index,window_id,axis,score,confidence,direction,unit");
+        for (index, snapshot) in selected {
+            for reading in snapshot.readings() {
+                println!(
+                    "{},{},{},{},{},{},{}",
+                    index,
+                    reading.window_id,
+                    reading.axis,
+                    reading.score.map(|s| s.to_string()).unwrap_or_default(),
+                    reading.confidence,
+                    reading.direction.map(|d| d.as_str()).unwrap_or_default(),
+                    reading.unit.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        return;
+    }
+
+    if let Some((_, snapshot)) = window_index.and(selected.first()) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(snapshot).unwrap_or_else(|_| "{}".to_string())
+        );
+        return;
+    }
+
+    // Session summary over the window snapshots
+    let windows: Vec<HsiSnapshot> = snapshots
+        .iter()
+        .filter(|s| !s.is_session_summary())
+        .cloned()
+        .collect();
+    let summary = export::SessionFileSummary::from_snapshots(&path, &windows);
+
+    println!("Session: {}", summary.file_name);
+    println!("  Windows: {}", windows.len());
+    println!(
+        "  Time range: {} .. {}",
+        summary.start.as_deref().unwrap_or("-"),
+        summary.end.as_deref().unwrap_or("-")
+    );
+    println!("  Total events: {}", summary.total_events);
+    println!();
+    println!(
+        "{:<30} {:>8} {:>8} {:>8}",
+        "AXIS", "MEAN", "STDDEV", "WINDOWS"
+    );
+    for stats in axis_statistics(&windows) {
+        println!(
+            "{:<30} {:>8.3} {:>8.3} {:>8}",
+            stats.axis, stats.mean, stats.std_dev, stats.count
+        );
+    }
+}
+
+fn cmd_delete(file: &Path, yes: bool) {
+    let config = Config::load().unwrap_or_default();
+    let path = resolve_session_file(&config, file);
+
+    if !path.is_file() {
+        eprintln!("Session file not found: {path:?}");
+        std::process::exit(1);
+    }
+
+    // Show what is about to be removed
+    let snapshots = match export::read_snapshots(&path) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            eprintln!("Error reading {path:?}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let summary = export::SessionFileSummary::from_snapshots(&path, &snapshots);
+    println!("Session: {}", summary.file_name);
+    println!("  Snapshots: {}", summary.snapshot_count);
+    println!("  Start: {}", summary.start.as_deref().unwrap_or("-"));
+    println!("  End: {}", summary.end.as_deref().unwrap_or("-"));
+    println!();
+
+    if !yes {
+        print!("Delete {}? [y/N] ", path.display());
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err()
+            || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        {
+            println!("Aborted.");
+            return;
+        }
+    }
+
+    if let Err(e) = std::fs::remove_file(&path) {
+        eprintln!("Error deleting {path:?}: {e}");
+        std::process::exit(1);
+    }
+
+    // Count the deletion in the log of the session that wrote the file
+    let log_path = snapshots
+        .iter()
+        .find_map(|snapshot| snapshot.meta.as_ref()?.get("session_id")?.as_str())
+        .map(|session_id| config.transparency_log_path(session_id))
+        .filter(|log_path| log_path.parent().is_some_and(Path::is_dir))
+        .unwrap_or_else(|| config.data_path.join("transparency.json"));
+    let transparency_log = create_shared_log_with_persistence(log_path);
+    transparency_log.record_snapshots_deleted(summary.snapshot_count as u64);
+    if let Err(e) = transparency_log.save() {
+        eprintln!("Warning: Could not save transparency log: {e}");
+    }
+
+    println!("Deleted {path:?}");
+}
+
+/// Resolve a session file argument, falling back to the export directory.
+fn resolve_session_file(config: &Config, file: &Path) -> PathBuf {
+    if file.exists() || file.is_absolute() {
+        file.to_path_buf()
+    } else {
+        config.export_path.join(file)
+    }
+}
+
+fn cmd_merge(inputs: &[PathBuf], output: &Path, deduplicate: bool) {
+    let config = Config::load().unwrap_or_default();
+
+    let mut combined: Vec<HsiSnapshot> = Vec::new();
+    for file in inputs {
+        let path = resolve_session_file(&config, file);
+        match export::read_snapshots(&path) {
+            Ok(snapshots) => {
+                println!("Read {} snapshot(s) from {path:?}", snapshots.len());
+                combined.extend(snapshots);
+            }
+            Err(e) => {
+                eprintln!("Error reading {path:?}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (merged, duplicates) = export::merge_snapshots(combined, deduplicate);
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = export::write_snapshot_file(output, &merged) {
+        eprintln!("Error writing {output:?}: {e}");
+        std::process::exit(1);
+    }
+
+    println!("Merged {} snapshot(s) into {output:?}", merged.len());
+    if deduplicate {
+        println!("Removed {duplicates} duplicate snapshot(s)");
+    }
+}
+
+fn cmd_replay(file: &Path, output: Option<&Path>) {
+    let config = Config::load().unwrap_or_default();
+    let path = resolve_session_file(&config, file);
+
+    let snapshots = match export::read_snapshots(&path) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            eprintln!("Error reading {path:?}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Collect the raw events of every window snapshot
+    let mut events: Vec<SensorEvent> = Vec::new();
+    let mut missing = 0;
+    for snapshot in snapshots.iter().filter(|s| !s.is_session_summary()) {
+        match snapshot.raw_events() {
+            Some(window) => {
+                events.extend(
+                    window
+                        .keyboard_events
+                        .into_iter()
+                        .map(SensorEvent::Keyboard),
+                );
+                events.extend(window.mouse_events.into_iter().map(SensorEvent::Mouse));
+                events.extend(window.touch_events.into_iter().map(SensorEvent::Touch));
+            }
+            None => missing += 1,
+        }
+    }
+    if events.is_empty() {
+        eprintln!("No raw events in {path:?}.");
+        eprintln!("Record sessions with `include_raw_events_in_export` enabled to replay them.");
+        std::process::exit(1);
+    }
+    if missing > 0 {
+        eprintln!("Warning: {missing} window(s) without raw events are left out");
+    }
+
+    // Re-run windowing and feature computation with the current config
+    let mut window_manager = create_window_manager(&config);
+    window_manager.replay_events(events);
+    window_manager.flush();
+
+    let mut hsi_builder = HsiBuilder::new()
+        .with_feature_config(config.feature_config.clone())
+        .with_raw_events(true);
+    let original = snapshots.first();
+    if let Some(session_id) = original
+        .and_then(|s| s.meta.as_ref())
+        .and_then(|meta| meta.get("session_id"))
+        .and_then(|id| id.as_str())
+    {
+        hsi_builder = hsi_builder.with_session_id(session_id.to_string());
+    }
+    if let Some(token) = original.and_then(|s| s.privacy.consent_token.clone()) {
+        hsi_builder = hsi_builder.with_consent_token(token);
+    }
+    // Keep the time zone the session was recorded in
+    if let Some(meta) = original.and_then(|s| s.meta.as_ref()) {
+        let local_timezone = meta
+            .get("local_timezone")
+            .and_then(|tz| tz.as_str())
+            .map(str::to_string);
+        hsi_builder = hsi_builder.with_local_timezone(local_timezone);
+    }
+    let hsi_builder = configure_snapshot_privacy(hsi_builder, &config);
+
+    let mut focus_tracker = DeepFocusTracker::new();
+    let mut delta_tracker = FeatureDeltaTracker::new();
+    let completed = window_manager.take_completed_windows();
+    let completed_features = compute_window_features(&completed, &config.feature_config);
+    let mut replayed: Vec<HsiSnapshot> = completed
+        .into_iter()
+        .zip(completed_features)
+        .map(|((resolution, window), features)| {
+            let delta = delta_tracker.observe(&window, &features);
+            hsi_builder.build_with_focus_tracker(
+                &window,
+                &features,
+                resolution,
+                Some(&delta),
+                &mut focus_tracker,
+            )
+        })
+        .collect();
+    let window_count = replayed.len();
+    if window_count > 0 {
+        replayed.push(hsi_builder.build_session_summary(replayed.clone()));
+    }
+
+    let output_path = match output {
+        Some(output) => output.to_path_buf(),
+        None => {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("session");
+            let stem = name
+                .trim_end_matches(export::GZIP_SUFFIX)
+                .trim_end_matches(".jsonl")
+                .trim_end_matches(".json");
+            config.export_path.join(export::export_file_name(
+                &format!("{stem}_replayed"),
+                "json",
+                config.compress_exports,
+            ))
+        }
+    };
+    if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = export::write_snapshot_file(&output_path, &replayed) {
+        eprintln!("Error writing {output_path:?}: {e}");
+        std::process::exit(1);
+    }
+
+    println!("Replayed {window_count} window(s) from {path:?} into {output_path:?}");
+}
+
+fn cmd_validate(file: &Path) {
+    let config = Config::load().unwrap_or_default();
+    let path = resolve_session_file(&config, file);
+
+    let snapshots = match export::read_snapshots(&path) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            eprintln!("Error reading {path:?}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut invalid = 0;
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let errors = validate_snapshot(snapshot);
+        if errors.is_empty() {
+            continue;
+        }
+        invalid += 1;
+        let window_id = snapshot.window_ids.first().map_or("-", String::as_str);
+        println!("Snapshot {index} ({window_id}):");
+        for error in errors {
+            println!("  - {error}");
+        }
+    }
+
+    if invalid > 0 {
+        println!();
+        println!(
+            "{invalid} of {} snapshot(s) do not conform to HSI 1.0",
+            snapshots.len()
+        );
+        std::process::exit(1);
+    }
+    println!(
+        "All {} snapshot(s) in {path:?} conform to HSI 1.0",
+        snapshots.len()
+    );
+}
+
+fn cmd_prune(days: Option<u64>) {
+    let config = Config::load().unwrap_or_default();
+
+    let Some(retention_days) = days.or(config.retention_days) else {
+        println!("No retention policy configured.");
+        println!(
+            "Set `retention_days` in {:?} or pass --days.",
+            Config::config_path()
+        );
+        return;
+    };
+
+    match retention::enforce_retention_policy(
+        &config.export_path,
+        retention_days,
+        &FileNamePattern::new(&config.export_filename_pattern),
+    ) {
+        Ok(deleted) => {
+            for path in &deleted {
+                println!("Deleted {path:?}");
+            }
+            println!(
+                "Pruned {} session file(s) older than {retention_days} days",
+                deleted.len()
+            );
+        }
+        Err(e) => {
+            eprintln!("Error applying retention policy: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_install_service(uninstall: bool, yes_i_consent: bool) {
+    if uninstall {
+        match service::uninstall() {
+            Ok(path) => println!("Service removed ({path:?})"),
+            Err(e) => {
+                eprintln!("Error removing service: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // The service starts collection unattended, so consent is recorded now
+    obtain_consent(yes_i_consent);
+
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(e) => {
+            eprintln!("Error locating the agent binary: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match service::install(&binary) {
+        Ok(path) => {
+            println!("Service installed: {path:?}");
+            println!(
+                "The agent will run `{} {}` at login.",
+                binary.display(),
+                service::SERVICE_ARGS.join(" ")
+            );
+            println!("Use 'synheart-sensor install-service --uninstall' to remove it.");
+        }
+        Err(e) => {
+            eprintln!("Error installing service: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 fn cmd_config() {
     let config = Config::load().unwrap_or_default();
 
@@ -896,6 +2527,35 @@ fn cmd_config() {
     );
 }
 
+/// Change one setting in the config file and print the result.
+fn cmd_config_set(key: &str, value: &str) {
+    // Patch the file alone so environment overrides are not persisted
+    let mut config = match Config::load_file() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading config: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = config.set_value(key, value) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+    if let Err(e) = config.save() {
+        eprintln!("Error saving config: {e}");
+        std::process::exit(1);
+    }
+
+    let config_path = Config::config_path();
+    println!("Updated {key} in {config_path:?}");
+    println!();
+    match config.encode(ConfigFormat::from_path(&config_path)) {
+        Ok(content) => println!("{content}"),
+        Err(e) => eprintln!("Error: {e}"),
+    }
+}
+
 /// Set up Ctrl+C handler.
 fn ctrlc_handler(running: Arc<AtomicBool>) {
     ctrlc::set_handler(move || {
@@ -904,25 +2564,71 @@ fn ctrlc_handler(running: Arc<AtomicBool>) {
     .expect("Error setting Ctrl+C handler");
 }
 
-/// Create gateway client from CLI args or runtime directory.
+/// Open the queue of snapshots awaiting gateway sync, reporting restored ones.
 #[cfg(feature = "gateway")]
-fn create_gateway_client(
+fn open_pending_queue(config: &Config) -> Option<PersistentQueue> {
+    match PersistentQueue::open(config.pending_queue_path(), config.max_queued_snapshots) {
+        Ok(queue) => {
+            if !queue.is_empty() {
+                println!("  Gateway queue: {} unsent snapshots restored", queue.len());
+            }
+            Some(queue)
+        }
+        Err(e) => {
+            eprintln!("Warning: Could not open gateway queue: {e}");
+            None
+        }
+    }
+}
+
+/// Create gateway configuration from CLI args or the gateway config directory.
+///
+/// `config_dir` replaces the default SyniLife runtime directory, and any host,
+/// TLS or CA bundle set in `connection` overrides what it holds.
+#[cfg(feature = "gateway")]
+fn create_gateway_config(
     port: Option<u16>,
     token: Option<String>,
-) -> Result<BlockingGatewayClient, synheart_sensor_agent::GatewayError> {
-    // If both port and token are provided, use them directly
-    if let (Some(p), Some(t)) = (port, token.clone()) {
-        let config = GatewayConfig::new("127.0.0.1", p, t);
-        return BlockingGatewayClient::new(config);
-    }
+    config_dir: Option<&Path>,
+    connection: &synheart_sensor_agent::GatewayConnectionConfig,
+    transport: &str,
+    content_type: &str,
+) -> Result<GatewayConfig, synheart_sensor_agent::GatewayError> {
+    use synheart_sensor_agent::gateway::DEFAULT_GATEWAY_HOST;
+
+    let transport: GatewayTransport = transport.parse()?;
+    let content_type: ContentType = content_type.parse()?;
 
-    // Try to load from runtime directory
-    match BlockingGatewayClient::from_runtime() {
-        Ok(client) => Ok(client),
+    // If both port and token are provided, use them directly
+    let loaded = if let (Some(p), Some(t)) = (port, token.clone()) {
+        Ok(GatewayConfig::new(DEFAULT_GATEWAY_HOST, p, t))
+    } else {
+        // Try to load from the config directory
+        match config_dir {
+            Some(dir) => GatewayConfig::from_dir(dir),
+            None => GatewayConfig::from_runtime_dir(),
+        }
+    };
+    match loaded {
+        Ok(mut config) => {
+            if let Some(host) = &connection.host {
+                config = config.with_host(host.clone());
+            }
+            if connection.tls || connection.ca_cert_path.is_some() {
+                let ca_cert = connection
+                    .ca_cert_path
+                    .clone()
+                    .or_else(|| config.ca_cert_path.clone());
+                config = config.with_tls(ca_cert);
+            }
+            Ok(config
+                .with_transport(transport)
+                .with_content_type(content_type))
+        }
         Err(e) => {
             // If partial args provided, try to fill in the gaps
             if port.is_some() || token.is_some() {
-                eprintln!("Warning: Partial gateway config provided, trying runtime directory...");
+                eprintln!("Warning: Partial gateway config provided, trying config directory...");
             }
             Err(e)
         }
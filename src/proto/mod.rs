@@ -0,0 +1,345 @@
+//! Protocol Buffers encoding of HSI snapshots.
+//!
+//! The message types are generated by `prost-build` from
+//! `synheart_sensor_agent.proto` and mirror the JSON snapshot format. They
+//! are used for compact exports (`export --format proto`) and for gateway
+//! sync over bandwidth-constrained links.
+
+use crate::collector::types::Platform;
+use crate::core::hsi;
+use std::collections::HashMap;
+
+include!(concat!(env!("OUT_DIR"), "/synheart_sensor_agent.rs"));
+
+/// Errors converting a protobuf message back into a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtoError {
+    /// A required message field is not set
+    MissingField(String),
+    /// An enum field holds an unknown or unspecified value
+    InvalidEnum { field: String, value: i32 },
+    /// A `meta` value is not valid JSON
+    InvalidMeta { key: String, message: String },
+}
+
+impl std::fmt::Display for ProtoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtoError::MissingField(field) => write!(f, "Missing required field: {field}"),
+            ProtoError::InvalidEnum { field, value } => {
+                write!(f, "Invalid value {value} for {field}")
+            }
+            ProtoError::InvalidMeta { key, message } => {
+                write!(f, "Invalid JSON in meta.{key}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {}
+
+impl hsi::HsiSnapshot {
+    /// Convert this snapshot into its protobuf message.
+    ///
+    /// `meta` values are encoded as JSON strings.
+    pub fn to_proto(&self) -> HsiSnapshot {
+        HsiSnapshot {
+            hsi_version: self.hsi_version.clone(),
+            observed_at_utc: self.observed_at_utc.clone(),
+            computed_at_utc: self.computed_at_utc.clone(),
+            producer: Some(HsiProducer {
+                name: self.producer.name.clone(),
+                version: self.producer.version.clone(),
+                instance_id: self.producer.instance_id.clone(),
+            }),
+            window_ids: self.window_ids.clone(),
+            windows: self
+                .windows
+                .iter()
+                .map(|(id, window)| {
+                    (
+                        id.clone(),
+                        HsiWindow {
+                            start: window.start.clone(),
+                            end: window.end.clone(),
+                            label: window.label.as_ref().map(|l| l.as_str().to_string()),
+                            resolution_secs: window.resolution_secs,
+                        },
+                    )
+                })
+                .collect(),
+            source_ids: self.source_ids.clone().unwrap_or_default(),
+            sources: self
+                .sources
+                .iter()
+                .flatten()
+                .map(|(id, source)| {
+                    (
+                        id.clone(),
+                        HsiSource {
+                            r#type: source_type_to_proto(source.source_type) as i32,
+                            quality: source.quality,
+                            degraded: source.degraded,
+                            notes: source.notes.clone(),
+                            platform: source.platform.map(|p| p.as_str().to_string()),
+                        },
+                    )
+                })
+                .collect(),
+            axes: self.axes.as_ref().map(|axes| HsiAxes {
+                affect: axes.affect.as_ref().map(domain_to_proto),
+                engagement: axes.engagement.as_ref().map(domain_to_proto),
+                behavior: axes.behavior.as_ref().map(domain_to_proto),
+            }),
+            privacy: Some(HsiPrivacy {
+                contains_pii: self.privacy.contains_pii,
+                raw_biosignals_allowed: self.privacy.raw_biosignals_allowed,
+                derived_metrics_allowed: self.privacy.derived_metrics_allowed,
+                notes: self.privacy.notes.clone(),
+                consent_token: self.privacy.consent_token.clone(),
+                differential_privacy_epsilon: self.privacy.differential_privacy_epsilon,
+            }),
+            meta: self
+                .meta
+                .iter()
+                .flatten()
+                .map(|(key, value)| (key.clone(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Convert a protobuf message back into a snapshot.
+    ///
+    /// Empty `source_ids`, `sources`, `meta` and evidence lists become `None`.
+    pub fn from_proto(p: HsiSnapshot) -> Result<hsi::HsiSnapshot, ProtoError> {
+        let producer = p
+            .producer
+            .ok_or_else(|| ProtoError::MissingField("producer".to_string()))?;
+        let privacy = p
+            .privacy
+            .ok_or_else(|| ProtoError::MissingField("privacy".to_string()))?;
+
+        let mut sources = HashMap::with_capacity(p.sources.len());
+        for (id, source) in p.sources {
+            let source_type = match HsiSourceType::try_from(source.r#type) {
+                Ok(source_type) => source_type_from_proto(source_type),
+                Err(_) => None,
+            }
+            .ok_or_else(|| ProtoError::InvalidEnum {
+                field: format!("sources.{id}.type"),
+                value: source.r#type,
+            })?;
+            sources.insert(
+                id,
+                hsi::HsiSource {
+                    source_type,
+                    quality: source.quality,
+                    degraded: source.degraded,
+                    notes: source.notes,
+                    platform: source.platform.as_deref().map(platform_from_proto),
+                },
+            );
+        }
+
+        let mut meta = HashMap::with_capacity(p.meta.len());
+        for (key, value) in p.meta {
+            let value = serde_json::from_str(&value).map_err(|e| ProtoError::InvalidMeta {
+                key: key.clone(),
+                message: e.to_string(),
+            })?;
+            meta.insert(key, value);
+        }
+
+        let axes = match p.axes {
+            Some(axes) => Some(hsi::HsiAxes {
+                affect: axes.affect.map(domain_from_proto).transpose()?,
+                engagement: axes.engagement.map(domain_from_proto).transpose()?,
+                behavior: axes.behavior.map(domain_from_proto).transpose()?,
+            }),
+            None => None,
+        };
+
+        Ok(hsi::HsiSnapshot {
+            hsi_version: p.hsi_version,
+            observed_at_utc: p.observed_at_utc,
+            computed_at_utc: p.computed_at_utc,
+            producer: hsi::HsiProducer {
+                name: producer.name,
+                version: producer.version,
+                instance_id: producer.instance_id,
+            },
+            window_ids: p.window_ids,
+            windows: p
+                .windows
+                .into_iter()
+                .map(|(id, window)| {
+                    (
+                        id,
+                        hsi::HsiWindow {
+                            start: window.start,
+                            end: window.end,
+                            label: window.label.map(hsi::HsiWindowLabel::from),
+                            resolution_secs: window.resolution_secs,
+                        },
+                    )
+                })
+                .collect(),
+            source_ids: non_empty(p.source_ids),
+            sources: (!sources.is_empty()).then_some(sources),
+            axes,
+            privacy: hsi::HsiPrivacy {
+                contains_pii: privacy.contains_pii,
+                raw_biosignals_allowed: privacy.raw_biosignals_allowed,
+                derived_metrics_allowed: privacy.derived_metrics_allowed,
+                notes: privacy.notes,
+                consent_token: privacy.consent_token,
+                differential_privacy_epsilon: privacy.differential_privacy_epsilon,
+            },
+            meta: (!meta.is_empty()).then_some(meta),
+        })
+    }
+}
+
+fn domain_to_proto(domain: &hsi::HsiAxesDomain) -> HsiAxesDomain {
+    HsiAxesDomain {
+        readings: domain
+            .iter()
+            .map(|reading| HsiAxisReading {
+                axis: reading.axis.clone(),
+                score: reading.score,
+                confidence: reading.confidence,
+                window_id: reading.window_id.clone(),
+                direction: reading
+                    .direction
+                    .map(direction_to_proto)
+                    .unwrap_or(HsiDirection::Unspecified) as i32,
+                unit: reading.unit.clone(),
+                evidence_source_ids: reading.evidence_source_ids.clone().unwrap_or_default(),
+                notes: reading.notes.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn domain_from_proto(domain: HsiAxesDomain) -> Result<hsi::HsiAxesDomain, ProtoError> {
+    let mut readings = Vec::with_capacity(domain.readings.len());
+    for reading in domain.readings {
+        // An unspecified direction is an absent one
+        let direction = match HsiDirection::try_from(reading.direction) {
+            Ok(HsiDirection::Unspecified) => None,
+            Ok(direction) => direction_from_proto(direction),
+            Err(_) => {
+                return Err(ProtoError::InvalidEnum {
+                    field: format!("{}.direction", reading.axis),
+                    value: reading.direction,
+                })
+            }
+        };
+        readings.push(hsi::HsiAxisReading {
+            axis: reading.axis,
+            score: reading.score,
+            confidence: reading.confidence,
+            window_id: reading.window_id,
+            direction,
+            unit: reading.unit,
+            evidence_source_ids: non_empty(reading.evidence_source_ids),
+            notes: reading.notes,
+        });
+    }
+    Ok(hsi::HsiAxesDomain { readings })
+}
+
+fn direction_to_proto(direction: hsi::HsiDirection) -> HsiDirection {
+    match direction {
+        hsi::HsiDirection::HigherIsMore => HsiDirection::HigherIsMore,
+        hsi::HsiDirection::HigherIsLess => HsiDirection::HigherIsLess,
+        hsi::HsiDirection::Bidirectional => HsiDirection::Bidirectional,
+    }
+}
+
+fn direction_from_proto(direction: HsiDirection) -> Option<hsi::HsiDirection> {
+    match direction {
+        HsiDirection::Unspecified => None,
+        HsiDirection::HigherIsMore => Some(hsi::HsiDirection::HigherIsMore),
+        HsiDirection::HigherIsLess => Some(hsi::HsiDirection::HigherIsLess),
+        HsiDirection::Bidirectional => Some(hsi::HsiDirection::Bidirectional),
+    }
+}
+
+fn source_type_to_proto(source_type: hsi::HsiSourceType) -> HsiSourceType {
+    match source_type {
+        hsi::HsiSourceType::Sensor => HsiSourceType::Sensor,
+        hsi::HsiSourceType::App => HsiSourceType::App,
+        hsi::HsiSourceType::SelfReport => HsiSourceType::SelfReport,
+        hsi::HsiSourceType::Observer => HsiSourceType::Observer,
+        hsi::HsiSourceType::Derived => HsiSourceType::Derived,
+        hsi::HsiSourceType::Other => HsiSourceType::Other,
+    }
+}
+
+fn source_type_from_proto(source_type: HsiSourceType) -> Option<hsi::HsiSourceType> {
+    match source_type {
+        HsiSourceType::Unspecified => None,
+        HsiSourceType::Sensor => Some(hsi::HsiSourceType::Sensor),
+        HsiSourceType::App => Some(hsi::HsiSourceType::App),
+        HsiSourceType::SelfReport => Some(hsi::HsiSourceType::SelfReport),
+        HsiSourceType::Observer => Some(hsi::HsiSourceType::Observer),
+        HsiSourceType::Derived => Some(hsi::HsiSourceType::Derived),
+        HsiSourceType::Other => Some(hsi::HsiSourceType::Other),
+    }
+}
+
+/// Unrecognized platform names decode as `Platform::Unknown`.
+fn platform_from_proto(name: &str) -> Platform {
+    match name {
+        "macos" => Platform::MacOS,
+        "windows" => Platform::Windows,
+        "linux" => Platform::Linux,
+        _ => Platform::Unknown,
+    }
+}
+
+fn non_empty(values: Vec<String>) -> Option<Vec<String>> {
+    (!values.is_empty()).then_some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use chrono::{Duration, Utc};
+    use prost::Message;
+
+    #[test]
+    fn test_proto_round_trip() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+
+        let bytes = snapshot.to_proto().encode_to_vec();
+        let decoded =
+            hsi::HsiSnapshot::from_proto(HsiSnapshot::decode(bytes.as_slice()).unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&snapshot).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_proto_rejects_missing_producer() {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let features = compute_features(&window, &FeatureConfig::default());
+        let mut message = HsiBuilder::new()
+            .build(&window, &features, Duration::seconds(10), None)
+            .to_proto();
+        message.producer = None;
+
+        assert_eq!(
+            hsi::HsiSnapshot::from_proto(message).unwrap_err(),
+            ProtoError::MissingField("producer".to_string())
+        );
+    }
+}
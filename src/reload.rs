@@ -0,0 +1,221 @@
+//! Configuration reload for a running agent.
+//!
+//! On POSIX systems `SIGHUP` asks the agent to re-read its config file. Only
+//! settings that can change without losing data are applied: the window
+//! duration, session gap threshold, input sources, export compression and
+//! flux baseline window.
+
+use crate::config::Config;
+use crate::core::WindowManager;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Tracks reload requests delivered by `SIGHUP`.
+///
+/// The signal is received on a background thread; the main loop polls
+/// [`ReloadSignal::take`]. On platforms without `SIGHUP` no request is ever
+/// raised.
+pub struct ReloadSignal {
+    /// Set when a reload was requested and not yet taken
+    requested: Arc<AtomicBool>,
+}
+
+impl ReloadSignal {
+    /// Start listening for `SIGHUP`.
+    ///
+    /// Once installed, `SIGHUP` no longer terminates the process.
+    pub fn install() -> std::io::Result<Self> {
+        let requested = Arc::new(AtomicBool::new(false));
+
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            // Register before returning so no signal sent afterwards is missed
+            let mut hangup = {
+                let _guard = runtime.enter();
+                signal(SignalKind::hangup())?
+            };
+
+            let flag = requested.clone();
+            std::thread::Builder::new()
+                .name("reload-signal".to_string())
+                .spawn(move || {
+                    runtime.block_on(async move {
+                        while hangup.recv().await.is_some() {
+                            flag.store(true, Ordering::SeqCst);
+                        }
+                    })
+                })?;
+        }
+
+        Ok(Self { requested })
+    }
+
+    /// Check for a pending reload request, clearing it.
+    pub fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Which reloadable settings changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// New windows use a different duration
+    pub window_duration: bool,
+    /// The session gap threshold changed
+    pub session_gap_threshold: bool,
+    /// Keyboard or mouse capture was enabled or disabled
+    pub sources: bool,
+    /// Export compression was switched on or off
+    pub compress_exports: bool,
+    /// The flux baseline window size changed
+    pub flux_baseline_window: bool,
+}
+
+impl ConfigChanges {
+    /// Check whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Apply the reloadable settings of `reloaded` to `current` and the window manager.
+///
+/// Other settings (paths, storage backend, ...) only take effect on restart.
+/// A source change that would disable every source is ignored. The caller is
+/// responsible for restarting the collector when `sources` changed and for
+/// resizing the flux processor when `flux_baseline_window` changed.
+pub fn apply_config(
+    current: &mut Config,
+    reloaded: &Config,
+    window_manager: &mut WindowManager,
+) -> ConfigChanges {
+    let mut changes = ConfigChanges::default();
+
+    if reloaded.window_duration != current.window_duration {
+        current.window_duration = reloaded.window_duration;
+        // Adaptive windows are sized by `adaptive_window`, which needs a restart
+        if current.adaptive_window.is_none() {
+            window_manager.set_resolutions(vec![current.window_duration.as_secs()]);
+        }
+        changes.window_duration = true;
+    }
+
+    if reloaded.session_gap_threshold_secs != current.session_gap_threshold_secs {
+        current.session_gap_threshold_secs = reloaded.session_gap_threshold_secs;
+        window_manager.set_session_gap_threshold(current.session_gap_threshold_secs);
+        changes.session_gap_threshold = true;
+    }
+
+    if reloaded.sources != current.sources && reloaded.sources.any_enabled() {
+        current.sources = reloaded.sources.clone();
+        changes.sources = true;
+    }
+
+    if reloaded.compress_exports != current.compress_exports {
+        current.compress_exports = reloaded.compress_exports;
+        changes.compress_exports = true;
+    }
+
+    if reloaded.flux.baseline_window != current.flux.baseline_window
+        && reloaded.flux.baseline_window > 0
+    {
+        current.flux.baseline_window = reloaded.flux.baseline_window;
+        changes.flux_baseline_window = true;
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::types::KeyboardEvent;
+    use crate::collector::SensorEvent;
+    use chrono::{Duration, Utc};
+
+    fn key_event_at(offset_secs: i64, start: chrono::DateTime<Utc>) -> SensorEvent {
+        let mut event = KeyboardEvent::new(true);
+        event.timestamp = start + Duration::seconds(offset_secs);
+        SensorEvent::Keyboard(event)
+    }
+
+    #[test]
+    fn test_apply_config_ignores_disabling_all_sources() {
+        let mut current = Config::default();
+        let mut reloaded = Config::default();
+        reloaded.sources.keyboard = false;
+        reloaded.sources.mouse = false;
+        let mut manager = WindowManager::new(10, 300, None);
+
+        let changes = apply_config(&mut current, &reloaded, &mut manager);
+        assert!(changes.is_empty());
+        assert!(current.sources.any_enabled());
+    }
+
+    #[test]
+    fn test_apply_config_flux_baseline_window() {
+        let mut current = Config::default();
+        let mut reloaded = Config::default();
+        let mut manager = WindowManager::new(10, 300, None);
+
+        reloaded.flux.baseline_window = 0;
+        assert!(apply_config(&mut current, &reloaded, &mut manager).is_empty());
+
+        reloaded.flux.baseline_window = 5;
+        let changes = apply_config(&mut current, &reloaded, &mut manager);
+        assert!(changes.flux_baseline_window);
+        assert_eq!(current.flux.baseline_window, 5);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sighup_changes_window_duration() {
+        let signal = ReloadSignal::install().unwrap();
+        let mut current = Config {
+            window_duration: std::time::Duration::from_secs(10),
+            ..Config::default()
+        };
+        let mut manager = WindowManager::new(10, 300, None);
+
+        // Mid-session: a window is in progress
+        let start = Utc::now();
+        manager.process_event(key_event_at(0, start));
+        manager.process_event(key_event_at(5, start));
+
+        std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !signal.take() {
+            assert!(std::time::Instant::now() < deadline, "SIGHUP not received");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let reloaded = Config {
+            window_duration: std::time::Duration::from_secs(30),
+            ..current.clone()
+        };
+        let changes = apply_config(&mut current, &reloaded, &mut manager);
+        assert!(changes.window_duration);
+        assert!(!changes.sources);
+
+        manager.process_event(key_event_at(8, start));
+        manager.process_event(key_event_at(20, start));
+        manager.flush();
+
+        // The in-progress window completes at the old duration; new ones use the new duration
+        let windows = manager.take_completed_windows();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].0, Duration::seconds(10));
+        assert_eq!(windows[0].1.event_count(), 2);
+        assert_eq!(windows[1].0, Duration::seconds(30));
+        assert_eq!(windows[1].1.event_count(), 2);
+        assert_eq!(windows[1].1.end - windows[1].1.start, Duration::seconds(30));
+    }
+}
@@ -0,0 +1,148 @@
+//! Data retention policy for exported session files.
+//!
+//! Session files are the files named by the configured export filename
+//! pattern. A file's age is taken from the date and time in its name (UTC)
+//! or, when the pattern has no date, from its modification time. Files older
+//! than the retention period are deleted. Files that do not follow the
+//! pattern, such as combined exports, are left untouched.
+
+use crate::export::FileNamePattern;
+use chrono::{DateTime, Duration, Utc};
+use std::path::{Path, PathBuf};
+
+/// Delete session files in `export_path` older than `retention_days` days.
+///
+/// Returns the paths of the deleted files.
+pub fn enforce_retention_policy(
+    export_path: &Path,
+    retention_days: u64,
+    pattern: &FileNamePattern,
+) -> std::io::Result<Vec<PathBuf>> {
+    enforce_retention_policy_at(export_path, retention_days, pattern, Utc::now())
+}
+
+/// Apply the retention policy relative to the given current time.
+pub fn enforce_retention_policy_at(
+    export_path: &Path,
+    retention_days: u64,
+    pattern: &FileNamePattern,
+    now: DateTime<Utc>,
+) -> std::io::Result<Vec<PathBuf>> {
+    let cutoff = now - Duration::days(retention_days.min(i64::MAX as u64 / 86_400) as i64);
+    let mut deleted = Vec::new();
+
+    for entry in std::fs::read_dir(export_path)? {
+        let path = entry?.path();
+        let Some(started_at) = session_timestamp(&path, pattern) else {
+            continue;
+        };
+
+        if started_at < cutoff {
+            std::fs::remove_file(&path)?;
+            deleted.push(path);
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Get the start time of a session file named by `pattern`.
+///
+/// Returns `None` for files the pattern does not name.
+pub fn session_timestamp(path: &Path, pattern: &FileNamePattern) -> Option<DateTime<Utc>> {
+    let name = path.file_name()?.to_str()?;
+    if !pattern.matches(name) {
+        return None;
+    }
+    pattern.timestamp(name).or_else(|| {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        Some(modified.into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn default_pattern() -> FileNamePattern {
+        FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN)
+    }
+
+    #[test]
+    fn test_session_timestamp_parsing() {
+        let pattern = default_pattern();
+        let expected = Utc.with_ymd_and_hms(2024, 1, 15, 14, 32, 10).unwrap();
+        let timestamp = |name: &str| session_timestamp(Path::new(name), &pattern);
+        assert_eq!(timestamp("session_20240115_143210.json"), Some(expected));
+        assert_eq!(timestamp("session_20240115_143210.json.gz"), Some(expected));
+        assert_eq!(
+            timestamp("session_20240115_143210_enriched.json"),
+            Some(expected)
+        );
+        assert_eq!(timestamp("export_20240115_143210.json"), None);
+        assert_eq!(timestamp("session_20240115_143210.txt"), None);
+        assert_eq!(timestamp("session_garbage.json"), None);
+    }
+
+    #[test]
+    fn test_enforce_retention_policy() {
+        let dir = std::env::temp_dir().join(format!("synheart-retention-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "session_20240101_000000.json",
+            "session_20231225_000000.jsonl",
+            "session_20240109_000000.json.gz",
+            "export_20240101_000000.json",
+        ] {
+            std::fs::write(dir.join(name), "[]").unwrap();
+        }
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
+        let mut deleted = enforce_retention_policy_at(&dir, 7, &default_pattern(), now).unwrap();
+        deleted.sort();
+
+        assert_eq!(
+            deleted,
+            vec![
+                dir.join("session_20231225_000000.jsonl"),
+                dir.join("session_20240101_000000.json"),
+            ]
+        );
+        assert!(dir.join("session_20240109_000000.json.gz").exists());
+        assert!(dir.join("export_20240101_000000.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_retention_policy_custom_pattern() {
+        let dir = std::env::temp_dir().join(format!("synheart-retention-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "20240101-000000_abc.json",
+            "20240109-000000_abc.jsonl",
+            "20240101-000000_abc_enriched.json",
+            "session_20240101_000000.json",
+        ] {
+            std::fs::write(dir.join(name), "[]").unwrap();
+        }
+
+        let pattern = FileNamePattern::new("{date}-{time}_{session_id}");
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
+        let mut deleted = enforce_retention_policy_at(&dir, 7, &pattern, now).unwrap();
+        deleted.sort();
+
+        assert_eq!(
+            deleted,
+            vec![
+                dir.join("20240101-000000_abc.json"),
+                dir.join("20240101-000000_abc_enriched.json"),
+            ]
+        );
+        assert!(dir.join("20240109-000000_abc.jsonl").exists());
+        assert!(dir.join("session_20240101_000000.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,12 @@
 //! HTTP server for receiving behavioral data from Chrome extension.
 //!
 //! This module provides an HTTP server that:
-//! - Accepts raw behavioral data from Chrome extension via POST /ingest
+//! - Accepts raw behavioral data from Chrome extension via POST /ingest,
+//!   optionally requiring a Bearer token and limiting requests per client IP
 //! - Processes it through synheart-flux's BehaviorProcessor
 //! - Sends processed HSI to synheart-core-gateway
+//! - Exposes agent counters for Prometheus via GET /metrics
+//! - Pushes each completed window to WebSocket subscribers on GET /ws/stream
 //!
 //! # Architecture
 //!
@@ -13,24 +16,63 @@
 //!                                    [Flux Processing]
 //! ```
 
-use crate::core::HsiSnapshot;
+use crate::core::{compute_features, FeatureConfig, HsiSnapshot, WindowFeatures};
+use crate::flux::behavior_session_to_window;
 use crate::gateway::GatewayConfig;
 use crate::gateway::{BehavioralSession as GatewayBehavioralSession, SessionMeta, SessionPayload};
+use crate::transparency::{create_shared_log, SharedTransparencyLog, TransparencyStats};
 use axum::{
-    extract::State,
-    http::{HeaderValue, StatusCode},
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use synheart_flux::behavior::BehaviorSession as FluxBehaviorSession;
 use synheart_flux::BehaviorProcessor;
 use tokio::net::TcpListener;
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 
+/// Number of window updates buffered per stream subscriber.
+///
+/// A subscriber that falls further behind is disconnected.
+pub const STREAM_BUFFER_SIZE: usize = 64;
+
+/// A completed window pushed to GET /ws/stream subscribers.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureUpdate {
+    /// Features of the window (None if its events could not be read)
+    pub features: Option<WindowFeatures>,
+    /// The window snapshot's `meta` block
+    pub meta: HashMap<String, serde_json::Value>,
+}
+
+/// POST /ingest requests per minute allowed by `synheart-sensor serve` unless
+/// configured otherwise. Library users enable a limit with
+/// [`ServerConfig::with_max_ingest_rpm`].
+pub const DEFAULT_MAX_INGEST_RPM: u32 = 60;
+
+/// Length of a rate limiting window.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Origins allowed by the CORS layer unless configured otherwise.
+pub const DEFAULT_CORS_ORIGINS: &[&str] = &[
+    "http://localhost",
+    "http://127.0.0.1",
+    // Chrome extension pages
+    "chrome-extension://",
+];
+
 /// Server configuration
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -40,6 +82,23 @@ pub struct ServerConfig {
     pub gateway_config: GatewayConfig,
     /// State directory for baselines
     pub state_dir: PathBuf,
+    /// Counters reported by GET /metrics
+    pub transparency_log: SharedTransparencyLog,
+    /// Origins allowed to make cross-origin requests
+    pub cors_origins: Vec<String>,
+    /// Bearer token required on POST /ingest (None accepts unauthenticated requests)
+    pub ingest_token: Option<String>,
+    /// POST /ingest requests allowed per client IP per minute (None, the
+    /// default, disables the limit)
+    ///
+    /// The server only listens on loopback, so every local client shares
+    /// 127.0.0.1 and the limit is in effect global.
+    pub max_ingest_rpm: Option<u32>,
+    /// Window updates pushed to GET /ws/stream subscribers
+    ///
+    /// Library users running their own collector can send updates here as
+    /// windows complete.
+    pub feature_updates: broadcast::Sender<FeatureUpdate>,
 }
 
 impl ServerConfig {
@@ -49,8 +108,40 @@ impl ServerConfig {
             port,
             gateway_config,
             state_dir,
+            transparency_log: create_shared_log(),
+            cors_origins: DEFAULT_CORS_ORIGINS.iter().map(|o| o.to_string()).collect(),
+            ingest_token: None,
+            max_ingest_rpm: None,
+            feature_updates: broadcast::channel(STREAM_BUFFER_SIZE).0,
         }
     }
+
+    /// Report the counters of an existing transparency log (e.g. the agent's own)
+    pub fn with_transparency_log(mut self, transparency_log: SharedTransparencyLog) -> Self {
+        self.transparency_log = transparency_log;
+        self
+    }
+
+    /// Require `Authorization: Bearer <token>` on POST /ingest
+    pub fn with_ingest_token(mut self, ingest_token: String) -> Self {
+        self.ingest_token = Some(ingest_token);
+        self
+    }
+
+    /// Set the POST /ingest requests allowed per client IP per minute (None disables the limit)
+    ///
+    /// Clients all connect from loopback, so this caps the server's total
+    /// ingest rate.
+    pub fn with_max_ingest_rpm(mut self, max_ingest_rpm: Option<u32>) -> Self {
+        self.max_ingest_rpm = max_ingest_rpm;
+        self
+    }
+
+    /// Replace the origins allowed to make cross-origin requests
+    pub fn with_cors_origins(mut self, cors_origins: Vec<String>) -> Self {
+        self.cors_origins = cors_origins;
+        self
+    }
 }
 
 /// Shared server state
@@ -63,6 +154,48 @@ pub struct ServerState {
     http_client: reqwest::Client,
     /// State directory
     state_dir: PathBuf,
+    /// Counters reported by GET /metrics
+    transparency_log: SharedTransparencyLog,
+    /// Window updates pushed to GET /ws/stream subscribers
+    feature_updates: broadcast::Sender<FeatureUpdate>,
+    /// Bearer token required on POST /ingest
+    ingest_token: Option<String>,
+    /// Per-IP limit on POST /ingest requests
+    rate_limiter: Option<RateLimiter>,
+}
+
+/// Counts requests per client IP in fixed one-minute windows.
+///
+/// A client's window starts with its first request and resets once a minute
+/// has passed.
+struct RateLimiter {
+    /// Requests allowed per window
+    max_requests: u32,
+    /// Request count and window start per client
+    clients: Mutex<HashMap<IpAddr, (u32, Instant)>>,
+}
+
+impl RateLimiter {
+    fn new(max_requests: u32) -> Self {
+        Self {
+            max_requests,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `ip` and return whether it is within the limit.
+    fn check(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        // Forget clients whose window has ended so the map stays small
+        clients.retain(|_, (_, started)| now.duration_since(*started) < RATE_LIMIT_WINDOW);
+
+        let (count, _) = clients.entry(ip).or_insert((0, now));
+        if *count >= self.max_requests {
+            return false;
+        }
+        *count += 1;
+        true
+    }
 }
 
 impl ServerState {
@@ -86,11 +219,15 @@ impl ServerState {
         Self {
             processor: RwLock::new(processor),
             gateway_config: config.gateway_config.clone(),
-            http_client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
+            http_client: config
+                .gateway_config
+                .http_client()
                 .expect("Failed to create HTTP client"),
             state_dir: config.state_dir.clone(),
+            transparency_log: config.transparency_log.clone(),
+            feature_updates: config.feature_updates.clone(),
+            ingest_token: config.ingest_token.clone(),
+            rate_limiter: config.max_ingest_rpm.map(RateLimiter::new),
         }
     }
 
@@ -147,10 +284,196 @@ async fn health() -> Json<HealthResponse> {
     })
 }
 
+/// GET /metrics
+///
+/// Transparency log counters in the Prometheus text exposition format.
+async fn metrics(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        render_metrics(&state.transparency_log.stats()),
+    )
+}
+
+/// Render transparency statistics as Prometheus metrics.
+fn render_metrics(stats: &TransparencyStats) -> String {
+    let metrics = [
+        (
+            "synheart_keyboard_events_total",
+            "counter",
+            "Keyboard events processed",
+            stats.keyboard_events,
+        ),
+        (
+            "synheart_mouse_events_total",
+            "counter",
+            "Mouse events processed",
+            stats.mouse_events,
+        ),
+        (
+            "synheart_windows_completed_total",
+            "counter",
+            "Collection windows completed",
+            stats.windows_completed,
+        ),
+        (
+            "synheart_snapshots_exported_total",
+            "counter",
+            "HSI snapshots exported",
+            stats.snapshots_exported,
+        ),
+        (
+            "synheart_events_dropped_total",
+            "counter",
+            "Events dropped because the event buffer was full",
+            stats.events_dropped,
+        ),
+        (
+            "synheart_late_events_dropped_total",
+            "counter",
+            "Out-of-order events dropped because their window had completed",
+            stats.late_events_dropped,
+        ),
+        (
+            "synheart_gateway_syncs_total",
+            "counter",
+            "Successful gateway syncs",
+            stats.gateway_syncs_succeeded,
+        ),
+        (
+            "synheart_gateway_sync_errors_total",
+            "counter",
+            "Failed gateway syncs",
+            stats.gateway_syncs_failed,
+        ),
+        (
+            "synheart_session_duration_seconds",
+            "gauge",
+            "Duration of the current session",
+            stats.session_duration_secs,
+        ),
+    ];
+
+    let mut body = String::new();
+    for (name, kind, help, value) in metrics {
+        body.push_str(&format!(
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n"
+        ));
+    }
+    body
+}
+
+/// GET /ws/stream
+///
+/// Upgrades to a WebSocket that receives a JSON `FeatureUpdate` text frame
+/// for every completed window.
+async fn stream(ws: WebSocketUpgrade, State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    let updates = state.feature_updates.subscribe();
+    ws.on_upgrade(move |socket| stream_updates(socket, updates))
+}
+
+/// Forward window updates to one subscriber until either side goes away.
+///
+/// A subscriber that falls more than `STREAM_BUFFER_SIZE` updates behind
+/// is disconnected rather than slowing down the others.
+async fn stream_updates(mut socket: WebSocket, mut updates: broadcast::Receiver<FeatureUpdate>) {
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    let Ok(json) = serde_json::to_string(&update) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Dropping stream subscriber that fell {} updates behind", skipped);
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Subscribers only listen; anything they send is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Reject clients over the per-IP request limit with 429.
+async fn limit_ingest_rate(
+    State(state): State<Arc<ServerState>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(ref limiter) = state.rate_limiter {
+        if !limiter.check(client.ip(), Instant::now()) {
+            tracing::warn!("Rate limit exceeded for {}", client.ip());
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse {
+                    error: format!(
+                        "More than {} ingest requests per minute",
+                        limiter.max_requests
+                    ),
+                    code: "RATE_LIMIT_EXCEEDED".to_string(),
+                }),
+            ));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// Reject requests without the configured Bearer token with 401.
+///
+/// Runs before the body is parsed, so unauthenticated requests never reach
+/// flux or the gateway.
+async fn require_ingest_token(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(ref expected) = state.ingest_token {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if !provided.is_some_and(|token| tokens_match(token, expected)) {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    error: "Missing or invalid bearer token".to_string(),
+                    code: "UNAUTHORIZED".to_string(),
+                }),
+            ));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// Compare tokens in time independent of where they first differ.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 /// POST /ingest
 ///
 /// Accepts raw behavioral data from Chrome extension, processes with flux,
-/// and forwards to gateway.
+/// and forwards to gateway. Stream subscribers get the window before it is
+/// forwarded, so they keep receiving updates while the gateway is down.
 async fn ingest(
     State(state): State<Arc<ServerState>>,
     Json(data): Json<BehavioralSession>,
@@ -180,6 +503,16 @@ async fn ingest(
         })?
     };
 
+    // Features of the ingested events, computed as for locally captured windows
+    let features = serde_json::from_str::<FluxBehaviorSession>(&session_json)
+        .ok()
+        .map(|session| {
+            compute_features(
+                &behavior_session_to_window(&session),
+                &FeatureConfig::default(),
+            )
+        });
+
     // Parse HSI payload (we forward as a snapshot to core-gateway)
     let hsi_snapshot: HsiSnapshot = serde_json::from_str(&hsi_json).map_err(|e| {
         (
@@ -191,6 +524,12 @@ async fn ingest(
         )
     })?;
 
+    // Push to stream subscribers (no subscribers is not an error)
+    let _ = state.feature_updates.send(FeatureUpdate {
+        features,
+        meta: hsi_snapshot.meta.clone().unwrap_or_default(),
+    });
+
     // Extract session fields from the inbound payload for gateway session envelope.
     // (If the Chrome extension omits fields, fall back to safe defaults.)
     let session_obj = data.session.as_object();
@@ -238,6 +577,7 @@ async fn ingest(
         .await
         .map_err(|e| {
             tracing::error!("Failed to forward to gateway: {}", e);
+            state.transparency_log.record_gateway_sync(1, false);
             (
                 StatusCode::BAD_GATEWAY,
                 Json(ErrorResponse {
@@ -254,6 +594,7 @@ async fn ingest(
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
         tracing::error!("Gateway returned error {}: {}", status, body);
+        state.transparency_log.record_gateway_sync(1, false);
         return Err((
             StatusCode::BAD_GATEWAY,
             Json(ErrorResponse {
@@ -263,6 +604,9 @@ async fn ingest(
         ));
     }
 
+    state.transparency_log.record_gateway_sync(1, true);
+    state.transparency_log.record_snapshot_exported();
+
     // Save baselines periodically
     state.save_baselines().await;
 
@@ -273,23 +617,46 @@ async fn ingest(
     }))
 }
 
+/// Parse CORS origins into header values, failing on the first malformed one.
+fn cors_header_values(origins: &[String]) -> anyhow::Result<Vec<HeaderValue>> {
+    origins
+        .iter()
+        .map(|origin| {
+            HeaderValue::from_str(origin)
+                .map_err(|e| anyhow::anyhow!("Invalid CORS origin {origin:?}: {e}"))
+        })
+        .collect()
+}
+
 /// Run the HTTP server
+///
+/// Fails before binding if any configured CORS origin is not a valid header value.
 pub async fn run(
     config: ServerConfig,
 ) -> anyhow::Result<(SocketAddr, tokio::sync::oneshot::Sender<()>)> {
+    let cors_origins = cors_header_values(&config.cors_origins)?;
     let state = Arc::new(ServerState::new(&config));
 
     let app = Router::new()
         .route("/health", get(health))
-        .route("/ingest", post(ingest))
+        .route(
+            "/ingest",
+            post(ingest)
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    require_ingest_token,
+                ))
+                // Outermost, so unauthenticated floods are limited too
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    limit_ingest_rate,
+                )),
+        )
+        .route("/metrics", get(metrics))
+        .route("/ws/stream", get(stream))
         .layer(
             CorsLayer::new()
-                .allow_origin([
-                    HeaderValue::from_static("http://localhost"),
-                    HeaderValue::from_static("http://127.0.0.1"),
-                    // Allow chrome-extension origins
-                    HeaderValue::from_static("chrome-extension://"),
-                ])
+                .allow_origin(cors_origins)
                 .allow_methods(Any)
                 .allow_headers(Any),
         )
@@ -304,12 +671,15 @@ pub async fn run(
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
 
     tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app)
-            .with_graceful_shutdown(async {
-                let _ = shutdown_rx.await;
-                tracing::info!("Server shutdown signal received");
-            })
-            .await
+        if let Err(e) = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+            tracing::info!("Server shutdown signal received");
+        })
+        .await
         {
             tracing::error!("Server error: {}", e);
         }
@@ -317,3 +687,216 @@ pub async fn run(
 
     Ok((actual_addr, shutdown_tx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::TransparencyLog;
+
+    #[test]
+    fn test_render_metrics() {
+        let log = TransparencyLog::new();
+        log.record_keyboard_events(12);
+        log.record_gateway_sync(1, false);
+        log.record_gateway_sync(2, true);
+        log.record_gateway_sync(3, true);
+        log.record_late_events_dropped(4);
+
+        let body = render_metrics(&log.stats());
+        assert!(body.contains("# TYPE synheart_keyboard_events_total counter\n"));
+        assert!(body.contains("\nsynheart_keyboard_events_total 12\n"));
+        assert!(body.contains("# TYPE synheart_gateway_syncs_total counter\n"));
+        assert!(body.contains("\nsynheart_gateway_syncs_total 2\n"));
+        assert!(body.contains("\nsynheart_gateway_sync_errors_total 1\n"));
+        assert!(body.contains("\nsynheart_late_events_dropped_total 4\n"));
+        assert!(body.contains("# TYPE synheart_session_duration_seconds gauge\n"));
+        assert_eq!(body.lines().count(), 9 * 3);
+    }
+
+    #[test]
+    fn test_cors_header_values() {
+        let defaults: Vec<String> = DEFAULT_CORS_ORIGINS.iter().map(|o| o.to_string()).collect();
+        assert_eq!(cors_header_values(&defaults).unwrap().len(), 3);
+
+        let malformed = vec!["http://localhost".to_string(), "bad\norigin".to_string()];
+        let err = cors_header_values(&malformed).unwrap_err();
+        assert!(err.to_string().contains("bad\\norigin"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_requires_token() {
+        // Nothing listens on the gateway port, so authorized requests fail downstream
+        let gateway_config = GatewayConfig::new("127.0.0.1", 1, "gateway-token");
+        let config = ServerConfig::new(0, gateway_config, std::env::temp_dir())
+            .with_ingest_token("secret".to_string());
+        let (addr, shutdown_tx) = run(config).await.unwrap();
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{addr}/ingest");
+        let body = serde_json::json!({ "session": {} });
+
+        let unauthorized = client
+            .post(&url)
+            .bearer_auth("wrong")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let missing = client.post(&url).json(&body).send().await.unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let authorized = client
+            .post(&url)
+            .bearer_auth("secret")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert!(authorized.status().is_server_error());
+
+        // Health stays open
+        let health = client
+            .get(format!("http://{addr}/health"))
+            .send()
+            .await
+            .unwrap();
+        assert!(health.status().is_success());
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[test]
+    fn test_rate_limiter_resets_each_minute() {
+        let limiter = RateLimiter::new(2);
+        let client: IpAddr = [127, 0, 0, 1].into();
+        let other: IpAddr = [127, 0, 0, 2].into();
+        let start = Instant::now();
+
+        assert!(limiter.check(client, start));
+        assert!(limiter.check(client, start + Duration::from_secs(1)));
+        assert!(!limiter.check(client, start + Duration::from_secs(59)));
+        assert!(limiter.check(other, start + Duration::from_secs(59)));
+        assert!(limiter.check(client, start + RATE_LIMIT_WINDOW));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rate_limit() {
+        let gateway_config = GatewayConfig::new("127.0.0.1", 1, "gateway-token");
+        let config = ServerConfig::new(0, gateway_config, std::env::temp_dir());
+        // Off unless enabled
+        assert_eq!(config.max_ingest_rpm, None);
+        let config = config.with_max_ingest_rpm(Some(1));
+        let (addr, shutdown_tx) = run(config).await.unwrap();
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{addr}/ingest");
+        let body = serde_json::json!({ "session": {} });
+
+        let first = client.post(&url).json(&body).send().await.unwrap();
+        assert_ne!(first.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+        let second = client.post(&url).json(&body).send().await.unwrap();
+        assert_eq!(second.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        let error: serde_json::Value = second.json().await.unwrap();
+        assert_eq!(error["code"], "RATE_LIMIT_EXCEEDED");
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_feature_updates_reach_all_subscribers() {
+        let (sender, _) = broadcast::channel(STREAM_BUFFER_SIZE);
+        let mut subscribers: Vec<_> = (0..10).map(|_| sender.subscribe()).collect();
+
+        let mut meta = HashMap::new();
+        meta.insert("keyboard_events".to_string(), serde_json::json!(12));
+        sender
+            .send(FeatureUpdate {
+                features: Some(WindowFeatures::default()),
+                meta,
+            })
+            .unwrap();
+
+        for subscriber in &mut subscribers {
+            let update = subscriber.recv().await.unwrap();
+            assert_eq!(update.meta["keyboard_events"], 12);
+            assert!(update.features.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_receives_ingested_window() {
+        use crate::collector::types::{KeyboardEvent, SensorEvent};
+        use crate::core::EventWindow;
+        use crate::flux::SensorBehaviorAdapter;
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        // Nothing listens on the gateway port, so forwarding fails
+        let gateway_config = GatewayConfig::new("127.0.0.1", 1, "gateway-token");
+        let config = ServerConfig::new(0, gateway_config, std::env::temp_dir());
+        let (addr, shutdown_tx) = run(config).await.unwrap();
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws/stream"))
+            .await
+            .unwrap();
+
+        let start = chrono::Utc::now() - chrono::Duration::seconds(10);
+        let mut window = EventWindow::new(start, chrono::Duration::seconds(10));
+        for i in 0..20 {
+            let mut event = KeyboardEvent::new(true);
+            event.timestamp = start + chrono::Duration::milliseconds(200 * i);
+            window.add_event(SensorEvent::Keyboard(event));
+        }
+        let session = SensorBehaviorAdapter::with_defaults().convert("stream-test", &window);
+        let body = serde_json::json!({ "session": session });
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{addr}/ingest"))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_GATEWAY);
+
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let ClientMessage::Text(text) = message else {
+            panic!("expected a text frame, got {message:?}");
+        };
+        let update: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(update["features"]["keyboard"]["typing_rate"], 2.0);
+        assert!(update["meta"].is_object());
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_lags() {
+        let (sender, _) = broadcast::channel(STREAM_BUFFER_SIZE);
+        let mut slow = sender.subscribe();
+
+        for _ in 0..=STREAM_BUFFER_SIZE {
+            sender
+                .send(FeatureUpdate {
+                    features: None,
+                    meta: HashMap::new(),
+                })
+                .unwrap();
+        }
+
+        assert!(matches!(slow.recv().await, Err(RecvError::Lagged(1))));
+    }
+}
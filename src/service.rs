@@ -0,0 +1,313 @@
+//! Run-at-login service registration.
+//!
+//! Generates and installs the platform's per-user service definition so the
+//! agent starts with `start --foreground` at login:
+//!
+//! - macOS: a LaunchAgent plist loaded with `launchctl`
+//! - Linux: a systemd user unit enabled with `systemctl --user`
+//! - Windows: a Task Scheduler task imported with `schtasks`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Service identifier (LaunchAgent label).
+pub const SERVICE_LABEL: &str = "ai.synheart.sensor-agent";
+
+/// Systemd unit file name.
+pub const SYSTEMD_UNIT_NAME: &str = "synheart-sensor-agent.service";
+
+/// Task Scheduler task name.
+pub const TASK_NAME: &str = "SynheartSensorAgent";
+
+/// Arguments the service passes to the agent binary.
+pub const SERVICE_ARGS: [&str; 2] = ["start", "--foreground"];
+
+/// Generate a LaunchAgent plist that keeps the agent running from login.
+pub fn launchd_plist(binary: &Path) -> String {
+    let mut arguments = format!(
+        "        <string>{}</string>\n",
+        xml_escape(&binary.to_string_lossy())
+    );
+    for arg in SERVICE_ARGS {
+        arguments.push_str(&format!("        <string>{arg}</string>\n"));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{SERVICE_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// Generate a systemd user unit that starts the agent with the session.
+pub fn systemd_unit(binary: &Path) -> String {
+    format!(
+        "[Unit]
+Description=Synheart Sensor Agent
+After=graphical-session.target
+
+[Service]
+Type=simple
+ExecStart=\"{}\" {}
+Restart=on-failure
+
+[Install]
+WantedBy=default.target
+",
+        binary.display(),
+        SERVICE_ARGS.join(" ")
+    )
+}
+
+/// Generate a Task Scheduler definition that starts the agent at logon.
+pub fn task_scheduler_xml(binary: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>Synheart Sensor Agent</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+    </LogonTrigger>
+  </Triggers>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>3</Count>
+    </RestartOnFailure>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{}</Command>
+      <Arguments>{}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        xml_escape(&binary.to_string_lossy()),
+        SERVICE_ARGS.join(" ")
+    )
+}
+
+/// Escape text for inclusion in an XML element.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Get the path of the service definition file for this platform.
+pub fn service_file_path() -> Result<PathBuf, ServiceError> {
+    #[cfg(target_os = "macos")]
+    {
+        let home = dirs::home_dir()
+            .ok_or_else(|| ServiceError::Io("Could not determine home directory".to_string()))?;
+        Ok(home
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{SERVICE_LABEL}.plist")))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| ServiceError::Io("Could not determine config directory".to_string()))?;
+        Ok(config_dir
+            .join("systemd")
+            .join("user")
+            .join(SYSTEMD_UNIT_NAME))
+    }
+
+    #[cfg(windows)]
+    {
+        let data_dir = dirs::data_local_dir()
+            .ok_or_else(|| ServiceError::Io("Could not determine data directory".to_string()))?;
+        Ok(data_dir
+            .join("synheart-sensor-agent")
+            .join(format!("{TASK_NAME}.xml")))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    {
+        Err(ServiceError::Unsupported)
+    }
+}
+
+/// Install and register the service to run `binary` at login.
+///
+/// Returns the path of the written service definition.
+pub fn install(binary: &Path) -> Result<PathBuf, ServiceError> {
+    let path = service_file_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ServiceError::Io(e.to_string()))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::fs::write(&path, launchd_plist(binary))
+            .map_err(|e| ServiceError::Io(e.to_string()))?;
+        // Replace a previously loaded definition
+        let _ = Command::new("launchctl").arg("unload").arg(&path).output();
+        run(Command::new("launchctl").arg("load").arg(&path))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::write(&path, systemd_unit(binary)).map_err(|e| ServiceError::Io(e.to_string()))?;
+        run(Command::new("systemctl").args(["--user", "daemon-reload"]))?;
+        run(Command::new("systemctl").args(["--user", "enable", SYSTEMD_UNIT_NAME]))?;
+    }
+
+    #[cfg(windows)]
+    {
+        // schtasks expects the definition as UTF-16 with a byte order mark
+        let bytes: Vec<u8> = std::iter::once(0xFEFF)
+            .chain(task_scheduler_xml(binary).encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        std::fs::write(&path, bytes).map_err(|e| ServiceError::Io(e.to_string()))?;
+        run(Command::new("schtasks")
+            .args(["/Create", "/TN", TASK_NAME, "/XML"])
+            .arg(&path)
+            .arg("/F"))?;
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    let _ = binary;
+
+    Ok(path)
+}
+
+/// Unregister the service and remove its definition file.
+///
+/// Returns the path of the removed service definition.
+pub fn uninstall() -> Result<PathBuf, ServiceError> {
+    let path = service_file_path()?;
+
+    #[cfg(target_os = "macos")]
+    {
+        if path.exists() {
+            run(Command::new("launchctl").arg("unload").arg(&path))?;
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if path.exists() {
+            run(Command::new("systemctl").args(["--user", "disable", "--now", SYSTEMD_UNIT_NAME]))?;
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        if path.exists() {
+            run(Command::new("schtasks").args(["/Delete", "/TN", TASK_NAME, "/F"]))?;
+        }
+    }
+
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| ServiceError::Io(e.to_string()))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        run(Command::new("systemctl").args(["--user", "daemon-reload"]))?;
+    }
+
+    Ok(path)
+}
+
+/// Run a service manager command, failing on a non-zero exit status.
+#[allow(dead_code)]
+fn run(command: &mut Command) -> Result<(), ServiceError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| ServiceError::Command(format!("Failed to run {program}: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(ServiceError::Command(format!(
+            "{program} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Service installation errors.
+#[derive(Debug)]
+pub enum ServiceError {
+    /// Writing or removing the service definition failed
+    Io(String),
+    /// The platform service manager reported an error
+    Command(String),
+    /// No service manager integration for this platform
+    Unsupported,
+}
+
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceError::Io(msg) => write!(f, "Service file error: {msg}"),
+            ServiceError::Command(msg) => write!(f, "Service manager error: {msg}"),
+            ServiceError::Unsupported => {
+                write!(f, "Service installation is not supported on this platform")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launchd_plist() {
+        let plist = launchd_plist(Path::new("/Applications/Synheart & Co/synheart-sensor"));
+        assert!(plist.contains("<string>ai.synheart.sensor-agent</string>"));
+        assert!(plist.contains(
+            "<array>\n        <string>/Applications/Synheart &amp; Co/synheart-sensor</string>\n        <string>start</string>\n        <string>--foreground</string>\n    </array>"
+        ));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+        assert!(plist.contains("<key>KeepAlive</key>\n    <true/>"));
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = systemd_unit(Path::new("/usr/local/bin/synheart-sensor"));
+        assert!(unit.contains("ExecStart=\"/usr/local/bin/synheart-sensor\" start --foreground"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_task_scheduler_xml() {
+        let xml = task_scheduler_xml(Path::new(r"C:\Program Files\Synheart\synheart-sensor.exe"));
+        assert!(xml.contains(r"<Command>C:\Program Files\Synheart\synheart-sensor.exe</Command>"));
+        assert!(xml.contains("<Arguments>start --foreground</Arguments>"));
+        assert!(xml.contains("<LogonTrigger>"));
+    }
+}
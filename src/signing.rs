@@ -0,0 +1,204 @@
+//! HMAC signing of snapshots for tamper detection.
+//!
+//! A signed snapshot carries a hex-encoded HMAC-SHA256 in `meta.signature`.
+//! The signature covers the snapshot's `axes` and `windows`, serialized as
+//! JSON with sorted keys, so other `meta` entries can still be added after
+//! signing without invalidating it.
+
+use crate::core::hsi::HsiSnapshot;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::path::Path;
+
+/// Required length of a signing key in bytes.
+pub const SIGNING_KEY_LEN: usize = 32;
+
+/// Key of the signature entry in snapshot `meta`.
+pub const SIGNATURE_META_KEY: &str = "signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs snapshots with an HMAC-SHA256 key.
+#[derive(Clone)]
+pub struct SnapshotSigner {
+    key: [u8; SIGNING_KEY_LEN],
+}
+
+impl SnapshotSigner {
+    /// Create a signer from a 32-byte key.
+    pub fn new(key: &[u8]) -> Result<Self, SigningError> {
+        let key: [u8; SIGNING_KEY_LEN] = key
+            .try_into()
+            .map_err(|_| SigningError::InvalidKeyLength(key.len()))?;
+        Ok(Self { key })
+    }
+
+    /// Load a key file holding either 32 raw bytes or 64 hex characters.
+    pub fn from_file(path: &Path) -> Result<Self, SigningError> {
+        let bytes = std::fs::read(path).map_err(|e| SigningError::Io(e.to_string()))?;
+        match std::str::from_utf8(&bytes).ok().and_then(decode_hex_key) {
+            Some(key) => Self::new(&key),
+            None => Self::new(&bytes),
+        }
+    }
+
+    /// Add `meta.signature` to a snapshot, replacing any previous signature.
+    pub fn sign(&self, snapshot: &mut HsiSnapshot) {
+        let signature = encode_hex(&signed_mac(&self.key, snapshot).finalize().into_bytes());
+        snapshot.meta.get_or_insert_with(Default::default).insert(
+            SIGNATURE_META_KEY.to_string(),
+            serde_json::Value::String(signature),
+        );
+    }
+}
+
+impl std::fmt::Debug for SnapshotSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key
+        f.debug_struct("SnapshotSigner").finish_non_exhaustive()
+    }
+}
+
+/// Checks snapshot signatures.
+pub struct SnapshotVerifier;
+
+impl SnapshotVerifier {
+    /// Check that a snapshot carries a valid signature for `key`.
+    ///
+    /// Returns false for unsigned snapshots, malformed signatures and keys
+    /// of the wrong length.
+    pub fn verify(snapshot: &HsiSnapshot, key: &[u8]) -> bool {
+        let Ok(key) = <[u8; SIGNING_KEY_LEN]>::try_from(key) else {
+            return false;
+        };
+        let signature = snapshot
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(SIGNATURE_META_KEY))
+            .and_then(|value| value.as_str())
+            .and_then(decode_hex);
+        match signature {
+            Some(signature) => signed_mac(&key, snapshot).verify_slice(&signature).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Start an HMAC over the signed parts of a snapshot.
+fn signed_mac(key: &[u8; SIGNING_KEY_LEN], snapshot: &HsiSnapshot) -> HmacSha256 {
+    // serde_json objects keep keys sorted, so the encoding is independent of HashMap order
+    let payload = serde_json::json!({
+        "axes": snapshot.axes,
+        "windows": snapshot.windows,
+    });
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(payload.to_string().as_bytes());
+    mac
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn decode_hex_key(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    if text.len() == SIGNING_KEY_LEN * 2 {
+        decode_hex(text)
+    } else {
+        None
+    }
+}
+
+/// Signing errors.
+#[derive(Debug)]
+pub enum SigningError {
+    /// The key is not `SIGNING_KEY_LEN` bytes long
+    InvalidKeyLength(usize),
+    /// The key file could not be read
+    Io(String),
+}
+
+impl std::fmt::Display for SigningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningError::InvalidKeyLength(len) => write!(
+                f,
+                "Signing key must be {SIGNING_KEY_LEN} bytes (or {} hex characters), got {len} bytes",
+                SIGNING_KEY_LEN * 2
+            ),
+            SigningError::Io(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SigningError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_snapshot;
+
+    const KEY: [u8; SIGNING_KEY_LEN] = [7; SIGNING_KEY_LEN];
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut snapshot = sample_snapshot();
+        assert!(!SnapshotVerifier::verify(&snapshot, &KEY));
+
+        SnapshotSigner::new(&KEY).unwrap().sign(&mut snapshot);
+        assert!(SnapshotVerifier::verify(&snapshot, &KEY));
+        assert!(!SnapshotVerifier::verify(&snapshot, &[8; SIGNING_KEY_LEN]));
+
+        // Survives a JSON round trip and unrelated meta changes
+        let mut decoded: HsiSnapshot =
+            serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        decoded
+            .meta
+            .as_mut()
+            .unwrap()
+            .insert("note".to_string(), serde_json::json!("added later"));
+        assert!(SnapshotVerifier::verify(&decoded, &KEY));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let mut snapshot = sample_snapshot();
+        SnapshotSigner::new(&KEY).unwrap().sign(&mut snapshot);
+
+        let behavior = snapshot
+            .axes
+            .as_mut()
+            .and_then(|axes| axes.behavior.as_mut())
+            .unwrap();
+        behavior.readings[0].score = Some(0.99);
+        assert!(!SnapshotVerifier::verify(&snapshot, &KEY));
+    }
+
+    #[test]
+    fn test_key_length_and_hex_key_file() {
+        assert!(matches!(
+            SnapshotSigner::new(&[0; 16]),
+            Err(SigningError::InvalidKeyLength(16))
+        ));
+
+        let path = std::env::temp_dir().join(format!("signing_key_{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("{}\n", encode_hex(&KEY))).unwrap();
+        let mut snapshot = sample_snapshot();
+        SnapshotSigner::from_file(&path)
+            .unwrap()
+            .sign(&mut snapshot);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(SnapshotVerifier::verify(&snapshot, &KEY));
+    }
+}
@@ -0,0 +1,203 @@
+//! Queryable persistence for HSI snapshots.
+//!
+//! The `SnapshotStore` trait abstracts over storage backends. The SQLite
+//! implementation (requires the `sqlite` feature) keeps each snapshot as a
+//! serialized JSON blob alongside an indexed observation timestamp, so
+//! historical sessions can be queried by time range.
+
+use crate::core::hsi::HsiSnapshot;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Result type for snapshot store operations.
+pub type Result<T> = std::result::Result<T, StoreError>;
+
+/// Where session snapshots are persisted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// One JSON (or JSONL) file per session in the export directory
+    #[default]
+    Json,
+    /// A single SQLite database in the data directory
+    Sqlite,
+}
+
+/// A persistent collection of HSI snapshots.
+pub trait SnapshotStore {
+    /// Store a snapshot.
+    fn insert(&self, snapshot: &HsiSnapshot) -> Result<()>;
+
+    /// Get all snapshots observed in `[start, end)`, oldest first.
+    fn query_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<HsiSnapshot>>;
+}
+
+/// Snapshot store errors.
+#[derive(Debug)]
+pub enum StoreError {
+    /// Database open/query error
+    Database(String),
+    /// Snapshot (de)serialization error
+    Serialization(String),
+    /// Snapshot has an unparseable `observed_at_utc` timestamp
+    InvalidTimestamp(String),
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Database(msg) => write!(f, "Store database error: {msg}"),
+            StoreError::Serialization(msg) => write!(f, "Store serialization error: {msg}"),
+            StoreError::InvalidTimestamp(msg) => write!(f, "Store invalid timestamp: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSnapshotStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{Result, SnapshotStore, StoreError};
+    use crate::core::hsi::HsiSnapshot;
+    use chrono::{DateTime, Utc};
+    use rusqlite::{params, Connection};
+    use std::path::Path;
+
+    /// SQLite-backed snapshot store.
+    ///
+    /// `observed_at_utc` is stored as Unix milliseconds so range queries
+    /// compare numerically and can use the index.
+    pub struct SqliteSnapshotStore {
+        conn: Connection,
+    }
+
+    impl SqliteSnapshotStore {
+        /// Open (or create) a store at the given database path.
+        pub fn open(path: &Path) -> Result<Self> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| StoreError::Database(e.to_string()))?;
+            }
+            let conn = Connection::open(path).map_err(|e| StoreError::Database(e.to_string()))?;
+            Self::with_connection(conn)
+        }
+
+        /// Open a transient in-memory store.
+        pub fn open_in_memory() -> Result<Self> {
+            let conn =
+                Connection::open_in_memory().map_err(|e| StoreError::Database(e.to_string()))?;
+            Self::with_connection(conn)
+        }
+
+        fn with_connection(conn: Connection) -> Result<Self> {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS snapshots (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    observed_at_utc INTEGER NOT NULL,
+                    snapshot TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_snapshots_observed_at
+                    ON snapshots (observed_at_utc);",
+            )
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+            Ok(Self { conn })
+        }
+    }
+
+    impl SnapshotStore for SqliteSnapshotStore {
+        fn insert(&self, snapshot: &HsiSnapshot) -> Result<()> {
+            let observed_at = DateTime::parse_from_rfc3339(&snapshot.observed_at_utc)
+                .map_err(|e| StoreError::InvalidTimestamp(e.to_string()))?;
+            let json = serde_json::to_string(snapshot)
+                .map_err(|e| StoreError::Serialization(e.to_string()))?;
+
+            self.conn
+                .execute(
+                    "INSERT INTO snapshots (observed_at_utc, snapshot) VALUES (?1, ?2)",
+                    params![observed_at.timestamp_millis(), json],
+                )
+                .map_err(|e| StoreError::Database(e.to_string()))?;
+            Ok(())
+        }
+
+        fn query_range(
+            &self,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> Result<Vec<HsiSnapshot>> {
+            let mut stmt = self
+                .conn
+                .prepare(
+                    "SELECT snapshot FROM snapshots
+                     WHERE observed_at_utc >= ?1 AND observed_at_utc < ?2
+                     ORDER BY observed_at_utc, id",
+                )
+                .map_err(|e| StoreError::Database(e.to_string()))?;
+
+            let rows = stmt
+                .query_map(
+                    params![start.timestamp_millis(), end.timestamp_millis()],
+                    |row| row.get::<_, String>(0),
+                )
+                .map_err(|e| StoreError::Database(e.to_string()))?;
+
+            let mut snapshots = Vec::new();
+            for row in rows {
+                let json = row.map_err(|e| StoreError::Database(e.to_string()))?;
+                snapshots.push(
+                    serde_json::from_str(&json)
+                        .map_err(|e| StoreError::Serialization(e.to_string()))?,
+                );
+            }
+            Ok(snapshots)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_backend_serde() {
+        assert_eq!(
+            serde_json::to_value(StorageBackend::Sqlite).unwrap(),
+            serde_json::json!("sqlite")
+        );
+        assert_eq!(StorageBackend::default(), StorageBackend::Json);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_insert_and_query_range() {
+        use crate::core::features::{compute_features, FeatureConfig};
+        use crate::core::hsi::HsiBuilder;
+        use crate::core::windowing::EventWindow;
+        use chrono::Duration;
+
+        let store = SqliteSnapshotStore::open_in_memory().unwrap();
+        let builder = HsiBuilder::new();
+        let start = Utc::now();
+
+        for i in 0..3 {
+            let window = EventWindow::new(start + Duration::seconds(10 * i), Duration::seconds(10));
+            let features = compute_features(&window, &FeatureConfig::default());
+            let snapshot = builder.build(&window, &features, Duration::seconds(10), None);
+            store.insert(&snapshot).unwrap();
+        }
+
+        // Windows end at +10s, +20s and +30s
+        let all = store
+            .query_range(start, start + Duration::seconds(60))
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].observed_at_utc < all[2].observed_at_utc);
+
+        let middle = store
+            .query_range(start + Duration::seconds(15), start + Duration::seconds(25))
+            .unwrap();
+        assert_eq!(middle.len(), 1);
+    }
+}
@@ -0,0 +1,270 @@
+//! Helpers for comparing computed features in tests.
+//!
+//! `WindowFeatures` derives `PartialEq`, but NaN never equals itself and
+//! floating-point results can differ in the last bits between code paths.
+//! [`assert_features_approx_eq!`](crate::assert_features_approx_eq) compares
+//! every field within an epsilon and treats two NaNs as equal.
+//!
+//! Unit tests also share their snapshot fixtures from here.
+
+use crate::core::features::{BehavioralSignals, KeyboardFeatures, MouseFeatures, WindowFeatures};
+#[cfg(test)]
+use crate::core::hsi::HsiSnapshot;
+use std::fmt::Debug;
+
+/// Build the snapshot of an empty 10-second window starting now.
+#[cfg(test)]
+pub(crate) fn sample_snapshot() -> HsiSnapshot {
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use crate::core::windowing::EventWindow;
+    use chrono::{Duration, Utc};
+
+    let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+    let features = compute_features(&window, &FeatureConfig::default());
+    HsiBuilder::new().build(&window, &features, Duration::seconds(10), None)
+}
+
+/// Assert that two `WindowFeatures` are equal, with float fields compared
+/// within `epsilon`.
+///
+/// On failure the panic message lists every field that differs.
+#[macro_export]
+macro_rules! assert_features_approx_eq {
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        if let Err(mismatches) = $crate::test_utils::features_approx_eq(&$left, &$right, $epsilon) {
+            panic!(
+                "features differ by more than {}:\n  {}",
+                $epsilon,
+                mismatches.join("\n  ")
+            );
+        }
+    };
+}
+
+/// Compare the listed fields of a feature struct.
+///
+/// The struct is destructured without `..`, so adding a field fails to compile
+/// until it is listed here.
+macro_rules! compare_fields {
+    (
+        $mismatches:expr, $epsilon:expr, $prefix:literal, $ty:ident, $left:expr, $right:expr,
+        floats: [$($float:ident),* $(,)?],
+        exact: [$($exact:ident),* $(,)?] $(,)?
+    ) => {{
+        let $ty { $($float: _,)* $($exact: _,)* } = $left;
+        $(
+            compare_f64(
+                $mismatches,
+                concat!($prefix, stringify!($float)),
+                $left.$float,
+                $right.$float,
+                $epsilon,
+            );
+        )*
+        $(
+            compare_exact(
+                $mismatches,
+                concat!($prefix, stringify!($exact)),
+                &$left.$exact,
+                &$right.$exact,
+            );
+        )*
+    }};
+}
+
+/// Compare two `WindowFeatures`, returning a description of each field that
+/// differs.
+///
+/// Float fields match when both are NaN, equal (including infinities), or
+/// within `epsilon` of each other. Other fields must be equal.
+pub fn features_approx_eq(
+    left: &WindowFeatures,
+    right: &WindowFeatures,
+    epsilon: f64,
+) -> Result<(), Vec<String>> {
+    let WindowFeatures {
+        keyboard,
+        mouse,
+        behavioral,
+        rhythm_class,
+        mouse_class,
+        data_quality,
+    } = left;
+
+    let mut mismatches = Vec::new();
+    compare_fields!(
+        &mut mismatches,
+        epsilon,
+        "keyboard.",
+        KeyboardFeatures,
+        keyboard,
+        &right.keyboard,
+        floats: [
+            typing_rate,
+            mean_pause_ms,
+            latency_variability,
+            iki_p25_ms,
+            iki_p50_ms,
+            iki_p75_ms,
+            iki_p95_ms,
+            iki_entropy,
+            iki_autocorrelation_lag1,
+            hold_time_mean,
+            burst_index,
+            session_continuity,
+            typing_cadence_stability,
+            typing_gap_ratio,
+            typing_interaction_intensity,
+            keyboard_scroll_rate,
+            modifier_active_tap_ratio,
+        ],
+        exact: [
+            pause_count,
+            typing_tap_count,
+            navigation_key_count,
+            pause_histogram,
+        ],
+    );
+    compare_fields!(
+        &mut mismatches,
+        epsilon,
+        "mouse.",
+        MouseFeatures,
+        mouse,
+        &right.mouse,
+        floats: [
+            mouse_activity_rate,
+            mean_velocity,
+            velocity_variability,
+            velocity_p25,
+            velocity_p50,
+            velocity_p75,
+            velocity_p95,
+            velocity_p99,
+            click_rate,
+            click_hold_mean_ms,
+            click_hold_variability,
+            scroll_rate,
+            scroll_reversal_rate,
+            idle_ratio,
+            micro_adjustment_ratio,
+            drag_movement_ratio,
+            middle_click_rate,
+            trackpad_scroll_rate,
+            trackpad_pinch_rate,
+            scroll_to_click_ratio,
+        ],
+        exact: [
+            acceleration_spikes,
+            scroll_direction_reversals,
+            idle_time_ms,
+            drag_episode_count,
+            dominant_modality,
+        ],
+    );
+    compare_fields!(
+        &mut mismatches,
+        epsilon,
+        "behavioral.",
+        BehavioralSignals,
+        behavioral,
+        &right.behavioral,
+        floats: [
+            interaction_rhythm,
+            friction,
+            motor_stability,
+            focus_continuity_proxy,
+            burstiness,
+            keyboard_confidence,
+            mouse_confidence,
+        ],
+        exact: [deep_focus_block],
+    );
+    compare_exact(
+        &mut mismatches,
+        "rhythm_class",
+        rhythm_class,
+        &right.rhythm_class,
+    );
+    compare_exact(
+        &mut mismatches,
+        "mouse_class",
+        mouse_class,
+        &right.mouse_class,
+    );
+    compare_exact(
+        &mut mismatches,
+        "data_quality",
+        data_quality,
+        &right.data_quality,
+    );
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+fn compare_f64(mismatches: &mut Vec<String>, name: &str, left: f64, right: f64, epsilon: f64) {
+    let matches =
+        left == right || (left.is_nan() && right.is_nan()) || (left - right).abs() <= epsilon;
+    if !matches {
+        mismatches.push(format!("{name}: {left} != {right}"));
+    }
+}
+
+fn compare_exact<T: PartialEq + Debug>(
+    mismatches: &mut Vec<String>,
+    name: &str,
+    left: &T,
+    right: &T,
+) {
+    if left != right {
+        mismatches.push(format!("{name}: {left:?} != {right:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features_within_epsilon_match() {
+        let left = WindowFeatures::default();
+        let mut right = left.clone();
+        right.keyboard.typing_rate += 1e-12;
+        right.mouse.mean_velocity = f64::NAN;
+
+        let mut left_nan = left.clone();
+        left_nan.mouse.mean_velocity = f64::NAN;
+        assert_features_approx_eq!(left_nan, right, 1e-9);
+    }
+
+    #[test]
+    fn test_features_mismatch_lists_fields() {
+        let left = WindowFeatures::default();
+        let mut right = left.clone();
+        right.keyboard.typing_rate = 2.0;
+        right.mouse.idle_time_ms = 5;
+
+        let mismatches = features_approx_eq(&left, &right, 1e-9).unwrap_err();
+        assert_eq!(
+            mismatches,
+            vec![
+                "keyboard.typing_rate: 0 != 2".to_string(),
+                "mouse.idle_time_ms: 0 != 5".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "keyboard.typing_rate")]
+    fn test_assert_features_approx_eq_panics() {
+        let left = WindowFeatures::default();
+        let mut right = left.clone();
+        right.keyboard.typing_rate = 2.0;
+        assert_features_approx_eq!(left, right, 1e-9);
+    }
+}
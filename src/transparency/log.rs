@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Transparency statistics for the current session.
 #[derive(Debug)]
@@ -20,6 +20,27 @@ pub struct TransparencyLog {
     windows_completed: AtomicU64,
     /// Number of HSI snapshots exported
     snapshots_exported: AtomicU64,
+    /// Number of HSI snapshots deleted by the user
+    snapshots_deleted: AtomicU64,
+    /// Number of events dropped because the event buffer was full
+    events_dropped: AtomicU64,
+    /// Number of out-of-order events dropped because their clock-aligned
+    /// window had already completed
+    late_events_dropped: AtomicU64,
+    /// Number of successful gateway syncs
+    gateway_syncs_succeeded: AtomicU64,
+    /// Number of failed gateway syncs
+    gateway_syncs_failed: AtomicU64,
+    /// Number of snapshots delivered to the gateway
+    gateway_snapshots_synced: AtomicU64,
+    /// When the last gateway sync was attempted (Unix milliseconds, 0 if none)
+    last_sync_at: AtomicU64,
+    /// When the last keyboard event was recorded (Unix milliseconds, 0 if none)
+    last_keyboard_event: AtomicU64,
+    /// When the last mouse event was recorded (Unix milliseconds, 0 if none)
+    last_mouse_event: AtomicU64,
+    /// When the collector's capture thread was restarted after a panic
+    collector_restarts: Mutex<Vec<DateTime<Utc>>>,
     /// Session start time
     session_start: DateTime<Utc>,
     /// Path for persisting stats
@@ -34,6 +55,16 @@ impl TransparencyLog {
             mouse_events: AtomicU64::new(0),
             windows_completed: AtomicU64::new(0),
             snapshots_exported: AtomicU64::new(0),
+            snapshots_deleted: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
+            late_events_dropped: AtomicU64::new(0),
+            gateway_syncs_succeeded: AtomicU64::new(0),
+            gateway_syncs_failed: AtomicU64::new(0),
+            gateway_snapshots_synced: AtomicU64::new(0),
+            last_sync_at: AtomicU64::new(0),
+            last_keyboard_event: AtomicU64::new(0),
+            last_mouse_event: AtomicU64::new(0),
+            collector_restarts: Mutex::new(Vec::new()),
             session_start: Utc::now(),
             persist_path: None,
         }
@@ -54,22 +85,29 @@ impl TransparencyLog {
 
     /// Record a keyboard event.
     pub fn record_keyboard_event(&self) {
-        self.keyboard_events.fetch_add(1, Ordering::Relaxed);
+        self.record_keyboard_events(1);
     }
 
     /// Record multiple keyboard events.
     pub fn record_keyboard_events(&self, count: u64) {
         self.keyboard_events.fetch_add(count, Ordering::Relaxed);
+        if count > 0 {
+            self.last_keyboard_event
+                .store(now_millis(), Ordering::Relaxed);
+        }
     }
 
     /// Record a mouse event.
     pub fn record_mouse_event(&self) {
-        self.mouse_events.fetch_add(1, Ordering::Relaxed);
+        self.record_mouse_events(1);
     }
 
     /// Record multiple mouse events.
     pub fn record_mouse_events(&self, count: u64) {
         self.mouse_events.fetch_add(count, Ordering::Relaxed);
+        if count > 0 {
+            self.last_mouse_event.store(now_millis(), Ordering::Relaxed);
+        }
     }
 
     /// Record a completed window.
@@ -82,6 +120,44 @@ impl TransparencyLog {
         self.snapshots_exported.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record snapshots removed by deleting a session file.
+    pub fn record_snapshots_deleted(&self, count: u64) {
+        self.snapshots_deleted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Record events lost because the event buffer overflowed.
+    pub fn record_events_dropped(&self, count: u64) {
+        self.events_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Record out-of-order events whose clock-aligned window had already
+    /// completed.
+    pub fn record_late_events_dropped(&self, count: u64) {
+        self.late_events_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Record a gateway sync of `snapshot_count` snapshots.
+    ///
+    /// A failed sync is one that failed after all retries; its snapshots are
+    /// not counted as delivered.
+    pub fn record_gateway_sync(&self, snapshot_count: usize, success: bool) {
+        if success {
+            self.gateway_syncs_succeeded.fetch_add(1, Ordering::Relaxed);
+            self.gateway_snapshots_synced
+                .fetch_add(snapshot_count as u64, Ordering::Relaxed);
+        } else {
+            self.gateway_syncs_failed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.last_sync_at.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// Record a restart of the collector's capture thread.
+    pub fn record_collector_restart(&self, at: DateTime<Utc>) {
+        if let Ok(mut restarts) = self.collector_restarts.lock() {
+            restarts.push(at);
+        }
+    }
+
     /// Get the current statistics.
     pub fn stats(&self) -> TransparencyStats {
         TransparencyStats {
@@ -89,20 +165,55 @@ impl TransparencyLog {
             mouse_events: self.mouse_events.load(Ordering::Relaxed),
             windows_completed: self.windows_completed.load(Ordering::Relaxed),
             snapshots_exported: self.snapshots_exported.load(Ordering::Relaxed),
+            snapshots_deleted: self.snapshots_deleted.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            late_events_dropped: self.late_events_dropped.load(Ordering::Relaxed),
+            gateway_syncs_succeeded: self.gateway_syncs_succeeded.load(Ordering::Relaxed),
+            gateway_syncs_failed: self.gateway_syncs_failed.load(Ordering::Relaxed),
+            gateway_snapshots_synced: self.gateway_snapshots_synced.load(Ordering::Relaxed),
+            last_sync_at: from_millis(self.last_sync_at.load(Ordering::Relaxed)),
+            collector_restarts: self
+                .collector_restarts
+                .lock()
+                .map(|restarts| restarts.clone())
+                .unwrap_or_default(),
+            last_keyboard_event_at: from_millis(self.last_keyboard_event.load(Ordering::Relaxed)),
+            last_mouse_event_at: from_millis(self.last_mouse_event.load(Ordering::Relaxed)),
             session_start: self.session_start,
             session_duration_secs: (Utc::now() - self.session_start).num_seconds() as u64,
         }
     }
 
     /// Get a summary string for display.
+    ///
+    /// Data loss from dropped events is called out before the statistics.
     pub fn summary(&self) -> String {
         let stats = self.stats();
+        let data_loss = if stats.events_dropped > 0 {
+            format!(
+                "WARNING: {} events dropped (event buffer full); \
+                 affected windows are incomplete\n\n",
+                stats.events_dropped
+            )
+        } else {
+            String::new()
+        };
+        let last_sync = match stats.last_sync_at {
+            Some(at) => format!("last sync {}", describe_elapsed(Utc::now() - at)),
+            None => "no sync yet".to_string(),
+        };
         format!(
-            "Session Statistics:\n\
+            "{data_loss}Session Statistics:\n\
              - Keyboard events processed: {}\n\
              - Mouse events processed: {}\n\
              - Windows completed: {}\n\
              - Snapshots exported: {}\n\
+             - Snapshots deleted: {}\n\
+             - Events dropped: {}\n\
+             - Late events dropped: {}\n\
+             - Collector restarts: {}\n\
+             - Gateway syncs: {} succeeded, {} failed, {last_sync}\n\
+             - Snapshots sent to gateway: {}\n\
              - Session duration: {} seconds\n\
              \n\
              Privacy Guarantee:\n\
@@ -113,6 +224,13 @@ impl TransparencyLog {
             stats.mouse_events,
             stats.windows_completed,
             stats.snapshots_exported,
+            stats.snapshots_deleted,
+            stats.events_dropped,
+            stats.late_events_dropped,
+            stats.collector_restarts.len(),
+            stats.gateway_syncs_succeeded,
+            stats.gateway_syncs_failed,
+            stats.gateway_snapshots_synced,
             stats.session_duration_secs
         )
     }
@@ -131,6 +249,13 @@ impl TransparencyLog {
                 mouse_events: stats.mouse_events,
                 windows_completed: stats.windows_completed,
                 snapshots_exported: stats.snapshots_exported,
+                snapshots_deleted: stats.snapshots_deleted,
+                events_dropped: stats.events_dropped,
+                late_events_dropped: stats.late_events_dropped,
+                gateway_syncs_succeeded: stats.gateway_syncs_succeeded,
+                gateway_syncs_failed: stats.gateway_syncs_failed,
+                gateway_snapshots_synced: stats.gateway_snapshots_synced,
+                last_sync_at: stats.last_sync_at,
                 last_updated: Utc::now(),
             };
 
@@ -158,6 +283,22 @@ impl TransparencyLog {
                     .store(persisted.windows_completed, Ordering::Relaxed);
                 self.snapshots_exported
                     .store(persisted.snapshots_exported, Ordering::Relaxed);
+                self.snapshots_deleted
+                    .store(persisted.snapshots_deleted, Ordering::Relaxed);
+                self.events_dropped
+                    .store(persisted.events_dropped, Ordering::Relaxed);
+                self.late_events_dropped
+                    .store(persisted.late_events_dropped, Ordering::Relaxed);
+                self.gateway_syncs_succeeded
+                    .store(persisted.gateway_syncs_succeeded, Ordering::Relaxed);
+                self.gateway_syncs_failed
+                    .store(persisted.gateway_syncs_failed, Ordering::Relaxed);
+                self.gateway_snapshots_synced
+                    .store(persisted.gateway_snapshots_synced, Ordering::Relaxed);
+                if let Some(at) = persisted.last_sync_at {
+                    self.last_sync_at
+                        .store(at.timestamp_millis().max(0) as u64, Ordering::Relaxed);
+                }
             }
         }
         Ok(())
@@ -169,6 +310,18 @@ impl TransparencyLog {
         self.mouse_events.store(0, Ordering::Relaxed);
         self.windows_completed.store(0, Ordering::Relaxed);
         self.snapshots_exported.store(0, Ordering::Relaxed);
+        self.snapshots_deleted.store(0, Ordering::Relaxed);
+        self.events_dropped.store(0, Ordering::Relaxed);
+        self.late_events_dropped.store(0, Ordering::Relaxed);
+        self.gateway_syncs_succeeded.store(0, Ordering::Relaxed);
+        self.gateway_syncs_failed.store(0, Ordering::Relaxed);
+        self.gateway_snapshots_synced.store(0, Ordering::Relaxed);
+        self.last_sync_at.store(0, Ordering::Relaxed);
+        self.last_keyboard_event.store(0, Ordering::Relaxed);
+        self.last_mouse_event.store(0, Ordering::Relaxed);
+        if let Ok(mut restarts) = self.collector_restarts.lock() {
+            restarts.clear();
+        }
     }
 }
 
@@ -185,10 +338,68 @@ pub struct TransparencyStats {
     pub mouse_events: u64,
     pub windows_completed: u64,
     pub snapshots_exported: u64,
+    pub snapshots_deleted: u64,
+    pub events_dropped: u64,
+    /// Out-of-order events dropped because their clock-aligned window had
+    /// already completed
+    #[serde(default)]
+    pub late_events_dropped: u64,
+    /// Gateway syncs that delivered their snapshots
+    #[serde(default)]
+    pub gateway_syncs_succeeded: u64,
+    /// Gateway syncs that failed after all retries
+    #[serde(default, alias = "gateway_sync_errors")]
+    pub gateway_syncs_failed: u64,
+    /// Snapshots delivered to the gateway
+    #[serde(default)]
+    pub gateway_snapshots_synced: u64,
+    /// When the last gateway sync was attempted
+    #[serde(default)]
+    pub last_sync_at: Option<DateTime<Utc>>,
+    /// Capture thread restarts during this session
+    pub collector_restarts: Vec<DateTime<Utc>>,
+    /// When the last keyboard event was recorded this session
+    #[serde(default)]
+    pub last_keyboard_event_at: Option<DateTime<Utc>>,
+    /// When the last mouse event was recorded this session
+    #[serde(default)]
+    pub last_mouse_event_at: Option<DateTime<Utc>>,
     pub session_start: DateTime<Utc>,
     pub session_duration_secs: u64,
 }
 
+impl TransparencyStats {
+    /// When the last event of either kind was recorded this session.
+    pub fn last_event_at(&self) -> Option<DateTime<Utc>> {
+        self.last_keyboard_event_at.max(self.last_mouse_event_at)
+    }
+}
+
+/// Describe how long ago something happened, e.g. "3 seconds ago".
+pub fn describe_elapsed(elapsed: chrono::Duration) -> String {
+    let secs = elapsed.num_seconds().max(0);
+    let (value, unit) = match secs {
+        0..=59 => (secs, "second"),
+        60..=3599 => (secs / 60, "minute"),
+        _ => (secs / 3600, "hour"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{value} {unit}{plural} ago")
+}
+
+/// Current time in Unix milliseconds.
+fn now_millis() -> u64 {
+    Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// Convert Unix milliseconds back to a timestamp, with 0 meaning never.
+fn from_millis(millis: u64) -> Option<DateTime<Utc>> {
+    if millis == 0 {
+        return None;
+    }
+    DateTime::from_timestamp_millis(millis as i64)
+}
+
 /// Stats format for persistence.
 #[derive(Debug, Serialize, Deserialize)]
 struct PersistedStats {
@@ -196,6 +407,20 @@ struct PersistedStats {
     mouse_events: u64,
     windows_completed: u64,
     snapshots_exported: u64,
+    #[serde(default)]
+    snapshots_deleted: u64,
+    #[serde(default)]
+    events_dropped: u64,
+    #[serde(default)]
+    late_events_dropped: u64,
+    #[serde(default)]
+    gateway_syncs_succeeded: u64,
+    #[serde(default, alias = "gateway_sync_errors")]
+    gateway_syncs_failed: u64,
+    #[serde(default)]
+    gateway_snapshots_synced: u64,
+    #[serde(default)]
+    last_sync_at: Option<DateTime<Utc>>,
     last_updated: DateTime<Utc>,
 }
 
@@ -229,6 +454,26 @@ mod tests {
         assert_eq!(stats.mouse_events, 1);
     }
 
+    #[test]
+    fn test_last_event_timestamps() {
+        let log = TransparencyLog::new();
+        let stats = log.stats();
+        assert_eq!(stats.last_keyboard_event_at, None);
+        assert_eq!(stats.last_event_at(), None);
+
+        let before = Utc::now() - chrono::Duration::milliseconds(1);
+        log.record_mouse_event();
+        log.record_keyboard_events(0);
+        let stats = log.stats();
+        assert_eq!(stats.last_keyboard_event_at, None);
+        let last_mouse = stats.last_mouse_event_at.unwrap();
+        assert!(last_mouse >= before && last_mouse <= Utc::now());
+        assert_eq!(stats.last_event_at(), Some(last_mouse));
+
+        log.reset();
+        assert_eq!(log.stats().last_mouse_event_at, None);
+    }
+
     #[test]
     fn test_transparency_log_reset() {
         let log = TransparencyLog::new();
@@ -242,6 +487,132 @@ mod tests {
         assert_eq!(stats.mouse_events, 0);
     }
 
+    #[test]
+    fn test_snapshots_deleted_persisted() {
+        let path = std::env::temp_dir().join(format!(
+            "synheart-transparency-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let log = TransparencyLog::with_persistence(path.clone());
+        log.record_snapshots_deleted(7);
+        log.save().unwrap();
+
+        let reloaded = TransparencyLog::with_persistence(path.clone());
+        assert_eq!(reloaded.stats().snapshots_deleted, 7);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_events_dropped_counting() {
+        let log = TransparencyLog::new();
+        assert!(!log.summary().contains("WARNING"));
+
+        log.record_events_dropped(3);
+        log.record_events_dropped(2);
+        assert_eq!(log.stats().events_dropped, 5);
+        assert!(log.summary().starts_with("WARNING: 5 events dropped"));
+        assert!(log.summary().contains("Events dropped: 5"));
+
+        log.reset();
+        assert_eq!(log.stats().events_dropped, 0);
+    }
+
+    #[test]
+    fn test_events_dropped_persisted() {
+        let path = std::env::temp_dir().join(format!(
+            "synheart-transparency-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let log = TransparencyLog::with_persistence(path.clone());
+        log.record_events_dropped(42);
+        log.save().unwrap();
+
+        let persisted: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(persisted["events_dropped"], 42);
+
+        // Loss accumulates across sessions
+        let reloaded = TransparencyLog::with_persistence(path.clone());
+        reloaded.record_events_dropped(8);
+        assert_eq!(reloaded.stats().events_dropped, 50);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_late_events_dropped_counting() {
+        let path = std::env::temp_dir().join(format!(
+            "synheart-transparency-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let log = TransparencyLog::with_persistence(path.clone());
+        log.record_late_events_dropped(2);
+        log.record_late_events_dropped(1);
+        assert_eq!(log.stats().late_events_dropped, 3);
+        // Kept apart from events lost to a full buffer
+        assert_eq!(log.stats().events_dropped, 0);
+        assert!(!log.summary().contains("WARNING"));
+        assert!(log.summary().contains("Late events dropped: 3"));
+        log.save().unwrap();
+
+        let reloaded = TransparencyLog::with_persistence(path.clone());
+        assert_eq!(reloaded.stats().late_events_dropped, 3);
+        reloaded.reset();
+        assert_eq!(reloaded.stats().late_events_dropped, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_collector_restarts_recorded() {
+        let log = TransparencyLog::new();
+        let at = Utc::now();
+
+        log.record_collector_restart(at);
+        assert_eq!(log.stats().collector_restarts, vec![at]);
+        assert!(log.summary().contains("Collector restarts: 1"));
+    }
+
+    #[test]
+    fn test_gateway_syncs_recorded() {
+        let log = TransparencyLog::new();
+        assert!(log
+            .summary()
+            .contains("Gateway syncs: 0 succeeded, 0 failed, no sync yet"));
+
+        log.record_gateway_sync(3, true);
+        log.record_gateway_sync(2, false);
+        let stats = log.stats();
+        assert_eq!(stats.gateway_syncs_succeeded, 1);
+        assert_eq!(stats.gateway_syncs_failed, 1);
+        assert_eq!(stats.gateway_snapshots_synced, 3);
+        assert!(stats.last_sync_at.is_some());
+        assert!(log
+            .summary()
+            .contains("Gateway syncs: 1 succeeded, 1 failed, last sync 0 seconds ago"));
+    }
+
+    #[test]
+    fn test_describe_elapsed() {
+        assert_eq!(
+            describe_elapsed(chrono::Duration::seconds(1)),
+            "1 second ago"
+        );
+        assert_eq!(
+            describe_elapsed(chrono::Duration::seconds(45)),
+            "45 seconds ago"
+        );
+        assert_eq!(
+            describe_elapsed(chrono::Duration::seconds(150)),
+            "2 minutes ago"
+        );
+        assert_eq!(describe_elapsed(chrono::Duration::hours(3)), "3 hours ago");
+    }
+
     #[test]
     fn test_summary_format() {
         let log = TransparencyLog::new();
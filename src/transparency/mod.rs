@@ -5,8 +5,14 @@
 
 pub mod log;
 
+#[cfg(feature = "transparency-server")]
+pub mod server;
+
 // Re-export commonly used types
 pub use log::{
-    create_shared_log, create_shared_log_with_persistence, SharedTransparencyLog, TransparencyLog,
-    TransparencyStats,
+    create_shared_log, create_shared_log_with_persistence, describe_elapsed, SharedTransparencyLog,
+    TransparencyLog, TransparencyStats,
 };
+
+#[cfg(feature = "transparency-server")]
+pub use server::serve_transparency;
@@ -0,0 +1,85 @@
+//! Read-only HTTP endpoint for auditing collection while the agent runs.
+//!
+//! - `GET /transparency` returns the current [`TransparencyStats`] as JSON
+//! - `GET /transparency/summary` returns the same text summary printed on exit
+//!
+//! The server only binds to localhost and runs on a single-threaded runtime,
+//! so it adds one thread to the agent.
+
+use super::log::{SharedTransparencyLog, TransparencyStats};
+use axum::{extract::State, routing::get, Json, Router};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+/// Serve the transparency endpoints on `127.0.0.1:<port>`.
+///
+/// Blocks the calling thread until the server fails, so run it on a
+/// dedicated thread.
+pub fn serve_transparency(log: SharedTransparencyLog, port: u16) -> std::io::Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+        serve(listener, log).await
+    })
+}
+
+/// Serve the transparency endpoints on an already bound listener.
+async fn serve(listener: TcpListener, log: SharedTransparencyLog) -> std::io::Result<()> {
+    let app = Router::new()
+        .route("/transparency", get(stats))
+        .route("/transparency/summary", get(summary))
+        .with_state(log);
+    axum::serve(listener, app).await
+}
+
+/// GET /transparency
+async fn stats(State(log): State<SharedTransparencyLog>) -> Json<TransparencyStats> {
+    Json(log.stats())
+}
+
+/// GET /transparency/summary
+async fn summary(State(log): State<SharedTransparencyLog>) -> String {
+    log.summary()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transparency::create_shared_log;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request =
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_transparency_endpoints() {
+        let log = create_shared_log();
+        log.record_keyboard_events(42);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, log.clone()));
+
+        let response = get(addr, "/transparency").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("application/json"));
+        assert!(response.contains("\"keyboard_events\":42"));
+
+        // Reflects events recorded after startup
+        log.record_mouse_events(7);
+        let response = get(addr, "/transparency/summary").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("text/plain"));
+        assert!(response.contains("Keyboard events processed: 42"));
+        assert!(response.contains("Mouse events processed: 7"));
+    }
+}
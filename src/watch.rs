@@ -0,0 +1,338 @@
+//! Live view of the latest window's features.
+//!
+//! `synheart-sensor watch` asks the running agent for its latest completed
+//! window over the control socket or, when no agent answers, reads the last
+//! snapshot of the most recently written streaming (`.jsonl`) session file
+//! named by the export filename pattern.
+//! [`render_dashboard`] formats the readout as an ANSI table that fits in
+//! 80 columns.
+
+use crate::core::features::WindowFeatures;
+use crate::core::hsi::HsiSnapshot;
+use crate::core::windowing::EventWindow;
+use crate::export::{self, ExportError, FileNamePattern};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Clear the terminal and move the cursor to the top left.
+pub const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Width of the dashboard in columns.
+pub const DASHBOARD_WIDTH: usize = 80;
+
+/// Typing rate shown as a full bar, matching the `typing_rate` axis scaling.
+const TYPING_RATE_FULL_SCALE: f64 = 10.0;
+
+/// Width of the bar column in cells.
+const BAR_WIDTH: usize = 40;
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// The features of one completed window shown by the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowReadout {
+    /// When the window ended
+    pub window_end: DateTime<Utc>,
+    /// Typing keys per second
+    pub typing_rate: f64,
+    /// Number of pauses in the window
+    pub pause_count: u32,
+    /// Keystroke clustering (0-1)
+    pub burst_index: f64,
+    /// Mouse movement events per second
+    pub mouse_activity_rate: f64,
+    /// Focus continuity proxy (0-1)
+    pub focus_continuity: f64,
+}
+
+impl WindowReadout {
+    /// Take the readout from freshly computed window features.
+    pub fn from_features(window: &EventWindow, features: &WindowFeatures) -> Self {
+        Self {
+            window_end: window.end,
+            typing_rate: features.keyboard.typing_rate,
+            pause_count: features.keyboard.pause_count,
+            burst_index: features.keyboard.burst_index,
+            mouse_activity_rate: features.mouse.mouse_activity_rate,
+            focus_continuity: features.behavioral.focus_continuity_proxy,
+        }
+    }
+
+    /// Recover the readout from a window snapshot.
+    ///
+    /// Returns `None` for session summaries and snapshots without a window
+    /// end. Values missing from snapshots written by older versions read as
+    /// zero.
+    pub fn from_snapshot(snapshot: &HsiSnapshot) -> Option<Self> {
+        if snapshot.is_session_summary() {
+            return None;
+        }
+        let window_end = snapshot
+            .window_ids
+            .first()
+            .and_then(|id| snapshot.windows.get(id))
+            .and_then(|window| DateTime::parse_from_rfc3339(&window.end).ok())?
+            .with_timezone(&Utc);
+        let meta = |key: &str| {
+            snapshot
+                .meta
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0)
+        };
+
+        Some(Self {
+            window_end,
+            typing_rate: meta("raw_typing_rate"),
+            pause_count: meta("pause_count") as u32,
+            burst_index: snapshot.axis_score("typing_burstiness").unwrap_or(0.0),
+            mouse_activity_rate: meta("raw_mouse_activity_rate"),
+            focus_continuity: snapshot.axis_score("focus_continuity").unwrap_or(0.0),
+        })
+    }
+}
+
+/// Find the most recently modified uncompressed `.jsonl` session file named
+/// by `pattern` in `dir`, which is where streaming export appends snapshots.
+pub fn latest_stream_file(dir: &Path, pattern: &FileNamePattern) -> Option<PathBuf> {
+    export::snapshot_files(dir, pattern)
+        .into_iter()
+        .filter(|path| export::is_jsonl(path) && !export::is_compressed(path))
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Read the readout of the last window snapshot in a JSON Lines file.
+///
+/// Lines that do not parse, such as a line still being written, are skipped.
+pub fn read_latest(path: &Path) -> Result<Option<WindowReadout>, ExportError> {
+    let content = export::read_to_string(path).map_err(|e| ExportError::IoError(e.to_string()))?;
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<HsiSnapshot>(line).ok())
+        .find_map(|snapshot| WindowReadout::from_snapshot(&snapshot)))
+}
+
+/// Render the dashboard for `readout`, read from `source`, as of `now`.
+///
+/// Every line fits in [`DASHBOARD_WIDTH`] columns.
+pub fn render_dashboard(
+    readout: Option<&WindowReadout>,
+    source: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let rule = "─".repeat(DASHBOARD_WIDTH);
+    let mut out = String::new();
+    out.push_str(&format!("{BOLD}Synheart Sensor - live features{RESET}\n"));
+    out.push_str(&format!(
+        "{DIM}Source: {}{RESET}\n",
+        truncate(source, DASHBOARD_WIDTH - "Source: ".len())
+    ));
+    out.push_str(&rule);
+    out.push('\n');
+
+    let Some(readout) = readout else {
+        out.push_str(&format!(
+            "{YELLOW}Waiting for the first completed window...{RESET}\n"
+        ));
+        return out;
+    };
+
+    let age = (now - readout.window_end).num_seconds().max(0);
+    out.push_str(&format!(
+        "Window ended {} UTC ({age}s ago)\n\n",
+        readout.window_end.format("%H:%M:%S")
+    ));
+    out.push_str(&format!("{BOLD}{:<20}{:>16}{RESET}\n", "FEATURE", "VALUE"));
+
+    let rows = [
+        (
+            "Typing rate",
+            format!("{:.2} keys/s", readout.typing_rate),
+            Some(readout.typing_rate / TYPING_RATE_FULL_SCALE),
+        ),
+        ("Pause count", readout.pause_count.to_string(), None),
+        (
+            "Burst index",
+            format!("{:.2}", readout.burst_index),
+            Some(readout.burst_index),
+        ),
+        (
+            "Mouse activity",
+            format!("{:.1} moves/s", readout.mouse_activity_rate),
+            None,
+        ),
+        (
+            "Focus continuity",
+            format!("{:.2}", readout.focus_continuity),
+            Some(readout.focus_continuity),
+        ),
+    ];
+    for (label, value, fraction) in rows {
+        let value = truncate(&value, 16);
+        let bar = fraction.map(bar).unwrap_or_default();
+        out.push_str(&format!(
+            "{label:<20}{BOLD}{value:>16}{RESET}  {CYAN}{bar}{RESET}\n"
+        ));
+    }
+    out.push_str(&rule);
+    out.push('\n');
+    out.push_str(&format!("{DIM}Press Ctrl+C to exit{RESET}\n"));
+    out
+}
+
+/// A horizontal bar filled to `fraction` (clamped to 0-1) of [`BAR_WIDTH`].
+fn bar(fraction: f64) -> String {
+    let fraction = if fraction.is_finite() {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+/// Shorten `text` to at most `max` characters.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max.saturating_sub(3)).collect();
+    short.push_str("...");
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::{compute_features, FeatureConfig};
+    use crate::core::hsi::HsiBuilder;
+    use chrono::Duration;
+
+    /// Visible width of a line, ignoring ANSI escape sequences.
+    fn visible_width(line: &str) -> usize {
+        let mut width = 0;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                width += 1;
+            }
+        }
+        width
+    }
+
+    fn sample() -> (EventWindow, WindowFeatures) {
+        let window = EventWindow::new(Utc::now(), Duration::seconds(10));
+        let mut features = compute_features(&window, &FeatureConfig::default());
+        features.keyboard.typing_rate = 3.5;
+        features.keyboard.pause_count = 4;
+        features.keyboard.burst_index = 0.25;
+        features.mouse.mouse_activity_rate = 12.0;
+        features.behavioral.focus_continuity_proxy = 0.8;
+        (window, features)
+    }
+
+    #[test]
+    fn test_readout_from_snapshot_matches_features() {
+        let (window, features) = sample();
+        let snapshot = HsiBuilder::new().build(&window, &features, Duration::seconds(10), None);
+
+        let from_snapshot = WindowReadout::from_snapshot(&snapshot).unwrap();
+        let from_features = WindowReadout::from_features(&window, &features);
+        assert_eq!(from_snapshot.pause_count, 4);
+        assert!((from_snapshot.typing_rate - 3.5).abs() < 1e-9);
+        assert!((from_snapshot.mouse_activity_rate - 12.0).abs() < 1e-9);
+        assert!((from_snapshot.burst_index - 0.25).abs() < 1e-9);
+        assert!((from_snapshot.focus_continuity - 0.8).abs() < 1e-9);
+        assert_eq!(
+            from_snapshot.window_end.timestamp_millis(),
+            from_features.window_end.timestamp_millis()
+        );
+
+        let summary = HsiBuilder::new().build_session_summary(vec![snapshot]);
+        assert!(WindowReadout::from_snapshot(&summary).is_none());
+    }
+
+    #[test]
+    fn test_read_latest_stream_file() {
+        let (window, features) = sample();
+        let builder = HsiBuilder::new();
+        let first = builder.build(&window, &features, Duration::seconds(10), None);
+        let later = EventWindow::new(window.end, Duration::seconds(10));
+        let second = builder.build(&later, &features, Duration::seconds(10), None);
+
+        let dir = std::env::temp_dir().join(format!("synheart-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session_20240115_143000.jsonl");
+        let content = format!(
+            "{}\n{}\n{{\"partial",
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+        std::fs::write(&path, content).unwrap();
+
+        let pattern = FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN);
+        assert_eq!(latest_stream_file(&dir, &pattern), Some(path.clone()));
+        let readout = read_latest(&path).unwrap().unwrap();
+        assert_eq!(
+            readout.window_end.timestamp_millis(),
+            later.end.timestamp_millis()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_latest_stream_file_custom_pattern() {
+        let dir = std::env::temp_dir().join(format!("synheart-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("20240115-143000_abc.jsonl");
+        std::fs::write(&path, "").unwrap();
+        std::fs::write(dir.join("notes.jsonl"), "").unwrap();
+
+        let pattern = FileNamePattern::new("{date}-{time}_{session_id}");
+        assert_eq!(latest_stream_file(&dir, &pattern), Some(path));
+        let default = FileNamePattern::new(crate::config::DEFAULT_EXPORT_FILENAME_PATTERN);
+        assert_eq!(latest_stream_file(&dir, &default), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dashboard_fits_80_columns() {
+        let (window, mut features) = sample();
+        features.keyboard.typing_rate = 1e9;
+        features.mouse.mouse_activity_rate = 123_456.7;
+        let readout = WindowReadout::from_features(&window, &features);
+        let source = "x".repeat(200);
+
+        for dashboard in [
+            render_dashboard(Some(&readout), &source, Utc::now()),
+            render_dashboard(None, &source, Utc::now()),
+        ] {
+            for line in dashboard.lines() {
+                assert!(visible_width(line) <= DASHBOARD_WIDTH, "{line:?}");
+            }
+        }
+        let dashboard = render_dashboard(Some(&readout), "agent", Utc::now());
+        assert!(dashboard.contains("Pause count"));
+        assert!(dashboard.contains("Focus continuity"));
+    }
+}
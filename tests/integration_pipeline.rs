@@ -0,0 +1,91 @@
+//! End-to-end test of the pipeline from raw events to a JSON round-tripped HSI snapshot
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use synheart_sensor_agent::collector::{KeyboardEvent, MouseEvent, SensorEvent};
+use synheart_sensor_agent::{
+    compute_features, FeatureConfig, HsiBuilder, HsiSnapshot, WindowManager,
+};
+
+const TOLERANCE: f64 = 0.01;
+
+/// 25 key presses and 25 mouse movements over 10 seconds, alternating every 200ms.
+///
+/// Key presses are 400ms apart: steady typing at 2.5 keys/sec with no bursts
+/// and no pauses.
+fn synthetic_events(start: DateTime<Utc>) -> Vec<SensorEvent> {
+    let mut events = Vec::new();
+    for i in 0..25 {
+        let mut key = KeyboardEvent::new(true);
+        key.timestamp = start + Duration::milliseconds(i * 400);
+        events.push(SensorEvent::Keyboard(key));
+
+        let mut movement = MouseEvent::movement(3.0, 4.0);
+        movement.timestamp = start + Duration::milliseconds(i * 400 + 200);
+        events.push(SensorEvent::Mouse(movement));
+    }
+    events
+}
+
+fn assert_close(snapshot: &HsiSnapshot, axis: &str, expected: f64) {
+    let score = snapshot
+        .axis_score(axis)
+        .unwrap_or_else(|| panic!("missing axis {axis}"));
+    assert!(
+        (score - expected).abs() < TOLERANCE,
+        "{axis}: expected {expected}, got {score}"
+    );
+}
+
+#[test]
+fn test_events_to_snapshot_json_round_trip() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 15, 14, 32, 0).unwrap();
+    let events = synthetic_events(start);
+    assert_eq!(events.len(), 50);
+
+    let mut manager = WindowManager::new(10, 300, None);
+    for event in events {
+        manager.process_event(event);
+    }
+    manager.flush();
+
+    let mut completed = manager.take_completed_windows();
+    assert_eq!(completed.len(), 1);
+    let (resolution, window) = completed.remove(0);
+    assert_eq!(window.start, start);
+    assert_eq!(window.end, start + Duration::seconds(10));
+    assert_eq!(window.event_count(), 50);
+
+    let features = compute_features(&window, &FeatureConfig::default());
+    let snapshot = HsiBuilder::new().build(&window, &features, resolution, None);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let decoded: HsiSnapshot = serde_json::from_str(&json).unwrap();
+
+    // Nothing is lost or changed by the round trip
+    assert_eq!(
+        serde_json::to_value(&decoded).unwrap(),
+        serde_json::to_value(&snapshot).unwrap()
+    );
+    let original_axes: Vec<&str> = snapshot.readings().map(|r| r.axis.as_str()).collect();
+    let decoded_axes: Vec<&str> = decoded.readings().map(|r| r.axis.as_str()).collect();
+    assert_eq!(decoded_axes, original_axes);
+
+    // 2.5 keys/sec, normalized against 10 keys/sec
+    assert_close(&decoded, "typing_rate", 0.25);
+    // All intervals are 400ms: none under the 100ms burst interval
+    assert_close(&decoded, "typing_burstiness", 0.0);
+    // 24 active intervals of 400ms over a 10s window
+    assert_close(&decoded, "session_continuity", 0.96);
+    // Perfectly regular intervals
+    assert_close(&decoded, "typing_cadence_stability", 1.0);
+    // No interval exceeds the 500ms pause threshold
+    assert_close(&decoded, "typing_gap_ratio", 0.0);
+    // 0.25 * 0.4 + 1.0 * 0.3 + (1.0 - 0.0) * 0.3
+    assert_close(&decoded, "typing_interaction_intensity", 0.7);
+    // No navigation keys
+    assert_close(&decoded, "keyboard_scroll_rate", 0.0);
+
+    // 50 events give a window quality of 0.95, and behavior axes report 90% of it
+    let typing_rate = decoded.find_reading("typing_rate").unwrap();
+    assert!((typing_rate.confidence - 0.855).abs() < TOLERANCE);
+}
@@ -0,0 +1,129 @@
+//! Property tests for keyboard feature computation
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use proptest::prelude::*;
+use synheart_sensor_agent::assert_features_approx_eq;
+use synheart_sensor_agent::collector::types::KeyboardEventType;
+use synheart_sensor_agent::collector::{KeyboardEvent, SensorEvent};
+use synheart_sensor_agent::core::{compute_features, EventWindow, FeatureConfig, IKI_ENTROPY_BINS};
+
+const EPSILON: f64 = 1e-9;
+
+/// One generated key event: gap since the previous event, key down, navigation key, modifier held.
+type EventSpec = (i64, bool, bool, bool);
+
+fn event_specs() -> impl Strategy<Value = Vec<EventSpec>> {
+    prop::collection::vec(
+        (1i64..3_000, any::<bool>(), any::<bool>(), any::<bool>()),
+        0..300,
+    )
+}
+
+/// Build a window holding the events, with timestamps in increasing order.
+///
+/// The window lasts at least as long as the events span, plus `extra_secs`.
+fn keyboard_window(specs: &[EventSpec], extra_secs: i64) -> EventWindow {
+    let start: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 15, 14, 32, 0).unwrap();
+    let span_ms: i64 = specs.iter().map(|(gap_ms, ..)| gap_ms).sum();
+    let duration = Duration::milliseconds(span_ms) + Duration::seconds(extra_secs);
+    let mut window = EventWindow::new(start, duration);
+
+    let mut timestamp = start;
+    for &(gap_ms, is_key_down, is_navigation, modifier_active) in specs {
+        timestamp += Duration::milliseconds(gap_ms);
+        let event_type = if is_navigation {
+            KeyboardEventType::NavigationKey
+        } else {
+            KeyboardEventType::TypingTap
+        };
+        let mut event =
+            KeyboardEvent::with_type(is_key_down, event_type).with_modifier_active(modifier_active);
+        event.timestamp = timestamp;
+        window.add_event(SensorEvent::Keyboard(event));
+    }
+    window
+}
+
+fn assert_unit_range(name: &str, value: f64) {
+    assert!(
+        value.is_finite() && (0.0..=1.0).contains(&value),
+        "{name} = {value} is outside 0-1"
+    );
+}
+
+fn assert_non_negative(name: &str, value: f64) {
+    assert!(
+        value.is_finite() && value >= 0.0,
+        "{name} = {value} is negative or not finite"
+    );
+}
+
+proptest! {
+    #[test]
+    fn keyboard_features_stay_in_documented_ranges(
+        specs in event_specs(),
+        extra_secs in 1i64..60,
+    ) {
+        let window = keyboard_window(&specs, extra_secs);
+        let features = compute_features(&window, &FeatureConfig::default());
+        let keyboard = &features.keyboard;
+
+        assert_unit_range("typing_cadence_stability", keyboard.typing_cadence_stability);
+        assert_unit_range("burst_index", keyboard.burst_index);
+        assert_unit_range("session_continuity", keyboard.session_continuity);
+        assert_unit_range("typing_gap_ratio", keyboard.typing_gap_ratio);
+        assert_unit_range("typing_interaction_intensity", keyboard.typing_interaction_intensity);
+        assert_unit_range("modifier_active_tap_ratio", keyboard.modifier_active_tap_ratio);
+
+        assert_non_negative("typing_rate", keyboard.typing_rate);
+        assert_non_negative("mean_pause_ms", keyboard.mean_pause_ms);
+        assert_non_negative("latency_variability", keyboard.latency_variability);
+        assert_non_negative("hold_time_mean", keyboard.hold_time_mean);
+        assert_non_negative("keyboard_scroll_rate", keyboard.keyboard_scroll_rate);
+
+        let autocorrelation = keyboard.iki_autocorrelation_lag1;
+        prop_assert!(autocorrelation.is_finite());
+        prop_assert!((-1.0 - EPSILON..=1.0 + EPSILON).contains(&autocorrelation));
+
+        let max_entropy = (IKI_ENTROPY_BINS as f64).log2();
+        prop_assert!(keyboard.iki_entropy >= 0.0);
+        prop_assert!(keyboard.iki_entropy <= max_entropy + EPSILON);
+
+        assert_non_negative("iki_p25_ms", keyboard.iki_p25_ms);
+        prop_assert!(keyboard.iki_p25_ms <= keyboard.iki_p50_ms + EPSILON);
+        prop_assert!(keyboard.iki_p50_ms <= keyboard.iki_p75_ms + EPSILON);
+        prop_assert!(keyboard.iki_p75_ms <= keyboard.iki_p95_ms + EPSILON);
+
+        let key_downs = specs.iter().filter(|(_, is_key_down, ..)| *is_key_down).count() as u32;
+        prop_assert_eq!(keyboard.typing_tap_count + keyboard.navigation_key_count, key_downs);
+        prop_assert!(keyboard.pause_count <= keyboard.typing_tap_count.saturating_sub(1));
+    }
+
+    #[test]
+    fn behavioral_signals_stay_in_range_for_keyboard_only_windows(
+        specs in event_specs(),
+        extra_secs in 1i64..60,
+    ) {
+        let window = keyboard_window(&specs, extra_secs);
+        let signals = compute_features(&window, &FeatureConfig::default()).behavioral;
+
+        assert_unit_range("interaction_rhythm", signals.interaction_rhythm);
+        assert_unit_range("friction", signals.friction);
+        assert_unit_range("motor_stability", signals.motor_stability);
+        assert_unit_range("focus_continuity_proxy", signals.focus_continuity_proxy);
+        assert_unit_range("burstiness", signals.burstiness);
+    }
+
+    #[test]
+    fn features_are_deterministic(
+        specs in event_specs(),
+        extra_secs in 1i64..60,
+    ) {
+        let window = keyboard_window(&specs, extra_secs);
+        let config = FeatureConfig::default();
+        let first = compute_features(&window, &config);
+        let second = compute_features(&window.clone(), &config);
+
+        assert_features_approx_eq!(first, second, 0.0);
+    }
+}